@@ -0,0 +1,82 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+//? use serde::{Deserialize, Serialize};
+
+use std::f64::consts::{PI, TAU};
+
+/// Arcseconds to radians.
+pub const ARCSEC_TO_RAD: f64 = PI / (180.0 * 3600.0);
+
+/// Reduces an angle in radians to `[0, tau)`.
+#[must_use]
+pub fn norm_0_tau(rad: f64) -> f64 {
+    let r = rad.rem_euclid(TAU);
+    // `rem_euclid` can round up to exactly `TAU` for tiny negative inputs.
+    if r < TAU {
+        r
+    } else {
+        0.0
+    }
+}
+
+/// Reduces an angle in radians to `[-pi, pi)`.
+#[must_use]
+pub fn norm_mpi_pi(rad: f64) -> f64 {
+    let r = norm_0_tau(rad);
+    if r < PI {
+        r
+    } else {
+        r - TAU
+    }
+}
+
+/// Reduces an angle in degrees to `[0, 360)`.
+#[must_use]
+pub fn norm_0_360(deg: f64) -> f64 {
+    let d = deg.rem_euclid(360.0);
+    if d < 360.0 {
+        d
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        assert_ron_snapshot!(norm_0_360(-90.0), @"270.0");
+        assert_ron_snapshot!(norm_0_360(720.5), @"0.5");
+        assert_ron_snapshot!(norm_0_tau(-1e-300), @"0.0");
+        assert!((norm_mpi_pi(3.0 * PI / 2.0) + PI / 2.0).abs() < 1e-15);
+        assert!((norm_0_tau(-PI / 2.0) - 3.0 * PI / 2.0).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,540 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Apparent places of solar system bodies and stars: where a body is seen, rather than where it is.
+//!
+//! Starting from an [`Ephemeris`], the corrections are applied in the order of the IERS
+//! Conventions and SOFA (`iauAtciq` and friends):
+//!
+//! 1. light-time, iterating for the position the body had when the light left it,
+//! 2. gravitational deflection of that light by the Sun,
+//! 3. aberration, from the observer's barycentric velocity (annual) plus, for an observer on
+//!    the ground, the Earth's rotation (diurnal),
+//! 4. precession-nutation, from the GCRS to the true equator and equinox of date.
+//!
+//! Stars take the same steps from their catalog places, with the parallax in place of
+//! light-time.
+//!
+//! Each can be switched off in [`Corrections`], to see how much it contributes.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::angle::{norm_0_tau, ARCSEC_TO_RAD};
+use crate::ephemeris::{Ephemeris, NaifId, StateVector, AU_KM};
+use crate::frames::{gcrs_to_tirs, gcrs_to_tod};
+use crate::horizontal::Observer;
+use crate::julian::SECONDS_PER_DAY;
+use crate::nutation::{iau2000b, Iau2000a, Nutation};
+use crate::rotation::{mul_vec, transpose};
+use crate::stars::Star;
+
+/// Speed of light, km/s.
+pub const C_KM_S: f64 = 299_792.458;
+
+/// Schwarzschild radius of the Sun, au: 2 GM_sun / c^2.
+const SUN_SCHWARZSCHILD_RADIUS_AU: f64 = 1.97412574336e-8;
+
+/// Earth rotation rate, rad/s.
+const EARTH_ROTATION_RAD_S: f64 = 7.292115e-5;
+
+/// Which corrections to apply. All of them by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Corrections {
+    pub light_time: bool,
+    pub deflection: bool,
+    pub annual_aberration: bool,
+    pub diurnal_aberration: bool,
+    pub precession_nutation: bool,
+}
+
+impl Corrections {
+    pub const ALL: Self = Self {
+        light_time: true,
+        deflection: true,
+        annual_aberration: true,
+        diurnal_aberration: true,
+        precession_nutation: true,
+    };
+
+    /// The geometric position in the GCRS.
+    pub const NONE: Self = Self {
+        light_time: false,
+        deflection: false,
+        annual_aberration: false,
+        diurnal_aberration: false,
+        precession_nutation: false,
+    };
+}
+
+impl Default for Corrections {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Where a body appears.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct ApparentPlace {
+    /// Unit vector toward the body: true equator and equinox of date if precession-nutation
+    /// was applied, otherwise GCRS axes.
+    pub direction: [f64; 3],
+
+    /// Right ascension, radians in `[0, tau)`, in the same frame as `direction`.
+    pub ra: f64,
+
+    /// Declination, radians.
+    pub dec: f64,
+
+    /// Distance from the observer to where the body was when the light left it, km.
+    pub distance_km: f64,
+
+    /// Light travel time, seconds.
+    pub light_time_s: f64,
+}
+
+/// Computes apparent places from an ephemeris, for an observer at the geocenter or on the
+/// ground.
+#[derive(Clone, Copy)]
+pub struct ApparentPlaceCalc<'a> {
+    pub ephemeris: &'a dyn Ephemeris,
+
+    /// `None` for the geocenter.
+    pub observer: Option<Observer>,
+
+    /// The full nutation model, if loaded. IAU 2000B otherwise.
+    pub iau2000a: Option<&'a Iau2000a>,
+
+    pub corrections: Corrections,
+}
+
+impl<'a> ApparentPlaceCalc<'a> {
+    pub fn new(ephemeris: &'a dyn Ephemeris) -> Self {
+        Self {
+            ephemeris,
+            observer: None,
+            iau2000a: None,
+            corrections: Corrections::ALL,
+        }
+    }
+
+    fn nutation(&self, jd_tt: f64) -> Nutation {
+        match self.iau2000a {
+            Some(model) => model.nutation(jd_tt),
+            None => iau2000b(jd_tt),
+        }
+    }
+
+    /// The solar system barycenter, or the Sun for heliocentric theories like VSOP87D,
+    /// which costs about 10 mas of aberration.
+    fn origin(&self) -> NaifId {
+        let ssb = NaifId::SOLAR_SYSTEM_BARYCENTER;
+        if self.ephemeris.bodies().contains(&ssb) {
+            ssb
+        } else {
+            NaifId::SUN
+        }
+    }
+
+    /// The observer's barycentric state, GCRS.
+    fn observer_state(&self, jd_tt: f64, jd_ut1: f64) -> Result<StateVector> {
+        let eph = self.ephemeris;
+        let earth = eph.state(NaifId::EARTH, self.origin(), jd_tt)?;
+        let earth = if self.corrections.annual_aberration {
+            earth
+        } else {
+            StateVector {
+                velocity_km_s: [0.0; 3],
+                ..earth
+            }
+        };
+        let Some(observer) = &self.observer else {
+            return Ok(earth);
+        };
+
+        // Turn the ground position and its rotation velocity into the GCRS.
+        let tirs_to_gcrs = transpose(&gcrs_to_tirs(jd_tt, jd_ut1, self.nutation(jd_tt)));
+        let r = observer.itrs_position_km();
+        let v = if self.corrections.diurnal_aberration {
+            [
+                -EARTH_ROTATION_RAD_S * r[1],
+                EARTH_ROTATION_RAD_S * r[0],
+                0.0,
+            ]
+        } else {
+            [0.0; 3]
+        };
+        let geocentric = StateVector {
+            position_km: mul_vec(&tirs_to_gcrs, &r),
+            velocity_km_s: mul_vec(&tirs_to_gcrs, &v),
+        };
+        Ok(earth.add(&geocentric))
+    }
+
+    /// The apparent place of `target`. `jd_tt` is used as TDB; `jd_ut1` only matters for
+    /// an observer on the ground.
+    pub fn place(&self, target: NaifId, jd_tt: f64, jd_ut1: f64) -> Result<ApparentPlace> {
+        let eph = self.ephemeris;
+        let origin = self.origin();
+        let obs = self.observer_state(jd_tt, jd_ut1)?;
+
+        // Light-time: where the body was when the light we see now left it.
+        let mut light_time_days = 0.0;
+        let mut target_bary = eph.state(target, origin, jd_tt)?;
+        if self.corrections.light_time {
+            for i in 0.. {
+                ensure!(i < 10, "light-time iteration did not converge");
+                target_bary = eph.state(target, origin, jd_tt - light_time_days)?;
+                let distance_km = target_bary.sub(&obs).distance_km();
+                let next = distance_km / C_KM_S / SECONDS_PER_DAY;
+                let converged = (next - light_time_days).abs() < 1e-12;
+                light_time_days = next;
+                if converged {
+                    break;
+                }
+            }
+        }
+        let p = sub(target_bary.position_km, obs.position_km);
+        let distance_km = norm(p);
+        ensure!(0.0 < distance_km, "target coincides with observer");
+        let dir = scale(p, 1.0 / distance_km);
+
+        // Deflection by the Sun, unless the body is the Sun.
+        let sun = eph.state(NaifId::SUN, origin, jd_tt)?;
+        let q = if target != NaifId::SUN {
+            let sun_then = eph.state(NaifId::SUN, origin, jd_tt - light_time_days)?;
+            Some(unit(sub(target_bary.position_km, sun_then.position_km)))
+        } else {
+            None
+        };
+
+        let dir = self.deflect_aberrate_rotate(dir, q, &obs, &sun, jd_tt);
+        Ok(ApparentPlace::new(
+            dir,
+            distance_km,
+            light_time_days * SECONDS_PER_DAY,
+        ))
+    }
+
+    /// The apparent place of a star, from its catalog place moved to the date by its space
+    /// motion. The parallax is applied when it is known, from the observer's barycentric
+    /// position, as SOFA's `iauPmpx`. The distance and light time are infinite when it isn't.
+    pub fn star_place(&self, star: &Star, jd_tt: f64, jd_ut1: f64) -> Result<ApparentPlace> {
+        let obs = self.observer_state(jd_tt, jd_ut1)?;
+        let sun = self.ephemeris.state(NaifId::SUN, self.origin(), jd_tt)?;
+
+        let q = star.direction_at(jd_tt);
+        let parallax = star.parallax_mas / 1000.0 * ARCSEC_TO_RAD;
+        let dir = unit(sub(q, scale(obs.position_km, parallax / AU_KM)));
+        let distance_km = AU_KM / parallax;
+
+        let dir = self.deflect_aberrate_rotate(dir, Some(q), &obs, &sun, jd_tt);
+        Ok(ApparentPlace::new(dir, distance_km, distance_km / C_KM_S))
+    }
+
+    /// The steps after the geometric direction `dir` from the observer `obs`: deflection by
+    /// the `sun`, if the light passed it coming from `q` (the direction from the Sun to the
+    /// source), aberration, and precession-nutation.
+    fn deflect_aberrate_rotate(
+        &self,
+        mut dir: [f64; 3],
+        q: Option<[f64; 3]>,
+        obs: &StateVector,
+        sun: &StateVector,
+        jd_tt: f64,
+    ) -> [f64; 3] {
+        let sun_to_obs = sub(obs.position_km, sun.position_km);
+        if let (true, Some(q)) = (self.corrections.deflection, q) {
+            let e = unit(sun_to_obs);
+            dir = deflect(dir, q, e, norm(sun_to_obs) / AU_KM);
+        }
+
+        // Aberration. The velocity already has the switched-off parts removed.
+        let v = scale(obs.velocity_km_s, 1.0 / C_KM_S);
+        if v != [0.0; 3] {
+            dir = aberrate(dir, v, norm(sun_to_obs) / AU_KM);
+        }
+
+        if self.corrections.precession_nutation {
+            dir = mul_vec(&gcrs_to_tod(jd_tt, self.nutation(jd_tt)), &dir);
+        }
+        dir
+    }
+}
+
+impl ApparentPlace {
+    fn new(direction: [f64; 3], distance_km: f64, light_time_s: f64) -> Self {
+        let [x, y, z] = direction;
+        Self {
+            direction,
+            ra: norm_0_tau(y.atan2(x)),
+            dec: z.atan2((x * x + y * y).sqrt()),
+            distance_km,
+            light_time_s,
+        }
+    }
+}
+
+/// Light deflection by the Sun, as SOFA's `iauLd` with unit mass.
+/// `p` is the direction to the source, `q` the direction from the Sun to the source, and
+/// `e` the direction from the Sun to the observer, `em_au` away.
+fn deflect(p: [f64; 3], q: [f64; 3], e: [f64; 3], em_au: f64) -> [f64; 3] {
+    // Avoids the singularity when the source is directly behind the Sun.
+    const DLIM: f64 = 1e-9;
+    let qpe = add(q, e);
+    let w = SUN_SCHWARZSCHILD_RADIUS_AU / em_au / dot(q, qpe).max(DLIM);
+    let peq = cross(p, cross(e, q));
+    add(p, scale(peq, w))
+}
+
+/// Stellar aberration, relativistic, as SOFA's `iauAb`. `v` is the observer's barycentric
+/// velocity in units of c, and `s_au` its distance from the Sun.
+fn aberrate(pnat: [f64; 3], v: [f64; 3], s_au: f64) -> [f64; 3] {
+    let pdv = dot(pnat, v);
+    let bm1 = (1.0 - dot(v, v)).sqrt();
+    let w1 = 1.0 + pdv / (1.0 + bm1);
+    let w2 = SUN_SCHWARZSCHILD_RADIUS_AU / s_au;
+    let p: [f64; 3] =
+        std::array::from_fn(|i| pnat[i] * bm1 + w1 * v[i] + w2 * (v[i] - pdv * pnat[i]));
+    unit(p)
+}
+
+/// Angle between two directions, radians.
+#[must_use]
+pub fn angular_separation(a: [f64; 3], b: [f64; 3]) -> f64 {
+    norm(cross(a, b)).atan2(dot(a, b))
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f64; 3], k: f64) -> [f64; 3] {
+    a.map(|x| x * k)
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+fn unit(a: [f64; 3]) -> [f64; 3] {
+    scale(a, 1.0 / norm(a))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use crate::ephemeris::{Vsop87Ephemeris, WithLowPrecisionMoon};
+    use insta::assert_ron_snapshot;
+
+    fn round_to(x: f64, places: i32) -> f64 {
+        let k = 10f64.powi(places);
+        (x * k).round() / k
+    }
+
+    fn arcsec(a: &ApparentPlace, b: &ApparentPlace) -> f64 {
+        angular_separation(a.direction, b.direction) / ARCSEC_TO_RAD
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let eph = Vsop87Ephemeris::abridged();
+        let calc = ApparentPlaceCalc::new(&eph);
+
+        // Meeus example 25.b, 1992 October 13.0 TD: 13h13m30.749s, -7°47'01.74".
+        let jd = 2_448_908.5;
+        let sun = calc.place(NaifId::SUN, jd, jd)?;
+        let ra_meeus = (13.0 + 13.0 / 60.0 + 30.749 / 3600.0) * 15.0;
+        let dec_meeus = -(7.0 + 47.0 / 60.0 + 1.74 / 3600.0);
+        assert!(
+            (sun.ra.to_degrees() - ra_meeus).abs() * 3600.0 < 2.0,
+            "{sun:?}"
+        );
+        assert!(
+            (sun.dec.to_degrees() - dec_meeus).abs() * 3600.0 < 2.0,
+            "{sun:?}"
+        );
+        assert_ron_snapshot!(round_to(sun.light_time_s, 1), @"497.8");
+
+        // How much each correction moves the Sun, arcseconds.
+        let with = |corrections: Corrections| ApparentPlaceCalc {
+            corrections,
+            ..calc
+        };
+        let geometric = with(Corrections::NONE).place(NaifId::SUN, jd, jd)?;
+        let aberration = with(Corrections {
+            annual_aberration: true,
+            ..Corrections::NONE
+        })
+        .place(NaifId::SUN, jd, jd)?;
+        assert_ron_snapshot!(round_to(arcsec(&geometric, &aberration), 1), @"20.5");
+
+        // Light-time is nothing for the Sun at the origin of VSOP87D, but Venus moves.
+        let light_time = with(Corrections {
+            light_time: true,
+            ..Corrections::NONE
+        });
+        assert_eq!(
+            light_time.place(NaifId::SUN, jd, jd)?.direction,
+            geometric.direction
+        );
+        let venus_geometric = with(Corrections::NONE).place(NaifId::VENUS, jd, jd)?;
+        let venus_retarded = light_time.place(NaifId::VENUS, jd, jd)?;
+        assert_ron_snapshot!(round_to(arcsec(&venus_geometric, &venus_retarded), 1), @"16.6");
+
+        // Deflection moves Venus by milliarcseconds.
+        let no_deflection = with(Corrections {
+            deflection: false,
+            ..Corrections::ALL
+        });
+        let venus = calc.place(NaifId::VENUS, jd, jd)?;
+        let venus_undeflected = no_deflection.place(NaifId::VENUS, jd, jd)?;
+        assert_ron_snapshot!(round_to(arcsec(&venus, &venus_undeflected) * 1e3, 0), @"5.0");
+
+        // Diurnal aberration is at most about 0.32" at the equator.
+        let ground = ApparentPlaceCalc {
+            observer: Some(Observer::new(0.0, 0.0, 0.0)),
+            ..calc
+        };
+        let no_diurnal = ApparentPlaceCalc {
+            corrections: Corrections {
+                diurnal_aberration: false,
+                ..Corrections::ALL
+            },
+            ..ground
+        };
+        let a = ground.place(NaifId::SUN, jd, jd)?;
+        let b = no_diurnal.place(NaifId::SUN, jd, jd)?;
+        assert!(0.0 < arcsec(&a, &b) && arcsec(&a, &b) < 0.33);
+
+        // At 6h local time the Sun is rising, and its parallax is almost 9".
+        let sunrise = ApparentPlaceCalc {
+            observer: Some(Observer::new(0.0, 90.0, 0.0)),
+            ..no_diurnal
+        };
+        let rising = sunrise.place(NaifId::SUN, jd, jd)?;
+        assert_ron_snapshot!(round_to(arcsec(&rising, &b), 1), @"8.4");
+
+        assert!(calc.place(NaifId::MOON, jd, jd).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn t_moon() -> anyhow::Result<()> {
+        let eph = WithLowPrecisionMoon::new(Vsop87Ephemeris::abridged());
+        let calc = ApparentPlaceCalc::new(&eph);
+
+        // Meeus example 47.a, 1992 April 12.0 TD: α 134.688470°, δ 13.768368°. The
+        // low-precision Moon is good to a few tenths of a degree.
+        let jd = 2_448_724.5;
+        let moon = calc.place(NaifId::MOON, jd, jd)?;
+        assert!((moon.ra.to_degrees() - 134.688470).abs() < 0.3, "{moon:?}");
+        assert!((moon.dec.to_degrees() - 13.768368).abs() < 0.3, "{moon:?}");
+        assert_ron_snapshot!(round_to(moon.light_time_s, 2), @"1.23");
+
+        // From the ground the parallax moves it by up to a degree.
+        let ground = ApparentPlaceCalc {
+            observer: Some(Observer::new(0.0, 0.0, 0.0)),
+            ..calc
+        };
+        let topocentric = ground.place(NaifId::MOON, jd, jd)?;
+        let parallax_deg = angular_separation(moon.direction, topocentric.direction).to_degrees();
+        assert!(0.0 < parallax_deg && parallax_deg < 1.0, "{parallax_deg}");
+        Ok(())
+    }
+
+    #[test]
+    fn t_star() -> anyhow::Result<()> {
+        let eph = Vsop87Ephemeris::abridged();
+        let calc = ApparentPlaceCalc::new(&eph);
+
+        // Meeus example 23.a, θ Persei on 2028 November 13.19 TD. J2000 place 2h44m11.986s,
+        // +49°13'42.48", proper motion +0.03425 s and -0.0895" a year; apparent place
+        // 2h46m14.390s, +49°21'07.45".
+        let dec = (49.0 + 13.0 / 60.0 + 42.48 / 3600.0_f64).to_radians();
+        let star = Star {
+            name: "θ Persei".into(),
+            hr: None,
+            hip: None,
+            ra: ((2.0 + 44.0 / 60.0 + 11.986 / 3600.0) * 15.0_f64).to_radians(),
+            dec,
+            epoch_jd_tt: crate::julian::J2000,
+            pm_ra_cosdec_mas_yr: 0.03425 * 15.0 * 1000.0 * dec.cos(),
+            pm_dec_mas_yr: -89.5,
+            parallax_mas: 0.0,
+            radial_velocity_km_s: 0.0,
+            vmag: 4.1,
+            b_v: None,
+        };
+        let jd = 2_462_088.69;
+        let place = calc.star_place(&star, jd, jd)?;
+        let ra_meeus = (2.0 + 46.0 / 60.0 + 14.390 / 3600.0) * 15.0;
+        let dec_meeus = 49.0 + 21.0 / 60.0 + 7.45 / 3600.0;
+        let cos_dec = place.dec.cos();
+        assert!(
+            (place.ra.to_degrees() - ra_meeus).abs() * cos_dec * 3600.0 < 1.0,
+            "{place:?}"
+        );
+        assert!(
+            (place.dec.to_degrees() - dec_meeus).abs() * 3600.0 < 1.0,
+            "{place:?}"
+        );
+        assert_eq!(place.distance_km, f64::INFINITY);
+
+        // Aberration and nutation are most of the way from the mean place of date.
+        let (ra, dec) = star.mean_of_date(jd);
+        let mean = [dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin()];
+        let arcsec = angular_separation(mean, place.direction) / ARCSEC_TO_RAD;
+        assert_ron_snapshot!(round_to(arcsec, 0), @"33.0");
+
+        // A parallax of 1" moves it by under 1".
+        let near = Star {
+            parallax_mas: 1000.0,
+            ..star.clone()
+        };
+        let near_place = calc.star_place(&near, jd, jd)?;
+        let arcsec = angular_separation(near_place.direction, place.direction) / ARCSEC_TO_RAD;
+        assert!(0.0 < arcsec && arcsec < 1.0, "{arcsec}");
+        assert_ron_snapshot!(round_to(near_place.distance_km / AU_KM, 0), @"206265.0");
+        Ok(())
+    }
+}
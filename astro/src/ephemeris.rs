@@ -0,0 +1,365 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! A common interface to planetary ephemerides, whether analytic (VSOP87) or interpolated
+//! from tables (JPL DE via SPK or ASCII files).
+//!
+//! Bodies are named by their NAIF integer IDs. States are in the ICRF (J2000 equator), in km
+//! and km/s, at a TDB Julian date.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::julian::{julian_centuries_since_j2000, J2000, SECONDS_PER_DAY};
+use crate::vsop87::{Planet, Vsop87Body, Vsop87Version};
+
+/// Astronomical unit, km. Exact by definition (IAU 2012 Resolution B2).
+pub const AU_KM: f64 = 149_597_870.7;
+
+//=================================================================================================|
+
+/// A NAIF body ID, as used by SPICE and the SPK format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct NaifId(pub i32);
+
+impl NaifId {
+    pub const SOLAR_SYSTEM_BARYCENTER: NaifId = NaifId(0);
+    pub const MERCURY_BARYCENTER: NaifId = NaifId(1);
+    pub const VENUS_BARYCENTER: NaifId = NaifId(2);
+    pub const EARTH_MOON_BARYCENTER: NaifId = NaifId(3);
+    pub const MARS_BARYCENTER: NaifId = NaifId(4);
+    pub const JUPITER_BARYCENTER: NaifId = NaifId(5);
+    pub const SATURN_BARYCENTER: NaifId = NaifId(6);
+    pub const URANUS_BARYCENTER: NaifId = NaifId(7);
+    pub const NEPTUNE_BARYCENTER: NaifId = NaifId(8);
+    pub const PLUTO_BARYCENTER: NaifId = NaifId(9);
+    pub const SUN: NaifId = NaifId(10);
+    pub const MERCURY: NaifId = NaifId(199);
+    pub const VENUS: NaifId = NaifId(299);
+    pub const MOON: NaifId = NaifId(301);
+    pub const EARTH: NaifId = NaifId(399);
+}
+
+/// Position and velocity.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct StateVector {
+    pub position_km: [f64; 3],
+    pub velocity_km_s: [f64; 3],
+}
+
+impl StateVector {
+    #[must_use]
+    pub fn sub(&self, rhs: &StateVector) -> StateVector {
+        let d = |a: [f64; 3], b: [f64; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+        StateVector {
+            position_km: d(self.position_km, rhs.position_km),
+            velocity_km_s: d(self.velocity_km_s, rhs.velocity_km_s),
+        }
+    }
+
+    #[must_use]
+    pub fn add(&self, rhs: &StateVector) -> StateVector {
+        let s = |a: [f64; 3], b: [f64; 3]| [a[0] + b[0], a[1] + b[1], a[2] + b[2]];
+        StateVector {
+            position_km: s(self.position_km, rhs.position_km),
+            velocity_km_s: s(self.velocity_km_s, rhs.velocity_km_s),
+        }
+    }
+
+    #[must_use]
+    pub fn scale(&self, k: f64) -> StateVector {
+        StateVector {
+            position_km: self.position_km.map(|x| x * k),
+            velocity_km_s: self.velocity_km_s.map(|x| x * k),
+        }
+    }
+
+    pub fn distance_km(&self) -> f64 {
+        let [x, y, z] = self.position_km;
+        (x * x + y * y + z * z).sqrt()
+    }
+}
+
+/// A source of positions and velocities of solar system bodies.
+pub trait Ephemeris {
+    /// State of `target` relative to `center` at a TDB Julian date, ICRF, km and km/s.
+    fn state(&self, target: NaifId, center: NaifId, jd_tdb: f64) -> Result<StateVector>;
+
+    /// The bodies this ephemeris can place.
+    fn bodies(&self) -> Vec<NaifId>;
+}
+
+//=================================================================================================|
+
+/// The VSOP87 analytic theory as an [`Ephemeris`]. Knows the Sun and whichever planets were
+/// loaded. Velocities are by numerical differentiation.
+#[derive(Debug, Default, Clone)]
+pub struct Vsop87Ephemeris {
+    bodies: Vec<Vsop87Body>,
+}
+
+impl Vsop87Ephemeris {
+    /// Uses the compiled-in abridged series, which place only Venus, the Earth and the Sun.
+    pub fn abridged() -> Self {
+        Self {
+            bodies: vec![Vsop87Body::abridged_venus(), Vsop87Body::abridged_earth()],
+        }
+    }
+
+    pub fn new(bodies: Vec<Vsop87Body>) -> Self {
+        Self { bodies }
+    }
+
+    fn body(&self, id: NaifId) -> Option<&Vsop87Body> {
+        self.bodies
+            .iter()
+            .find(|b| planet_naif_ids(b.planet).contains(&id))
+    }
+
+    /// Heliocentric (version D) or barycentric (version E) position, ICRF, km.
+    fn position_km(&self, body: &Vsop87Body, jd_tdb: f64) -> [f64; 3] {
+        let ecl_j2000 = match body.version {
+            Vsop87Version::D => {
+                let [lon, lat, r] = body.evaluate(jd_tdb);
+                let (lon, lat) = precess_ecliptic_to_j2000(lon, lat, jd_tdb);
+                let (sin_lon, cos_lon) = lon.sin_cos();
+                let (sin_lat, cos_lat) = lat.sin_cos();
+                [r * cos_lat * cos_lon, r * cos_lat * sin_lon, r * sin_lat]
+            }
+            Vsop87Version::E => body.evaluate(jd_tdb),
+        };
+        vsop87_ecliptic_j2000_to_icrf(ecl_j2000).map(|au| au * AU_KM)
+    }
+
+    fn state_of(&self, id: NaifId, jd_tdb: f64) -> Result<StateVector> {
+        let Some(body) = self.body(id) else {
+            // The origin of the series is the Sun for version D.
+            let heliocentric = self.bodies.iter().all(|b| b.version == Vsop87Version::D);
+            ensure!(
+                id == NaifId::SUN && heliocentric,
+                "VSOP87: no series loaded for NAIF ID {}",
+                id.0
+            );
+            return Ok(StateVector::default());
+        };
+
+        // Central difference over one minute.
+        const H_DAYS: f64 = 1.0 / (24.0 * 60.0);
+        let p = self.position_km(body, jd_tdb);
+        let p_plus = self.position_km(body, jd_tdb + H_DAYS);
+        let p_minus = self.position_km(body, jd_tdb - H_DAYS);
+        let v = [0, 1, 2].map(|i| (p_plus[i] - p_minus[i]) / (2.0 * H_DAYS * SECONDS_PER_DAY));
+
+        Ok(StateVector {
+            position_km: p,
+            velocity_km_s: v,
+        })
+    }
+}
+
+impl Ephemeris for Vsop87Ephemeris {
+    fn state(&self, target: NaifId, center: NaifId, jd_tdb: f64) -> Result<StateVector> {
+        let versions_agree = self.bodies.windows(2).all(|w| w[0].version == w[1].version);
+        ensure!(versions_agree, "VSOP87: can't mix versions D and E");
+        Ok(self
+            .state_of(target, jd_tdb)?
+            .sub(&self.state_of(center, jd_tdb)?))
+    }
+
+    fn bodies(&self) -> Vec<NaifId> {
+        let mut v: Vec<NaifId> = self
+            .bodies
+            .iter()
+            .map(|b| planet_naif_ids(b.planet)[0])
+            .collect();
+        if self.bodies.iter().all(|b| b.version == Vsop87Version::D) {
+            v.push(NaifId::SUN);
+        }
+        v
+    }
+}
+
+//=================================================================================================|
+
+/// Adds the Moon to an ephemeris that knows the Earth, from the low-precision formulas of
+/// [`moon::apparent_low_precision`](crate::moon::apparent_low_precision). Good to a few tenths
+/// of a degree, which is enough to draw it. Velocities are by numerical differentiation.
+#[derive(Debug, Default, Clone)]
+pub struct WithLowPrecisionMoon<E> {
+    pub inner: E,
+}
+
+impl<E: Ephemeris> WithLowPrecisionMoon<E> {
+    pub fn new(inner: E) -> Self {
+        Self { inner }
+    }
+
+    /// The Moon relative to the Earth, ICRF, km.
+    fn moon_position_km(jd_tdb: f64) -> [f64; 3] {
+        let moon = crate::moon::apparent_low_precision(jd_tdb);
+        let (lon, lat) = precess_ecliptic_to_j2000(moon.lon, moon.lat, jd_tdb);
+        let (sin_lon, cos_lon) = lon.sin_cos();
+        let (sin_lat, cos_lat) = lat.sin_cos();
+        let r = moon.distance_km;
+        vsop87_ecliptic_j2000_to_icrf([r * cos_lat * cos_lon, r * cos_lat * sin_lon, r * sin_lat])
+    }
+
+    /// State relative to the Earth.
+    fn geocentric(&self, id: NaifId, jd_tdb: f64) -> Result<StateVector> {
+        if id != NaifId::MOON {
+            return self.inner.state(id, NaifId::EARTH, jd_tdb);
+        }
+
+        // Central difference over one minute.
+        const H_DAYS: f64 = 1.0 / (24.0 * 60.0);
+        let p = Self::moon_position_km(jd_tdb);
+        let p_plus = Self::moon_position_km(jd_tdb + H_DAYS);
+        let p_minus = Self::moon_position_km(jd_tdb - H_DAYS);
+        let v = [0, 1, 2].map(|i| (p_plus[i] - p_minus[i]) / (2.0 * H_DAYS * SECONDS_PER_DAY));
+
+        Ok(StateVector {
+            position_km: p,
+            velocity_km_s: v,
+        })
+    }
+}
+
+impl<E: Ephemeris> Ephemeris for WithLowPrecisionMoon<E> {
+    fn state(&self, target: NaifId, center: NaifId, jd_tdb: f64) -> Result<StateVector> {
+        if target != NaifId::MOON && center != NaifId::MOON {
+            return self.inner.state(target, center, jd_tdb);
+        }
+        Ok(self
+            .geocentric(target, jd_tdb)?
+            .sub(&self.geocentric(center, jd_tdb)?))
+    }
+
+    fn bodies(&self) -> Vec<NaifId> {
+        let mut v = self.inner.bodies();
+        if !v.contains(&NaifId::MOON) {
+            v.push(NaifId::MOON);
+        }
+        v
+    }
+}
+
+/// NAIF IDs a VSOP87 body answers to. VSOP87 Mercury and Venus have no moons, so their
+/// barycenters are the planets; the outer planets are the barycenters of their systems.
+fn planet_naif_ids(planet: Planet) -> &'static [NaifId] {
+    match planet {
+        Planet::Mercury => &[NaifId::MERCURY, NaifId::MERCURY_BARYCENTER],
+        Planet::Venus => &[NaifId::VENUS, NaifId::VENUS_BARYCENTER],
+        Planet::Earth => &[NaifId::EARTH],
+        Planet::Mars => &[NaifId::MARS_BARYCENTER],
+        Planet::Jupiter => &[NaifId::JUPITER_BARYCENTER],
+        Planet::Saturn => &[NaifId::SATURN_BARYCENTER],
+        Planet::Uranus => &[NaifId::URANUS_BARYCENTER],
+        Planet::Neptune => &[NaifId::NEPTUNE_BARYCENTER],
+    }
+}
+
+/// Precesses ecliptic coordinates of date to the ecliptic and equinox of J2000.
+/// Meeus, "Astronomical Algorithms" 2nd ed., eq. 21.5 and 21.7.
+fn precess_ecliptic_to_j2000(lon: f64, lat: f64, jd: f64) -> (f64, f64) {
+    let asec = |x: f64| (x / 3600.0).to_radians();
+    let T = julian_centuries_since_j2000(jd);
+    let t = -T;
+
+    let eta = asec(
+        (47.0029 - 0.06603 * T + 0.000598 * T * T) * t
+            + (-0.03302 + 0.000598 * T) * t * t
+            + 0.000060 * t * t * t,
+    );
+    let pi = 174.876384_f64.to_radians() + asec(3289.4789 * T + 0.60622 * T * T)
+        - asec((869.8089 + 0.50491 * T) * t)
+        + asec(0.03536 * t * t);
+    let p = asec(
+        (5029.0966 + 2.22226 * T - 0.000042 * T * T) * t + (1.11113 - 0.000042 * T) * t * t
+            - 0.000006 * t * t * t,
+    );
+
+    let (sin_eta, cos_eta) = eta.sin_cos();
+    let (sin_lat, cos_lat) = lat.sin_cos();
+    let (sin_d, cos_d) = (pi - lon).sin_cos();
+    let a = cos_eta * cos_lat * sin_d - sin_eta * sin_lat;
+    let b = cos_lat * cos_d;
+    let c = cos_eta * sin_lat + sin_eta * cos_lat * sin_d;
+    (p + pi - a.atan2(b), c.asin())
+}
+
+/// From the dynamical ecliptic and equinox of J2000 used by VSOP87 to the FK5 (≈ ICRF)
+/// equator, per the VSOP87 documentation.
+fn vsop87_ecliptic_j2000_to_icrf([x, y, z]: [f64; 3]) -> [f64; 3] {
+    [
+        x + 0.000000440360 * y - 0.000000190919 * z,
+        -0.000000479966 * x + 0.917482137087 * y - 0.397776982902 * z,
+        0.397776982902 * y + 0.917482137087 * z,
+    ]
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let eph = Vsop87Ephemeris::abridged();
+        assert_eq!(eph.bodies(), [NaifId::VENUS, NaifId::EARTH, NaifId::SUN]);
+
+        // Earth at J2000 in the ICRF, from DE440: (-2.649903e7, 1.327574e8, 5.755671e7) km.
+        let earth = eph.state(NaifId::EARTH, NaifId::SUN, J2000)?;
+        let expected = [-2.649903e7, 1.327574e8, 5.755671e7];
+        for (got, want) in earth.position_km.iter().zip(expected) {
+            assert!((got - want).abs() < 2e3, "{earth:?}");
+        }
+        // About 30 km/s, mostly in -x this time of year.
+        assert!((earth.velocity_km_s[0] - -29.8).abs() < 0.2, "{earth:?}");
+
+        // Reversing target and center negates.
+        let sun = eph.state(NaifId::SUN, NaifId::EARTH, J2000)?;
+        assert_eq!(sun.position_km, earth.position_km.map(|x| -x));
+
+        assert!(eph.state(NaifId::MOON, NaifId::EARTH, J2000).is_err());
+
+        // The low-precision Moon, about 402 000 km from the Earth at J2000.
+        let eph = WithLowPrecisionMoon::new(eph);
+        assert_eq!(
+            eph.bodies(),
+            [NaifId::VENUS, NaifId::EARTH, NaifId::SUN, NaifId::MOON]
+        );
+        let moon = eph.state(NaifId::MOON, NaifId::EARTH, J2000)?;
+        assert_ron_snapshot!((moon.distance_km() / 1e3).round(), @"402.0");
+        let moon_from_sun = eph.state(NaifId::MOON, NaifId::SUN, J2000)?;
+        let earth = eph.state(NaifId::EARTH, NaifId::SUN, J2000)?;
+        assert_eq!(moon_from_sun.position_km, moon.add(&earth).position_km);
+        assert_eq!(
+            eph.state(NaifId::EARTH, NaifId::SUN, J2000)?,
+            eph.inner.state(NaifId::EARTH, NaifId::SUN, J2000)?
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Series for the CIO locator `s + XY/2`, IAU 2006/2000A, from IERS Conventions (2010)
+//! table 5.2d as tabulated in SOFA's `iauS06`. Units are arcseconds.
+
+/// Multipliers of l, l', F, D, Om, L_Ve, L_E, p_A, then the sine and cosine coefficients.
+pub(crate) struct Term {
+    pub nfa: [i8; 8],
+    pub s: f64,
+    pub c: f64,
+}
+
+const fn term(nfa: [i8; 8], s: f64, c: f64) -> Term {
+    Term { nfa, s, c }
+}
+
+/// Polynomial coefficients, T^0 through T^5.
+pub(crate) const SP: [f64; 6] = [
+    94.00e-6,
+    3808.65e-6,
+    -122.68e-6,
+    -72574.11e-6,
+    27.98e-6,
+    15.62e-6,
+];
+
+#[rustfmt::skip]
+pub(crate) const S0: [Term; 33] = [
+    // 1-10
+    term([ 0, 0, 0, 0, 1, 0, 0, 0], -2640.73e-6,  0.39e-6),
+    term([ 0, 0, 0, 0, 2, 0, 0, 0],   -63.53e-6,  0.02e-6),
+    term([ 0, 0, 2,-2, 3, 0, 0, 0],   -11.75e-6, -0.01e-6),
+    term([ 0, 0, 2,-2, 1, 0, 0, 0],   -11.21e-6, -0.01e-6),
+    term([ 0, 0, 2,-2, 2, 0, 0, 0],     4.57e-6,  0.00e-6),
+    term([ 0, 0, 2, 0, 3, 0, 0, 0],    -2.02e-6,  0.00e-6),
+    term([ 0, 0, 2, 0, 1, 0, 0, 0],    -1.98e-6,  0.00e-6),
+    term([ 0, 0, 0, 0, 3, 0, 0, 0],     1.72e-6,  0.00e-6),
+    term([ 0, 1, 0, 0, 1, 0, 0, 0],     1.41e-6,  0.01e-6),
+    term([ 0, 1, 0, 0,-1, 0, 0, 0],     1.26e-6,  0.01e-6),
+    // 11-20
+    term([ 1, 0, 0, 0,-1, 0, 0, 0],     0.63e-6,  0.00e-6),
+    term([ 1, 0, 0, 0, 1, 0, 0, 0],     0.63e-6,  0.00e-6),
+    term([ 0, 1, 2,-2, 3, 0, 0, 0],    -0.46e-6,  0.00e-6),
+    term([ 0, 1, 2,-2, 1, 0, 0, 0],    -0.45e-6,  0.00e-6),
+    term([ 0, 0, 4,-4, 4, 0, 0, 0],    -0.36e-6,  0.00e-6),
+    term([ 0, 0, 1,-1, 1,-8,12, 0],     0.24e-6,  0.12e-6),
+    term([ 0, 0, 2, 0, 0, 0, 0, 0],    -0.32e-6,  0.00e-6),
+    term([ 0, 0, 2, 0, 2, 0, 0, 0],    -0.28e-6,  0.00e-6),
+    term([ 1, 0, 2, 0, 3, 0, 0, 0],    -0.27e-6,  0.00e-6),
+    term([ 1, 0, 2, 0, 1, 0, 0, 0],    -0.26e-6,  0.00e-6),
+    // 21-30
+    term([ 0, 0, 2,-2, 0, 0, 0, 0],     0.21e-6,  0.00e-6),
+    term([ 0, 1,-2, 2,-3, 0, 0, 0],    -0.19e-6,  0.00e-6),
+    term([ 0, 1,-2, 2,-1, 0, 0, 0],    -0.18e-6,  0.00e-6),
+    term([ 0, 0, 0, 0, 0, 8,-13,-1],    0.10e-6, -0.05e-6),
+    term([ 0, 0, 0, 2, 0, 0, 0, 0],    -0.15e-6,  0.00e-6),
+    term([ 2, 0,-2, 0,-1, 0, 0, 0],     0.14e-6,  0.00e-6),
+    term([ 0, 1, 2,-2, 2, 0, 0, 0],     0.14e-6,  0.00e-6),
+    term([ 1, 0, 0,-2, 1, 0, 0, 0],    -0.14e-6,  0.00e-6),
+    term([ 1, 0, 0,-2,-1, 0, 0, 0],    -0.14e-6,  0.00e-6),
+    term([ 0, 0, 4,-2, 4, 0, 0, 0],    -0.13e-6,  0.00e-6),
+    // 31-33
+    term([ 0, 0, 2,-2, 4, 0, 0, 0],     0.11e-6,  0.00e-6),
+    term([ 1, 0,-2, 0,-3, 0, 0, 0],    -0.11e-6,  0.00e-6),
+    term([ 1, 0,-2, 0,-1, 0, 0, 0],    -0.11e-6,  0.00e-6),
+];
+
+#[rustfmt::skip]
+pub(crate) const S1: [Term; 3] = [
+    term([ 0, 0, 0, 0, 2, 0, 0, 0],    -0.07e-6,  3.57e-6),
+    term([ 0, 0, 0, 0, 1, 0, 0, 0],     1.73e-6, -0.03e-6),
+    term([ 0, 0, 2,-2, 3, 0, 0, 0],     0.00e-6,  0.48e-6),
+];
+
+#[rustfmt::skip]
+pub(crate) const S2: [Term; 25] = [
+    // 1-10
+    term([ 0, 0, 0, 0, 1, 0, 0, 0],   743.52e-6, -0.17e-6),
+    term([ 0, 0, 2,-2, 2, 0, 0, 0],    56.91e-6,  0.06e-6),
+    term([ 0, 0, 2, 0, 2, 0, 0, 0],     9.84e-6, -0.01e-6),
+    term([ 0, 0, 0, 0, 2, 0, 0, 0],    -8.85e-6,  0.01e-6),
+    term([ 0, 1, 0, 0, 0, 0, 0, 0],    -6.38e-6, -0.05e-6),
+    term([ 1, 0, 0, 0, 0, 0, 0, 0],    -3.07e-6,  0.00e-6),
+    term([ 0, 1, 2,-2, 2, 0, 0, 0],     2.23e-6,  0.00e-6),
+    term([ 0, 0, 2, 0, 1, 0, 0, 0],     1.67e-6,  0.00e-6),
+    term([ 1, 0, 2, 0, 2, 0, 0, 0],     1.30e-6,  0.00e-6),
+    term([ 0, 1,-2, 2,-2, 0, 0, 0],     0.93e-6,  0.00e-6),
+    // 11-20
+    term([ 1, 0, 0,-2, 0, 0, 0, 0],     0.68e-6,  0.00e-6),
+    term([ 0, 0, 2,-2, 1, 0, 0, 0],    -0.55e-6,  0.00e-6),
+    term([ 1, 0,-2, 0,-2, 0, 0, 0],     0.53e-6,  0.00e-6),
+    term([ 0, 0, 0, 2, 0, 0, 0, 0],    -0.27e-6,  0.00e-6),
+    term([ 1, 0, 0, 0, 1, 0, 0, 0],    -0.27e-6,  0.00e-6),
+    term([ 1, 0,-2,-2,-2, 0, 0, 0],    -0.26e-6,  0.00e-6),
+    term([ 1, 0, 0, 0,-1, 0, 0, 0],    -0.25e-6,  0.00e-6),
+    term([ 1, 0, 2, 0, 1, 0, 0, 0],     0.22e-6,  0.00e-6),
+    term([ 2, 0, 0,-2, 0, 0, 0, 0],    -0.21e-6,  0.00e-6),
+    term([ 2, 0,-2, 0,-1, 0, 0, 0],     0.20e-6,  0.00e-6),
+    // 21-25
+    term([ 0, 0, 2, 2, 2, 0, 0, 0],     0.17e-6,  0.00e-6),
+    term([ 2, 0, 2, 0, 2, 0, 0, 0],     0.13e-6,  0.00e-6),
+    term([ 2, 0, 0, 0, 0, 0, 0, 0],    -0.13e-6,  0.00e-6),
+    term([ 1, 0, 2,-2, 2, 0, 0, 0],    -0.12e-6,  0.00e-6),
+    term([ 0, 0, 2, 0, 0, 0, 0, 0],    -0.11e-6,  0.00e-6),
+];
+
+#[rustfmt::skip]
+pub(crate) const S3: [Term; 4] = [
+    term([ 0, 0, 0, 0, 1, 0, 0, 0],     0.30e-6, -23.42e-6),
+    term([ 0, 0, 2,-2, 2, 0, 0, 0],    -0.03e-6,  -1.46e-6),
+    term([ 0, 0, 2, 0, 2, 0, 0, 0],    -0.01e-6,  -0.25e-6),
+    term([ 0, 0, 0, 0, 2, 0, 0, 0],     0.00e-6,   0.23e-6),
+];
+
+#[rustfmt::skip]
+pub(crate) const S4: [Term; 1] = [
+    term([ 0, 0, 0, 0, 1, 0, 0, 0],    -0.26e-6,  -0.01e-6),
+];
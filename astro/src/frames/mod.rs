@@ -0,0 +1,245 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Rotation matrices from the GCRS to the intermediate frames of the IAU 2006/2000 system,
+//! following the IERS Conventions (2010) ch. 5:
+//!
+//! - mean of date (MOD): frame bias and precession,
+//! - true of date (TOD): plus nutation, equinox-based,
+//! - CIRS: the celestial intermediate reference system, CIO-based,
+//! - TIRS: CIRS turned by the Earth rotation angle.
+//!
+//! Nutation is passed in as the caller's choice of [`crate::nutation::iau2000b`] or
+//! [`crate::nutation::Iau2000a`]; it is adjusted for IAU 2006 precession here.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+//? use serde::{Deserialize, Serialize};
+
+use crate::angle::{norm_0_tau, ARCSEC_TO_RAD};
+use crate::julian::{julian_centuries_since_j2000, J2000};
+use crate::nutation::{fundamental_arguments, Nutation};
+use crate::precession::{bias_precession, mean_obliquity_2006, FukushimaWilliams};
+use crate::rotation::{mul, rx, ry, rz, Matrix3, IDENTITY};
+
+mod cio_locator;
+
+/// GCRS to mean equator and equinox of date.
+#[must_use]
+pub fn gcrs_to_mod(jd_tt: f64) -> Matrix3 {
+    bias_precession(jd_tt)
+}
+
+/// Mean of date to true of date.
+#[must_use]
+pub fn nutation_matrix(jd_tt: f64, nut: Nutation) -> Matrix3 {
+    let nut = nut.iau2006_adjusted(jd_tt);
+    let epsa = mean_obliquity_2006(jd_tt);
+    mul(&rx(-(epsa + nut.deps)), &mul(&rz(-nut.dpsi), &rx(epsa)))
+}
+
+/// GCRS to true equator and equinox of date: the bias-precession-nutation matrix.
+#[must_use]
+pub fn gcrs_to_tod(jd_tt: f64, nut: Nutation) -> Matrix3 {
+    FukushimaWilliams::iau2006(jd_tt).matrix_with_nutation(nut.iau2006_adjusted(jd_tt))
+}
+
+/// The GCRS coordinates of the celestial intermediate pole, from the bottom row of the
+/// bias-precession-nutation matrix.
+#[must_use]
+pub fn cip_xy(gcrs_to_tod: &Matrix3) -> (f64, f64) {
+    (gcrs_to_tod[2][0], gcrs_to_tod[2][1])
+}
+
+/// The CIO locator `s`, radians, given the CIP coordinates. IAU 2006/2000A, as SOFA's `iauS06`.
+#[must_use]
+pub fn cio_locator_s06(jd_tt: f64, x: f64, y: f64) -> f64 {
+    use cio_locator::{Term, S0, S1, S2, S3, S4, SP};
+
+    let T = julian_centuries_since_j2000(jd_tt);
+    let fa = fundamental_arguments(T);
+    let fa = [fa[0], fa[1], fa[2], fa[3], fa[4], fa[6], fa[7], fa[13]];
+
+    // Smallest terms first.
+    let sum = |terms: &[Term]| -> f64 {
+        terms
+            .iter()
+            .rev()
+            .map(|term| {
+                let a: f64 = term
+                    .nfa
+                    .iter()
+                    .zip(fa)
+                    .map(|(&n, a)| f64::from(n) * a)
+                    .sum();
+                let (sin, cos) = a.sin_cos();
+                term.s * sin + term.c * cos
+            })
+            .sum()
+    };
+    let w = [
+        SP[0] + sum(&S0),
+        SP[1] + sum(&S1),
+        SP[2] + sum(&S2),
+        SP[3] + sum(&S3),
+        SP[4] + sum(&S4),
+        SP[5],
+    ];
+    let poly = w.iter().rev().fold(0.0, |acc, &w_j| acc * T + w_j);
+    poly * ARCSEC_TO_RAD - x * y / 2.0
+}
+
+/// GCRS to CIRS, given the CIP coordinates and CIO locator, as SOFA's `iauC2ixys`.
+#[must_use]
+pub fn gcrs_to_cirs_xys(x: f64, y: f64, s: f64) -> Matrix3 {
+    let r2 = x * x + y * y;
+    let e = if 0.0 < r2 { y.atan2(x) } else { 0.0 };
+    let d = (r2 / (1.0 - r2)).sqrt().atan();
+    mul(&rz(-(e + s)), &mul(&ry(d), &rz(e)))
+}
+
+/// GCRS to CIRS.
+#[must_use]
+pub fn gcrs_to_cirs(jd_tt: f64, nut: Nutation) -> Matrix3 {
+    let (x, y) = cip_xy(&gcrs_to_tod(jd_tt, nut));
+    gcrs_to_cirs_xys(x, y, cio_locator_s06(jd_tt, x, y))
+}
+
+/// Earth rotation angle, radians in `[0, tau)`, IAU 2000.
+#[must_use]
+pub fn earth_rotation_angle(jd_ut1: f64) -> f64 {
+    let t = jd_ut1 - J2000;
+    // Fractional part of the JD, separately, to keep precision.
+    let f = jd_ut1.rem_euclid(1.0);
+    norm_0_tau(std::f64::consts::TAU * (f + 0.7790572732640 + 0.00273781191135448 * t))
+}
+
+/// Greenwich mean sidereal time, radians in `[0, tau)`, consistent with IAU 2006 precession.
+#[must_use]
+pub fn gmst06(jd_ut1: f64, jd_tt: f64) -> f64 {
+    let T = julian_centuries_since_j2000(jd_tt);
+    let poly = 0.014506
+        + T * (4612.156534
+            + T * (1.3915817 + T * (-0.00000044 + T * (-0.000029956 + T * -0.0000000368))));
+    norm_0_tau(earth_rotation_angle(jd_ut1) + poly * ARCSEC_TO_RAD)
+}
+
+/// The equation of the origins, the angle from the CIO to the equinox along the CIP equator,
+/// as SOFA's `iauEors`.
+#[must_use]
+pub fn equation_of_origins(gcrs_to_tod: &Matrix3, s: f64) -> f64 {
+    let r = gcrs_to_tod;
+    let x = r[2][0];
+    let ax = x / (1.0 + r[2][2]);
+    let xs = 1.0 - ax * x;
+    let ys = -ax * r[2][1];
+    let zs = -x;
+    let p = r[0][0] * xs + r[0][1] * ys + r[0][2] * zs;
+    let q = r[1][0] * xs + r[1][1] * ys + r[1][2] * zs;
+    if p != 0.0 || q != 0.0 {
+        s - q.atan2(p)
+    } else {
+        s
+    }
+}
+
+/// Greenwich apparent sidereal time, radians in `[0, tau)`.
+#[must_use]
+pub fn gast(jd_ut1: f64, jd_tt: f64, nut: Nutation) -> f64 {
+    let npb = gcrs_to_tod(jd_tt, nut);
+    let (x, y) = cip_xy(&npb);
+    let s = cio_locator_s06(jd_tt, x, y);
+    norm_0_tau(earth_rotation_angle(jd_ut1) - equation_of_origins(&npb, s))
+}
+
+/// GCRS to TIRS. Polar motion, which would carry on to the ITRS, is not applied.
+#[must_use]
+pub fn gcrs_to_tirs(jd_tt: f64, jd_ut1: f64, nut: Nutation) -> Matrix3 {
+    mul(&rz(earth_rotation_angle(jd_ut1)), &gcrs_to_cirs(jd_tt, nut))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+#[allow(clippy::excessive_precision)] // SOFA test values, verbatim.
+mod t {
+    use super::*;
+    use crate::nutation::iau2000b;
+    use crate::rotation::max_abs_diff;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        // SOFA t_era00, t_gmst06.
+        let jd = 2_400_000.5 + 54388.0;
+        assert!((earth_rotation_angle(jd) - 0.4022837240028158102).abs() < 1e-12);
+        let jd = 2_400_000.5 + 53736.0;
+        assert!((gmst06(jd, jd) - 1.754174971870091203).abs() < 1e-12);
+
+        // SOFA t_gst06a is 1.754166137675019159, with the full 2000A nutation.
+        let nut = iau2000b(jd);
+        assert!((gast(jd, jd, nut) - 1.754166137675019159).abs() < 1e-8);
+
+        // SOFA t_s06.
+        let s = cio_locator_s06(jd, 0.5791308486706011000e-3, 0.4020579816732961219e-4);
+        assert!((s - -0.1220032213076463117e-7).abs() < 1e-15);
+
+        // SOFA t_c2ixys.
+        let rc2i = gcrs_to_cirs_xys(
+            0.5791308486706011000e-3,
+            0.4020579816732961219e-4,
+            -0.1220040848472271978e-7,
+        );
+        let expected = [
+            [
+                0.9999998323037157138,
+                0.5581984869168499149e-9,
+                -0.5791308491611282180e-3,
+            ],
+            [
+                -0.2384261642670440317e-7,
+                0.9999999991917468964,
+                -0.4020579110169668931e-4,
+            ],
+            [
+                0.5791308486706011000e-3,
+                0.4020579816732961219e-4,
+                0.9999998314954627590,
+            ],
+        ];
+        assert!(max_abs_diff(&rc2i, &expected) < 1e-12);
+
+        // The equinox and CIO routes to the terrestrial frame agree.
+        let via_tod = mul(&rz(gast(jd, jd, nut)), &gcrs_to_tod(jd, nut));
+        assert!(max_abs_diff(&gcrs_to_tirs(jd, jd, nut), &via_tod) < 1e-14);
+
+        // TOD is MOD plus nutation.
+        let tod = mul(&nutation_matrix(jd, nut), &gcrs_to_mod(jd));
+        assert!(max_abs_diff(&tod, &gcrs_to_tod(jd, nut)) < 1e-14);
+
+        // The CIP is where the TOD pole is, to within the 2000B error.
+        let (x, y) = cip_xy(&gcrs_to_tod(jd, nut));
+        assert!((x - 0.5791308486706011000e-3).abs() < 1e-8);
+        assert!((y - 0.4020579816732961219e-4).abs() < 1e-8);
+    }
+}
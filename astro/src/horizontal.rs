@@ -0,0 +1,177 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::angle::{norm_0_360, norm_0_tau};
+use crate::julian::{julian_centuries_since_j2000, J2000};
+
+/// A place on the Earth from which the sky is observed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Observer {
+    /// Geodetic latitude, degrees, north positive.
+    pub lat_deg: f64,
+
+    /// Longitude, degrees, east positive.
+    pub lon_deg: f64,
+
+    /// Height above the ellipsoid, meters.
+    pub height_m: f64,
+}
+
+impl Observer {
+    pub fn new(lat_deg: f64, lon_deg: f64, height_m: f64) -> Self {
+        Self {
+            lat_deg,
+            lon_deg,
+            height_m,
+        }
+    }
+
+    /// Earth-fixed (ITRS) position on the WGS84 ellipsoid, km.
+    #[must_use]
+    pub fn itrs_position_km(&self) -> [f64; 3] {
+        const A_KM: f64 = 6378.137;
+        const F: f64 = 1.0 / 298.257223563;
+        let e2 = F * (2.0 - F);
+        let (sin_lat, cos_lat) = self.lat_deg.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = self.lon_deg.to_radians().sin_cos();
+        let n = A_KM / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        let h = self.height_m / 1000.0;
+        [
+            (n + h) * cos_lat * cos_lon,
+            (n + h) * cos_lat * sin_lon,
+            (n * (1.0 - e2) + h) * sin_lat,
+        ]
+    }
+}
+
+/// Local horizontal coordinates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Horizontal {
+    /// Azimuth, radians in `[0, tau)`, measured from north through east.
+    pub azimuth: f64,
+
+    /// Elevation (altitude) above the horizon, radians.
+    pub elevation: f64,
+}
+
+impl Horizontal {
+    pub fn azimuth_deg(&self) -> f64 {
+        self.azimuth.to_degrees()
+    }
+
+    pub fn elevation_deg(&self) -> f64 {
+        self.elevation.to_degrees()
+    }
+}
+
+/// Greenwich mean sidereal time, radians, for a UT1 Julian date.
+///
+/// Meeus, "Astronomical Algorithms" 2nd ed., eq. 12.4.
+#[must_use]
+pub fn gmst(jd_ut1: f64) -> f64 {
+    let T = julian_centuries_since_j2000(jd_ut1);
+    let deg = 280.46061837
+        + 360.98564736629 * (jd_ut1 - J2000)
+        + T * T * (0.000387933 - T / 38_710_000.0);
+    norm_0_360(deg).to_radians()
+}
+
+/// Local mean sidereal time, radians.
+#[must_use]
+pub fn local_sidereal_time(jd_ut1: f64, observer: &Observer) -> f64 {
+    norm_0_tau(gmst(jd_ut1) + observer.lon_deg.to_radians())
+}
+
+/// Converts equatorial coordinates of date to horizontal coordinates, given the local sidereal
+/// time and the observer's latitude. No refraction is applied.
+#[must_use]
+pub fn equatorial_to_horizontal(ra: f64, dec: f64, lst: f64, lat: f64) -> Horizontal {
+    let hour_angle = lst - ra;
+    let (sin_h, cos_h) = hour_angle.sin_cos();
+    let (sin_dec, cos_dec) = dec.sin_cos();
+    let (sin_lat, cos_lat) = lat.sin_cos();
+
+    let elevation = (sin_lat * sin_dec + cos_lat * cos_dec * cos_h).asin();
+    let azimuth = (-cos_dec * sin_h).atan2(sin_dec * cos_lat - cos_dec * sin_lat * cos_h);
+
+    Horizontal {
+        azimuth: norm_0_tau(azimuth),
+        elevation,
+    }
+}
+
+/// Topocentric elevation, radians, from the geocentric elevation of a body with the given
+/// equatorial horizontal parallax. Ignores the flattening, which is good to a few arcseconds
+/// for the Moon.
+#[must_use]
+pub fn elevation_with_parallax(elevation: f64, horizontal_parallax: f64) -> f64 {
+    elevation - (horizontal_parallax.sin() * elevation.cos()).asin()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    fn round_to(x: f64, places: i32) -> f64 {
+        let k = 10f64.powi(places);
+        (x * k).round() / k
+    }
+
+    #[test]
+    fn t() {
+        // Meeus examples 12.a and 12.b.
+        assert_ron_snapshot!(round_to(gmst(2_446_895.5).to_degrees(), 6), @"197.693195");
+        let jd = 2_446_895.5 + (19.0 + 21.0 / 60.0) / 24.0;
+        assert_ron_snapshot!(round_to(gmst(jd).to_degrees(), 6), @"128.737873");
+
+        // Meeus example 13.b, Venus seen from the US Naval Observatory.
+        let observer = Observer::new(
+            38.0 + 55.0 / 60.0 + 17.0 / 3600.0,
+            -(77.0 + 3.0 / 60.0 + 56.0 / 3600.0),
+            0.0,
+        );
+        let lst = local_sidereal_time(jd, &observer);
+        let hor = equatorial_to_horizontal(
+            347.3193375_f64.to_radians(),
+            (-6.719892_f64).to_radians(),
+            lst,
+            observer.lat_deg.to_radians(),
+        );
+        // Meeus measures azimuth from the south: 68.0337°, and uses apparent sidereal time.
+        assert_ron_snapshot!(round_to(hor.azimuth_deg(), 2), @"248.03");
+        assert_ron_snapshot!(round_to(hor.elevation_deg(), 2), @"15.12");
+
+        // The Moon on the horizon is about a degree lower than seen from the geocenter, and at
+        // the zenith it's not displaced at all.
+        let hp = 0.95_f64.to_radians();
+        assert_ron_snapshot!(round_to(elevation_with_parallax(0.0, hp).to_degrees(), 3), @"-0.95");
+        assert_ron_snapshot!(round_to(elevation_with_parallax(std::f64::consts::FRAC_PI_2, hp).to_degrees(), 3), @"90.0");
+    }
+}
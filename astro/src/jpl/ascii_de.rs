@@ -0,0 +1,448 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! The ASCII distribution of the JPL DE ephemerides: a header file (`header.440`) and data
+//! files (`ascp01550.440`, ...) of Chebyshev coefficients with Fortran `D` exponents.
+//!
+//! Reference: ftp://ssd.jpl.nasa.gov/pub/eph/planets/ascii/ and the "JPL Planetary and Lunar
+//! Ephemerides" export documentation.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::ephemeris::{Ephemeris, NaifId, StateVector};
+use crate::jpl::chebyshev;
+use crate::julian::SECONDS_PER_DAY;
+
+/// Rows of the group 1050 pointer table, by position.
+const MERCURY: usize = 0;
+const VENUS: usize = 1;
+const EMB: usize = 2;
+const MARS: usize = 3;
+const JUPITER: usize = 4;
+const SATURN: usize = 5;
+const URANUS: usize = 6;
+const NEPTUNE: usize = 7;
+const PLUTO: usize = 8;
+const MOON_GEOCENTRIC: usize = 9;
+const SUN: usize = 10;
+
+/// Where one body's coefficients are within a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CoeffPointer {
+    /// 1-based offset of the first coefficient within a record.
+    pub offset: usize,
+
+    /// Coefficients per component per sub-interval.
+    pub ncoef: usize,
+
+    /// Sub-intervals per record.
+    pub nsub: usize,
+}
+
+/// A DE ephemeris loaded from the ASCII files.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AsciiDe {
+    pub title: String,
+    pub days_per_record: f64,
+    pub constants: Vec<(String, f64)>,
+    pub pointers: Vec<CoeffPointer>,
+
+    /// Each record starts with its own start and end Julian dates.
+    records: Vec<Vec<f64>>,
+}
+
+fn parse_fortran_f64(s: &str) -> Result<f64> {
+    s.replace(['D', 'd'], "E")
+        .parse::<f64>()
+        .with_context(|| format!("bad number '{s}'"))
+}
+
+impl AsciiDe {
+    /// Parses the header file and any number of data files, which may be given in any order
+    /// and may overlap.
+    pub fn parse(header: &str, data_files: &[&str]) -> Result<Self> {
+        let mut groups: Vec<(u32, Vec<&str>)> = vec![];
+        for line in header.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["GROUP", n] => groups.push((n.parse()?, vec![])),
+                _ => {
+                    if let Some((_, lines)) = groups.last_mut() {
+                        lines.push(line);
+                    }
+                }
+            }
+        }
+        let group = |n: u32| -> Result<&[&str]> {
+            groups
+                .iter()
+                .find(|(g, _)| *g == n)
+                .map(|(_, lines)| lines.as_slice())
+                .ok_or_else(|| anyhow!("header has no GROUP {n}"))
+        };
+        let tokens = |n: u32| -> Result<Vec<&str>> {
+            Ok(group(n)?
+                .iter()
+                .flat_map(|line| line.split_whitespace())
+                .collect())
+        };
+
+        let title = group(1010)?
+            .iter()
+            .map(|s| s.trim())
+            .find(|s| !s.is_empty())
+            .unwrap_or_default()
+            .to_string();
+
+        let g1030 = tokens(1030)?;
+        ensure!(g1030.len() == 3, "GROUP 1030 should have 3 numbers");
+        let days_per_record = parse_fortran_f64(g1030[2])?;
+        ensure!(0.0 < days_per_record, "bad record length");
+
+        let g1040 = tokens(1040)?;
+        let g1041 = tokens(1041)?;
+        let n_names: usize = g1040
+            .first()
+            .ok_or_else(|| anyhow!("empty GROUP 1040"))?
+            .parse()?;
+        let n_values: usize = g1041
+            .first()
+            .ok_or_else(|| anyhow!("empty GROUP 1041"))?
+            .parse()?;
+        ensure!(
+            n_names == n_values && n_names < g1040.len() && n_values < g1041.len(),
+            "GROUP 1040 and 1041 disagree"
+        );
+        let constants = g1040[1..=n_names]
+            .iter()
+            .zip(&g1041[1..=n_values])
+            .map(|(name, value)| Ok((name.to_string(), parse_fortran_f64(value)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let g1050: Vec<usize> = tokens(1050)?
+            .iter()
+            .map(|s| s.parse::<usize>())
+            .collect::<Result<_, _>>()?;
+        ensure!(
+            !g1050.is_empty() && g1050.len() % 3 == 0,
+            "GROUP 1050 should have three equal rows"
+        );
+        let cols = g1050.len() / 3;
+        let pointers = (0..cols)
+            .map(|c| CoeffPointer {
+                offset: g1050[c],
+                ncoef: g1050[cols + c],
+                nsub: g1050[2 * cols + c],
+            })
+            .collect::<Vec<_>>();
+        ensure!(
+            pointers.iter().all(|p| 0 < p.offset || p.ncoef == 0),
+            "GROUP 1050 offsets start at 1"
+        );
+
+        let mut self_ = Self {
+            title,
+            days_per_record,
+            constants,
+            pointers,
+            records: vec![],
+        };
+        for data in data_files {
+            self_.add_data(data)?;
+        }
+        Ok(self_)
+    }
+
+    /// Adds the records of one data file.
+    pub fn add_data(&mut self, data: &str) -> Result<()> {
+        let mut tokens = data.split_whitespace();
+        while let Some(block) = tokens.next() {
+            let block_no: usize = block
+                .parse()
+                .with_context(|| format!("bad block number '{block}'"))?;
+            let ncoeff: usize = tokens
+                .next()
+                .ok_or_else(|| anyhow!("block {block_no}: missing coefficient count"))?
+                .parse()?;
+            let record = (0..ncoeff)
+                .map(|_| {
+                    let s = tokens
+                        .next()
+                        .ok_or_else(|| anyhow!("block {block_no}: ends early"))?;
+                    parse_fortran_f64(s)
+                })
+                .collect::<Result<Vec<f64>>>()?;
+            ensure!(2 <= record.len(), "block {block_no}: no dates");
+
+            // Data files repeat the last record of the previous file.
+            if !self.records.iter().any(|r| r[0] == record[0]) {
+                self.records.push(record);
+            }
+
+            // Data files pad the last line of a block with zeros up to a multiple of three.
+            for _ in 0..(3 - ncoeff % 3) % 3 {
+                tokens.next();
+            }
+        }
+        self.records.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        Ok(())
+    }
+
+    pub fn constant(&self, name: &str) -> Option<f64> {
+        self.constants
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| *v)
+    }
+
+    /// Julian dates covered by the loaded records.
+    pub fn coverage(&self) -> Option<(f64, f64)> {
+        Some((self.records.first()?[0], self.records.last()?[1]))
+    }
+
+    /// Position (km) and velocity (km/day) of a row of the pointer table.
+    fn interpolate(&self, body_ix: usize, jd_tdb: f64) -> Result<([f64; 3], [f64; 3])> {
+        let p = self
+            .pointers
+            .get(body_ix)
+            .filter(|p| 0 < p.ncoef && 0 < p.nsub)
+            .ok_or_else(|| anyhow!("{}: no coefficients for body {body_ix}", self.title))?;
+
+        let (start, end) = self.coverage().ok_or_else(|| anyhow!("no data loaded"))?;
+        ensure!(
+            start <= jd_tdb && jd_tdb <= end,
+            "JD {jd_tdb} outside {start}..{end}"
+        );
+        let record = self
+            .records
+            .iter()
+            .find(|r| r[0] <= jd_tdb && jd_tdb <= r[1])
+            .ok_or_else(|| anyhow!("no record covers JD {jd_tdb}"))?;
+
+        let frac = (jd_tdb - record[0]) / self.days_per_record;
+        let sub = ((frac * p.nsub as f64).floor() as usize).min(p.nsub - 1);
+        let sub_days = self.days_per_record / p.nsub as f64;
+        let sub_start = record[0] + sub as f64 * sub_days;
+        let s = 2.0 * (jd_tdb - sub_start) / sub_days - 1.0;
+
+        let mut pos = [0.0; 3];
+        let mut vel = [0.0; 3];
+        for i in 0..3 {
+            let range = || {
+                let block = sub.checked_mul(3)?.checked_add(i)?;
+                let first = block.checked_mul(p.ncoef)?.checked_add(p.offset - 1)?;
+                Some(first..first.checked_add(p.ncoef)?)
+            };
+            let coeffs = range()
+                .and_then(|range| record.get(range))
+                .ok_or_else(|| anyhow!("record too short for body {body_ix}"))?;
+            let (x, dx_ds) = chebyshev(coeffs, s);
+            pos[i] = x;
+            vel[i] = dx_ds * 2.0 / sub_days;
+        }
+        Ok((pos, vel))
+    }
+
+    fn state_of_row(&self, body_ix: usize, jd_tdb: f64) -> Result<StateVector> {
+        let (pos, vel_per_day) = self.interpolate(body_ix, jd_tdb)?;
+        Ok(StateVector {
+            position_km: pos,
+            velocity_km_s: vel_per_day.map(|v| v / SECONDS_PER_DAY),
+        })
+    }
+
+    /// State relative to the solar system barycenter, ICRF.
+    fn state_wrt_ssb(&self, id: NaifId, jd_tdb: f64) -> Result<StateVector> {
+        let row = match id {
+            NaifId::SOLAR_SYSTEM_BARYCENTER => return Ok(StateVector::default()),
+            NaifId::MERCURY | NaifId::MERCURY_BARYCENTER => MERCURY,
+            NaifId::VENUS | NaifId::VENUS_BARYCENTER => VENUS,
+            NaifId::EARTH_MOON_BARYCENTER => EMB,
+            NaifId::MARS_BARYCENTER => MARS,
+            NaifId::JUPITER_BARYCENTER => JUPITER,
+            NaifId::SATURN_BARYCENTER => SATURN,
+            NaifId::URANUS_BARYCENTER => URANUS,
+            NaifId::NEPTUNE_BARYCENTER => NEPTUNE,
+            NaifId::PLUTO_BARYCENTER => PLUTO,
+            NaifId::SUN => SUN,
+            NaifId::EARTH | NaifId::MOON => {
+                let emrat = self
+                    .constant("EMRAT")
+                    .ok_or_else(|| anyhow!("header has no EMRAT"))?;
+                let emb = self.state_of_row(EMB, jd_tdb)?;
+                let moon_geo = self.state_of_row(MOON_GEOCENTRIC, jd_tdb)?;
+                let earth = emb.sub(&moon_geo.scale(1.0 / (1.0 + emrat)));
+                return Ok(if id == NaifId::EARTH {
+                    earth
+                } else {
+                    earth.add(&moon_geo)
+                });
+            }
+            _ => bail!("{}: no data for NAIF ID {}", self.title, id.0),
+        };
+        self.state_of_row(row, jd_tdb)
+    }
+}
+
+impl Ephemeris for AsciiDe {
+    fn state(&self, target: NaifId, center: NaifId, jd_tdb: f64) -> Result<StateVector> {
+        Ok(self
+            .state_wrt_ssb(target, jd_tdb)?
+            .sub(&self.state_wrt_ssb(center, jd_tdb)?))
+    }
+
+    fn bodies(&self) -> Vec<NaifId> {
+        let has = |ix: usize| self.pointers.get(ix).is_some_and(|p| 0 < p.ncoef);
+        let rows = [
+            (MERCURY, NaifId::MERCURY),
+            (VENUS, NaifId::VENUS),
+            (EMB, NaifId::EARTH_MOON_BARYCENTER),
+            (MARS, NaifId::MARS_BARYCENTER),
+            (JUPITER, NaifId::JUPITER_BARYCENTER),
+            (SATURN, NaifId::SATURN_BARYCENTER),
+            (URANUS, NaifId::URANUS_BARYCENTER),
+            (NEPTUNE, NaifId::NEPTUNE_BARYCENTER),
+            (PLUTO, NaifId::PLUTO_BARYCENTER),
+            (SUN, NaifId::SUN),
+        ];
+        let mut v = vec![NaifId::SOLAR_SYSTEM_BARYCENTER];
+        v.extend(rows.iter().filter(|(ix, _)| has(*ix)).map(|(_, id)| *id));
+        if has(EMB) && has(MOON_GEOCENTRIC) {
+            v.extend([NaifId::EARTH, NaifId::MOON]);
+        }
+        v.sort();
+        v
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    // A made-up ephemeris in the DE format: 32-day records, with the EMB (2 coefficients,
+    // 1 sub-interval), the Moon (2 coefficients, 2 sub-intervals) and the Sun (1 coefficient).
+    const HEADER: &str = "KSIZE=    46    NCOEFF=    23
+
+GROUP   1010
+
+JPL Planetary Ephemeris DE999/LE999 (test)
+Start Epoch: JED=   2451536.5
+Final Epoch: JED=   2451600.5
+
+GROUP   1030
+
+  2451536.50  2451600.50         32.
+
+GROUP   1040
+
+     2
+  DENUM   EMRAT
+
+GROUP   1041
+
+     2
+  0.999000000000000000D+03  0.800000000000000000D+02
+
+GROUP   1050
+
+     0     0     3     0     0     0     0     0     0     9    21
+     0     0     2     0     0     0     0     0     0     2     1
+     0     0     1     0     0     0     0     0     0     2     1
+";
+
+    const DATA_1: &str = "     1    23
+  0.245153650000000000D+07  0.245156850000000000D+07 -0.260000000000000000D+08
+  0.160000000000000000D+04  0.130000000000000000D+09  0.320000000000000000D+03
+  0.560000000000000000D+08  0.000000000000000000D+00  0.324000000000000000D+06
+  0.000000000000000000D+00  0.000000000000000000D+00  0.000000000000000000D+00
+  0.000000000000000000D+00  0.000000000000000000D+00  0.000000000000000000D+00
+  0.000000000000000000D+00  0.384000000000000000D+06  0.000000000000000000D+00
+  0.000000000000000000D+00  0.000000000000000000D+00 -0.100000000000000000D+07
+ -0.400000000000000000D+06 -0.100000000000000000D+06  0.000000000000000000D+00
+";
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let de = AsciiDe::parse(HEADER, &[DATA_1, DATA_1])?;
+        assert_ron_snapshot!(de.title, @r###""JPL Planetary Ephemeris DE999/LE999 (test)""###);
+        assert_ron_snapshot!(de.constant("EMRAT"), @"Some(80.0)");
+        assert_ron_snapshot!(de.coverage(), @"Some((2451536.5, 2451568.5))");
+        assert_eq!(
+            de.bodies(),
+            [
+                NaifId::SOLAR_SYSTEM_BARYCENTER,
+                NaifId::EARTH_MOON_BARYCENTER,
+                NaifId::SUN,
+                NaifId::MOON,
+                NaifId::EARTH,
+            ]
+        );
+
+        // The middle of the record, where s = 0.
+        let jd = 2_451_552.5;
+        let emb = de.state(
+            NaifId::EARTH_MOON_BARYCENTER,
+            NaifId::SOLAR_SYSTEM_BARYCENTER,
+            jd,
+        )?;
+        assert_eq!(emb.position_km, [-2.6e7, 1.3e8, 5.6e7]);
+        // dx/ds = 1600 km over a 16-day half-record is 100 km/day.
+        assert!((emb.velocity_km_s[0] - 100.0 / SECONDS_PER_DAY).abs() < 1e-12);
+
+        // The Moon's first sub-interval has only x = 324000 km, its second only y = 384000 km.
+        let moon = de.state(NaifId::MOON, NaifId::EARTH, jd - 1.0)?;
+        assert_eq!(moon.position_km, [324000.0, 0.0, 0.0]);
+        let moon = de.state(NaifId::MOON, NaifId::EARTH, jd + 1.0)?;
+        assert_eq!(moon.position_km, [0.0, 384000.0, 0.0]);
+
+        // The Earth sits 1/81 of the way from the EMB toward the anti-Moon.
+        let earth = de.state(NaifId::EARTH, NaifId::EARTH_MOON_BARYCENTER, jd - 1.0)?;
+        assert_ron_snapshot!(earth.position_km, @"(-4000.0, 0.0, 0.0)");
+
+        let sun = de.state(NaifId::SUN, NaifId::SOLAR_SYSTEM_BARYCENTER, jd)?;
+        assert_eq!(sun.position_km, [-1.0e6, -4.0e5, -1.0e5]);
+
+        assert!(de.state(NaifId::MARS_BARYCENTER, NaifId::SUN, jd).is_err());
+        assert!(de.state(NaifId::SUN, NaifId::EARTH, 2_451_600.0).is_err());
+
+        // Pointers past the end of a record, and an offset before its start.
+        let far = HEADER.replace("     9    21\n", "     9 999999999999\n");
+        let de = AsciiDe::parse(&far, &[DATA_1])?;
+        assert!(de
+            .state(NaifId::SUN, NaifId::SOLAR_SYSTEM_BARYCENTER, jd)
+            .is_err());
+        let huge = HEADER.replacen("     2     1\n", &format!("     2 {}\n", usize::MAX), 1);
+        let de = AsciiDe::parse(&huge, &[DATA_1])?;
+        assert!(de
+            .state(NaifId::SUN, NaifId::SOLAR_SYSTEM_BARYCENTER, jd)
+            .is_err());
+        let zero = HEADER.replace("     9    21\n", "     9     0\n");
+        assert!(AsciiDe::parse(&zero, &[DATA_1]).is_err());
+        Ok(())
+    }
+}
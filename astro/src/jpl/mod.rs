@@ -0,0 +1,83 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! JPL development ephemerides (DE405, DE440, ...), read from binary SPK kernels or from the
+//! original ASCII distribution. Both store each coordinate as Chebyshev polynomials over
+//! fixed-length time intervals.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+//? use serde::{Deserialize, Serialize};
+
+pub mod ascii_de;
+pub use crate::jpl::ascii_de::AsciiDe;
+
+pub mod spk;
+pub use crate::jpl::spk::Spk;
+
+//=================================================================================================|
+
+/// Evaluates `sum c[k] T_k(s)` and its derivative with respect to `s`, for `s` in `[-1, 1]`.
+#[must_use]
+pub fn chebyshev(coeffs: &[f64], s: f64) -> (f64, f64) {
+    let (mut t_prev, mut t_cur) = (1.0, s);
+    let (mut dt_prev, mut dt_cur) = (0.0, 1.0);
+
+    let mut value = coeffs.first().copied().unwrap_or_default();
+    let mut derivative = 0.0;
+    if let Some(&c1) = coeffs.get(1) {
+        value += c1 * t_cur;
+        derivative += c1 * dt_cur;
+    }
+
+    for &c in coeffs.iter().skip(2) {
+        let t_next = 2.0 * s * t_cur - t_prev;
+        let dt_next = 2.0 * t_cur + 2.0 * s * dt_cur - dt_prev;
+        value += c * t_next;
+        derivative += c * dt_next;
+        (t_prev, t_cur) = (t_cur, t_next);
+        (dt_prev, dt_cur) = (dt_cur, dt_next);
+    }
+
+    (value, derivative)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        // T_0 + 2 T_1 + 3 T_2 = 1 + 2s + 3(2s² - 1) = 6s² + 2s - 2
+        let c = [1.0, 2.0, 3.0];
+        assert_ron_snapshot!(chebyshev(&c, 0.5), @"(0.5, 8.0)");
+        assert_ron_snapshot!(chebyshev(&c, -1.0), @"(2.0, -10.0)");
+
+        // T_3 = 4s³ - 3s, T_3' = 12s² - 3
+        assert_ron_snapshot!(chebyshev(&[0.0, 0.0, 0.0, 1.0], 0.5), @"(-1.0, 0.0)");
+        assert_ron_snapshot!(chebyshev(&[], 0.5), @"(0.0, 0.0)");
+    }
+}
@@ -0,0 +1,581 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! NAIF DAF/SPK binary kernels (`.bsp`), segment types 2 and 3 (Chebyshev position only, and
+//! Chebyshev position and velocity). These are what JPL distributes DE430, DE440 etc. as.
+//!
+//! Reference: NAIF "DAF Required Reading" and "SPK Required Reading".
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::ephemeris::{Ephemeris, NaifId, StateVector};
+use crate::jpl::chebyshev;
+use crate::julian::{J2000, SECONDS_PER_DAY};
+
+/// Bytes per DAF record.
+const RECORD_LEN: usize = 1024;
+
+/// Seconds past J2000 TDB ("ephemeris time"), the time argument of SPK files.
+#[must_use]
+pub fn et_from_jd_tdb(jd_tdb: f64) -> f64 {
+    (jd_tdb - J2000) * SECONDS_PER_DAY
+}
+
+/// SPK reference frame codes we understand.
+const FRAME_J2000: i32 = 1;
+const FRAME_ECLIPJ2000: i32 = 17;
+
+/// One segment summary.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SpkSegment {
+    pub name: String,
+    pub target: NaifId,
+    pub center: NaifId,
+    pub frame: i32,
+    pub data_type: i32,
+
+    /// Coverage, seconds past J2000 TDB.
+    pub start_et: f64,
+    pub end_et: f64,
+
+    /// First and last word (1-based, 8-byte) of the segment data.
+    start_word: usize,
+    end_word: usize,
+}
+
+/// An SPK kernel held in memory.
+#[derive(Clone)]
+pub struct Spk {
+    data: Vec<u8>,
+    little_endian: bool,
+    pub internal_name: String,
+    pub segments: Vec<SpkSegment>,
+}
+
+impl std::fmt::Debug for Spk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Spk")
+            .field("len", &self.data.len())
+            .field("little_endian", &self.little_endian)
+            .field("internal_name", &self.internal_name)
+            .field("segments", &self.segments)
+            .finish()
+    }
+}
+
+impl Spk {
+    pub fn read(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::from_bytes(data).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        ensure!(RECORD_LEN <= data.len(), "too short for a DAF file record");
+
+        let id_word = String::from_utf8_lossy(&data[0..8]).to_string();
+        ensure!(
+            id_word == "DAF/SPK " || id_word == "NAIF/DAF",
+            "not an SPK file: '{id_word}'"
+        );
+
+        let little_endian = match &data[88..96] {
+            b"LTL-IEEE" => true,
+            b"BIG-IEEE" => false,
+            // Pre-1990s files have no format string. ND is always 2 for SPK.
+            _ => i32::from_le_bytes(data[8..12].try_into().unwrap()) == 2,
+        };
+
+        let mut self_ = Self {
+            data,
+            little_endian,
+            internal_name: String::new(),
+            segments: vec![],
+        };
+
+        let nd = self_.i32_at_byte(8)? as usize;
+        let ni = self_.i32_at_byte(12)? as usize;
+        ensure!(
+            nd == 2 && ni == 6,
+            "SPK should have ND=2, NI=6, not {nd}, {ni}"
+        );
+        self_.internal_name = String::from_utf8_lossy(&self_.data[16..76])
+            .trim_end()
+            .to_string();
+        let fward = self_.i32_at_byte(76)?;
+        ensure!(
+            0 < fward,
+            "SPK first summary record should be positive, not {fward}"
+        );
+
+        // Doubles per summary, and characters per name.
+        let ss = nd + (ni + 1) / 2;
+        let nc = 8 * ss;
+
+        let mut record = fward as usize;
+        let mut records_seen = 0;
+        while record != 0 {
+            records_seen += 1;
+            ensure!(records_seen < 100_000, "summary record chain doesn't end");
+
+            let name_record_byte = record
+                .checked_mul(RECORD_LEN)
+                .context("SPK summary record out of range")?;
+            let base_word = (name_record_byte - RECORD_LEN) / 8 + 1;
+            let next = self_.f64_at_word(base_word)? as usize;
+            let nsum = self_.f64_at_word(base_word + 2)? as usize;
+
+            for i in 0..nsum {
+                let summary_word = base_word + 3 + i * ss;
+                let summary_byte = (summary_word - 1) * 8;
+                let int_at = |k: usize| self_.i32_at_byte(summary_byte + nd * 8 + k * 4);
+
+                let name_bytes = self_
+                    .data
+                    .get(name_record_byte + i * nc..name_record_byte + (i + 1) * nc)
+                    .unwrap_or_default();
+
+                self_.segments.push(SpkSegment {
+                    name: String::from_utf8_lossy(name_bytes).trim_end().to_string(),
+                    start_et: self_.f64_at_word(summary_word)?,
+                    end_et: self_.f64_at_word(summary_word + 1)?,
+                    target: NaifId(int_at(0)?),
+                    center: NaifId(int_at(1)?),
+                    frame: int_at(2)?,
+                    data_type: int_at(3)?,
+                    start_word: int_at(4)? as usize,
+                    end_word: int_at(5)? as usize,
+                });
+            }
+
+            record = next;
+        }
+
+        Ok(self_)
+    }
+
+    fn bytes<const N: usize>(&self, byte: usize) -> Result<[u8; N]> {
+        let slice = self
+            .data
+            .get(byte..byte + N)
+            .ok_or_else(|| anyhow!("read past end of SPK at byte {byte}"))?;
+        Ok(slice.try_into().unwrap())
+    }
+
+    fn i32_at_byte(&self, byte: usize) -> Result<i32> {
+        let b = self.bytes::<4>(byte)?;
+        Ok(if self.little_endian {
+            i32::from_le_bytes(b)
+        } else {
+            i32::from_be_bytes(b)
+        })
+    }
+
+    /// Reads the double at a 1-based word address.
+    fn f64_at_word(&self, word: usize) -> Result<f64> {
+        ensure!(0 < word, "DAF word addresses start at 1");
+        let offset = (word - 1)
+            .checked_mul(8)
+            .context("DAF word address out of range")?;
+        let b = self.bytes::<8>(offset)?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(b)
+        } else {
+            f64::from_be_bytes(b)
+        })
+    }
+
+    fn f64s_at_word(&self, word: usize, n: usize) -> Result<Vec<f64>> {
+        (word..word + n).map(|w| self.f64_at_word(w)).collect()
+    }
+
+    /// State of the segment's target relative to its center, in the segment's frame.
+    pub fn segment_state(&self, segment: &SpkSegment, et: f64) -> Result<StateVector> {
+        ensure!(
+            segment.start_et <= et && et <= segment.end_et,
+            "ET {et} outside segment '{}'",
+            segment.name
+        );
+
+        // The directory at the end of the segment, after the records.
+        let directory_word = segment
+            .end_word
+            .checked_sub(3)
+            .filter(|&word| segment.start_word <= word)
+            .context("SPK segment too short")?;
+        let init = self.f64_at_word(directory_word)?;
+        let intlen = self.f64_at_word(directory_word + 1)?;
+        let rsize = self.f64_at_word(directory_word + 2)? as usize;
+        let n = self.f64_at_word(directory_word + 3)? as usize;
+        ensure!(0 < n && 0.0 < intlen && 2 < rsize, "bad segment directory");
+
+        let ix = (((et - init) / intlen).floor().max(0.0) as usize).min(n - 1);
+        let record_word = ix
+            .checked_mul(rsize)
+            .and_then(|offset| segment.start_word.checked_add(offset))
+            .filter(|&word| {
+                word.checked_add(rsize)
+                    .is_some_and(|end| end <= directory_word)
+            })
+            .context("SPK segment record outside the segment")?;
+        let record = self.f64s_at_word(record_word, rsize)?;
+        let (mid, radius) = (record[0], record[1]);
+        let s = (et - mid) / radius;
+
+        match segment.data_type {
+            2 => {
+                let ncoef = (rsize - 2) / 3;
+                let mut sv = StateVector::default();
+                for i in 0..3 {
+                    let (p, dp_ds) = chebyshev(&record[2 + i * ncoef..2 + (i + 1) * ncoef], s);
+                    sv.position_km[i] = p;
+                    sv.velocity_km_s[i] = dp_ds / radius;
+                }
+                Ok(sv)
+            }
+            3 => {
+                let ncoef = (rsize - 2) / 6;
+                let comp = |i: usize| chebyshev(&record[2 + i * ncoef..2 + (i + 1) * ncoef], s).0;
+                Ok(StateVector {
+                    position_km: [comp(0), comp(1), comp(2)],
+                    velocity_km_s: [comp(3), comp(4), comp(5)],
+                })
+            }
+            t => bail!("SPK segment type {t} is not supported"),
+        }
+    }
+
+    /// The segment to use for `target` at `et`. Later segments take precedence.
+    fn find_segment(&self, target: NaifId, et: f64) -> Result<&SpkSegment> {
+        self.segments
+            .iter()
+            .rev()
+            .find(|s| s.target == target && s.start_et <= et && et <= s.end_et)
+            .ok_or_else(|| anyhow!("SPK has no data for NAIF ID {} at ET {et}", target.0))
+    }
+
+    /// State relative to the solar system barycenter, ICRF, following the chain of centers.
+    fn state_wrt_ssb(&self, id: NaifId, et: f64) -> Result<StateVector> {
+        let mut sv = StateVector::default();
+        let mut id = id;
+        for _ in 0..16 {
+            if id == NaifId::SOLAR_SYSTEM_BARYCENTER {
+                return Ok(sv);
+            }
+            let segment = self.find_segment(id, et)?;
+            let seg_sv = self.segment_state(segment, et)?;
+            let seg_sv = match segment.frame {
+                FRAME_J2000 => seg_sv,
+                FRAME_ECLIPJ2000 => eclipj2000_to_j2000(&seg_sv),
+                f => bail!("SPK frame {f} is not supported"),
+            };
+            sv = sv.add(&seg_sv);
+            id = segment.center;
+        }
+        bail!("SPK center chain too long")
+    }
+}
+
+/// Rotates from the J2000 ecliptic to the J2000 equator, by the obliquity SPICE uses.
+fn eclipj2000_to_j2000(sv: &StateVector) -> StateVector {
+    let (s, c) = (84381.448_f64 / 3600.0).to_radians().sin_cos();
+    let rot = |[x, y, z]: [f64; 3]| [x, c * y - s * z, s * y + c * z];
+    StateVector {
+        position_km: rot(sv.position_km),
+        velocity_km_s: rot(sv.velocity_km_s),
+    }
+}
+
+impl Ephemeris for Spk {
+    fn state(&self, target: NaifId, center: NaifId, jd_tdb: f64) -> Result<StateVector> {
+        let et = et_from_jd_tdb(jd_tdb);
+        Ok(self
+            .state_wrt_ssb(target, et)?
+            .sub(&self.state_wrt_ssb(center, et)?))
+    }
+
+    fn bodies(&self) -> Vec<NaifId> {
+        let mut v: Vec<NaifId> = self.segments.iter().map(|s| s.target).collect();
+        v.push(NaifId::SOLAR_SYSTEM_BARYCENTER);
+        v.sort();
+        v.dedup();
+        v
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+pub(crate) mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    /// A segment to write into a test kernel: `records` is a list of (mid, radius, coefficients).
+    pub(crate) struct TestSegment {
+        pub name: &'static str,
+        pub target: i32,
+        pub center: i32,
+        pub frame: i32,
+        pub data_type: i32,
+        pub init: f64,
+        pub intlen: f64,
+        pub records: Vec<Vec<f64>>,
+    }
+
+    /// Writes a minimal SPK: file record, one summary record, one name record, then data.
+    pub(crate) fn build_spk(little_endian: bool, segments: &[TestSegment]) -> Vec<u8> {
+        let put_i32 = |buf: &mut Vec<u8>, at: usize, v: i32| {
+            let b = if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            };
+            buf[at..at + 4].copy_from_slice(&b);
+        };
+        let put_f64 = |buf: &mut Vec<u8>, at: usize, v: f64| {
+            let b = if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            };
+            buf[at..at + 8].copy_from_slice(&b);
+        };
+
+        let mut buf = vec![0u8; 3 * RECORD_LEN];
+        buf[0..8].copy_from_slice(b"DAF/SPK ");
+        put_i32(&mut buf, 8, 2);
+        put_i32(&mut buf, 12, 6);
+        buf[16..76].copy_from_slice(format!("{:<60}", "TINY TEST KERNEL").as_bytes());
+        put_i32(&mut buf, 76, 2);
+        put_i32(&mut buf, 80, 2);
+        buf[88..96].copy_from_slice(if little_endian {
+            b"LTL-IEEE"
+        } else {
+            b"BIG-IEEE"
+        });
+
+        let summary_base = RECORD_LEN;
+        put_f64(&mut buf, summary_base, 0.0);
+        put_f64(&mut buf, summary_base + 8, 0.0);
+        put_f64(&mut buf, summary_base + 16, segments.len() as f64);
+
+        for (i, seg) in segments.iter().enumerate() {
+            let start_word = buf.len() / 8 + 1;
+            let rsize = seg.records[0].len();
+            for record in &seg.records {
+                for &x in record {
+                    buf.extend_from_slice(&[0u8; 8]);
+                    let at = buf.len() - 8;
+                    put_f64(&mut buf, at, x);
+                }
+            }
+            for x in [seg.init, seg.intlen, rsize as f64, seg.records.len() as f64] {
+                buf.extend_from_slice(&[0u8; 8]);
+                let at = buf.len() - 8;
+                put_f64(&mut buf, at, x);
+            }
+            let end_word = buf.len() / 8;
+
+            let at = summary_base + 24 + i * 40;
+            let end_et = seg.init + seg.intlen * seg.records.len() as f64;
+            put_f64(&mut buf, at, seg.init);
+            put_f64(&mut buf, at + 8, end_et);
+            let ints = [
+                seg.target,
+                seg.center,
+                seg.frame,
+                seg.data_type,
+                start_word as i32,
+                end_word as i32,
+            ];
+            for (k, v) in ints.into_iter().enumerate() {
+                put_i32(&mut buf, at + 16 + 4 * k, v);
+            }
+
+            let name_at = 2 * RECORD_LEN + i * 40;
+            buf[name_at..name_at + 40].copy_from_slice(format!("{:<40}", seg.name).as_bytes());
+        }
+
+        // Pad to a whole record, as real files are.
+        buf.resize((buf.len() + RECORD_LEN - 1) / RECORD_LEN * RECORD_LEN, 0);
+        buf
+    }
+
+    /// Coefficients of a linear function `x0 + v (t - t0)` over `[mid - radius, mid + radius]`.
+    fn linear(x0: f64, v: f64, t0: f64, mid: f64, radius: f64) -> [f64; 2] {
+        [x0 + v * (mid - t0), v * radius]
+    }
+
+    /// The Earth-Moon barycenter moving in a straight line, the Earth about it as a type 3
+    /// segment, and the Sun at rest off the barycenter, over two one-day records each.
+    pub(crate) fn tiny_kernel_segments() -> Vec<TestSegment> {
+        const DAY: f64 = SECONDS_PER_DAY;
+        let records_type2 = |x0: [f64; 3], v: [f64; 3]| -> Vec<Vec<f64>> {
+            (0..2)
+                .map(|k| {
+                    let mid = -DAY + DAY * k as f64 + DAY / 2.0;
+                    let mut r = vec![mid, DAY / 2.0];
+                    for i in 0..3 {
+                        let [c0, c1] = linear(x0[i], v[i], 0.0, mid, DAY / 2.0);
+                        r.extend_from_slice(&[c0, c1, 0.0]);
+                    }
+                    r
+                })
+                .collect()
+        };
+
+        vec![
+            TestSegment {
+                name: "EMB WRT SSB",
+                target: 3,
+                center: 0,
+                frame: FRAME_J2000,
+                data_type: 2,
+                init: -DAY,
+                intlen: DAY,
+                records: records_type2([-2.6e7, 1.3e8, 5.6e7], [-29.0, -5.0, -2.0]),
+            },
+            TestSegment {
+                name: "SUN WRT SSB",
+                target: 10,
+                center: 0,
+                frame: FRAME_J2000,
+                data_type: 2,
+                init: -DAY,
+                intlen: DAY,
+                records: records_type2([-1.0e6, -4.0e5, -1.0e5], [0.0, 0.0, 0.0]),
+            },
+            TestSegment {
+                name: "EARTH WRT EMB",
+                target: 399,
+                center: 3,
+                frame: FRAME_J2000,
+                data_type: 3,
+                init: -DAY,
+                intlen: DAY,
+                records: (0..2)
+                    .map(|k| {
+                        let mid = -DAY / 2.0 + DAY * k as f64;
+                        // Constant offset, zero velocity: only the T_0 coefficient is set.
+                        let mut r = vec![mid, DAY / 2.0];
+                        for x in [4000.0, -1000.0, 500.0, 0.0, 0.0, 0.0] {
+                            r.extend_from_slice(&[x, 0.0]);
+                        }
+                        r
+                    })
+                    .collect(),
+            },
+        ]
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        for little_endian in [true, false] {
+            let spk = Spk::from_bytes(build_spk(little_endian, &tiny_kernel_segments()))?;
+            assert_eq!(spk.little_endian, little_endian);
+            assert_eq!(spk.internal_name, "TINY TEST KERNEL");
+            assert_eq!(
+                spk.segments
+                    .iter()
+                    .map(|s| s.name.as_str())
+                    .collect::<Vec<_>>(),
+                ["EMB WRT SSB", "SUN WRT SSB", "EARTH WRT EMB"]
+            );
+            assert_eq!(
+                spk.bodies(),
+                [
+                    NaifId::SOLAR_SYSTEM_BARYCENTER,
+                    NaifId::EARTH_MOON_BARYCENTER,
+                    NaifId::SUN,
+                    NaifId::EARTH,
+                ]
+            );
+
+            // Six hours after J2000, in the second record.
+            let jd = J2000 + 0.25;
+            let t = 0.25 * SECONDS_PER_DAY;
+            let emb = spk.state(
+                NaifId::EARTH_MOON_BARYCENTER,
+                NaifId::SOLAR_SYSTEM_BARYCENTER,
+                jd,
+            )?;
+            assert_eq!(
+                emb.position_km,
+                [-2.6e7 - 29.0 * t, 1.3e8 - 5.0 * t, 5.6e7 - 2.0 * t]
+            );
+            assert_eq!(emb.velocity_km_s, [-29.0, -5.0, -2.0]);
+
+            // Earth relative to the Sun chains through the EMB and the SSB.
+            let earth = spk.state(NaifId::EARTH, NaifId::SUN, jd)?;
+            let expected = [
+                -2.6e7 - 29.0 * t + 4000.0 + 1.0e6,
+                1.3e8 - 5.0 * t - 1000.0 + 4.0e5,
+                5.6e7 - 2.0 * t + 500.0 + 1.0e5,
+            ];
+            for (got, want) in earth.position_km.iter().zip(expected) {
+                assert!((got - want).abs() < 1e-6);
+            }
+            assert_eq!(earth.velocity_km_s, [-29.0, -5.0, -2.0]);
+
+            // Continuous across the record boundary.
+            let a = spk.state(
+                NaifId::EARTH_MOON_BARYCENTER,
+                NaifId::SOLAR_SYSTEM_BARYCENTER,
+                J2000 - 1e-9,
+            )?;
+            let b = spk.state(
+                NaifId::EARTH_MOON_BARYCENTER,
+                NaifId::SOLAR_SYSTEM_BARYCENTER,
+                J2000 + 1e-9,
+            )?;
+            // 2e-9 days at 29 km/s is about 5 m.
+            assert!((a.position_km[0] - b.position_km[0]).abs() < 0.01);
+
+            // Outside coverage.
+            assert!(spk.state(NaifId::EARTH, NaifId::SUN, J2000 + 2.0).is_err());
+            assert!(spk.state(NaifId::MOON, NaifId::SUN, J2000).is_err());
+
+            // Truncated or malformed segments are errors, not panics.
+            let mut segment = spk.segments[0].clone();
+            segment.start_et = f64::MIN;
+            segment.end_et = f64::MAX;
+            for (start_word, end_word) in [(1, 2), (10, 3), (5, 6), (1, usize::MAX)] {
+                segment.start_word = start_word;
+                segment.end_word = end_word;
+                assert!(spk.segment_state(&segment, 0.0).is_err());
+            }
+        }
+
+        assert!(Spk::from_bytes(vec![0; 2048]).is_err());
+
+        // FWARD must name a summary record in the file.
+        for fward in [-1, 0, i32::MAX] {
+            let mut bytes = build_spk(true, &tiny_kernel_segments());
+            bytes[76..80].copy_from_slice(&fward.to_le_bytes());
+            assert!(Spk::from_bytes(bytes).is_err());
+        }
+        Ok(())
+    }
+}
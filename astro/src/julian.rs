@@ -0,0 +1,166 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+//? use serde::{Deserialize, Serialize};
+
+/// Julian date of the J2000.0 epoch, 2000-01-01 12:00:00 TT.
+pub const J2000: f64 = 2_451_545.0;
+
+/// Julian date of the Modified Julian Date zero point, 1858-11-17 00:00.
+pub const MJD_ZERO: f64 = 2_400_000.5;
+
+/// Days in a Julian year.
+pub const DAYS_PER_JULIAN_YEAR: f64 = 365.25;
+
+/// Days in a Julian century.
+pub const DAYS_PER_JULIAN_CENTURY: f64 = 36_525.0;
+
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// TT - TAI, exact by definition.
+pub const TT_MINUS_TAI_SECONDS: f64 = 32.184;
+
+/// The UTC dates from which TAI - UTC took each value in seconds, from IERS Bulletin C. There
+/// has been no leap second since 2017; a new one has to be added here when it is announced.
+const LEAP_SECONDS: [(i32, u32, f64); 28] = [
+    (1972, 1, 10.0),
+    (1972, 7, 11.0),
+    (1973, 1, 12.0),
+    (1974, 1, 13.0),
+    (1975, 1, 14.0),
+    (1976, 1, 15.0),
+    (1977, 1, 16.0),
+    (1978, 1, 17.0),
+    (1979, 1, 18.0),
+    (1980, 1, 19.0),
+    (1981, 7, 20.0),
+    (1982, 7, 21.0),
+    (1983, 7, 22.0),
+    (1985, 7, 23.0),
+    (1988, 1, 24.0),
+    (1990, 1, 25.0),
+    (1991, 1, 26.0),
+    (1992, 7, 27.0),
+    (1993, 7, 28.0),
+    (1994, 7, 29.0),
+    (1996, 1, 30.0),
+    (1997, 7, 31.0),
+    (1999, 1, 32.0),
+    (2006, 1, 33.0),
+    (2009, 1, 34.0),
+    (2012, 7, 35.0),
+    (2015, 7, 36.0),
+    (2017, 1, 37.0),
+];
+
+/// TAI - UTC in seconds at a Julian date in TAI. Before 1972, when UTC didn't yet tick SI
+/// seconds, this is the 10 s it started with.
+#[must_use]
+pub fn tai_minus_utc_seconds(jd_tai: f64) -> f64 {
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|&&(year, month, tai_minus_utc)| {
+            let jd_tai_from = jd_from_gregorian(year, month, 1.0) + tai_minus_utc / SECONDS_PER_DAY;
+            jd_tai_from <= jd_tai
+        })
+        .map_or(LEAP_SECONDS[0].2, |&(_, _, tai_minus_utc)| tai_minus_utc)
+}
+
+/// Julian date for a proleptic Gregorian calendar date. `day` may have a fractional part.
+///
+/// Meeus, "Astronomical Algorithms" 2nd ed., ch. 7. Valid for all years, including negative
+/// (astronomical) years, since this always uses the Gregorian calendar.
+#[must_use]
+pub fn jd_from_gregorian(year: i32, month: u32, day: f64) -> f64 {
+    let (y, m) = if month <= 2 {
+        (year as f64 - 1.0, month as f64 + 12.0)
+    } else {
+        (year as f64, month as f64)
+    };
+    let a = (y / 100.0).floor();
+    let b = 2.0 - a + (a / 4.0).floor();
+    (365.25 * (y + 4716.0)).floor() + (30.6001 * (m + 1.0)).floor() + day + b - 1524.5
+}
+
+/// Proleptic Gregorian calendar date `(year, month, day)` for a Julian date.
+/// The day has a fractional part.
+///
+/// Meeus, "Astronomical Algorithms" 2nd ed., ch. 7.
+#[must_use]
+pub fn gregorian_from_jd(jd: f64) -> (i32, u32, f64) {
+    let jd = jd + 0.5;
+    let z = jd.floor();
+    let f = jd - z;
+    let alpha = ((z - 1_867_216.25) / 36_524.25).floor();
+    let a = z + 1.0 + alpha - (alpha / 4.0).floor();
+    let b = a + 1524.0;
+    let c = ((b - 122.1) / 365.25).floor();
+    let d = (365.25 * c).floor();
+    let e = ((b - d) / 30.6001).floor();
+    let day = b - d - (30.6001 * e).floor() + f;
+    let month = if e < 14.0 { e - 1.0 } else { e - 13.0 };
+    let year = if month > 2.0 { c - 4716.0 } else { c - 4715.0 };
+    (year as i32, month as u32, day)
+}
+
+/// Julian centuries elapsed since J2000.0, the `T` argument of most series.
+#[must_use]
+pub fn julian_centuries_since_j2000(jd: f64) -> f64 {
+    (jd - J2000) / DAYS_PER_JULIAN_CENTURY
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        // Examples from Meeus ch. 7.
+        assert_ron_snapshot!(jd_from_gregorian(2000, 1, 1.5), @"2451545.0");
+        assert_ron_snapshot!(jd_from_gregorian(1987, 1, 27.0), @"2446822.5");
+        assert_ron_snapshot!(jd_from_gregorian(1988, 6, 19.5), @"2447332.0");
+        assert_ron_snapshot!(jd_from_gregorian(1600, 12, 31.0), @"2305812.5");
+        assert!((jd_from_gregorian(1957, 10, 4.81) - 2_436_116.31).abs() < 1e-6);
+
+        assert_ron_snapshot!(gregorian_from_jd(2436116.0), @"(1957, 10, 4.5)");
+        assert_ron_snapshot!(gregorian_from_jd(2451545.0), @"(2000, 1, 1.5)");
+        assert_ron_snapshot!(gregorian_from_jd(jd_from_gregorian(-1000, 3, 1.0)), @"(-1000, 3, 1.0)");
+
+        assert_ron_snapshot!(julian_centuries_since_j2000(J2000 + DAYS_PER_JULIAN_CENTURY), @"1.0");
+
+        // 2017-01-01 00:00:00 UTC is 00:00:37 TAI, the second after 2016-12-31 23:59:60 UTC.
+        let jd_2017 = jd_from_gregorian(2017, 1, 1.0);
+        let second = 1.0 / SECONDS_PER_DAY;
+        assert_eq!(tai_minus_utc_seconds(jd_2017 + 36.5 * second), 36.0);
+        assert_eq!(tai_minus_utc_seconds(jd_2017 + 37.5 * second), 37.0);
+        assert_eq!(tai_minus_utc_seconds(J2000), 32.0);
+        assert_eq!(tai_minus_utc_seconds(jd_from_gregorian(1960, 1, 1.0)), 10.0);
+        assert_eq!(tai_minus_utc_seconds(jd_from_gregorian(2100, 1, 1.0)), 37.0);
+    }
+}
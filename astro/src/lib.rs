@@ -0,0 +1,71 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Positional astronomy: time scales, solar position, and observer-relative coordinates.
+//!
+//! Angles are `f64` radians unless the name says otherwise (`_deg`, `_hours`).
+//! Times are Julian dates (`f64` days), tagged by time scale in the name (`jd_ut`, `jd_tt`).
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+//? use serde::{Deserialize, Serialize};
+
+pub mod angle;
+
+pub mod julian;
+pub use crate::julian::{jd_from_gregorian, julian_centuries_since_j2000, J2000};
+
+pub mod sun;
+
+pub mod moon;
+pub use crate::moon::MoonApparent;
+
+pub mod horizontal;
+pub use crate::horizontal::{Horizontal, Observer};
+
+pub mod vsop87;
+pub use crate::vsop87::{HeliocentricEcliptic, Planet, Vsop87Body};
+
+pub mod rotation;
+
+pub mod precession;
+
+pub mod nutation;
+pub use crate::nutation::Nutation;
+
+pub mod frames;
+
+pub mod ephemeris;
+pub use crate::ephemeris::{Ephemeris, NaifId, StateVector};
+
+pub mod jpl;
+
+pub mod apparent;
+pub use crate::apparent::{ApparentPlace, ApparentPlaceCalc, Corrections};
+
+pub mod stars;
+pub use crate::stars::{Star, StarCatalog};
+
+pub mod sun_path;
+pub use crate::sun_path::{SunPathCurve, SunPathProjection, SunSample};
@@ -0,0 +1,131 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::angle::norm_0_tau;
+use crate::julian::julian_centuries_since_j2000;
+
+/// Earth equatorial radius, km, as used for the lunar parallax.
+const EARTH_RADIUS_KM: f64 = 6378.14;
+
+/// Geocentric position of the Moon, referred to the mean equator and equinox of date.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct MoonApparent {
+    /// Ecliptic longitude, radians.
+    pub lon: f64,
+
+    /// Ecliptic latitude, radians.
+    pub lat: f64,
+
+    /// Equatorial horizontal parallax, radians.
+    pub horizontal_parallax: f64,
+
+    /// Earth-Moon distance, km.
+    pub distance_km: f64,
+
+    /// Right ascension, radians in `[0, tau)`.
+    pub ra: f64,
+
+    /// Declination, radians.
+    pub dec: f64,
+}
+
+/// Position of the Moon from the low-precision formulas of the Astronomical Almanac, section
+/// D. Good to about 0.3° in longitude and 0.2° in latitude between 1950 and 2050, which is
+/// plenty for placing it in a drawing of the sky. The parallax matters more: up to a degree
+/// between the geocentric and topocentric places.
+#[must_use]
+pub fn apparent_low_precision(jd_tt: f64) -> MoonApparent {
+    let T = julian_centuries_since_j2000(jd_tt);
+    let sin = |deg: f64| deg.to_radians().sin();
+    let cos = |deg: f64| deg.to_radians().cos();
+
+    // Degrees.
+    let lon = 218.32 + 481_267.881 * T + 6.29 * sin(135.0 + 477_198.87 * T)
+        - 1.27 * sin(259.3 - 413_335.36 * T)
+        + 0.66 * sin(235.7 + 890_534.22 * T)
+        + 0.21 * sin(269.9 + 954_397.74 * T)
+        - 0.19 * sin(357.5 + 35_999.05 * T)
+        - 0.11 * sin(186.5 + 966_404.03 * T);
+    let lat = 5.13 * sin(93.3 + 483_202.02 * T) + 0.28 * sin(228.2 + 960_400.89 * T)
+        - 0.28 * sin(318.3 + 6_003.15 * T)
+        - 0.17 * sin(217.6 - 407_332.21 * T);
+    let hp = 0.9508
+        + 0.0518 * cos(135.0 + 477_198.87 * T)
+        + 0.0095 * cos(259.3 - 413_335.36 * T)
+        + 0.0078 * cos(235.7 + 890_534.22 * T)
+        + 0.0028 * cos(269.9 + 954_397.74 * T);
+
+    let (lon, lat, hp) = (lon.to_radians(), lat.to_radians(), hp.to_radians());
+    let obliquity = (23.439_291 - 0.013_004_2 * T).to_radians();
+
+    // Direction cosines, ecliptic to equatorial.
+    let (sin_lon, cos_lon) = lon.sin_cos();
+    let (sin_lat, cos_lat) = lat.sin_cos();
+    let (sin_eps, cos_eps) = obliquity.sin_cos();
+    let x = cos_lat * cos_lon;
+    let y = cos_eps * cos_lat * sin_lon - sin_eps * sin_lat;
+    let z = sin_eps * cos_lat * sin_lon + cos_eps * sin_lat;
+
+    MoonApparent {
+        lon: norm_0_tau(lon),
+        lat,
+        horizontal_parallax: hp,
+        distance_km: EARTH_RADIUS_KM / hp.sin(),
+        ra: norm_0_tau(y.atan2(x)),
+        dec: z.asin(),
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    fn round_to(x: f64, places: i32) -> f64 {
+        let k = 10f64.powi(places);
+        (x * k).round() / k
+    }
+
+    #[test]
+    fn t() {
+        // Meeus example 47.a, 1992 April 12.0 TD: λ 133.162655°, β -3.229126°,
+        // Δ 368409.7 km, π 0.991990°.
+        let moon = apparent_low_precision(2_448_724.5);
+        assert!((moon.lon.to_degrees() - 133.162655).abs() < 0.3);
+        assert!((moon.lat.to_degrees() + 3.229126).abs() < 0.2);
+        assert!((moon.horizontal_parallax.to_degrees() - 0.991990).abs() < 0.003);
+        assert_ron_snapshot!(round_to(moon.lon.to_degrees(), 3), @"133.257");
+        assert_ron_snapshot!(round_to(moon.lat.to_degrees(), 3), @"-3.307");
+        assert_ron_snapshot!(round_to(moon.distance_km, 0), @"368077.0");
+
+        // Meeus gives α 134.688470°, δ 13.768368° for the apparent place.
+        assert_ron_snapshot!(round_to(moon.ra.to_degrees(), 2), @"134.75");
+        assert_ron_snapshot!(round_to(moon.dec.to_degrees(), 2), @"13.67");
+    }
+}
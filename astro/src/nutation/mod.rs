@@ -0,0 +1,430 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Nutation: the IAU 2000A model, read from the IERS Conventions (2010) tables 5.3a and 5.3b,
+//! and the 77-term IAU 2000B model, which is compiled in and good to about a milliarcsecond.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::angle::ARCSEC_TO_RAD;
+use crate::julian::julian_centuries_since_j2000;
+
+mod iau2000b;
+
+const TURNAS: f64 = 1_296_000.0;
+const TAU: f64 = std::f64::consts::TAU;
+
+/// Nutation in longitude and obliquity, radians.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Nutation {
+    pub dpsi: f64,
+    pub deps: f64,
+}
+
+impl Nutation {
+    /// The IAU 2000 nutation adjusted to be consistent with IAU 2006 precession
+    /// (Wallace & Capitaine 2006), as in SOFA's `iauNut06a`. Changes it by a few microarcseconds.
+    #[must_use]
+    pub fn iau2006_adjusted(self, jd_tt: f64) -> Self {
+        let fj2 = -2.7774e-6 * julian_centuries_since_j2000(jd_tt);
+        Self {
+            dpsi: self.dpsi + self.dpsi * (0.4697e-6 + fj2),
+            deps: self.deps + self.deps * fj2,
+        }
+    }
+}
+
+/// The fundamental arguments of the IERS Conventions (2003), radians: the Delaunay arguments
+/// l, l', F, D, Om, the mean longitudes of Mercury through Neptune, and the general
+/// precession in longitude p_A. In the column order of the IERS tables.
+#[must_use]
+pub fn fundamental_arguments(T: f64) -> [f64; 14] {
+    let asec = |x: f64| (x % TURNAS) * ARCSEC_TO_RAD;
+    [
+        // l, mean anomaly of the Moon.
+        asec(
+            485868.249036
+                + T * (1717915923.2178 + T * (31.8792 + T * (0.051635 + T * -0.00024470))),
+        ),
+        // l', mean anomaly of the Sun.
+        asec(
+            1287104.793048
+                + T * (129596581.0481 + T * (-0.5532 + T * (0.000136 + T * -0.00001149))),
+        ),
+        // F = L - Om, L being the mean longitude of the Moon.
+        asec(
+            335779.526232
+                + T * (1739527262.8478 + T * (-12.7512 + T * (-0.001037 + T * 0.00000417))),
+        ),
+        // D, mean elongation of the Moon from the Sun.
+        asec(
+            1072260.703692
+                + T * (1602961601.2090 + T * (-6.3706 + T * (0.006593 + T * -0.00003169))),
+        ),
+        // Om, mean longitude of the ascending node of the Moon.
+        asec(450160.398036 + T * (-6962890.5431 + T * (7.4722 + T * (0.007702 + T * -0.00005939)))),
+        (4.402608842 + 2608.7903141574 * T) % TAU,
+        (3.176146697 + 1021.3285546211 * T) % TAU,
+        (1.753470314 + 628.3075849991 * T) % TAU,
+        (6.203480913 + 334.0612426700 * T) % TAU,
+        (0.599546497 + 52.9690962641 * T) % TAU,
+        (0.874016757 + 21.3299104960 * T) % TAU,
+        (5.481293872 + 7.4781598567 * T) % TAU,
+        (5.311886287 + 3.8133035638 * T) % TAU,
+        (0.024381750 + 0.00000538691 * T) * T,
+    ]
+}
+
+/// IAU 2000B nutation, as SOFA's `iauNut00b`.
+#[must_use]
+pub fn iau2000b(jd_tt: f64) -> Nutation {
+    // 0.1 microarcsecond.
+    const U2R: f64 = ARCSEC_TO_RAD / 1e7;
+    // Fixed offsets in lieu of the planetary terms.
+    const DPPLAN: f64 = -0.135e-3 * ARCSEC_TO_RAD;
+    const DEPLAN: f64 = 0.388e-3 * ARCSEC_TO_RAD;
+
+    let T = julian_centuries_since_j2000(jd_tt);
+
+    // Delaunay arguments, linear in T, from Simon et al. (1994).
+    let asec = |x: f64| (x % TURNAS) * ARCSEC_TO_RAD;
+    let args = [
+        asec(485868.249036 + 1717915923.2178 * T),
+        asec(1287104.79305 + 129596581.0481 * T),
+        asec(335779.526232 + 1739527262.8478 * T),
+        asec(1072260.70369 + 1602961601.2090 * T),
+        asec(450160.398036 - 6962890.5431 * T),
+    ];
+
+    let (mut dp, mut de) = (0.0, 0.0);
+    for term in iau2000b::TERMS.iter().rev() {
+        let arg = term
+            .nals
+            .iter()
+            .zip(args)
+            .map(|(&n, a)| f64::from(n) * a)
+            .sum::<f64>()
+            % TAU;
+        let (sarg, carg) = arg.sin_cos();
+        dp += (term.ps + term.pst * T) * sarg + term.pc * carg;
+        de += (term.ec + term.ect * T) * carg + term.es * sarg;
+    }
+
+    Nutation {
+        dpsi: dp * U2R + DPPLAN,
+        deps: de * U2R + DEPLAN,
+    }
+}
+
+/// One row of IERS table 5.3a or 5.3b, microarcseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct SeriesTerm {
+    pub sin: f64,
+    pub cos: f64,
+
+    /// Multipliers of the [`fundamental_arguments`].
+    pub multipliers: [i8; 14],
+}
+
+/// A Poisson series: `sum_j T^j sum_i (S_ij sin ARG_i + C_ij cos ARG_i)`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Series {
+    pub terms_by_power: Vec<Vec<SeriesTerm>>,
+}
+
+impl Series {
+    /// Parses an IERS Conventions table in the layout of tab5.3a.txt:
+    /// `j = 0  Number of terms = 1320` headings, then rows of
+    /// `i  A_i  A"_i  l l' F D Om L_Me L_Ve L_E L_Ma L_J L_Sa L_U L_Ne p_A`.
+    /// The first coefficient column multiplies the sine, the second the cosine, which holds
+    /// for both the longitude and obliquity tables.
+    pub fn parse_iers(text: &str) -> Result<Self> {
+        let mut terms_by_power: Vec<Vec<SeriesTerm>> = vec![];
+        let mut expected: Vec<usize> = vec![];
+        for (line_ix, line) in text.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if let ["j", "=", j, "Number", "of", "terms", "=", n] = fields.as_slice() {
+                let j: usize = j.parse()?;
+                ensure!(
+                    j == terms_by_power.len(),
+                    "line {}: expected j = {}",
+                    line_ix + 1,
+                    terms_by_power.len()
+                );
+                terms_by_power.push(vec![]);
+                expected.push(n.parse()?);
+                continue;
+            }
+
+            // Anything else that doesn't look like a row is commentary.
+            if fields.len() != 17 || fields[0].parse::<usize>().is_err() {
+                continue;
+            }
+            let Some(terms) = terms_by_power.last_mut() else {
+                bail!("line {}: term before any 'j = ' heading", line_ix + 1);
+            };
+            let ctx = || format!("line {}", line_ix + 1);
+            let mut multipliers = [0_i8; 14];
+            for (m, s) in multipliers.iter_mut().zip(&fields[3..]) {
+                *m = s.parse().with_context(ctx)?;
+            }
+            terms.push(SeriesTerm {
+                sin: fields[1].parse().with_context(ctx)?,
+                cos: fields[2].parse().with_context(ctx)?,
+                multipliers,
+            });
+        }
+
+        ensure!(!terms_by_power.is_empty(), "no 'j = ' headings");
+        for (j, (terms, n)) in terms_by_power.iter().zip(expected).enumerate() {
+            ensure!(
+                terms.len() == n,
+                "j = {j}: expected {n} terms, found {}",
+                terms.len()
+            );
+        }
+        Ok(Self { terms_by_power })
+    }
+
+    #[must_use]
+    pub fn term_count(&self) -> usize {
+        self.terms_by_power.iter().map(Vec::len).sum()
+    }
+
+    /// Microarcseconds.
+    #[must_use]
+    pub fn evaluate(&self, T: f64, args: &[f64; 14]) -> f64 {
+        let mut sum = 0.0;
+        let mut t_j = 1.0;
+        for terms in &self.terms_by_power {
+            // Smallest terms first.
+            let s: f64 = terms
+                .iter()
+                .rev()
+                .map(|term| {
+                    let arg: f64 = term
+                        .multipliers
+                        .iter()
+                        .zip(args)
+                        .map(|(&m, a)| f64::from(m) * a)
+                        .sum();
+                    let (sin, cos) = arg.sin_cos();
+                    term.sin * sin + term.cos * cos
+                })
+                .sum();
+            sum += s * t_j;
+            t_j *= T;
+        }
+        sum
+    }
+}
+
+/// The full IAU 2000A nutation model (1365 luni-solar and planetary terms). The tables are
+/// distributed by the IERS as tab5.3a.txt (longitude) and tab5.3b.txt (obliquity).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Iau2000a {
+    pub longitude: Series,
+    pub obliquity: Series,
+}
+
+impl Iau2000a {
+    pub fn parse(tab5_3a: &str, tab5_3b: &str) -> Result<Self> {
+        Ok(Self {
+            longitude: Series::parse_iers(tab5_3a).context("table 5.3a")?,
+            obliquity: Series::parse_iers(tab5_3b).context("table 5.3b")?,
+        })
+    }
+
+    #[must_use]
+    pub fn nutation(&self, jd_tt: f64) -> Nutation {
+        const UAS_TO_RAD: f64 = ARCSEC_TO_RAD * 1e-6;
+        let T = julian_centuries_since_j2000(jd_tt);
+        let args = fundamental_arguments(T);
+        Nutation {
+            dpsi: self.longitude.evaluate(T, &args) * UAS_TO_RAD,
+            deps: self.obliquity.evaluate(T, &args) * UAS_TO_RAD,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+#[allow(clippy::excessive_precision)] // SOFA test values, verbatim.
+mod t {
+    use super::*;
+    use crate::julian::{DAYS_PER_JULIAN_CENTURY, J2000};
+    use insta::assert_ron_snapshot;
+
+    /// The 2000B terms written out as IERS tables.
+    fn iau2000b_as_iers_tables() -> (String, String) {
+        let row = |i: usize, s: f64, c: f64, nals: &[i8; 5]| {
+            let n: Vec<String> = nals.iter().map(|n| format!("{n:4}")).collect();
+            format!(
+                "{i:6} {s:15.2} {c:12.2} {}    0    0    0    0    0    0    0    0    0\n",
+                n.join(" ")
+            )
+        };
+        let terms = &iau2000b::TERMS;
+        let mut a = format!(
+            "Table 5.3a (test)\nj = 0  Number of terms = {}\n",
+            terms.len()
+        );
+        let mut b = format!(
+            "Table 5.3b (test)\nj = 0  Number of terms = {}\n",
+            terms.len()
+        );
+        for (i, t) in terms.iter().enumerate() {
+            a += &row(i + 1, t.ps / 10.0, t.pc / 10.0, &t.nals);
+            b += &row(i + 1, t.es / 10.0, t.ec / 10.0, &t.nals);
+        }
+        let a_rates: Vec<_> = terms.iter().filter(|t| t.pst != 0.0).collect();
+        a += &format!("\n j = 1  Number of terms = {}\n", a_rates.len());
+        for (i, t) in a_rates.iter().enumerate() {
+            a += &row(i + 1, t.pst / 10.0, 0.0, &t.nals);
+        }
+        let b_rates: Vec<_> = terms.iter().filter(|t| t.ect != 0.0).collect();
+        b += &format!("\n j = 1  Number of terms = {}\n", b_rates.len());
+        for (i, t) in b_rates.iter().enumerate() {
+            b += &row(i + 1, 0.0, t.ect / 10.0, &t.nals);
+        }
+        (a, b)
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        // SOFA t_sofa.c, t_fal03 etc., T = 0.8.
+        let args = fundamental_arguments(0.8);
+        assert!((args[0] - 5.132369751108684150).abs() < 1e-12);
+        assert!((args[4] - -5.973618440951302183).abs() < 1e-12);
+        assert!((args[7] - 1.744713738913081846).abs() < 1e-12);
+        assert!((args[13] - 0.1950884762240000000e-1).abs() < 1e-12);
+
+        // SOFA t_nut00b: 2400000.5 + 53736.0 TT.
+        let nut = iau2000b(2_400_000.5 + 53736.0);
+        assert!((nut.dpsi - -0.9632552291148362783e-5).abs() < 1e-13);
+        assert!((nut.deps - 0.4063197106621159367e-4).abs() < 1e-13);
+
+        // The 2000A machinery fed the 2000B terms reproduces 2000B, less its planetary offsets.
+        let (a, b) = iau2000b_as_iers_tables();
+        let model = Iau2000a::parse(&a, &b)?;
+        let counts = (model.longitude.term_count(), model.obliquity.term_count());
+        assert_ron_snapshot!(counts, @"(113, 96)");
+        let jd = 2_400_000.5 + 53736.0;
+        let nut_a = model.nutation(jd);
+        assert!((nut_a.dpsi - (nut.dpsi + 0.135e-3 * ARCSEC_TO_RAD)).abs() < 1e-10);
+        assert!((nut_a.deps - (nut.deps - 0.388e-3 * ARCSEC_TO_RAD)).abs() < 1e-10);
+
+        // Counts are checked.
+        let broken = a.replacen("Number of terms = 77", "Number of terms = 78", 1);
+        assert!(Iau2000a::parse(&broken, &b).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn t_planetary() -> anyhow::Result<()> {
+        // SOFA t_fal03 .. t_fapa03, T = 0.8, in table column order.
+        let sofa = [
+            5.132369751108684150,
+            6.226797973505507345,
+            0.2597711366745499518,
+            1.946709205396925672,
+            -5.973618440951302183,
+            5.417338184297289661,
+            3.424900460533758000,
+            1.744713738913081846,
+            3.275506840277781492,
+            5.275711665202481138,
+            5.371574539440827046,
+            5.180636450180413523,
+            2.079343830860413523,
+            0.1950884762240000000e-1,
+        ];
+        for (got, want) in fundamental_arguments(0.8).iter().zip(sofa) {
+            assert!((got - want).abs() < 1e-12, "{got} != {want}");
+        }
+
+        // Rows in the planetary columns, and in the T and T^2 parts, land on the arguments
+        // they name.
+        let a = "Table 5.3a (test)
+j = 0  Number of terms = 2
+     1        1000.00     -500.00    0    0    0    0    0    0    8  -16    4    5    0    0    0    0
+     2         -40.00       20.00    0    0    1   -1    1    0    0   -1    0    0    0    0    0    0
+ j = 1  Number of terms = 1
+     3          30.00       10.00    0    0    0    0    0    0    0    0    0    1    0    0    0    0
+ j = 2  Number of terms = 1
+     4           5.00        0.00    0    0    0    0    0    0    0    0    0    0    0    0    1    2
+";
+        let b = "Table 5.3b (test)
+j = 0  Number of terms = 1
+     1         -10.00      300.00    1    0    0    0    0    1   -1    0    0    0    0    0    0    0
+";
+        let model = Iau2000a::parse(a, b)?;
+        let T = 0.8;
+        let sum = |rows: &[(f64, f64, [i8; 14])], t_j: f64| -> f64 {
+            let terms = rows.iter().map(|(s, c, m)| {
+                let arg: f64 = m.iter().zip(sofa).map(|(&m, a)| f64::from(m) * a).sum();
+                s * arg.sin() + c * arg.cos()
+            });
+            t_j * terms.sum::<f64>()
+        };
+        let (a0, a1, a2, b0) = (
+            [
+                (1000.0, -500.0, [0, 0, 0, 0, 0, 0, 8, -16, 4, 5, 0, 0, 0, 0]),
+                (-40.0, 20.0, [0, 0, 1, -1, 1, 0, 0, -1, 0, 0, 0, 0, 0, 0]),
+            ],
+            [(30.0, 10.0, [0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0])],
+            [(5.0, 0.0, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2])],
+            [(-10.0, 300.0, [1, 0, 0, 0, 0, 1, -1, 0, 0, 0, 0, 0, 0, 0])],
+        );
+        let dpsi = sum(&a0, 1.0) + sum(&a1, T) + sum(&a2, T * T);
+        let deps = sum(&b0, 1.0);
+
+        let nut = model.nutation(J2000 + T * DAYS_PER_JULIAN_CENTURY);
+        let uas = ARCSEC_TO_RAD * 1e-6;
+        assert!((nut.dpsi - dpsi * uas).abs() < 1e-16, "{nut:?}");
+        assert!((nut.deps - deps * uas).abs() < 1e-16, "{nut:?}");
+        Ok(())
+    }
+
+    /// The full series, which isn't in the repository. Set `IERS_TAB5_3_DIR` to a directory
+    /// holding tab5.3a.txt and tab5.3b.txt and run with `--ignored`.
+    #[test]
+    #[ignore = "needs IERS tables 5.3a and 5.3b in IERS_TAB5_3_DIR"]
+    fn t_iau2000a() -> anyhow::Result<()> {
+        let dir = std::env::var("IERS_TAB5_3_DIR").context("IERS_TAB5_3_DIR")?;
+        let read = |name: &str| {
+            let path = std::path::Path::new(&dir).join(name);
+            std::fs::read_to_string(&path).with_context(|| format!("{}", path.display()))
+        };
+        let model = Iau2000a::parse(&read("tab5.3a.txt")?, &read("tab5.3b.txt")?)?;
+
+        // SOFA t_nut00a: 2400000.5 + 53736.0 TT.
+        let nut = model.nutation(2_400_000.5 + 53736.0);
+        assert!((nut.dpsi - -0.9630909107115518431e-5).abs() < 1e-13);
+        assert!((nut.deps - 0.4063239174001678710e-4).abs() < 1e-13);
+        Ok(())
+    }
+}
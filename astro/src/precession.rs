@@ -0,0 +1,182 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! IAU 2006 precession (Capitaine et al. 2003, Hilton et al. 2006) and the frame bias between
+//! the GCRS and the mean equator and equinox of J2000, both by way of the four
+//! Fukushima-Williams angles, as in SOFA's `iauPfw06` and `iauFw2m`.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::angle::ARCSEC_TO_RAD;
+use crate::julian::{julian_centuries_since_j2000, J2000};
+use crate::nutation::Nutation;
+use crate::rotation::{mul, rx, rz, transpose, Matrix3, IDENTITY};
+
+/// Mean obliquity of the ecliptic, IAU 2006, radians.
+#[must_use]
+pub fn mean_obliquity_2006(jd_tt: f64) -> f64 {
+    let T = julian_centuries_since_j2000(jd_tt);
+    (84381.406
+        + T * (-46.836769
+            + T * (-0.0001831 + T * (0.00200340 + T * (-0.000000576 + T * -0.0000000434)))))
+        * ARCSEC_TO_RAD
+}
+
+/// The Fukushima-Williams precession angles, radians.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct FukushimaWilliams {
+    /// F-W angle gamma_bar, from the GCRS right ascension origin to the ecliptic node.
+    pub gamb: f64,
+
+    /// F-W angle phi_bar, the obliquity of the ecliptic of date on the GCRS equator.
+    pub phib: f64,
+
+    /// F-W angle psi_bar, along the ecliptic of date.
+    pub psib: f64,
+
+    /// Mean obliquity of date.
+    pub epsa: f64,
+}
+
+impl FukushimaWilliams {
+    #[must_use]
+    pub fn iau2006(jd_tt: f64) -> Self {
+        let T = julian_centuries_since_j2000(jd_tt);
+        let asec = |x: f64| x * ARCSEC_TO_RAD;
+        Self {
+            gamb: asec(
+                -0.052928
+                    + T * (10.556378
+                        + T * (0.4932044
+                            + T * (-0.00031238 + T * (-0.000002788 + T * 0.0000000260)))),
+            ),
+            phib: asec(
+                84381.412819
+                    + T * (-46.811016
+                        + T * (0.0511268
+                            + T * (0.00053289 + T * (-0.000000440 + T * -0.0000000176)))),
+            ),
+            psib: asec(
+                -0.041775
+                    + T * (5038.481484
+                        + T * (1.5584175
+                            + T * (-0.00018522 + T * (-0.000026452 + T * -0.0000000148)))),
+            ),
+            epsa: mean_obliquity_2006(jd_tt),
+        }
+    }
+
+    /// The bias-precession matrix, GCRS to mean of date.
+    #[must_use]
+    pub fn matrix(&self) -> Matrix3 {
+        fw_matrix(self.gamb, self.phib, self.psib, self.epsa)
+    }
+
+    /// The bias-precession-nutation matrix, GCRS to true of date.
+    #[must_use]
+    pub fn matrix_with_nutation(&self, nut: Nutation) -> Matrix3 {
+        fw_matrix(
+            self.gamb,
+            self.phib,
+            self.psib + nut.dpsi,
+            self.epsa + nut.deps,
+        )
+    }
+}
+
+/// Forms a rotation matrix from Fukushima-Williams angles, as SOFA's `iauFw2m`.
+#[must_use]
+pub fn fw_matrix(gamb: f64, phib: f64, psi: f64, eps: f64) -> Matrix3 {
+    [rz(gamb), rx(phib), rz(-psi), rx(-eps)]
+        .iter()
+        .fold(IDENTITY, |r, m| mul(m, &r))
+}
+
+/// The frame bias matrix, GCRS to the mean equator and equinox of J2000. About 23 mas.
+#[must_use]
+pub fn frame_bias() -> Matrix3 {
+    FukushimaWilliams::iau2006(J2000).matrix()
+}
+
+/// Bias-precession matrix, GCRS to mean of date.
+#[must_use]
+pub fn bias_precession(jd_tt: f64) -> Matrix3 {
+    FukushimaWilliams::iau2006(jd_tt).matrix()
+}
+
+/// Precession matrix alone, mean J2000 to mean of date.
+#[must_use]
+pub fn precession(jd_tt: f64) -> Matrix3 {
+    mul(&bias_precession(jd_tt), &transpose(&frame_bias()))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+#[allow(clippy::excessive_precision)] // SOFA test values, verbatim.
+mod t {
+    use super::*;
+    use crate::rotation::max_abs_diff;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        // SOFA t_obl06.
+        assert!((mean_obliquity_2006(2_400_000.5 + 54388.0) - 0.4090749229387258204).abs() < 1e-14);
+
+        // SOFA t_pfw06.
+        let fw = FukushimaWilliams::iau2006(2_400_000.5 + 50123.9999);
+        assert!((fw.gamb - -0.2243387670997995690e-5).abs() < 1e-14);
+        assert!((fw.phib - 0.4091014602391312808).abs() < 1e-12);
+        assert!((fw.psib - -0.9501954178013031895e-3).abs() < 1e-12);
+        assert!((fw.epsa - 0.4091014316587367491).abs() < 1e-12);
+
+        // SOFA t_pmat06.
+        let rbp = bias_precession(2_400_000.5 + 50123.9999);
+        let expected = [
+            [
+                0.9999995505176007047,
+                0.8695404617348208406e-3,
+                0.3779735201865589104e-3,
+            ],
+            [
+                -0.8695404723772031414e-3,
+                0.9999996219496027161,
+                -0.1361752497080270143e-6,
+            ],
+            [
+                -0.3779734957034089490e-3,
+                -0.1924880847894457113e-6,
+                0.9999999285679971958,
+            ],
+        ];
+        assert!(max_abs_diff(&rbp, &expected) < 1e-12);
+
+        // The frame bias is a few tens of mas; the precession part is identity at J2000.
+        let b = frame_bias();
+        assert!((b[0][1] / ARCSEC_TO_RAD * 1e3).abs() < 30.0);
+        assert!(max_abs_diff(&precession(J2000), &IDENTITY) < 1e-15);
+    }
+}
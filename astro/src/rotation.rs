@@ -0,0 +1,118 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! 3x3 rotation matrices, in the SOFA convention: `rx(a)` rotates the *frame* by `a` about
+//! its x axis, so applying it to a vector gives that vector's components in the new frame.
+//! Matrices compose right to left, as in `mul(b, a)` = "first a, then b".
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+//? use serde::{Deserialize, Serialize};
+
+/// Row-major: `m[row][col]`.
+pub type Matrix3 = [[f64; 3]; 3];
+
+pub const IDENTITY: Matrix3 = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+#[must_use]
+pub fn rx(angle: f64) -> Matrix3 {
+    let (s, c) = angle.sin_cos();
+    [[1.0, 0.0, 0.0], [0.0, c, s], [0.0, -s, c]]
+}
+
+#[must_use]
+pub fn ry(angle: f64) -> Matrix3 {
+    let (s, c) = angle.sin_cos();
+    [[c, 0.0, -s], [0.0, 1.0, 0.0], [s, 0.0, c]]
+}
+
+#[must_use]
+pub fn rz(angle: f64) -> Matrix3 {
+    let (s, c) = angle.sin_cos();
+    [[c, s, 0.0], [-s, c, 0.0], [0.0, 0.0, 1.0]]
+}
+
+/// The matrix product `a b`.
+#[must_use]
+pub fn mul(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut m = [[0.0; 3]; 3];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, x) in row.iter_mut().enumerate() {
+            *x = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    m
+}
+
+/// The inverse of a rotation.
+#[must_use]
+pub fn transpose(m: &Matrix3) -> Matrix3 {
+    let mut t = [[0.0; 3]; 3];
+    for (i, row) in m.iter().enumerate() {
+        for (j, &x) in row.iter().enumerate() {
+            t[j][i] = x;
+        }
+    }
+    t
+}
+
+#[must_use]
+pub fn mul_vec(m: &Matrix3, v: &[f64; 3]) -> [f64; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+/// Largest absolute difference between two matrices' elements.
+#[must_use]
+pub fn max_abs_diff(a: &Matrix3, b: &Matrix3) -> f64 {
+    a.iter()
+        .flatten()
+        .zip(b.iter().flatten())
+        .map(|(x, y)| (x - y).abs())
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        let q = std::f64::consts::FRAC_PI_2;
+
+        // Turning the frame +90° about z puts the old +y axis along the new +x.
+        let v = mul_vec(&rz(q), &[0.0, 1.0, 0.0]);
+        assert!(
+            max_abs_diff(
+                &[v, [0.0; 3], [0.0; 3]],
+                &[[1.0, 0.0, 0.0], [0.0; 3], [0.0; 3]]
+            ) < 1e-15
+        );
+
+        let m = mul(&rx(0.3), &mul(&ry(-0.2), &rz(1.1)));
+        assert!(max_abs_diff(&mul(&m, &transpose(&m)), &IDENTITY) < 1e-15);
+        assert!(max_abs_diff(&mul(&rz(0.4), &rz(-0.4)), &IDENTITY) < 1e-15);
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! The 25 brightest stars, plus Polaris, from the Hipparcos catalogue (ICRS, J2000.0),
+//! rounded. Alpha Centauri is the A component with the combined magnitude.
+
+use super::Star;
+use crate::julian::J2000;
+
+struct Row {
+    name: &'static str,
+    hr: u32,
+    hip: u32,
+    ra_hms: (f64, f64, f64),
+    dec_dms: (f64, f64, f64),
+    pm_ra_cosdec: f64,
+    pm_dec: f64,
+    parallax: f64,
+    vmag: f64,
+    b_v: f64,
+}
+
+#[rustfmt::skip]
+const ROWS: [Row; 26] = [
+    Row { name: "Sirius",          hr: 2491, hip: 32349, ra_hms: ( 6.0, 45.0,  8.917), dec_dms: (-16.0, 42.0, 58.02), pm_ra_cosdec:  -546.01, pm_dec: -1223.07, parallax: 379.21, vmag: -1.46, b_v:  0.00 },
+    Row { name: "Canopus",         hr: 2326, hip: 30438, ra_hms: ( 6.0, 23.0, 57.110), dec_dms: (-52.0, 41.0, 44.38), pm_ra_cosdec:    19.93, pm_dec:    23.24, parallax:  10.55, vmag: -0.74, b_v:  0.15 },
+    Row { name: "Rigil Kentaurus", hr: 5459, hip: 71683, ra_hms: (14.0, 39.0, 36.494), dec_dms: (-60.0, 50.0,  2.37), pm_ra_cosdec: -3679.25, pm_dec:   473.67, parallax: 742.12, vmag: -0.27, b_v:  0.71 },
+    Row { name: "Arcturus",        hr: 5340, hip: 69673, ra_hms: (14.0, 15.0, 39.672), dec_dms: ( 19.0, 10.0, 56.67), pm_ra_cosdec: -1093.45, pm_dec: -1999.40, parallax:  88.85, vmag: -0.05, b_v:  1.23 },
+    Row { name: "Vega",            hr: 7001, hip: 91262, ra_hms: (18.0, 36.0, 56.336), dec_dms: ( 38.0, 47.0,  1.28), pm_ra_cosdec:   200.94, pm_dec:   286.23, parallax: 128.93, vmag:  0.03, b_v:  0.00 },
+    Row { name: "Capella",         hr: 1708, hip: 24608, ra_hms: ( 5.0, 16.0, 41.359), dec_dms: ( 45.0, 59.0, 52.77), pm_ra_cosdec:    75.52, pm_dec:  -427.11, parallax:  77.29, vmag:  0.08, b_v:  0.80 },
+    Row { name: "Rigel",           hr: 1713, hip: 24436, ra_hms: ( 5.0, 14.0, 32.272), dec_dms: ( -8.0, 12.0,  5.90), pm_ra_cosdec:     1.31, pm_dec:     0.50, parallax:   4.22, vmag:  0.13, b_v: -0.03 },
+    Row { name: "Procyon",         hr: 2943, hip: 37279, ra_hms: ( 7.0, 39.0, 18.119), dec_dms: (  5.0, 13.0, 29.96), pm_ra_cosdec:  -716.57, pm_dec: -1034.58, parallax: 285.93, vmag:  0.34, b_v:  0.42 },
+    Row { name: "Achernar",        hr:  472, hip:  7588, ra_hms: ( 1.0, 37.0, 42.846), dec_dms: (-57.0, 14.0, 12.31), pm_ra_cosdec:    88.02, pm_dec:   -40.08, parallax:  22.68, vmag:  0.46, b_v: -0.16 },
+    Row { name: "Betelgeuse",      hr: 2061, hip: 27989, ra_hms: ( 5.0, 55.0, 10.305), dec_dms: (  7.0, 24.0, 25.43), pm_ra_cosdec:    27.33, pm_dec:    10.86, parallax:   7.63, vmag:  0.50, b_v:  1.85 },
+    Row { name: "Hadar",           hr: 5267, hip: 68702, ra_hms: (14.0,  3.0, 49.405), dec_dms: (-60.0, 22.0, 22.93), pm_ra_cosdec:   -33.96, pm_dec:   -25.06, parallax:   6.21, vmag:  0.61, b_v: -0.23 },
+    Row { name: "Altair",          hr: 7557, hip: 97649, ra_hms: (19.0, 50.0, 46.999), dec_dms: (  8.0, 52.0,  5.96), pm_ra_cosdec:   536.82, pm_dec:   385.54, parallax: 194.44, vmag:  0.76, b_v:  0.22 },
+    Row { name: "Acrux",           hr: 4730, hip: 60718, ra_hms: (12.0, 26.0, 35.895), dec_dms: (-63.0,  5.0, 56.73), pm_ra_cosdec:   -35.37, pm_dec:   -14.73, parallax:  10.17, vmag:  0.76, b_v: -0.24 },
+    Row { name: "Aldebaran",       hr: 1457, hip: 21421, ra_hms: ( 4.0, 35.0, 55.239), dec_dms: ( 16.0, 30.0, 33.49), pm_ra_cosdec:    62.78, pm_dec:  -189.36, parallax:  50.09, vmag:  0.86, b_v:  1.54 },
+    Row { name: "Antares",         hr: 6134, hip: 80763, ra_hms: (16.0, 29.0, 24.460), dec_dms: (-26.0, 25.0, 55.21), pm_ra_cosdec:   -10.16, pm_dec:   -23.21, parallax:   5.89, vmag:  0.96, b_v:  1.83 },
+    Row { name: "Spica",           hr: 5056, hip: 65474, ra_hms: (13.0, 25.0, 11.579), dec_dms: (-11.0,  9.0, 40.76), pm_ra_cosdec:   -42.50, pm_dec:   -31.73, parallax:  13.06, vmag:  0.97, b_v: -0.23 },
+    Row { name: "Pollux",          hr: 2990, hip: 37826, ra_hms: ( 7.0, 45.0, 18.950), dec_dms: ( 28.0,  1.0, 34.32), pm_ra_cosdec:  -626.55, pm_dec:   -45.80, parallax:  96.74, vmag:  1.14, b_v:  1.00 },
+    Row { name: "Fomalhaut",       hr: 8728, hip: 113368, ra_hms: (22.0, 57.0, 39.047), dec_dms: (-29.0, 37.0, 20.05), pm_ra_cosdec:   329.22, pm_dec:  -164.22, parallax: 130.08, vmag:  1.16, b_v:  0.09 },
+    Row { name: "Deneb",           hr: 7924, hip: 102098, ra_hms: (20.0, 41.0, 25.915), dec_dms: ( 45.0, 16.0, 49.22), pm_ra_cosdec:     1.56, pm_dec:     1.55, parallax:   1.01, vmag:  1.25, b_v:  0.09 },
+    Row { name: "Mimosa",          hr: 4853, hip: 62434, ra_hms: (12.0, 47.0, 43.268), dec_dms: (-59.0, 41.0, 19.58), pm_ra_cosdec:   -48.24, pm_dec:   -12.82, parallax:   9.25, vmag:  1.25, b_v: -0.23 },
+    Row { name: "Regulus",         hr: 3982, hip: 49669, ra_hms: (10.0,  8.0, 22.311), dec_dms: ( 11.0, 58.0,  1.95), pm_ra_cosdec:  -249.40, pm_dec:     4.91, parallax:  42.09, vmag:  1.35, b_v: -0.11 },
+    Row { name: "Adhara",          hr: 2618, hip: 33579, ra_hms: ( 6.0, 58.0, 37.548), dec_dms: (-28.0, 58.0, 19.51), pm_ra_cosdec:     2.63, pm_dec:     2.29, parallax:   7.57, vmag:  1.50, b_v: -0.21 },
+    Row { name: "Castor",          hr: 2891, hip: 36850, ra_hms: ( 7.0, 34.0, 35.863), dec_dms: ( 31.0, 53.0, 17.82), pm_ra_cosdec:  -206.33, pm_dec:  -148.18, parallax:  64.12, vmag:  1.58, b_v:  0.03 },
+    Row { name: "Shaula",          hr: 6527, hip: 85927, ra_hms: (17.0, 33.0, 36.520), dec_dms: (-37.0,  6.0, 13.76), pm_ra_cosdec:    -8.90, pm_dec:   -29.95, parallax:   4.64, vmag:  1.62, b_v: -0.22 },
+    Row { name: "Bellatrix",       hr: 1790, hip: 25336, ra_hms: ( 5.0, 25.0,  7.863), dec_dms: (  6.0, 20.0, 58.93), pm_ra_cosdec:    -8.75, pm_dec:   -13.28, parallax:  12.92, vmag:  1.64, b_v: -0.22 },
+    Row { name: "Polaris",         hr:  424, hip: 11767, ra_hms: ( 2.0, 31.0, 49.095), dec_dms: ( 89.0, 15.0, 50.79), pm_ra_cosdec:    44.48, pm_dec:   -11.85, parallax:   7.54, vmag:  1.98, b_v:  0.60 },
+];
+
+pub(super) fn stars() -> Vec<Star> {
+    ROWS.iter()
+        .map(|row| {
+            let (h, m, s) = row.ra_hms;
+            let (d, dm, ds) = row.dec_dms;
+            let dec_deg = d.signum() * (d.abs() + dm / 60.0 + ds / 3600.0);
+            Star {
+                name: row.name.to_string(),
+                hr: Some(row.hr),
+                hip: Some(row.hip),
+                ra: ((h + m / 60.0 + s / 3600.0) * 15.0).to_radians(),
+                dec: dec_deg.to_radians(),
+                epoch_jd_tt: J2000,
+                pm_ra_cosdec_mas_yr: row.pm_ra_cosdec,
+                pm_dec_mas_yr: row.pm_dec,
+                parallax_mas: row.parallax,
+                radial_velocity_km_s: 0.0,
+                vmag: row.vmag,
+                b_v: Some(row.b_v),
+            }
+        })
+        .collect()
+}
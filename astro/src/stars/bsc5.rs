@@ -0,0 +1,125 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Reader for the Yale Bright Star Catalogue, CDS V/50. Byte positions are from its ReadMe.
+
+use anyhow::{Context, Result};
+
+use super::{columns, Star};
+use crate::julian::J2000;
+
+/// Parses an optional float field; blank is `None`.
+fn opt_f64(line: &str, first: usize, last: usize) -> Result<Option<f64>> {
+    let s = columns(line, first, last);
+    if s.is_empty() {
+        return Ok(None);
+    }
+    // The catalog writes "+.123" and "-.012".
+    let s = s.replacen("+.", "0.", 1).replacen("-.", "-0.", 1);
+    s.parse()
+        .map(Some)
+        .with_context(|| format!("bad number {s:?} in columns {first}-{last}"))
+}
+
+fn f64_or_zero(line: &str, first: usize, last: usize) -> Result<f64> {
+    Ok(opt_f64(line, first, last)?.unwrap_or_default())
+}
+
+pub(super) fn parse(text: &str) -> Result<Vec<Star>> {
+    let mut stars = Vec::new();
+    for (ix, line) in text.lines().enumerate() {
+        let line_no = ix + 1;
+        if columns(line, 76, 77).is_empty() {
+            // Novae and other entries without a J2000 position.
+            continue;
+        }
+        let star = (|| -> Result<Star> {
+            let hr: u32 = columns(line, 1, 4).parse().context("HR")?;
+
+            let ra_hours = f64_or_zero(line, 76, 77)?
+                + f64_or_zero(line, 78, 79)? / 60.0
+                + f64_or_zero(line, 80, 83)? / 3600.0;
+            let dec_abs = f64_or_zero(line, 85, 86)?
+                + f64_or_zero(line, 87, 88)? / 60.0
+                + f64_or_zero(line, 89, 90)? / 3600.0;
+            let dec_deg = if columns(line, 84, 84) == "-" {
+                -dec_abs
+            } else {
+                dec_abs
+            };
+
+            let name = match columns(line, 5, 14) {
+                "" => format!("HR {hr}"),
+                s => s.split_whitespace().collect::<Vec<_>>().join(" "),
+            };
+
+            Ok(Star {
+                name,
+                hr: Some(hr),
+                hip: None,
+                ra: (ra_hours * 15.0).to_radians(),
+                dec: dec_deg.to_radians(),
+                epoch_jd_tt: J2000,
+                pm_ra_cosdec_mas_yr: f64_or_zero(line, 149, 154)? * 1000.0,
+                pm_dec_mas_yr: f64_or_zero(line, 155, 160)? * 1000.0,
+                parallax_mas: f64_or_zero(line, 162, 166)?.max(0.0) * 1000.0,
+                radial_velocity_km_s: f64_or_zero(line, 167, 170)?,
+                vmag: opt_f64(line, 103, 107)?.context("Vmag")?,
+                b_v: opt_f64(line, 110, 114)?,
+            })
+        })()
+        .with_context(|| format!("BSC5 line {line_no}"))?;
+        stars.push(star);
+    }
+    Ok(stars)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+
+    /// Places each `(first_column, text)` into a blank fixed-width line.
+    fn fixed_width(fields: &[(usize, &str)]) -> String {
+        let mut line = vec![b' '; 197];
+        for &(first, s) in fields {
+            line[first - 1..first - 1 + s.len()].copy_from_slice(s.as_bytes());
+        }
+        String::from_utf8(line).unwrap()
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let arcturus = fixed_width(&[
+            (1, "5340"),
+            (5, "16Alp Boo"),
+            (76, "141539.7"),
+            (84, "+191057"),
+            (103, "-0.04"),
+            (110, "+1.23"),
+            (149, "-1.093-1.999"),
+            (162, "+.090"),
+            (167, "-005"),
+        ]);
+        let nova = fixed_width(&[(1, "  92"), (5, "        "), (103, " 6.00")]);
+        let text = format!("{arcturus}\n{nova}\n");
+
+        let stars = parse(&text)?;
+        assert_eq!(stars.len(), 1);
+        let s = &stars[0];
+        assert_eq!(s.name, "16Alp Boo");
+        assert_eq!(s.hr, Some(5340));
+        assert!((s.ra.to_degrees() - 213.9154).abs() < 1e-3);
+        assert!((s.dec.to_degrees() - 19.1825).abs() < 1e-3);
+        assert_eq!((s.vmag, s.b_v), (-0.04, Some(1.23)));
+        assert_eq!((s.pm_ra_cosdec_mas_yr, s.pm_dec_mas_yr), (-1093.0, -1999.0));
+        assert_eq!((s.parallax_mas, s.radial_velocity_km_s), (90.0, -5.0));
+
+        assert!(parse(&fixed_width(&[(1, "5340"), (76, "14"), (103, "x")])).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Reader for the Hipparcos main catalogue, CDS I/239 `hip_main.dat`. Field numbers are the
+//! `H0`..`H77` of its ReadMe.
+
+use anyhow::{bail, Context, Result};
+
+use super::{Star, HIPPARCOS_EPOCH};
+
+const H_HIP: usize = 1;
+const H_VMAG: usize = 5;
+const H_RA_DEG: usize = 8;
+const H_DEC_DEG: usize = 9;
+const H_PARALLAX: usize = 11;
+const H_PM_RA: usize = 12;
+const H_PM_DEC: usize = 13;
+const H_B_V: usize = 37;
+const FIELD_COUNT: usize = 78;
+
+pub(super) fn parse(text: &str) -> Result<Vec<Star>> {
+    let mut stars = Vec::new();
+    for (ix, line) in text.lines().enumerate() {
+        let line_no = ix + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+        if fields.len() < FIELD_COUNT {
+            bail!(
+                "Hipparcos line {line_no}: {} fields, expected {FIELD_COUNT}",
+                fields.len()
+            );
+        }
+        if fields[H_RA_DEG].is_empty() {
+            // A handful of entries have no astrometric solution.
+            continue;
+        }
+
+        let star = (|| -> Result<Star> {
+            let opt_f64 = |h: usize| -> Result<Option<f64>> {
+                match fields[h] {
+                    "" => Ok(None),
+                    s => s
+                        .parse()
+                        .map(Some)
+                        .with_context(|| format!("H{h}: bad number {s:?}")),
+                }
+            };
+            let hip: u32 = fields[H_HIP].parse().context("HIP")?;
+            Ok(Star {
+                name: format!("HIP {hip}"),
+                hr: None,
+                hip: Some(hip),
+                ra: opt_f64(H_RA_DEG)?.unwrap_or_default().to_radians(),
+                dec: opt_f64(H_DEC_DEG)?.context("DEdeg")?.to_radians(),
+                epoch_jd_tt: HIPPARCOS_EPOCH,
+                pm_ra_cosdec_mas_yr: opt_f64(H_PM_RA)?.unwrap_or_default(),
+                pm_dec_mas_yr: opt_f64(H_PM_DEC)?.unwrap_or_default(),
+                parallax_mas: opt_f64(H_PARALLAX)?.unwrap_or_default().max(0.0),
+                radial_velocity_km_s: 0.0,
+                vmag: opt_f64(H_VMAG)?.context("Vmag")?,
+                b_v: opt_f64(H_B_V)?,
+            })
+        })()
+        .with_context(|| format!("Hipparcos line {line_no}"))?;
+        stars.push(star);
+    }
+    Ok(stars)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+
+    fn line(fields: &[(usize, &str)]) -> String {
+        let mut v = vec![""; FIELD_COUNT];
+        v[0] = "H";
+        for &(h, s) in fields {
+            v[h] = s;
+        }
+        v.join("|")
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let arcturus = line(&[
+            (H_HIP, " 69673"),
+            (H_VMAG, "-0.05"),
+            (H_RA_DEG, "213.91811403"),
+            (H_DEC_DEG, "+19.18726997"),
+            (H_PARALLAX, "  88.85"),
+            (H_PM_RA, "-1093.45"),
+            (H_PM_DEC, "-1999.40"),
+            (H_B_V, " 1.239"),
+        ]);
+        let no_astrometry = line(&[(H_HIP, "55203"), (H_VMAG, "4.0")]);
+        let text = format!("{arcturus}\n{no_astrometry}\n");
+
+        let stars = parse(&text)?;
+        assert_eq!(stars.len(), 1);
+        let s = &stars[0];
+        assert_eq!((s.name.as_str(), s.hip), ("HIP 69673", Some(69673)));
+        assert_eq!((s.ra.to_degrees() * 1e6).round(), 213_918_114.0);
+        assert_eq!((s.dec.to_degrees() * 1e6).round(), 19_187_270.0);
+        assert_eq!(
+            (s.pm_ra_cosdec_mas_yr, s.pm_dec_mas_yr, s.parallax_mas),
+            (-1093.45, -1999.40, 88.85)
+        );
+        assert_eq!((s.vmag, s.b_v), (-0.05, Some(1.239)));
+        assert_eq!(s.epoch_jd_tt, HIPPARCOS_EPOCH);
+
+        assert!(parse("H|1|2").is_err());
+        Ok(())
+    }
+}
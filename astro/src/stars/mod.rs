@@ -0,0 +1,253 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Star catalogs: the Yale Bright Star Catalogue (BSC5), the Hipparcos main catalogue, and a
+//! few dozen of the brightest stars compiled in. Positions are carried from the catalog epoch
+//! to any date by space motion and then precessed.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::angle::{norm_0_tau, ARCSEC_TO_RAD};
+use crate::ephemeris::AU_KM;
+use crate::frames::gcrs_to_mod;
+use crate::julian::{DAYS_PER_JULIAN_YEAR, J2000, SECONDS_PER_DAY};
+use crate::rotation::mul_vec;
+
+mod bright;
+mod bsc5;
+mod hipparcos;
+
+const MAS_TO_RAD: f64 = ARCSEC_TO_RAD / 1000.0;
+
+/// The Hipparcos catalogue epoch, J1991.25, TT.
+pub const HIPPARCOS_EPOCH: f64 = J2000 - 8.75 * DAYS_PER_JULIAN_YEAR;
+
+/// One star, as of its catalog epoch. Coordinates are ICRS (or FK5, for the BSC, which is
+/// within a few tens of mas of it).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Star {
+    /// Proper name or Bayer/Flamsteed designation, if any.
+    pub name: String,
+
+    /// Harvard Revised (Bright Star Catalogue) number.
+    pub hr: Option<u32>,
+
+    /// Hipparcos number.
+    pub hip: Option<u32>,
+
+    /// Right ascension and declination at `epoch_jd_tt`, radians.
+    pub ra: f64,
+    pub dec: f64,
+
+    pub epoch_jd_tt: f64,
+
+    /// Proper motion in right ascension, including the cos(dec) factor, mas/yr.
+    pub pm_ra_cosdec_mas_yr: f64,
+
+    /// Proper motion in declination, mas/yr.
+    pub pm_dec_mas_yr: f64,
+
+    /// Zero if unknown.
+    pub parallax_mas: f64,
+
+    /// Zero if unknown.
+    pub radial_velocity_km_s: f64,
+
+    /// Visual magnitude.
+    pub vmag: f64,
+
+    /// B-V color index, if known.
+    pub b_v: Option<f64>,
+}
+
+impl Star {
+    /// Unit vector toward the star at a date, ICRS axes, by linear space motion from the
+    /// catalog epoch. Includes the foreshortening from radial velocity when the parallax is
+    /// known. This is the astrometric place: no aberration or deflection.
+    #[must_use]
+    pub fn direction_at(&self, jd_tt: f64) -> [f64; 3] {
+        let (sin_ra, cos_ra) = self.ra.sin_cos();
+        let (sin_dec, cos_dec) = self.dec.sin_cos();
+        let u = [cos_dec * cos_ra, cos_dec * sin_ra, sin_dec];
+        let e_ra = [-sin_ra, cos_ra, 0.0];
+        let e_dec = [-sin_dec * cos_ra, -sin_dec * sin_ra, cos_dec];
+
+        let years = (jd_tt - self.epoch_jd_tt) / DAYS_PER_JULIAN_YEAR;
+        let mu_ra = self.pm_ra_cosdec_mas_yr * MAS_TO_RAD;
+        let mu_dec = self.pm_dec_mas_yr * MAS_TO_RAD;
+
+        // Position and velocity in units of the distance, per year.
+        let radial = if 0.0 < self.parallax_mas {
+            let au_per_year =
+                self.radial_velocity_km_s * DAYS_PER_JULIAN_YEAR * SECONDS_PER_DAY / AU_KM;
+            au_per_year * self.parallax_mas * MAS_TO_RAD
+        } else {
+            0.0
+        };
+        let p: [f64; 3] = std::array::from_fn(|i| {
+            u[i] + years * (mu_ra * e_ra[i] + mu_dec * e_dec[i] + radial * u[i])
+        });
+        let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+        p.map(|x| x / r)
+    }
+
+    /// Right ascension and declination at a date, referred to the mean equator and equinox of
+    /// that date (IAU 2006 precession), radians.
+    #[must_use]
+    pub fn mean_of_date(&self, jd_tt: f64) -> (f64, f64) {
+        let [x, y, z] = mul_vec(&gcrs_to_mod(jd_tt), &self.direction_at(jd_tt));
+        (norm_0_tau(y.atan2(x)), z.atan2((x * x + y * y).sqrt()))
+    }
+}
+
+/// A list of stars.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StarCatalog {
+    pub name: String,
+    pub stars: Vec<Star>,
+}
+
+impl StarCatalog {
+    /// The Yale Bright Star Catalogue, 5th revised ed. (Hoffleit & Warren 1991), in the
+    /// fixed-width layout of CDS V/50 `catalog`. Entries without positions are skipped.
+    pub fn parse_bsc5(text: &str) -> Result<Self> {
+        Ok(Self {
+            name: "BSC5".to_string(),
+            stars: bsc5::parse(text)?,
+        })
+    }
+
+    /// The Hipparcos main catalogue (ESA 1997), in the `|`-separated layout of CDS I/239
+    /// `hip_main.dat`. Entries without astrometry are skipped.
+    pub fn parse_hipparcos(text: &str) -> Result<Self> {
+        Ok(Self {
+            name: "Hipparcos".to_string(),
+            stars: hipparcos::parse(text)?,
+        })
+    }
+
+    /// The brightest stars, compiled in, with positions good to about an arcsecond.
+    #[must_use]
+    pub fn bright_stars() -> Self {
+        Self {
+            name: "Bright stars".to_string(),
+            stars: bright::stars(),
+        }
+    }
+
+    /// Stars of at most `vmag`, which is brighter.
+    pub fn brighter_than(&self, vmag: f64) -> impl Iterator<Item = &Star> {
+        self.stars.iter().filter(move |s| s.vmag <= vmag)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&Star> {
+        self.stars
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Size to draw a star relative to one of `reference_vmag`, so that the drawn area goes as
+/// the flux.
+#[must_use]
+pub fn relative_size(vmag: f64, reference_vmag: f64) -> f64 {
+    10_f64.powf(-0.2 * (vmag - reference_vmag))
+}
+
+/// A rough sRGB color for a B-V color index, from blue-white (-0.4) to orange-red (2.0).
+#[must_use]
+pub fn color_from_b_v(b_v: f64) -> [u8; 3] {
+    const STOPS: [(f64, [f64; 3]); 5] = [
+        (-0.4, [155.0, 176.0, 255.0]),
+        (0.0, [202.0, 215.0, 255.0]),
+        (0.6, [255.0, 244.0, 234.0]),
+        (1.2, [255.0, 210.0, 161.0]),
+        (2.0, [255.0, 160.0, 96.0]),
+    ];
+    let b_v = b_v.clamp(STOPS[0].0, STOPS[STOPS.len() - 1].0);
+    let ix = STOPS
+        .iter()
+        .rposition(|(x, _)| *x <= b_v)
+        .unwrap_or(0)
+        .min(STOPS.len() - 2);
+    let ((x0, c0), (x1, c1)) = (STOPS[ix], STOPS[ix + 1]);
+    let k = (b_v - x0) / (x1 - x0);
+    std::array::from_fn(|i| (c0[i] + k * (c1[i] - c0[i])).round() as u8)
+}
+
+/// Reads columns `first..=last` (1-based, as in CDS ReadMe files), trimmed.
+fn columns(line: &str, first: usize, last: usize) -> &str {
+    line.get(first - 1..last.min(line.len()))
+        .unwrap_or_default()
+        .trim()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use crate::apparent::angular_separation;
+    use insta::assert_ron_snapshot;
+
+    fn round_to(x: f64, places: i32) -> f64 {
+        let k = 10f64.powi(places);
+        (x * k).round() / k
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let cat = StarCatalog::bright_stars();
+        assert_ron_snapshot!(cat.stars.len(), @"26");
+        let brightest: Vec<_> = cat.brighter_than(0.0).map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            brightest,
+            ["Sirius", "Canopus", "Rigil Kentaurus", "Arcturus"]
+        );
+
+        // Arcturus moves 2.28" a year.
+        let arcturus = cat
+            .find_by_name("arcturus")
+            .ok_or_else(|| anyhow!("no Arcturus"))?;
+        let century = 100.0 * DAYS_PER_JULIAN_YEAR;
+        let moved = angular_separation(
+            arcturus.direction_at(J2000),
+            arcturus.direction_at(J2000 + century),
+        );
+        assert_ron_snapshot!(round_to(moved / ARCSEC_TO_RAD, 1), @"227.9");
+
+        // Precession carries Polaris closer to the pole this century.
+        let polaris = cat
+            .find_by_name("Polaris")
+            .ok_or_else(|| anyhow!("no Polaris"))?;
+        let (_, dec_2000) = polaris.mean_of_date(J2000);
+        let (_, dec_2100) = polaris.mean_of_date(J2000 + century);
+        assert_ron_snapshot!((round_to(dec_2000.to_degrees(), 3), round_to(dec_2100.to_degrees(), 3)), @"(89.264, 89.539)");
+
+        assert_ron_snapshot!(round_to(relative_size(-1.46, 1.0), 3), @"3.105");
+        assert_ron_snapshot!((color_from_b_v(-1.0), color_from_b_v(0.6), color_from_b_v(1.85)), @"((155, 176, 255), (255, 244, 234), (255, 169, 108))");
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use std::sync::OnceLock;
+
+use crate::angle::{norm_0_tau, ARCSEC_TO_RAD};
+use crate::julian::julian_centuries_since_j2000;
+use crate::vsop87::Vsop87Body;
+
+/// Apparent geocentric position of the Sun, referred to the true equator and equinox of date.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct SunApparent {
+    /// Apparent ecliptic longitude, radians.
+    pub lon: f64,
+
+    /// Earth-Sun distance, au.
+    pub r_au: f64,
+
+    /// True obliquity of the ecliptic, radians.
+    pub obliquity: f64,
+
+    /// Right ascension, radians in `[0, tau)`.
+    pub ra: f64,
+
+    /// Declination, radians.
+    pub dec: f64,
+}
+
+fn earth_vsop87() -> &'static Vsop87Body {
+    static EARTH: OnceLock<Vsop87Body> = OnceLock::new();
+    EARTH.get_or_init(Vsop87Body::abridged_earth)
+}
+
+/// Nutation in longitude and obliquity, radians, from the four largest terms.
+/// Meeus, "Astronomical Algorithms" 2nd ed., ch. 22. Good to about 0.5".
+fn nutation_low_precision(T: f64) -> (f64, f64) {
+    let omega = (125.04452 - 1934.136261 * T).to_radians();
+    let L_sun = (280.4665 + 36000.7698 * T).to_radians();
+    let L_moon = (218.3165 + 481267.8813 * T).to_radians();
+    let dpsi = -17.20 * omega.sin() - 1.32 * (2.0 * L_sun).sin() - 0.23 * (2.0 * L_moon).sin()
+        + 0.21 * (2.0 * omega).sin();
+    let deps = 9.20 * omega.cos() + 0.57 * (2.0 * L_sun).cos() + 0.10 * (2.0 * L_moon).cos()
+        - 0.09 * (2.0 * omega).cos();
+    (dpsi * ARCSEC_TO_RAD, deps * ARCSEC_TO_RAD)
+}
+
+/// Apparent position of the Sun from the heliocentric Earth of VSOP87D, following Meeus,
+/// "Astronomical Algorithms" 2nd ed., ch. 25 "Higher accuracy". Good to about an arcsecond.
+///
+/// `jd_tt` is used as TDB, which differs by less than 2 ms.
+#[must_use]
+pub fn apparent(jd_tt: f64) -> SunApparent {
+    let T = julian_centuries_since_j2000(jd_tt);
+
+    // The Earth as seen from the Sun, turned around.
+    let earth = earth_vsop87().heliocentric_ecliptic(jd_tt).unwrap();
+    let lon = earth.lon + std::f64::consts::PI;
+    let lat = -earth.lat;
+
+    // From the dynamical ecliptic and equinox of VSOP87 to FK5.
+    let lon_fk5 = lon - (1.397 * T + 0.00031 * T * T).to_radians();
+    let lon = lon - 0.09033 * ARCSEC_TO_RAD;
+    let lat = lat + 0.03916 * ARCSEC_TO_RAD * (lon_fk5.cos() - lon_fk5.sin());
+
+    // Nutation and annual aberration.
+    let (dpsi, deps) = nutation_low_precision(T);
+    let lon = lon + dpsi - 20.4898 * ARCSEC_TO_RAD / earth.r_au;
+
+    let eps0_arcsec = 84381.448 - T * (46.8150 + T * (0.00059 - T * 0.001813));
+    let obliquity = eps0_arcsec * ARCSEC_TO_RAD + deps;
+
+    let (sin_lon, cos_lon) = lon.sin_cos();
+    let (sin_eps, cos_eps) = obliquity.sin_cos();
+    let ra = norm_0_tau((sin_lon * cos_eps - lat.tan() * sin_eps).atan2(cos_lon));
+    let dec = (lat.sin() * cos_eps + lat.cos() * sin_eps * sin_lon).asin();
+
+    SunApparent {
+        lon: norm_0_tau(lon),
+        r_au: earth.r_au,
+        obliquity,
+        ra,
+        dec,
+    }
+}
+
+/// Apparent position of the Sun from the low-accuracy theory of Meeus, "Astronomical
+/// Algorithms" 2nd ed., ch. 25. Good to about 0.01° between 1900 and 2100, which is plenty
+/// for drawing sun-path diagrams.
+#[must_use]
+pub fn apparent_low_precision(jd_tt: f64) -> SunApparent {
+    let T = julian_centuries_since_j2000(jd_tt);
+
+    // Geometric mean longitude and mean anomaly, degrees.
+    let L0 = 280.46646 + T * (36000.76983 + T * 0.0003032);
+    let M = 357.52911 + T * (35999.05029 - T * 0.0001537);
+    let e = 0.016708634 - T * (0.000042037 + T * 0.0000001267);
+
+    let M_rad = M.to_radians();
+    let C = (1.914602 - T * (0.004817 + T * 0.000014)) * M_rad.sin()
+        + (0.019993 - T * 0.000101) * (2.0 * M_rad).sin()
+        + 0.000289 * (3.0 * M_rad).sin();
+
+    let true_lon = L0 + C;
+    let true_anomaly = (M + C).to_radians();
+    let r_au = 1.000001018 * (1.0 - e * e) / (1.0 + e * true_anomaly.cos());
+
+    // Nutation and aberration, the short way.
+    let omega = (125.04 - 1934.136 * T).to_radians();
+    let lon = (true_lon - 0.00569 - 0.00478 * omega.sin()).to_radians();
+
+    // Mean obliquity (Meeus 22.2) corrected for nutation.
+    let eps0_arcsec = 84381.448 - T * (46.8150 + T * (0.00059 - T * 0.001813));
+    let obliquity = (eps0_arcsec / 3600.0 + 0.00256 * omega.cos()).to_radians();
+
+    let (sin_lon, cos_lon) = lon.sin_cos();
+    let ra = norm_0_tau((obliquity.cos() * sin_lon).atan2(cos_lon));
+    let dec = (obliquity.sin() * sin_lon).asin();
+
+    SunApparent {
+        lon: norm_0_tau(lon),
+        r_au,
+        obliquity,
+        ra,
+        dec,
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    fn round_to(x: f64, places: i32) -> f64 {
+        let k = 10f64.powi(places);
+        (x * k).round() / k
+    }
+
+    #[test]
+    fn t() {
+        // Meeus example 25.a, 1992 October 13.0 TD.
+        let sun = apparent_low_precision(2_448_908.5);
+        assert_ron_snapshot!(round_to(sun.lon.to_degrees(), 4), @"199.9089");
+        assert_ron_snapshot!(round_to(sun.r_au, 5), @"0.99766");
+        assert_ron_snapshot!(round_to(sun.obliquity.to_degrees(), 5), @"23.43999");
+        assert_ron_snapshot!(round_to(sun.ra.to_degrees(), 5), @"198.38083");
+        assert_ron_snapshot!(round_to(sun.dec.to_degrees(), 5), @"-7.78507");
+
+        // Meeus example 25.b, same date: 13h13m30.749s, -7°47'01.74".
+        let sun = apparent(2_448_908.5);
+        assert_ron_snapshot!(round_to(sun.lon.to_degrees(), 4), @"199.9061");
+        assert_ron_snapshot!(round_to(sun.r_au, 8), @"0.99760775");
+        assert_ron_snapshot!(round_to(sun.ra.to_degrees(), 3), @"198.378");
+        assert_ron_snapshot!(round_to(sun.dec.to_degrees(), 3), @"-7.784");
+    }
+}
@@ -0,0 +1,543 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Sun-path diagrams and analemmas for an observer.
+//!
+//! A sun path is the track of the Sun across the sky during one day. An analemma is the set of
+//! positions of the Sun at the same clock time on every day of a year.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+use std::fmt::Write;
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::horizontal::{equatorial_to_horizontal, local_sidereal_time, Observer};
+use crate::julian::{gregorian_from_jd, jd_from_gregorian, SECONDS_PER_DAY};
+use crate::sun;
+
+/// Approximate TT - UT1, used to get from clock time to the dynamical time the solar theory
+/// wants. The error from a fixed value is a second or so over decades, which is far below what
+/// can be seen on a sun-path diagram.
+pub const DELTA_T_SECONDS: f64 = 69.2;
+
+//=================================================================================================|
+
+/// The position of the Sun in the observer's sky at one instant.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct SunSample {
+    /// Julian date, UT.
+    pub jd_ut: f64,
+
+    /// Azimuth, degrees from north through east.
+    pub azimuth_deg: f64,
+
+    /// Geometric elevation above the horizon, degrees. No refraction is applied.
+    pub elevation_deg: f64,
+}
+
+impl SunSample {
+    #[must_use]
+    pub fn new(observer: &Observer, jd_ut: f64) -> Self {
+        let jd_tt = jd_ut + DELTA_T_SECONDS / SECONDS_PER_DAY;
+        let sun = sun::apparent(jd_tt);
+        let lst = local_sidereal_time(jd_ut, observer);
+        let hor = equatorial_to_horizontal(sun.ra, sun.dec, lst, observer.lat_deg.to_radians());
+        Self {
+            jd_ut,
+            azimuth_deg: hor.azimuth_deg(),
+            elevation_deg: hor.elevation_deg(),
+        }
+    }
+
+    pub fn is_above_horizon(&self) -> bool {
+        0.0 <= self.elevation_deg
+    }
+}
+
+//=================================================================================================|
+
+/// A labeled series of samples, drawn as one line on a diagram.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SunPathCurve {
+    pub label: String,
+    pub samples: Vec<SunSample>,
+}
+
+impl SunPathCurve {
+    /// The Sun's position at the same local clock time on every day of `year`.
+    ///
+    /// `clock_hours` is the local clock time, `utc_offset_hours` the offset of the local clock
+    /// from UTC (east positive, e.g. -8 for PST). Daylight saving is not applied, since it would
+    /// break the figure-eight into two pieces.
+    #[must_use]
+    pub fn analemma(
+        observer: &Observer,
+        year: i32,
+        clock_hours: f64,
+        utc_offset_hours: f64,
+    ) -> Self {
+        let ut_hours = clock_hours - utc_offset_hours;
+        let jd_first = jd_from_gregorian(year, 1, 1.0 + ut_hours / 24.0);
+        let days_in_year =
+            (jd_from_gregorian(year + 1, 1, 1.0) - jd_from_gregorian(year, 1, 1.0)) as u32;
+
+        let samples = (0..days_in_year)
+            .map(|day| SunSample::new(observer, jd_first + day as f64))
+            .collect();
+
+        Self {
+            label: format!("analemma {}", format_clock_hours(clock_hours)),
+            samples,
+        }
+    }
+
+    /// The Sun's track across the sky over one local calendar day, sampled every
+    /// `step_minutes` from local midnight to the following midnight inclusive.
+    #[must_use]
+    pub fn day_path(
+        observer: &Observer,
+        year: i32,
+        month: u32,
+        day: u32,
+        utc_offset_hours: f64,
+        step_minutes: u32,
+    ) -> Self {
+        let step_minutes = step_minutes.max(1);
+        let jd_midnight = jd_from_gregorian(year, month, day as f64 - utc_offset_hours / 24.0);
+
+        let samples = (0..=24 * 60 / step_minutes)
+            .map(|n| {
+                let minutes = (n * step_minutes) as f64;
+                SunSample::new(observer, jd_midnight + minutes / (24.0 * 60.0))
+            })
+            .collect();
+
+        Self {
+            label: format!("{year:04}-{month:02}-{day:02}"),
+            samples,
+        }
+    }
+
+    /// Maximal runs of consecutive samples that are visible in `projection`, in diagram
+    /// coordinates. A line should be drawn through each run separately.
+    #[must_use]
+    pub fn projected_segments(&self, projection: SunPathProjection) -> Vec<Vec<[f64; 2]>> {
+        let mut segments: Vec<Vec<[f64; 2]>> = vec![];
+        let mut current: Vec<[f64; 2]> = vec![];
+        let mut prev_az: Option<f64> = None;
+
+        for sample in &self.samples {
+            let opt_xy = projection.project(sample.azimuth_deg, sample.elevation_deg);
+
+            // In the Cartesian projection, a track crossing north wraps from one edge to the other.
+            let wraps = projection == SunPathProjection::Cartesian
+                && prev_az.is_some_and(|prev| 180.0 < (sample.azimuth_deg - prev).abs());
+            prev_az = Some(sample.azimuth_deg);
+
+            match opt_xy {
+                Some(xy) if !wraps => current.push(xy),
+                _ => {
+                    if 1 < current.len() {
+                        segments.push(std::mem::take(&mut current));
+                    } else {
+                        current.clear();
+                    }
+                    if let Some(xy) = opt_xy {
+                        current.push(xy);
+                    }
+                }
+            }
+        }
+        if 1 < current.len() {
+            segments.push(current);
+        }
+
+        segments
+    }
+}
+
+//=================================================================================================|
+
+/// How the sky is flattened onto a diagram.
+///
+/// Diagram coordinates are in `[-1, 1] x [-1, 1]` with `y` up.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum SunPathProjection {
+    /// Zenith at the center, horizon on the unit circle, north up and east to the right
+    /// (as if looking down on a map). Radius is linear in zenith angle.
+    #[default]
+    Polar,
+
+    /// Azimuth 0°..360° along `x`, elevation 0°..90° along `y`.
+    Cartesian,
+}
+
+impl SunPathProjection {
+    pub const ALL: [SunPathProjection; 2] =
+        [SunPathProjection::Polar, SunPathProjection::Cartesian];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SunPathProjection::Polar => "Polar",
+            SunPathProjection::Cartesian => "Cartesian",
+        }
+    }
+
+    /// Width / height of the diagram.
+    pub fn aspect_ratio(self) -> f64 {
+        match self {
+            SunPathProjection::Polar => 1.0,
+            SunPathProjection::Cartesian => 2.0,
+        }
+    }
+
+    /// Diagram coordinates for a direction, or `None` if it is below the horizon.
+    #[must_use]
+    pub fn project(self, azimuth_deg: f64, elevation_deg: f64) -> Option<[f64; 2]> {
+        if !(0.0..=90.0).contains(&elevation_deg) {
+            return None;
+        }
+        match self {
+            SunPathProjection::Polar => {
+                let r = (90.0 - elevation_deg) / 90.0;
+                let (sin_az, cos_az) = azimuth_deg.to_radians().sin_cos();
+                Some([r * sin_az, r * cos_az])
+            }
+            SunPathProjection::Cartesian => {
+                let az = azimuth_deg.rem_euclid(360.0);
+                Some([az / 180.0 - 1.0, elevation_deg / 45.0 - 1.0])
+            }
+        }
+    }
+
+    /// The direction `(azimuth_deg, elevation_deg)` at a point on the diagram, or `None` if the
+    /// point is outside the diagram.
+    #[must_use]
+    pub fn unproject(self, xy: [f64; 2]) -> Option<(f64, f64)> {
+        let [x, y] = xy;
+        match self {
+            SunPathProjection::Polar => {
+                let r = x.hypot(y);
+                (r <= 1.0).then(|| (x.atan2(y).to_degrees().rem_euclid(360.0), 90.0 * (1.0 - r)))
+            }
+            SunPathProjection::Cartesian => {
+                let inside = (-1.0..=1.0).contains(&x) && (-1.0..=1.0).contains(&y);
+                inside.then_some(((x + 1.0) * 180.0, (y + 1.0) * 45.0))
+            }
+        }
+    }
+
+    /// Grid lines in diagram coordinates: elevation circles (or rows) every 15° and azimuth
+    /// spokes (or columns) every 30°.
+    #[must_use]
+    pub fn grid_lines(self) -> Vec<Vec<[f64; 2]>> {
+        let mut lines = vec![];
+        for el in (0..90).step_by(15) {
+            let line = (0..=360)
+                .step_by(5)
+                .filter_map(|az| self.project(az as f64, el as f64))
+                .collect();
+            lines.push(line);
+        }
+        for az in (0..=360).step_by(30) {
+            if self == SunPathProjection::Polar && az == 360 {
+                continue;
+            }
+            let line = [0.0, 90.0]
+                .iter()
+                .filter_map(|&el| self.project(az as f64, el))
+                .collect();
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Compass point labels and the diagram coordinates to put them at.
+    #[must_use]
+    pub fn compass_labels(self) -> Vec<([f64; 2], &'static str)> {
+        let points = [(0.0, "N"), (90.0, "E"), (180.0, "S"), (270.0, "W")];
+        points
+            .iter()
+            .filter_map(|&(az, label)| {
+                let [x, y] = self.project(az, 0.0)?;
+                Some(match self {
+                    // Just outside the horizon circle.
+                    SunPathProjection::Polar => ([x * 1.08, y * 1.08], label),
+                    // Just below the horizon line.
+                    SunPathProjection::Cartesian => ([x, y - 0.08], label),
+                })
+            })
+            .collect()
+    }
+}
+
+//=================================================================================================|
+
+/// Formats fractional hours as `HH:MM`.
+pub fn format_clock_hours(hours: f64) -> String {
+    let minutes = (hours * 60.0).round() as i64;
+    format!(
+        "{:02}:{:02}",
+        minutes.div_euclid(60).rem_euclid(24),
+        minutes.rem_euclid(60)
+    )
+}
+
+/// Formats a UT Julian date as ISO 8601, to the second.
+pub fn format_jd_utc(jd_ut: f64) -> String {
+    // Round to the second first, so we never print 60 seconds.
+    let jd = (jd_ut * SECONDS_PER_DAY).round() / SECONDS_PER_DAY;
+    let (year, month, day) = gregorian_from_jd(jd);
+    let secs = ((day - day.floor()) * SECONDS_PER_DAY).round() as u32;
+    format!(
+        "{year:04}-{month:02}-{:02}T{:02}:{:02}:{:02}Z",
+        day.floor() as u32,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// All samples of all curves as CSV, one row per sample.
+#[must_use]
+pub fn to_csv(curves: &[SunPathCurve]) -> String {
+    let mut csv = String::from("curve,utc,jd_ut,azimuth_deg,elevation_deg\n");
+    for curve in curves {
+        let label = csv_field(&curve.label);
+        for s in &curve.samples {
+            let _ = writeln!(
+                csv,
+                "{label},{},{:.6},{:.4},{:.4}",
+                format_jd_utc(s.jd_ut),
+                s.jd_ut,
+                s.azimuth_deg,
+                s.elevation_deg
+            );
+        }
+    }
+    csv
+}
+
+/// Line colors for successive curves.
+pub const CURVE_COLORS: [[u8; 3]; 6] = [
+    [0xff, 0xa5, 0x00],
+    [0x1e, 0x90, 0xff],
+    [0x32, 0xcd, 0x32],
+    [0xdc, 0x14, 0x3c],
+    [0x94, 0x00, 0xd3],
+    [0x00, 0xce, 0xd1],
+];
+
+fn svg_color(ix: usize) -> String {
+    let [r, g, b] = CURVE_COLORS[ix % CURVE_COLORS.len()];
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn svg_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A standalone SVG document of the diagram. `height_px` is the height of the plot area; the
+/// width follows from the projection's aspect ratio.
+#[must_use]
+pub fn to_svg(curves: &[SunPathCurve], projection: SunPathProjection, height_px: u32) -> String {
+    const MARGIN: f64 = 24.0;
+    let plot_h = height_px as f64;
+    let plot_w = plot_h * projection.aspect_ratio();
+    let legend_h = 16.0 * curves.len() as f64;
+    let total_w = plot_w + 2.0 * MARGIN;
+    let total_h = plot_h + 2.0 * MARGIN + legend_h;
+
+    let to_px = |[x, y]: [f64; 2]| -> (f64, f64) {
+        (
+            MARGIN + (x + 1.0) * 0.5 * plot_w,
+            MARGIN + (1.0 - y) * 0.5 * plot_h,
+        )
+    };
+    let points_attr = |pts: &[[f64; 2]]| -> String {
+        let v: Vec<String> = pts
+            .iter()
+            .map(|&xy| {
+                let (px, py) = to_px(xy);
+                format!("{px:.1},{py:.1}")
+            })
+            .collect();
+        v.join(" ")
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{total_w:.0}" height="{total_h:.0}" viewBox="0 0 {total_w:.0} {total_h:.0}">"#
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    let _ = writeln!(
+        svg,
+        r##"<g fill="none" stroke="#c0c0c0" stroke-width="1">"##
+    );
+    for line in projection.grid_lines() {
+        let _ = writeln!(svg, r#"<polyline points="{}"/>"#, points_attr(&line));
+    }
+    let _ = writeln!(svg, "</g>");
+
+    let _ = writeln!(
+        svg,
+        r#"<g font-family="sans-serif" font-size="12" text-anchor="middle">"#
+    );
+    for (xy, label) in projection.compass_labels() {
+        let (px, py) = to_px(xy);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{px:.1}" y="{:.1}">{label}</text>"#,
+            py + 4.0
+        );
+    }
+    let _ = writeln!(svg, "</g>");
+
+    for (ix, curve) in curves.iter().enumerate() {
+        let color = svg_color(ix);
+        let _ = writeln!(
+            svg,
+            r#"<g fill="none" stroke="{color}" stroke-width="2"><title>{}</title>"#,
+            svg_escape(&curve.label)
+        );
+        for segment in curve.projected_segments(projection) {
+            let _ = writeln!(svg, r#"<polyline points="{}"/>"#, points_attr(&segment));
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    for (ix, curve) in curves.iter().enumerate() {
+        let y = plot_h + 2.0 * MARGIN + 16.0 * ix as f64 + 4.0;
+        let _ = writeln!(
+            svg,
+            r#"<text x="{MARGIN:.0}" y="{y:.0}" font-family="sans-serif" font-size="12" fill="{}">{}</text>"#,
+            svg_color(ix),
+            svg_escape(&curve.label)
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    fn round_to(x: f64, places: i32) -> f64 {
+        let k = 10f64.powi(places);
+        (x * k).round() / k
+    }
+
+    #[test]
+    fn analemma() {
+        // Royal Observatory, Greenwich.
+        let observer = Observer::new(51.4769, 0.0, 46.0);
+        let curve = SunPathCurve::analemma(&observer, 2024, 12.0, 0.0);
+        assert_ron_snapshot!(curve.label, @r###""analemma 12:00""###);
+        assert_ron_snapshot!(curve.samples.len(), @"366");
+
+        // Noon elevation ranges over the colatitude +/- the obliquity.
+        let (min_el, max_el) = curve
+            .samples
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), s| {
+                (lo.min(s.elevation_deg), hi.max(s.elevation_deg))
+            });
+        assert_ron_snapshot!((round_to(min_el, 1), round_to(max_el, 1)), @"(15.1, 62.0)");
+
+        // The equation of time spreads noon over roughly +/- 4° of azimuth around south.
+        let (min_az, max_az) = curve
+            .samples
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), s| {
+                (lo.min(s.azimuth_deg), hi.max(s.azimuth_deg))
+            });
+        assert!(170.0 < min_az && max_az < 190.0);
+
+        let segments = curve.projected_segments(SunPathProjection::Polar);
+        assert_ron_snapshot!(segments.len(), @"1");
+    }
+
+    #[test]
+    fn day_path() {
+        // Seattle, at the June solstice, on Pacific Standard Time.
+        let observer = Observer::new(47.6062, -122.3321, 50.0);
+        let curve = SunPathCurve::day_path(&observer, 2023, 6, 21, -8.0, 60);
+        assert_ron_snapshot!(curve.label, @r###""2023-06-21""###);
+        assert_ron_snapshot!(curve.samples.len(), @"25");
+        assert_ron_snapshot!(format_jd_utc(curve.samples[0].jd_ut), @r###""2023-06-21T08:00:00Z""###);
+
+        let max_el = curve
+            .samples
+            .iter()
+            .map(|s| s.elevation_deg)
+            .fold(f64::MIN, f64::max);
+        assert_ron_snapshot!(round_to(max_el, 0), @"66.0");
+
+        // Above the horizon for a single stretch, in both projections.
+        assert_ron_snapshot!(curve.projected_segments(SunPathProjection::Polar).len(), @"1");
+        assert_ron_snapshot!(curve.projected_segments(SunPathProjection::Cartesian).len(), @"1");
+
+        let csv = to_csv(std::slice::from_ref(&curve));
+        assert_ron_snapshot!(csv.lines().next(), @r###"Some("curve,utc,jd_ut,azimuth_deg,elevation_deg")"###);
+        assert_ron_snapshot!(csv.lines().count(), @"26");
+
+        let svg = to_svg(&[curve], SunPathProjection::Cartesian, 200);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("<title>2023-06-21</title>"));
+    }
+
+    #[test]
+    fn projection() {
+        for projection in SunPathProjection::ALL {
+            for (az, el) in [(0.0, 0.0), (45.0, 30.0), (200.0, 75.0), (359.0, 10.0)] {
+                let xy = projection.project(az, el).unwrap();
+                let (az2, el2) = projection.unproject(xy).unwrap();
+                assert!(
+                    (az - az2).abs() < 1e-9 && (el - el2).abs() < 1e-9,
+                    "{projection:?} {az} {el}"
+                );
+            }
+            assert_eq!(projection.project(10.0, -1.0), None);
+        }
+        assert_ron_snapshot!(SunPathProjection::Polar.project(90.0, 0.0).map(|[x, y]| [round_to(x, 9), round_to(y, 9)]), @"Some((1.0, 0.0))");
+        assert_ron_snapshot!(SunPathProjection::Cartesian.project(180.0, 45.0), @"Some((0.0, 0.0))");
+        assert_ron_snapshot!(format_clock_hours(13.5), @r###""13:30""###);
+    }
+}
@@ -0,0 +1,519 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! VSOP87 planetary theory (Bretagnon & Francou 1988).
+//!
+//! Two versions are supported:
+//!
+//! * VSOP87D: heliocentric ecliptic spherical coordinates (L, B, R), referred to the mean
+//!   ecliptic and equinox of date. This is the `ss_ecliptic` frame.
+//! * VSOP87E: barycentric ecliptic rectangular coordinates (X, Y, Z), referred to the
+//!   ecliptic and equinox of J2000.
+//!
+//! Only the Earth and Venus have compiled-in series, abridged VSOP87D. The other planets, and
+//! version E, are loaded from the official data files (e.g. `VSOP87D.ear`, `VSOP87E.jup`) at
+//! run time, with terms below a chosen amplitude dropped. The full Earth series has about 2500 terms; a
+//! truncation of 1e-7 keeps a few hundred of them and still gives sub-arcsecond positions.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::angle::norm_0_tau;
+use crate::julian::{DAYS_PER_JULIAN_YEAR, J2000};
+
+mod abridged;
+
+//=================================================================================================|
+
+/// The bodies covered by VSOP87D and VSOP87E.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum Planet {
+    Mercury,
+    Venus,
+    Earth,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+}
+
+impl Planet {
+    pub const ALL: [Planet; 8] = [
+        Planet::Mercury,
+        Planet::Venus,
+        Planet::Earth,
+        Planet::Mars,
+        Planet::Jupiter,
+        Planet::Saturn,
+        Planet::Uranus,
+        Planet::Neptune,
+    ];
+
+    /// File name extension of the official data files, e.g. `VSOP87D.ear`.
+    pub fn file_ext(self) -> &'static str {
+        match self {
+            Planet::Mercury => "mer",
+            Planet::Venus => "ven",
+            Planet::Earth => "ear",
+            Planet::Mars => "mar",
+            Planet::Jupiter => "jup",
+            Planet::Saturn => "sat",
+            Planet::Uranus => "ura",
+            Planet::Neptune => "nep",
+        }
+    }
+
+    /// Parses the body name as it appears in the header lines of the data files.
+    pub fn from_header_name(s: &str) -> Option<Planet> {
+        Planet::ALL
+            .into_iter()
+            .find(|p| format!("{p:?}").eq_ignore_ascii_case(s))
+    }
+}
+
+/// Which flavor of VSOP87 a series belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Vsop87Version {
+    /// Heliocentric spherical, ecliptic and equinox of date.
+    D,
+
+    /// Barycentric rectangular, ecliptic and equinox of J2000.
+    E,
+}
+
+/// Which terms to drop when loading a series.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Truncation {
+    /// Terms with amplitude below this (radians or au) are dropped. Zero keeps everything.
+    pub min_amplitude: f64,
+}
+
+impl Truncation {
+    pub const NONE: Truncation = Truncation { min_amplitude: 0.0 };
+
+    pub fn keeps(&self, term: &Vsop87Term) -> bool {
+        self.min_amplitude <= term.a.abs()
+    }
+}
+
+/// One periodic term, `a cos(b + c t)`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Vsop87Term {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+/// The series for one coordinate: `sum over n of t^n * sum over terms a cos(b + c t)`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Vsop87Series {
+    /// Indexed by the power of `t`.
+    pub terms_by_power: Vec<Vec<Vsop87Term>>,
+}
+
+impl Vsop87Series {
+    pub fn term_count(&self) -> usize {
+        self.terms_by_power.iter().map(|v| v.len()).sum()
+    }
+
+    /// Evaluates the series at `t` Julian millennia from J2000 TDB.
+    pub fn evaluate(&self, t: f64) -> f64 {
+        self.terms_by_power.iter().rev().fold(0.0, |acc, terms| {
+            let sum: f64 = terms
+                .iter()
+                .map(|term| term.a * (term.b + term.c * t).cos())
+                .sum();
+            acc * t + sum
+        })
+    }
+
+    fn truncated(&self, truncation: Truncation) -> Self {
+        Self {
+            terms_by_power: self
+                .terms_by_power
+                .iter()
+                .map(|terms| {
+                    terms
+                        .iter()
+                        .filter(|term| truncation.keeps(term))
+                        .copied()
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+//=================================================================================================|
+
+/// Heliocentric ecliptic spherical coordinates, the `ss_ecliptic` frame.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct HeliocentricEcliptic {
+    /// Ecliptic longitude, radians in `[0, tau)`.
+    pub lon: f64,
+
+    /// Ecliptic latitude, radians.
+    pub lat: f64,
+
+    /// Distance from the Sun, au.
+    pub r_au: f64,
+}
+
+impl HeliocentricEcliptic {
+    /// Rectangular coordinates, au: x toward the equinox, z toward the north ecliptic pole.
+    pub fn to_rectangular(&self) -> [f64; 3] {
+        let (sin_lon, cos_lon) = self.lon.sin_cos();
+        let (sin_lat, cos_lat) = self.lat.sin_cos();
+        [
+            self.r_au * cos_lat * cos_lon,
+            self.r_au * cos_lat * sin_lon,
+            self.r_au * sin_lat,
+        ]
+    }
+}
+
+/// Julian millennia since J2000, the time argument of VSOP87.
+#[must_use]
+pub fn julian_millennia_since_j2000(jd_tdb: f64) -> f64 {
+    (jd_tdb - J2000) / (1000.0 * DAYS_PER_JULIAN_YEAR)
+}
+
+/// The three coordinate series of one body in one version of the theory.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Vsop87Body {
+    pub planet: Planet,
+    pub version: Vsop87Version,
+
+    /// (L, B, R) for version D, (X, Y, Z) for version E.
+    pub coordinates: [Vsop87Series; 3],
+}
+
+impl Vsop87Body {
+    /// The compiled-in abridged VSOP87D series for the Earth.
+    pub fn abridged_earth() -> Self {
+        Self::from_abridged(Planet::Earth, &abridged::EARTH)
+    }
+
+    /// The compiled-in abridged VSOP87D series for Venus.
+    pub fn abridged_venus() -> Self {
+        Self::from_abridged(Planet::Venus, &abridged::VENUS)
+    }
+
+    fn from_abridged(planet: Planet, table: &abridged::AbridgedBody) -> Self {
+        let coordinates = table.map(|series_by_power| Vsop87Series {
+            terms_by_power: series_by_power
+                .iter()
+                .map(|terms| {
+                    terms
+                        .iter()
+                        .map(|&[a, b, c]| Vsop87Term {
+                            a: a * abridged::AMPLITUDE_UNIT,
+                            b,
+                            c,
+                        })
+                        .collect()
+                })
+                .collect(),
+        });
+
+        Self {
+            planet,
+            version: Vsop87Version::D,
+            coordinates,
+        }
+    }
+
+    /// Parses one of the official data files, e.g. `VSOP87D.ear`.
+    ///
+    /// Each block begins with a header line like
+    /// ` VSOP87 VERSION D4    EARTH     VARIABLE 1 (LBR)       *T**0   2022 TERMS ...`
+    /// and is followed by that many term lines, whose last three fields are A, B and C.
+    pub fn parse(text: &str, truncation: Truncation) -> Result<Self> {
+        let mut opt_planet_version: Option<(Planet, Vsop87Version)> = None;
+        let mut coordinates: [Vsop87Series; 3] = Default::default();
+        let mut opt_current: Option<(usize, usize, usize)> = None; // (variable, power, remaining)
+
+        for (line_ix, line) in text.lines().enumerate() {
+            let line_no = line_ix + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if line.contains("VSOP87") {
+                ensure!(
+                    opt_current.map_or(true, |(_, _, remaining)| remaining == 0),
+                    "line {line_no}: header before the end of the previous block"
+                );
+                let (planet, version, variable, power, count) =
+                    parse_header(line).with_context(|| format!("line {line_no}"))?;
+                match opt_planet_version {
+                    None => opt_planet_version = Some((planet, version)),
+                    Some(pv) => ensure!(
+                        pv == (planet, version),
+                        "line {line_no}: expecting {pv:?}, found {:?}",
+                        (planet, version)
+                    ),
+                }
+                let series = &mut coordinates[variable - 1];
+                if series.terms_by_power.len() <= power {
+                    series.terms_by_power.resize(power + 1, vec![]);
+                }
+                opt_current = Some((variable, power, count));
+                continue;
+            }
+
+            let Some((variable, power, remaining)) = opt_current.as_mut() else {
+                bail!("line {line_no}: term before any header");
+            };
+            ensure!(
+                0 < *remaining,
+                "line {line_no}: more terms than the header said"
+            );
+            *remaining -= 1;
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            ensure!(4 <= fields.len(), "line {line_no}: too few fields");
+            let parse_f64 = |s: &str| -> Result<f64> {
+                s.parse::<f64>()
+                    .with_context(|| format!("line {line_no}: bad number '{s}'"))
+            };
+            let n = fields.len();
+            let term = Vsop87Term {
+                a: parse_f64(fields[n - 3])?,
+                b: parse_f64(fields[n - 2])?,
+                c: parse_f64(fields[n - 1])?,
+            };
+            if truncation.keeps(&term) {
+                coordinates[*variable - 1].terms_by_power[*power].push(term);
+            }
+        }
+
+        let (planet, version) =
+            opt_planet_version.ok_or_else(|| anyhow!("no VSOP87 header found"))?;
+        if let Some((_, _, remaining)) = opt_current {
+            ensure!(remaining == 0, "file ends {remaining} terms short");
+        }
+
+        Ok(Self {
+            planet,
+            version,
+            coordinates,
+        })
+    }
+
+    /// A copy with small terms dropped.
+    pub fn truncated(&self, truncation: Truncation) -> Self {
+        Self {
+            planet: self.planet,
+            version: self.version,
+            coordinates: [
+                self.coordinates[0].truncated(truncation),
+                self.coordinates[1].truncated(truncation),
+                self.coordinates[2].truncated(truncation),
+            ],
+        }
+    }
+
+    pub fn term_count(&self) -> usize {
+        self.coordinates.iter().map(|s| s.term_count()).sum()
+    }
+
+    /// The three raw coordinates at a TDB Julian date.
+    pub fn evaluate(&self, jd_tdb: f64) -> [f64; 3] {
+        let t = julian_millennia_since_j2000(jd_tdb);
+        [
+            self.coordinates[0].evaluate(t),
+            self.coordinates[1].evaluate(t),
+            self.coordinates[2].evaluate(t),
+        ]
+    }
+
+    /// Heliocentric ecliptic coordinates of date. Requires a version D series.
+    pub fn heliocentric_ecliptic(&self, jd_tdb: f64) -> Result<HeliocentricEcliptic> {
+        ensure!(
+            self.version == Vsop87Version::D,
+            "{:?} is VSOP87{:?}, heliocentric spherical coordinates need VSOP87D",
+            self.planet,
+            self.version
+        );
+        let [lon, lat, r_au] = self.evaluate(jd_tdb);
+        Ok(HeliocentricEcliptic {
+            lon: norm_0_tau(lon),
+            lat,
+            r_au,
+        })
+    }
+}
+
+/// Returns (planet, version, variable 1..=3, power of t, term count).
+fn parse_header(line: &str) -> Result<(Planet, Vsop87Version, usize, usize, usize)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let find_after = |key: &str| -> Result<&str> {
+        let ix = fields
+            .iter()
+            .position(|&f| f == key)
+            .ok_or_else(|| anyhow!("header has no '{key}'"))?;
+        fields
+            .get(ix + 1)
+            .copied()
+            .ok_or_else(|| anyhow!("header ends after '{key}'"))
+    };
+
+    let version = match find_after("VERSION")?.chars().next() {
+        Some('D') => Vsop87Version::D,
+        Some('E') => Vsop87Version::E,
+        _ => bail!("only VSOP87 versions D and E are supported"),
+    };
+
+    let planet_ix = fields
+        .iter()
+        .position(|&f| f == "VERSION")
+        .map(|ix| ix + 2)
+        .unwrap_or_default();
+    let planet_name = fields.get(planet_ix).copied().unwrap_or_default();
+    let planet = Planet::from_header_name(planet_name)
+        .ok_or_else(|| anyhow!("unknown body '{planet_name}'"))?;
+
+    let variable: usize = find_after("VARIABLE")?.parse()?;
+    ensure!(
+        (1..=3).contains(&variable),
+        "variable {variable} out of range"
+    );
+
+    let power: usize = fields
+        .iter()
+        .find_map(|f| f.strip_prefix("*T**"))
+        .ok_or_else(|| anyhow!("header has no '*T**'"))?
+        .parse()?;
+
+    let count: usize = fields
+        .iter()
+        .position(|&f| f == "TERMS")
+        .and_then(|ix| ix.checked_sub(1))
+        .and_then(|ix| fields.get(ix))
+        .ok_or_else(|| anyhow!("header has no term count"))?
+        .parse()?;
+
+    Ok((planet, version, variable, power, count))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    fn round_to(x: f64, places: i32) -> f64 {
+        let k = 10f64.powi(places);
+        (x * k).round() / k
+    }
+
+    #[test]
+    fn abridged() -> anyhow::Result<()> {
+        let earth = Vsop87Body::abridged_earth();
+        assert_ron_snapshot!(earth.term_count(), @"195");
+
+        // Meeus example 32.a (via 25.b), 1992 October 13.0 TD. Meeus quotes L unreduced, as
+        // -43.63484796 rad.
+        let hel = earth.heliocentric_ecliptic(2_448_908.5)?;
+        assert_ron_snapshot!(round_to((hel.lon - norm_0_tau(-43.63484796)).abs(), 8), @"0.0");
+        assert_ron_snapshot!(round_to(hel.lat, 8), @"-0.00000312");
+        assert_ron_snapshot!(round_to(hel.r_au, 8), @"0.99760775");
+
+        // VSOP87 check table, VSOP87D Earth at J2000. The abridged series is good to a few
+        // tenths of an arcsecond, about 1e-6 rad, which is still a fraction of the latitude.
+        let hel = earth.heliocentric_ecliptic(J2000)?;
+        assert!((hel.lon - 1.7519238681).abs() < 2e-6);
+        assert!((hel.lat - -0.0000039656).abs() < 1e-6);
+        assert!((hel.r_au - 0.9833276819).abs() < 2e-7);
+
+        // Meeus example 32.a, Venus on 1992 December 20.0 TD.
+        let venus = Vsop87Body::abridged_venus();
+        let hel = venus.heliocentric_ecliptic(2_448_976.5)?;
+        assert_ron_snapshot!(round_to(hel.lon.to_degrees(), 5), @"26.11428");
+        assert_ron_snapshot!(round_to(hel.lat.to_degrees(), 5), @"-2.6207");
+        assert_ron_snapshot!(round_to(hel.r_au, 6), @"0.724603");
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncation() {
+        let earth = Vsop87Body::abridged_earth();
+        let small = earth.truncated(Truncation {
+            min_amplitude: 1e-6,
+        });
+        assert_ron_snapshot!(small.term_count(), @"69");
+
+        // Dropping terms below 1e-6 rad costs at most a few times 1e-6 rad.
+        for jd in [J2000, 2_448_908.5, 2_460_000.5] {
+            let [l0, b0, r0] = earth.evaluate(jd);
+            let [l1, b1, r1] = small.evaluate(jd);
+            assert!((l0 - l1).abs() < 3e-5 && (b0 - b1).abs() < 3e-5 && (r0 - r1).abs() < 3e-5);
+        }
+    }
+
+    // The first lines of `VSOP87D.ear`, with the term counts in the headers cut down to match.
+    const VSOP87D_EAR_EXCERPT: &str = "
+ VSOP87 VERSION D4    EARTH     VARIABLE 1 (LBR)       *T**0      3 TERMS    HIGH-PRECISION SOLUTION
+ 4310    1  0  0  0  0  0  0  0  0  0  0  0  0  0     1.75347045673 0.00000000000      0.00000000000
+ 4310    2  0  0  2 -2  0  0  0  0  0  0  0  0  0     0.03341656453 4.66925680415   6283.07584999140
+ 4310    3  0  0  4 -4  0  0  0  0  0  0  0  0  0     0.00034894275 4.62610242189  12566.15169998280
+ VSOP87 VERSION D4    EARTH     VARIABLE 1 (LBR)       *T**1      1 TERMS    HIGH-PRECISION SOLUTION
+ 4311    1  0  0  0  0  0  0  0  0  0  0  0  0  0  6283.31966747490 0.00000000000      0.00000000000
+ VSOP87 VERSION D4    EARTH     VARIABLE 2 (LBR)       *T**0      1 TERMS    HIGH-PRECISION SOLUTION
+ 4320    1  0  0  0  0  0  0  0  0  0  0  0  0 -1     0.00000279620 3.19870156017  84334.66158130829
+ VSOP87 VERSION D4    EARTH     VARIABLE 3 (LBR)       *T**0      2 TERMS    HIGH-PRECISION SOLUTION
+ 4330    1  0  0  0  0  0  0  0  0  0  0  0  0  0     1.00013988784 0.00000000000      0.00000000000
+ 4330    2  0  0  1 -1  0  0  0  0  0  0  0  0  0     0.01670699632 3.09846350258   6283.07584999140
+";
+
+    #[test]
+    fn parse() -> anyhow::Result<()> {
+        let earth = Vsop87Body::parse(VSOP87D_EAR_EXCERPT, Truncation::NONE)?;
+        assert_ron_snapshot!((earth.planet, earth.version, earth.term_count()), @"(Earth, D, 7)");
+        let term = earth.coordinates[0].terms_by_power[1][0];
+        assert_ron_snapshot!((term.a, term.b, term.c), @"(6283.3196674749, 0.0, 0.0)");
+
+        // Even these few terms put the Earth within a few arcminutes.
+        let hel = earth.heliocentric_ecliptic(J2000)?;
+        assert!((hel.lon - 1.7519238681).abs() < 1e-3);
+        assert!((hel.r_au - 0.9833276819).abs() < 1e-3);
+
+        let truncated = Vsop87Body::parse(
+            VSOP87D_EAR_EXCERPT,
+            Truncation {
+                min_amplitude: 1e-3,
+            },
+        )?;
+        assert_ron_snapshot!(truncated.term_count(), @"5");
+
+        assert!(Vsop87Body::parse("", Truncation::NONE).is_err());
+        let short = VSOP87D_EAR_EXCERPT.replace("*T**1      1 TERMS", "*T**1      2 TERMS");
+        assert!(Vsop87Body::parse(&short, Truncation::NONE).is_err());
+        Ok(())
+    }
+}
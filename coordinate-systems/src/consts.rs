@@ -32,7 +32,7 @@ use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
 //? use num_rational::Ratio;
 //? use num_traits::{NumCast, ToPrimitive, Zero};
 //? use once_cell::sync::Lazy;
-//? use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 //? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
 
 use ecs_namespace::NamespaceTag;
@@ -68,8 +68,66 @@ impl DimensionedConstant {
 
         None
     }
+
+    /// Finds a constant in the catalog by its group and name, e.g. `("wgs84", "a")`.
+    pub fn find(world: &World, group: &str, name: &str) -> Result<Entity> {
+        ecs_ns_get(world, NamePathSpec::absolute(["consts", group, name]))
+    }
+
+    /// The constant's name, value, unit, exactness and source, for citing it as an input.
+    pub fn citation(world: &World, e: Entity) -> Result<String> {
+        let name = world
+            .get::<&Name>(e)
+            .with_context(|| format!("{e:?} is not a DimensionedConstant"))?;
+        let estimate = Estimate::from_constant(world, e)?;
+
+        let value = crate::core::display_f64(estimate.value.to_f64()?);
+        let mut s = format!("{} = {value}", name.as_str());
+        if let Ok(unit) = world.get::<&ConstantUnit>(e) {
+            s += " ";
+            s += world.get::<&Abbr>(unit.0)?.as_str();
+        }
+        s += &format!(" ({}", estimate.exactness);
+        if let Ok(source) = world.get::<&ConstantSource>(e) {
+            s += &format!("; {}", *source);
+        }
+        s += ")";
+        Ok(s)
+    }
 }
 
+/// Where a [`DimensionedConstant`]'s value comes from.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstantSource {
+    #[display("SI Brochure, 9th edition (2019)")]
+    Si2019,
+
+    #[display("CODATA 2018")]
+    Codata2018,
+
+    #[display("IAU 2009 System of Astronomical Constants")]
+    Iau2009,
+
+    #[display("IAU 2012 Resolution B2")]
+    Iau2012B2,
+
+    #[display("IAU 2015 Resolution B3")]
+    Iau2015B3,
+
+    #[display("IERS Conventions (2010)")]
+    Iers2010,
+
+    #[display("NIMA TR8350.2 (WGS 84)")]
+    Wgs84,
+
+    #[display("Moritz, Geodetic Reference System 1980")]
+    Grs80,
+}
+
+/// The unit of a [`DimensionedConstant`]'s value. Dimensionless constants have none.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConstantUnit(pub Entity);
+
 //=================================================================================================|
 
 const APPROX_PI_TIMES_2_62: u64 =
@@ -86,7 +144,7 @@ fn ecs_add_const(
 ) -> Result<Entity> {
     let dc = DimensionedConstant {
         name: Name(String::from(name)),
-        dimension_kind: DimensionKind::Scale,
+        dimension_kind: DimensionKind::SCALE,
         exactness: Exactness::Approximate,
         value: EcsNum::RatioU64(RatioU64::new_raw(numer, denom)),
     };
@@ -96,6 +154,110 @@ fn ecs_add_const(
         .context("ecs_add_const")
 }
 
+struct CatalogEntry {
+    name: &'static str,
+    value: &'static str,
+    unit: Option<&'static str>,
+    exactness: Exactness,
+    source: ConstantSource,
+}
+
+const fn entry(
+    name: &'static str,
+    value: &'static str,
+    unit: &'static str,
+    exactness: Exactness,
+    source: ConstantSource,
+) -> CatalogEntry {
+    CatalogEntry {
+        name,
+        value,
+        unit: if unit.is_empty() { None } else { Some(unit) },
+        exactness,
+        source,
+    }
+}
+
+const DEFINED: Exactness = Exactness::Exact(ExactReason::ByDefinition);
+
+const fn standard(u: f64) -> Exactness {
+    Exactness::Uncertain(Uncertainty::Standard(u))
+}
+
+#[rustfmt::skip]
+const CATALOG: &[(&str, &[CatalogEntry])] = {
+    use ConstantSource::*;
+    &[
+        ("physical", &[
+            entry("c",   "299792458",   "m/s",        DEFINED,                Si2019),
+            entry("G",   "6.67430e-11", "m³/(kg·s²)", standard(0.00015e-11), Codata2018),
+        ]),
+        ("astronomical", &[
+            entry("au",                   "149597870700",     "m",     DEFINED,         Iau2012B2),
+            entry("gm_sun_tcb",           "1.32712442099e20", "m³/s²", standard(1.0e10), Iau2009),
+            entry("gm_sun_tdb",           "1.32712440041e20", "m³/s²", standard(1.0e10), Iau2009),
+            entry("nominal_gm_sun",       "1.3271244e20",     "m³/s²", DEFINED,         Iau2015B3),
+            entry("nominal_solar_radius", "6.957e8",          "m",     DEFINED,         Iau2015B3),
+            entry("tt_minus_tai",         "32.184",           "s",     DEFINED,         Iers2010),
+        ]),
+        ("earth", &[
+            entry("gm",              "3.986004418e14", "m³/s²", standard(8.0e5), Iers2010),
+            entry("omega",           "7.292115e-5",    "rad/s", DEFINED,         Iers2010),
+            entry("obliquity_j2000", "84381.406",      "″",     DEFINED,         Iers2010),
+        ]),
+        ("wgs84", &[
+            entry("a",     "6378137",        "m",     DEFINED, Wgs84),
+            entry("inv_f", "298.257223563",  "",      DEFINED, Wgs84),
+            entry("gm",    "3.986004418e14", "m³/s²", DEFINED, Wgs84),
+            entry("omega", "7.292115e-5",    "rad/s", DEFINED, Wgs84),
+        ]),
+        ("grs80", &[
+            entry("a",     "6378137",     "m",     DEFINED, Grs80),
+            entry("gm",    "3.986005e14", "m³/s²", DEFINED, Grs80),
+            entry("j2",    "108263e-8",   "",      DEFINED, Grs80),
+            entry("omega", "7.292115e-5", "rad/s", DEFINED, Grs80),
+        ]),
+    ]
+};
+
+/// Adds the catalog of authoritative constants under `/consts/<group>`. This needs the units, so
+/// it comes after `units::ecs_add_stuff`, which in turn needs the constants above.
+pub(crate) fn ecs_add_catalog(world: &mut World) -> Result<()> {
+    let ns_consts = ecs_ns_find_or_create(world, NamePathSpec::absolute(["consts"]))?;
+
+    for &(group, entries) in CATALOG {
+        let ns_group = world.attach_new::<NamespaceTag, _>(ns_consts, (Name::from(group),))?;
+
+        for entry in entries {
+            let opt_unit = entry
+                .unit
+                .map(|abbr| Unit::look_up_name_or_abbr(world, abbr))
+                .transpose()?;
+            let dimension_kind = match opt_unit {
+                Some(unit) => Unit::dimension_kind(world, unit)?,
+                None => DimensionKind::SCALE,
+            };
+
+            let dc = DimensionedConstant {
+                name: Name::from(entry.name),
+                dimension_kind,
+                exactness: entry.exactness,
+                value: crate::units::parse_decimal(entry.value)?,
+            };
+            let e = world
+                .attach_new::<NamespaceTag, _>(ns_group, dc)
+                .with_context(|| format!("ecs_add_catalog {group}/{}", entry.name))?;
+
+            world.insert_one(e, entry.source)?;
+            if let Some(unit) = opt_unit {
+                world.insert_one(e, ConstantUnit(unit))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
     let ns_root = RootNamespace::find_or_create(world)?;
 
@@ -120,3 +282,59 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
 
     Ok(())
 }
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let mut world = World::new();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let find = |group, name| DimensionedConstant::find(world, group, name).unwrap();
+        let cite = |group, name| DimensionedConstant::citation(world, find(group, name)).unwrap();
+
+        assert_ron_snapshot!(cite("physical", "c"), @r#""c = 299792458 m/s (ByDefinition; SI Brochure, 9th edition (2019))""#);
+        assert_ron_snapshot!(cite("physical", "G"), @r#""G = 6.6743e-11 m³/(kg·s²) (±1.5e-15; CODATA 2018)""#);
+        assert_ron_snapshot!(cite("earth", "gm"), @r#""gm = 3.986004418e14 m³/s² (±800000; IERS Conventions (2010))""#);
+        assert_ron_snapshot!(cite("earth", "obliquity_j2000"), @r#""obliquity_j2000 = 84381.406 ″ (ByDefinition; IERS Conventions (2010))""#);
+        assert_ron_snapshot!(cite("wgs84", "inv_f"), @r#""inv_f = 298.257223563 (ByDefinition; NIMA TR8350.2 (WGS 84))""#);
+        assert_ron_snapshot!(cite("grs80", "j2"), @r#""j2 = 0.00108263 (ByDefinition; Moritz, Geodetic Reference System 1980)""#);
+        assert!(DimensionedConstant::find(world, "wgs84", "b").is_err());
+
+        // Dimensions follow from the units.
+        let dk = |group, name| {
+            world
+                .get::<&DimensionKind>(find(group, name))
+                .map(|dk| dk.to_string())
+        };
+        assert_ron_snapshot!([dk("astronomical", "au")?, dk("earth", "omega")?, dk("earth", "gm")?], @r#"("Length", "AngularRate", "L³·T⁻²")"#);
+
+        // Light time for one au is exact, and the obliquity is exactly 23° 26′ 21.406″.
+        let light_time = Estimate::from_constant(world, find("astronomical", "au"))?
+            .div(&Estimate::from_constant(world, find("physical", "c"))?)?;
+        assert_ron_snapshot!(light_time.to_string(), @r#""1024642950/2053373 ByDefinition""#);
+        assert_ron_snapshot!(light_time.value.to_f64()?, @"499.00478383615643");
+        let e_obliquity = find("earth", "obliquity_j2000");
+        let obliquity = Quantity {
+            value: (*world.get::<&EcsNum>(e_obliquity)?).clone(),
+            unit: world.get::<&ConstantUnit>(e_obliquity)?.0,
+        };
+        let format = SexagesimalFormat::new(SexagesimalStyle::Degrees, 3, 3);
+        assert_ron_snapshot!(obliquity.format_sexagesimal(world, format)?, @r#""23° 26′ 21.406″""#);
+
+        // Uncertainties carry through: the Earth's GM in km³/s².
+        let gm = Estimate::from_constant(world, find("earth", "gm"))?;
+        let m3_per_km3 = EcsNum::RatioU64(RatioU64::from_integer(1_000_000_000));
+        let per_km3 = Estimate::exact(m3_per_km3, ExactReason::ByDefinition);
+        assert_ron_snapshot!(gm.div(&per_km3)?.to_string(), @r#""1993002209/5000 ±0.0008""#);
+
+        Ok(())
+    }
+}
@@ -31,9 +31,9 @@ use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
 //? use num_enum::{IntoPrimitive, TryFromPrimitive};
 //? use num_integer::Integer;
 //use num_rational::Ratio;
-//? use num_traits::{NumCast, ToPrimitive, Zero};
+use num_traits::ToPrimitive;
 //? use once_cell::sync::Lazy;
-//? use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 //? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
 
 use ecs_namespace::NamespaceTag;
@@ -52,17 +52,42 @@ impl std::convert::From<&str> for Abbr {
 
 //=================================================================================================|
 
-#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExactReason {
     MeasuredWithBothInfinitePrecisionAndAccuracy,
     ByProof,
     ByDefinition,
 }
 
-#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+/// A bound on the error of an approximate value, in the value's own unit.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Serialize, Deserialize)]
+pub enum Uncertainty {
+    /// Standard uncertainty, one standard deviation, as in the GUM.
+    #[display("±{}", display_f64(*_0))]
+    Standard(f64),
+
+    /// The true value is somewhere in `[lo, hi]`.
+    #[display("[{}, {}]", display_f64(*lo), display_f64(*hi))]
+    Interval { lo: f64, hi: f64 },
+}
+
+/// Plain decimal for ordinary magnitudes, scientific notation for very large or small ones.
+pub(crate) fn display_f64(x: f64) -> String {
+    if x == 0.0 || (1e-4..1e9).contains(&x.abs()) {
+        x.to_string()
+    } else {
+        format!("{x:e}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Serialize, Deserialize)]
 pub enum Exactness {
     Exact(ExactReason),
+
+    /// With no known bound on the error.
     Approximate,
+
+    Uncertain(Uncertainty),
 }
 
 //=================================================================================================|
@@ -93,8 +118,8 @@ impl EcsNum {
     pub fn recip(&self) -> Result<EcsNum> {
         use EcsNum::*;
         Ok(match self {
-            RatioU64(r) if *r.denom() != 0 => RatioU64(r.recip()),
-            BigRational(r) if !r.denom().is_zero() => BigRational(r.recip()),
+            RatioU64(r) if *r.numer() != 0 => RatioU64(r.recip()),
+            BigRational(r) if !r.numer().is_zero() => BigRational(r.recip()),
             F64(f) if f.is_normal() => F64(f.recip()),
             Entity(e) => Inverse(EcsNumRef(*e)),
             Inverse(ecsnumref) => Ref(*ecsnumref),
@@ -103,13 +128,29 @@ impl EcsNum {
             }
         })
     }
+
+    /// The value as an `f64`, for the variants that hold a number directly. Entity references
+    /// need the [`hecs::World`] to resolve and are an error here.
+    pub fn to_f64(&self) -> Result<f64> {
+        use EcsNum::*;
+        Ok(match self {
+            RatioU64(r) => *r.numer() as f64 / *r.denom() as f64,
+            BigRational(r) => r
+                .to_f64()
+                .ok_or_else(|| anyhow!("BigRational {r} out of range for f64"))?,
+            F64(f) => *f,
+            Entity(_) | Inverse(_) | Ref(_) => {
+                bail!("Can't convert {self:?} to f64 without a World");
+            }
+        })
+    }
 }
 
 //-------------------------------------------------------------------------------------------------|
 
 /// Reference to a [`EcsNum`] [`hecs::Entity`] in the [`hecs::World`].
 #[derive(Clone, Copy, Debug)]
-pub struct EcsNumRef(Entity);
+pub struct EcsNumRef(pub(crate) Entity);
 
 impl EcsNumRef {
     pub fn new(e: Entity, world: &World) -> Self {
@@ -119,16 +160,145 @@ impl EcsNumRef {
         );
         Self(e)
     }
+
+    pub fn entity(self) -> Entity {
+        self.0
+    }
 }
 
 //=================================================================================================|
 
+/// Angle is kept as a base dimension of its own, rather than as a ratio of lengths, so that turns
+/// and radians don't silently convert to plain numbers.
 #[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
-pub enum DimensionKind {
+pub enum BaseDimension {
     Length,
-    Angle,
-    Scale,
+    Mass,
     Time,
+    Angle,
+    Temperature,
+}
+
+impl BaseDimension {
+    pub const ALL: [BaseDimension; 5] = [
+        BaseDimension::Length,
+        BaseDimension::Mass,
+        BaseDimension::Time,
+        BaseDimension::Angle,
+        BaseDimension::Temperature,
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BaseDimension::Length => "L",
+            BaseDimension::Mass => "M",
+            BaseDimension::Time => "T",
+            BaseDimension::Angle => "A",
+            BaseDimension::Temperature => "Θ",
+        }
+    }
+}
+
+/// A dimension, as the vector of exponents of the [`BaseDimension`]s. E.g., velocity is L·T⁻¹.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DimensionKind(pub [i8; 5]);
+
+impl DimensionKind {
+    /// Dimensionless.
+    pub const SCALE: DimensionKind = DimensionKind([0, 0, 0, 0, 0]);
+    pub const LENGTH: DimensionKind = DimensionKind([1, 0, 0, 0, 0]);
+    pub const MASS: DimensionKind = DimensionKind([0, 1, 0, 0, 0]);
+    pub const TIME: DimensionKind = DimensionKind([0, 0, 1, 0, 0]);
+    pub const ANGLE: DimensionKind = DimensionKind([0, 0, 0, 1, 0]);
+    pub const TEMPERATURE: DimensionKind = DimensionKind([0, 0, 0, 0, 1]);
+
+    pub const AREA: DimensionKind = DimensionKind([2, 0, 0, 0, 0]);
+    pub const VELOCITY: DimensionKind = DimensionKind([1, 0, -1, 0, 0]);
+    pub const ANGULAR_RATE: DimensionKind = DimensionKind([0, 0, -1, 1, 0]);
+    pub const FREQUENCY: DimensionKind = DimensionKind([0, 0, -1, 0, 0]);
+    pub const PRESSURE: DimensionKind = DimensionKind([-1, 1, -2, 0, 0]);
+
+    const NAMED: [(DimensionKind, &'static str); 11] = [
+        (DimensionKind::SCALE, "Scale"),
+        (DimensionKind::LENGTH, "Length"),
+        (DimensionKind::MASS, "Mass"),
+        (DimensionKind::TIME, "Time"),
+        (DimensionKind::ANGLE, "Angle"),
+        (DimensionKind::TEMPERATURE, "Temperature"),
+        (DimensionKind::AREA, "Area"),
+        (DimensionKind::VELOCITY, "Velocity"),
+        (DimensionKind::ANGULAR_RATE, "AngularRate"),
+        (DimensionKind::FREQUENCY, "Frequency"),
+        (DimensionKind::PRESSURE, "Pressure"),
+    ];
+
+    pub fn exponent(self, base: BaseDimension) -> i8 {
+        self.0[base as usize]
+    }
+
+    pub fn is_dimensionless(self) -> bool {
+        self == DimensionKind::SCALE
+    }
+
+    fn combine(
+        &self,
+        rhs: &DimensionKind,
+        f: impl Fn(i8, i8) -> Option<i8>,
+    ) -> Result<DimensionKind> {
+        let mut exps = [0_i8; 5];
+        for (ix, exp) in exps.iter_mut().enumerate() {
+            *exp = f(self.0[ix], rhs.0[ix])
+                .ok_or_else(|| anyhow!("Dimension exponent overflow in {self} and {rhs}"))?;
+        }
+        Ok(DimensionKind(exps))
+    }
+
+    /// The dimension of a product.
+    pub fn mul(&self, rhs: &DimensionKind) -> Result<DimensionKind> {
+        self.combine(rhs, i8::checked_add)
+    }
+
+    /// The dimension of a quotient.
+    pub fn div(&self, rhs: &DimensionKind) -> Result<DimensionKind> {
+        self.combine(rhs, i8::checked_sub)
+    }
+
+    pub fn powi(&self, n: i8) -> Result<DimensionKind> {
+        self.combine(&DimensionKind::SCALE, |exp, _| exp.checked_mul(n))
+    }
+
+    pub fn recip(&self) -> Result<DimensionKind> {
+        self.powi(-1)
+    }
+}
+
+/// The name, if it has one, otherwise the product of base dimensions, e.g. `L·T⁻²`.
+impl std::fmt::Display for DimensionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((_, name)) = DimensionKind::NAMED.iter().find(|(dk, _)| dk == self) {
+            return f.write_str(name);
+        }
+
+        const SUPERSCRIPT_DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+        let superscript = |c: char| match c.to_digit(10) {
+            Some(d) => SUPERSCRIPT_DIGITS[d as usize],
+            None => '⁻',
+        };
+        let mut sep = "";
+        for base in BaseDimension::ALL {
+            let exp = self.exponent(base);
+            if exp != 0 {
+                f.write_str(sep)?;
+                f.write_str(base.symbol())?;
+                if exp != 1 {
+                    let exp: String = exp.to_string().chars().map(superscript).collect();
+                    f.write_str(&exp)?;
+                }
+                sep = "·";
+            }
+        }
+        Ok(())
+    }
 }
 
 //=================================================================================================|
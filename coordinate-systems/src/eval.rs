@@ -0,0 +1,676 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Arithmetic on [`EcsNum`] values, and evaluation of the ones that refer to other entities.
+//!
+//! Results stay exact as long as they can. `RatioU64` promotes to `BigRational` when it would
+//! overflow or go negative, and comes back down when the result fits again. Results that are
+//! irrational in general, such as most square roots and all the trig functions, are `F64`, as
+//! is anything computed from an `F64`.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use derive_more::Display;
+use hecs::{Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Signed, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+//? use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use crate::*;
+
+//=================================================================================================|
+
+/// The largest exact power [`EcsNum::pow`] computes, in bits of numerator and denominator.
+const MAX_EXACT_POW_BITS: u64 = 1 << 16;
+
+/// The two operands of a binary operation, brought to a common representation.
+enum Operands {
+    RatioU64(RatioU64, RatioU64),
+    BigRational(BigRational, BigRational),
+    F64(f64, f64),
+}
+
+fn ratiou64_to_bigrational(r: &RatioU64) -> BigRational {
+    BigRational::new_raw((*r.numer()).into(), (*r.denom()).into())
+}
+
+/// Reduces, and demotes to `RatioU64` if it fits.
+pub(crate) fn from_bigrational(r: BigRational) -> EcsNum {
+    let r = r.reduced();
+    if !r.is_negative() {
+        if let (Some(numer), Some(denom)) = (r.numer().to_u64(), r.denom().to_u64()) {
+            return EcsNum::RatioU64(RatioU64::new_raw(numer, denom));
+        }
+    }
+    EcsNum::BigRational(r)
+}
+
+impl EcsNum {
+    /// `RatioU64` and `BigRational` values are exact. An `F64` may happen to be, but we can't
+    /// tell.
+    pub fn is_exact(&self) -> bool {
+        matches!(self, EcsNum::RatioU64(_) | EcsNum::BigRational(_))
+    }
+
+    fn operands(&self, rhs: &EcsNum) -> Result<Operands> {
+        use EcsNum::*;
+        Ok(match (self, rhs) {
+            (RatioU64(a), RatioU64(b)) => Operands::RatioU64(*a, *b),
+            (RatioU64(a), BigRational(b)) => {
+                Operands::BigRational(ratiou64_to_bigrational(a), b.clone())
+            }
+            (BigRational(a), RatioU64(b)) => {
+                Operands::BigRational(a.clone(), ratiou64_to_bigrational(b))
+            }
+            (BigRational(a), BigRational(b)) => Operands::BigRational(a.clone(), b.clone()),
+            (Entity(_) | Inverse(_) | Ref(_), _) | (_, Entity(_) | Inverse(_) | Ref(_)) => {
+                bail!("Can't do arithmetic on {self} and {rhs} without evaluating them first");
+            }
+            _ => Operands::F64(self.to_f64()?, rhs.to_f64()?),
+        })
+    }
+
+    /// Applies an exact operation, first in `u64` and then, if that fails, in `BigInt`.
+    fn binary_op(
+        &self,
+        rhs: &EcsNum,
+        op_ratiou64: fn(&RatioU64, &RatioU64) -> Option<RatioU64>,
+        op_bigrational: fn(&BigRational, &BigRational) -> Option<BigRational>,
+        op_f64: fn(f64, f64) -> f64,
+    ) -> Result<EcsNum> {
+        Ok(match self.operands(rhs)? {
+            Operands::RatioU64(a, b) => match op_ratiou64(&a, &b) {
+                Some(r) => EcsNum::RatioU64(r.reduced()),
+                None => {
+                    let (a, b) = (ratiou64_to_bigrational(&a), ratiou64_to_bigrational(&b));
+                    let r = op_bigrational(&a, &b)
+                        .ok_or_else(|| anyhow!("Can't compute with {self} and {rhs}"))?;
+                    from_bigrational(r)
+                }
+            },
+            Operands::BigRational(a, b) => {
+                let r = op_bigrational(&a, &b)
+                    .ok_or_else(|| anyhow!("Can't compute with {self} and {rhs}"))?;
+                from_bigrational(r)
+            }
+            Operands::F64(a, b) => EcsNum::F64(op_f64(a, b)),
+        })
+    }
+
+    pub fn add(&self, rhs: &EcsNum) -> Result<EcsNum> {
+        self.binary_op(
+            rhs,
+            |a, b| a.checked_add(b),
+            |a, b| Some(a + b),
+            |a, b| a + b,
+        )
+    }
+
+    pub fn sub(&self, rhs: &EcsNum) -> Result<EcsNum> {
+        self.binary_op(
+            rhs,
+            |a, b| a.checked_sub(b),
+            |a, b| Some(a - b),
+            |a, b| a - b,
+        )
+    }
+
+    pub fn mul(&self, rhs: &EcsNum) -> Result<EcsNum> {
+        self.binary_op(
+            rhs,
+            |a, b| a.checked_mul(b),
+            |a, b| Some(a * b),
+            |a, b| a * b,
+        )
+    }
+
+    /// Division by an exact zero is an error. By an `F64` zero it's an infinity or NaN.
+    pub fn div(&self, rhs: &EcsNum) -> Result<EcsNum> {
+        ensure!(
+            !(rhs.is_exact() && rhs.is_zero()?),
+            "Can't divide {self} by zero"
+        );
+        self.binary_op(
+            rhs,
+            |a, b| a.checked_div(b),
+            |a, b| a.checked_div(b),
+            |a, b| a / b,
+        )
+    }
+
+    pub fn neg(&self) -> Result<EcsNum> {
+        EcsNum::RatioU64(RatioU64::zero()).sub(self)
+    }
+
+    pub fn is_zero(&self) -> Result<bool> {
+        use EcsNum::*;
+        Ok(match self {
+            RatioU64(r) => r.is_zero(),
+            BigRational(r) => r.is_zero(),
+            _ => self.to_f64()? == 0.0,
+        })
+    }
+
+    /// Exact for exact bases and integer exponents, unless the result would be too large to be
+    /// worth it. Otherwise `F64`.
+    pub fn pow(&self, exp: &EcsNum) -> Result<EcsNum> {
+        let opt_int_exp = match exp {
+            EcsNum::RatioU64(r) if r.is_integer() => r.to_integer().to_i32(),
+            EcsNum::BigRational(r) if r.is_integer() => r.to_integer().to_i32(),
+            _ => None,
+        };
+
+        let base = match self {
+            EcsNum::RatioU64(r) => Some(ratiou64_to_bigrational(r)),
+            EcsNum::BigRational(r) => Some(r.clone()),
+            _ => None,
+        };
+
+        // About log2 of the result's numerator and denominator.
+        let exact_bits = |base: &BigRational, exp: i32| {
+            let bits =
+                base.numer().bits().saturating_sub(1) + base.denom().bits().saturating_sub(1);
+            bits.saturating_mul(u64::from(exp.unsigned_abs()))
+        };
+
+        match (base, opt_int_exp) {
+            (Some(base), Some(exp)) if exact_bits(&base, exp) <= MAX_EXACT_POW_BITS => {
+                ensure!(
+                    !(base.is_zero() && exp < 0),
+                    "Can't raise zero to the negative power {exp}"
+                );
+                Ok(from_bigrational(base.pow(exp)))
+            }
+            _ => {
+                let f = self.to_f64()?.powf(exp.to_f64()?);
+                ensure!(f.is_finite(), "{self} to the power {exp} is out of range");
+                Ok(EcsNum::F64(f))
+            }
+        }
+    }
+
+    /// Exact when the numerator and denominator are perfect squares.
+    pub fn sqrt(&self) -> Result<EcsNum> {
+        let r = match self {
+            EcsNum::RatioU64(r) => ratiou64_to_bigrational(r),
+            EcsNum::BigRational(r) => r.clone(),
+            _ => {
+                let f = self.to_f64()?;
+                ensure!(0.0 <= f, "Can't take the square root of {self}");
+                return Ok(EcsNum::F64(f.sqrt()));
+            }
+        };
+        ensure!(!r.is_negative(), "Can't take the square root of {self}");
+
+        let r = r.reduced();
+        let (numer_sqrt, denom_sqrt) = (r.numer().sqrt(), r.denom().sqrt());
+        if &numer_sqrt * &numer_sqrt == *r.numer() && &denom_sqrt * &denom_sqrt == *r.denom() {
+            Ok(from_bigrational(BigRational::new_raw(
+                numer_sqrt, denom_sqrt,
+            )))
+        } else {
+            Ok(EcsNum::F64(self.to_f64()?.sqrt()))
+        }
+    }
+
+    /// Zero stays exact where the function of it is exactly zero or one.
+    fn trig(&self, f: fn(f64) -> f64, at_exact_zero: Option<u64>) -> Result<EcsNum> {
+        if let Some(v) = at_exact_zero {
+            if self.is_exact() && self.is_zero()? {
+                return Ok(EcsNum::RatioU64(RatioU64::from_integer(v)));
+            }
+        }
+        Ok(EcsNum::F64(f(self.to_f64()?)))
+    }
+
+    /// Of an angle in radians.
+    pub fn sin(&self) -> Result<EcsNum> {
+        self.trig(f64::sin, Some(0))
+    }
+
+    pub fn cos(&self) -> Result<EcsNum> {
+        self.trig(f64::cos, Some(1))
+    }
+
+    pub fn tan(&self) -> Result<EcsNum> {
+        self.trig(f64::tan, Some(0))
+    }
+
+    /// In radians.
+    pub fn asin(&self) -> Result<EcsNum> {
+        self.trig(f64::asin, Some(0))
+    }
+
+    pub fn acos(&self) -> Result<EcsNum> {
+        self.trig(f64::acos, None)
+    }
+
+    pub fn atan(&self) -> Result<EcsNum> {
+        self.trig(f64::atan, Some(0))
+    }
+
+    pub fn atan2(&self, x: &EcsNum) -> Result<EcsNum> {
+        Ok(EcsNum::F64(self.to_f64()?.atan2(x.to_f64()?)))
+    }
+
+    //---------------------------------------------------------------------------------------------|
+
+    /// Resolves `Entity`, `Ref` and `Inverse` through the [`EcsNum`] components of the entities
+    /// they refer to, however deeply. A cycle of references is an error.
+    pub fn evaluate(&self, world: &World) -> Result<EcsNum> {
+        let mut visiting = Vec::new();
+        self.evaluate_impl(world, &mut visiting)
+    }
+
+    fn evaluate_impl(&self, world: &World, visiting: &mut Vec<Entity>) -> Result<EcsNum> {
+        use EcsNum::*;
+        match self {
+            RatioU64(r) => Ok(RatioU64(r.reduced())),
+            BigRational(r) => Ok(from_bigrational(r.clone())),
+            F64(f) => Ok(F64(*f)),
+            Entity(e) => Self::evaluate_entity(world, *e, visiting),
+            Ref(ecsnumref) => Self::evaluate_entity(world, ecsnumref.entity(), visiting),
+            Inverse(ecsnumref) => {
+                Self::evaluate_entity(world, ecsnumref.entity(), visiting)?.recip()
+            }
+        }
+    }
+
+    fn evaluate_entity(world: &World, e: Entity, visiting: &mut Vec<Entity>) -> Result<EcsNum> {
+        ensure!(
+            !visiting.contains(&e),
+            "Cycle of EcsNum references: {visiting:?} back to {e:?}"
+        );
+
+        let ecsnum = world
+            .get::<&EcsNum>(e)
+            .with_context(|| format!("{e:?} has no EcsNum"))?
+            .clone();
+
+        visiting.push(e);
+        let result = ecsnum.evaluate_impl(world, visiting);
+        visiting.pop();
+        result
+    }
+}
+
+//=================================================================================================|
+
+/// A value with what's known about its error, such as the value of a [`DimensionedConstant`].
+/// Arithmetic on these propagates the [`Exactness`]:
+///
+/// * Exact values combine into exact values, where the result is a ratio. Where it's an
+///   `F64`, as for most square roots, the result is uncertain by its rounding.
+/// * `Approximate` with no bound makes the result `Approximate`.
+/// * Intervals propagate by interval arithmetic when no standard uncertainty is involved, so
+///   the bounds hold for any combination of inputs within their bounds.
+/// * Otherwise, standard uncertainties propagate linearly to first order, assuming the inputs
+///   are uncorrelated, as in the GUM. An interval counts as a uniform distribution, with a
+///   standard uncertainty of its half-width over √3.
+#[derive(Clone, Debug, Display)]
+#[display("{value} {exactness}")]
+pub struct Estimate {
+    pub value: EcsNum,
+    pub exactness: Exactness,
+}
+
+/// Which binary operation, for propagating its error.
+#[derive(Clone, Copy)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Estimate {
+    pub fn new(value: EcsNum, exactness: Exactness) -> Self {
+        Self { value, exactness }
+    }
+
+    pub fn exact(value: EcsNum, reason: ExactReason) -> Self {
+        Self::new(value, Exactness::Exact(reason))
+    }
+
+    /// The evaluated value and the exactness of a [`DimensionedConstant`] entity.
+    pub fn from_constant(world: &World, e: Entity) -> Result<Self> {
+        let not_a_constant = || format!("{e:?} is not a DimensionedConstant");
+        let exactness = *world.get::<&Exactness>(e).with_context(not_a_constant)?;
+        let value = world
+            .get::<&EcsNum>(e)
+            .with_context(not_a_constant)?
+            .evaluate(world)?;
+        Ok(Self::new(value, exactness))
+    }
+
+    pub fn is_exact(&self) -> bool {
+        matches!(self.exactness, Exactness::Exact(_))
+    }
+
+    /// The standard uncertainty, if any bound is known. Zero for exact values.
+    pub fn standard_uncertainty(&self) -> Option<f64> {
+        match self.exactness {
+            Exactness::Exact(_) => Some(0.0),
+            Exactness::Approximate => None,
+            Exactness::Uncertain(Uncertainty::Standard(u)) => Some(u),
+            Exactness::Uncertain(Uncertainty::Interval { lo, hi }) => {
+                Some((hi - lo) / 2.0 / 3f64.sqrt())
+            }
+        }
+    }
+
+    /// The interval, for exact values and those with an interval.
+    fn interval(&self) -> Result<Option<(f64, f64)>> {
+        Ok(match self.exactness {
+            Exactness::Exact(_) => {
+                let v = self.value.to_f64()?;
+                Some((v, v))
+            }
+            Exactness::Uncertain(Uncertainty::Interval { lo, hi }) => Some((lo, hi)),
+            _ => None,
+        })
+    }
+
+    /// An exact computation whose result had to be rounded to `F64`.
+    fn rounded(value: EcsNum) -> Result<Self> {
+        let v = value.to_f64()?;
+        let ulp = v.abs() * f64::EPSILON;
+        let exactness = Exactness::Uncertain(Uncertainty::Interval {
+            lo: v - ulp,
+            hi: v + ulp,
+        });
+        Ok(Self::new(value, exactness))
+    }
+
+    fn binary(&self, rhs: &Estimate, op: BinaryOp) -> Result<Estimate> {
+        use Exactness::*;
+
+        let value = match op {
+            BinaryOp::Add => self.value.add(&rhs.value)?,
+            BinaryOp::Sub => self.value.sub(&rhs.value)?,
+            BinaryOp::Mul => self.value.mul(&rhs.value)?,
+            BinaryOp::Div => self.value.div(&rhs.value)?,
+        };
+
+        let exactness = match (self.exactness, rhs.exactness) {
+            (Approximate, _) | (_, Approximate) => Approximate,
+            (Exact(a), Exact(b)) => {
+                if !value.is_exact() {
+                    return Self::rounded(value);
+                }
+                // Exact by the same reason, or else by the computation.
+                Exact(if a == b { a } else { ExactReason::ByProof })
+            }
+            _ => match (self.interval()?, rhs.interval()?) {
+                (Some((a_lo, a_hi)), Some((b_lo, b_hi))) => {
+                    let (lo, hi) = match op {
+                        BinaryOp::Add => (a_lo + b_lo, a_hi + b_hi),
+                        BinaryOp::Sub => (a_lo - b_hi, a_hi - b_lo),
+                        BinaryOp::Mul | BinaryOp::Div => {
+                            ensure!(
+                                !matches!(op, BinaryOp::Div) || 0.0 < b_lo || b_hi < 0.0,
+                                "Can't divide by {rhs}, whose interval contains zero"
+                            );
+                            let f = |a: f64, b: f64| match op {
+                                BinaryOp::Mul => a * b,
+                                _ => a / b,
+                            };
+                            let corners =
+                                [f(a_lo, b_lo), f(a_lo, b_hi), f(a_hi, b_lo), f(a_hi, b_hi)];
+                            let lo = corners.into_iter().fold(f64::INFINITY, f64::min);
+                            let hi = corners.into_iter().fold(f64::NEG_INFINITY, f64::max);
+                            (lo, hi)
+                        }
+                    };
+                    Uncertain(Uncertainty::Interval { lo, hi })
+                }
+                _ => {
+                    let (a, b) = (self.value.to_f64()?, rhs.value.to_f64()?);
+                    let (ua, ub) = (
+                        self.standard_uncertainty().unwrap_or_default(),
+                        rhs.standard_uncertainty().unwrap_or_default(),
+                    );
+                    // The partial derivatives of the result by each input.
+                    let (da, db) = match op {
+                        BinaryOp::Add => (1.0, 1.0),
+                        BinaryOp::Sub => (1.0, -1.0),
+                        BinaryOp::Mul => (b, a),
+                        BinaryOp::Div => (1.0 / b, -a / (b * b)),
+                    };
+                    Uncertain(Uncertainty::Standard((da * ua).hypot(db * ub)))
+                }
+            },
+        };
+        Ok(Self::new(value, exactness))
+    }
+
+    pub fn add(&self, rhs: &Estimate) -> Result<Estimate> {
+        self.binary(rhs, BinaryOp::Add)
+    }
+
+    pub fn sub(&self, rhs: &Estimate) -> Result<Estimate> {
+        self.binary(rhs, BinaryOp::Sub)
+    }
+
+    pub fn mul(&self, rhs: &Estimate) -> Result<Estimate> {
+        self.binary(rhs, BinaryOp::Mul)
+    }
+
+    pub fn div(&self, rhs: &Estimate) -> Result<Estimate> {
+        self.binary(rhs, BinaryOp::Div)
+    }
+
+    /// Applies a function that's monotonic over the interval, with derivative `df` for the
+    /// standard uncertainty.
+    fn unary(&self, value: EcsNum, f: impl Fn(f64) -> f64, df: f64) -> Result<Estimate> {
+        use Exactness::*;
+        let exactness = match self.exactness {
+            Exact(reason) if value.is_exact() => Exact(reason),
+            Exact(_) => return Self::rounded(value),
+            Approximate => Approximate,
+            Uncertain(Uncertainty::Interval { lo, hi }) => {
+                let (lo, hi) = (f(lo), f(hi));
+                Uncertain(Uncertainty::Interval {
+                    lo: lo.min(hi),
+                    hi: lo.max(hi),
+                })
+            }
+            Uncertain(Uncertainty::Standard(u)) => Uncertain(Uncertainty::Standard((df * u).abs())),
+        };
+        Ok(Self::new(value, exactness))
+    }
+
+    pub fn neg(&self) -> Result<Estimate> {
+        self.unary(self.value.neg()?, |x| -x, -1.0)
+    }
+
+    pub fn recip(&self) -> Result<Estimate> {
+        if let Some((lo, hi)) = self.interval()? {
+            ensure!(
+                0.0 < lo || hi < 0.0,
+                "Can't take the reciprocal of {self}, whose interval contains zero"
+            );
+        }
+        let x = self.value.to_f64()?;
+        self.unary(self.value.recip()?, f64::recip, -1.0 / (x * x))
+    }
+
+    pub fn sqrt(&self) -> Result<Estimate> {
+        if let Some((lo, _)) = self.interval()? {
+            ensure!(0.0 <= lo, "Can't take the square root of {self}");
+        }
+        let value = self.value.sqrt()?;
+        let df = 0.5 / value.to_f64()?;
+        self.unary(value, f64::sqrt, df)
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    fn r(numer: u64, denom: u64) -> EcsNum {
+        EcsNum::RatioU64(RatioU64::new(numer, denom))
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        // Exact, and reduced.
+        assert_ron_snapshot!(r(1, 3).add(&r(1, 6))?.to_string(), @r#""1/2""#);
+        assert_ron_snapshot!(r(1, 3).mul(&r(3, 4))?.to_string(), @r#""1/4""#);
+        assert_ron_snapshot!(r(1, 3).div(&r(2, 3))?.to_string(), @r#""1/2""#);
+
+        // Negative and overflowing results promote, and come back down when they fit.
+        let neg = r(1, 3).sub(&r(1, 2))?;
+        assert_ron_snapshot!(neg.to_string(), @r#""-1/6""#);
+        assert_ron_snapshot!(neg.add(&r(1, 2))?.to_string(), @r#""1/3""#);
+        let big = r(u64::MAX, 1).mul(&r(u64::MAX, 1))?;
+        assert_ron_snapshot!(big.to_string(), @r#""340282366920938463426481119284349108225""#);
+        assert_ron_snapshot!(big.div(&r(u64::MAX, 1))?.to_string(), @r#""18446744073709551615""#);
+        assert_ron_snapshot!(r(2, 1).pow(&r(70, 1))?.to_string(), @r#""1180591620717411303424""#);
+        assert_ron_snapshot!(r(2, 3).pow(&neg.mul(&r(6, 1))?)?.to_string(), @r#""3/2""#);
+        assert_ron_snapshot!(r(1, 1).pow(&r(2_000_000_000, 1))?.to_string(), @r#""1""#);
+        assert!(matches!(
+            r(1, 2).pow(&r(60_000, 1))?,
+            EcsNum::BigRational(_)
+        ));
+        assert!(matches!(r(1, 2).pow(&r(70_000, 1))?, EcsNum::F64(_)));
+        assert!(r(2, 1).pow(&r(2_000_000_000, 1)).is_err());
+
+        // Irrational results are F64.
+        assert_ron_snapshot!(r(9, 4).sqrt()?.to_string(), @r#""3/2""#);
+        assert_ron_snapshot!(r(2, 1).sqrt()?.to_string(), @r#""1.4142135623730951""#);
+        assert_ron_snapshot!(r(1, 4).pow(&r(1, 2))?.to_string(), @r#""0.5""#);
+        assert_ron_snapshot!(r(0, 1).cos()?.to_string(), @r#""1""#);
+        assert_ron_snapshot!(EcsNum::F64(0.5).add(&r(1, 4))?.to_string(), @r#""0.75""#);
+        assert!(r(1, 1).div(&r(0, 1)).is_err());
+        assert!(neg.sqrt().is_err());
+
+        // Evaluation through the World: the radian is tau_inv turns.
+        let mut world = World::new();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let scale = |world: &World, name: &str| -> Result<EcsNum> {
+            let e = Unit::look_up(world, DimensionKind::LENGTH, name)
+                .or_else(|| Unit::look_up(world, DimensionKind::ANGLE, name))
+                .ok_or_else(|| anyhow!("no unit {name}"))?;
+            let unit_def = world.get::<&UnitDef>(e)?;
+            let UnitDef::ScaleToBaseUnit { scale, .. } = &*unit_def else {
+                bail!("{name} is a base unit");
+            };
+            scale.evaluate(world)
+        };
+
+        let radian = scale(world, "radian")?;
+        assert!(radian.is_exact());
+        assert_ron_snapshot!(radian.to_f64()?, @"0.15915494309189535");
+        assert_ron_snapshot!(radian.recip()?.to_f64()?, @"6.283185307179586");
+
+        // Inches to meters, by way of millimeters, and miles to meters.
+        let inch_m = scale(world, "inch")?.mul(&scale(world, "millimeter")?)?;
+        assert_ron_snapshot!(inch_m.to_string(), @r#""127/5000""#);
+        let mile_m = scale(world, "mile")?
+            .mul(&scale(world, "foot")?)?
+            .mul(&inch_m)?;
+        assert_ron_snapshot!(mile_m.to_string(), @r#""201168/125""#);
+
+        // tau times tau_inv is exactly one, by way of BigRational.
+        let tau = DimensionedConstant::look_up(world, DimensionKind::SCALE, "tau").unwrap();
+        let tau_inv = DimensionedConstant::look_up(world, DimensionKind::SCALE, "tau_inv").unwrap();
+        let product = EcsNum::Entity(tau)
+            .evaluate(world)?
+            .mul(&EcsNum::Entity(tau_inv).evaluate(world)?)?;
+        assert_ron_snapshot!(product.to_string(), @r#""1""#);
+
+        // Inverse and Ref chains, and a cycle.
+        let e_a = world.spawn((r(4, 1),));
+        let e_b = world.spawn((EcsNum::Inverse(EcsNumRef::new(e_a, world)),));
+        let e_c = world.spawn((EcsNum::Ref(EcsNumRef::new(e_b, world)),));
+        assert_ron_snapshot!(EcsNum::Entity(e_c).evaluate(world)?.to_string(), @r#""1/4""#);
+        world.insert_one(e_a, EcsNum::Entity(e_c))?;
+        let err = EcsNum::Entity(e_c).evaluate(world).unwrap_err();
+        assert!(err.to_string().starts_with("Cycle"), "{err}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn t_estimate() -> anyhow::Result<()> {
+        use Uncertainty::*;
+
+        let defined = |n, d| Estimate::exact(r(n, d), ExactReason::ByDefinition);
+        let standard = |v, u| Estimate::new(EcsNum::F64(v), Exactness::Uncertain(Standard(u)));
+        let interval =
+            |v, lo, hi| Estimate::new(EcsNum::F64(v), Exactness::Uncertain(Interval { lo, hi }));
+
+        // Exact stays exact, until it has to be rounded.
+        assert_ron_snapshot!(defined(1, 3).mul(&defined(3, 1))?.to_string(), @r#""1 ByDefinition""#);
+        assert_ron_snapshot!(defined(2, 1).sqrt()?.exactness.to_string(), @r#""[1.414213562373095, 1.4142135623730954]""#);
+        let proved = Estimate::exact(r(2, 1), ExactReason::ByProof);
+        assert_ron_snapshot!(defined(1, 1).add(&proved)?.exactness.to_string(), @r#""ByProof""#);
+
+        // Standard uncertainties add in quadrature, and scale by the partial derivatives.
+        assert_ron_snapshot!(standard(10.0, 0.3).add(&standard(5.0, 0.4))?.to_string(), @r#""15 ±0.5""#);
+        assert_ron_snapshot!(standard(10.0, 0.1).mul(&defined(3, 1))?.to_string(), @r#""30 ±0.30000000000000004""#);
+        let q = standard(10.0, 0.1).div(&standard(2.0, 0.02))?;
+        assert_ron_snapshot!(q.standard_uncertainty().map(|u| (u * 1e6).round() / 1e6), @"Some(0.070711)");
+        assert_ron_snapshot!(standard(4.0, 0.4).sqrt()?.to_string(), @r#""2 ±0.1""#);
+
+        // Intervals by interval arithmetic, and as uniform distributions when mixed.
+        assert_ron_snapshot!(interval(1.5, 1.0, 2.0).sub(&interval(0.5, 0.0, 1.0))?.exactness.to_string(), @r#""[0, 2]""#);
+        assert_ron_snapshot!(interval(-0.5, -1.0, 2.0).mul(&interval(3.5, 3.0, 4.0))?.exactness.to_string(), @r#""[-4, 8]""#);
+        assert_ron_snapshot!(interval(1.5, 1.0, 2.0).recip()?.exactness.to_string(), @r#""[0.5, 1]""#);
+        assert!(interval(1.0, 1.0, 2.0)
+            .div(&interval(0.0, -1.0, 1.0))
+            .is_err());
+        let mixed = interval(1.0, 0.0, 2.0).add(&standard(1.0, 0.0))?;
+        assert_ron_snapshot!(mixed.standard_uncertainty().map(|u| (u * 1e6).round() / 1e6), @"Some(0.57735)");
+
+        // No bound in, no bound out.
+        let approx = Estimate::new(EcsNum::F64(1.0), Exactness::Approximate);
+        assert_ron_snapshot!(approx.add(&standard(1.0, 0.1))?.exactness.to_string(), @r#""Approximate""#);
+
+        // The WGS 84 polar radius follows exactly from the defining a and 1/f.
+        let mut world = World::new();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+        let e = crate::gis::lookup_epsg(world, 7030)?;
+        let def = OblateSpheroidRef::new(e, world).def(world)?;
+        let b = def.polar_radius(world)?;
+        assert_ron_snapshot!(b.exactness.to_string(), @r#""ByDefinition""#);
+        assert_ron_snapshot!(b.value.to_f64()?, @"6356752.314245179");
+
+        Ok(())
+    }
+}
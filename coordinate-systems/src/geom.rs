@@ -37,6 +37,8 @@ use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
 //? use serde::{Deserialize, Serialize};
 //? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
 
+use cgmath::{ElementWise, InnerSpace};
+
 use ecs_namespace::NamespaceTag;
 use crate::*;
 
@@ -95,6 +97,71 @@ pub struct OblateSpheroidDef {
     pub f: Flattening,
 }
 
+impl OblateSpheroidDef {
+    /// Equatorial semi-axis `a`, with its exactness.
+    pub fn equatorial_radius(&self, world: &World) -> Result<Estimate> {
+        Ok(Estimate::new(
+            self.a.value.evaluate(world)?,
+            self.a.exactness,
+        ))
+    }
+
+    /// Flattening `f`. It's a defining parameter, so it's taken as exact by definition when it's
+    /// a ratio.
+    pub fn flattening(&self, world: &World) -> Result<Estimate> {
+        let f = self.f.f()?.evaluate(world)?;
+        Ok(if f.is_exact() {
+            Estimate::exact(f, ExactReason::ByDefinition)
+        } else {
+            Estimate::new(f, Exactness::Approximate)
+        })
+    }
+
+    /// Polar semi-axis, `b = a (1 - f)`, with its exactness.
+    pub fn polar_radius(&self, world: &World) -> Result<Estimate> {
+        let a = self.equatorial_radius(world)?;
+        let f = self.flattening(world)?;
+        a.mul(&one().sub(&f)?)
+    }
+
+    /// First eccentricity squared, `e² = f (2 - f)`.
+    pub fn first_eccentricity_squared(&self, world: &World) -> Result<Estimate> {
+        let f = self.flattening(world)?;
+        f.mul(&one().add(&one())?.sub(&f)?)
+    }
+
+    /// Second eccentricity squared, `e′² = e² / (1 - e²)`.
+    pub fn second_eccentricity_squared(&self, world: &World) -> Result<Estimate> {
+        let e2 = self.first_eccentricity_squared(world)?;
+        e2.div(&one().sub(&e2)?)
+    }
+
+    /// The IUGG mean radius `R1 = (2a + b) / 3`.
+    pub fn arithmetic_mean_radius(&self, world: &World) -> Result<Estimate> {
+        let a = self.equatorial_radius(world)?;
+        let b = self.polar_radius(world)?;
+        let three = Estimate::exact(
+            EcsNum::RatioU64(RatioU64::from_integer(3)),
+            ExactReason::ByDefinition,
+        );
+        a.add(&a)?.add(&b)?.div(&three)
+    }
+
+    /// The shape, for the geometric operations on [`Ellipsoid3`].
+    pub fn ellipsoid3(&self, world: &World) -> Result<Ellipsoid3> {
+        let a = self.equatorial_radius(world)?.value.to_f64()?;
+        let b = self.polar_radius(world)?.value.to_f64()?;
+        Ellipsoid3::new(a, a, b)
+    }
+}
+
+fn one() -> Estimate {
+    Estimate::exact(
+        EcsNum::RatioU64(RatioU64::from_integer(1)),
+        ExactReason::ByDefinition,
+    )
+}
+
 //-------------------------------------------------------------------------------------------------|
 
 /// [`hecs::Bundle`] for an ellipsoid of revolution in the [`hecs::World`].
@@ -108,16 +175,43 @@ pub struct OblateSpheroid {
 //-------------------------------------------------------------------------------------------------|
 
 #[derive(Clone, Copy, Debug)]
-pub struct OblateSpheroidRef(Entity);
+pub struct OblateSpheroidRef(pub(crate) Entity);
 
 impl OblateSpheroidRef {
     pub fn new(e: Entity, world: &World) -> Self {
         debug_assert!(
-            world.satisfies::<&OblateSpheroid>(e).unwrap_or_default(),
+            world.satisfies::<&OblateSpheroidDef>(e).unwrap_or_default(),
             "Although this newtype can't prevent the OblateSpheroidRef Entity from being removed from the World, it should probably at least start out that way."
         );
         OblateSpheroidRef(e)
     }
+
+    pub fn entity(self) -> Entity {
+        self.0
+    }
+
+    /// A copy of the entity's definition.
+    pub fn def(&self, world: &World) -> Result<OblateSpheroidDef> {
+        let def = world
+            .get::<&OblateSpheroidDef>(self.0)
+            .context("OblateSpheroidRef::def")?;
+        Ok((*def).clone())
+    }
+
+    /// Finds one of the reference ellipsoids in `/geometry/ellipsoids` by name, e.g. `"WGS 84"`.
+    pub fn find(world: &World, name: &str) -> Result<Self> {
+        let ns_ellipsoids = ecs_ns_get(world, NamePathSpec::absolute(["geometry", "ellipsoids"]))?;
+        world
+            .children::<NamespaceTag>(ns_ellipsoids)
+            .find(|&e| {
+                world.satisfies::<&OblateSpheroidDef>(e).unwrap_or_default()
+                    && world
+                        .get::<&Option<Name>>(e)
+                        .is_ok_and(|opt_name| opt_name.as_ref().is_some_and(|n| n.as_str() == name))
+            })
+            .map(OblateSpheroidRef)
+            .with_context(|| format!("No reference ellipsoid named {name:?}"))
+    }
 }
 
 //-------------------------------------------------------------------------------------------------|
@@ -144,10 +238,739 @@ where
 
 //=================================================================================================|
 
+/// A sphere.
+#[derive(Clone, Debug, Display)]
+#[display("SphereDef {{ r: {r} }}")]
+pub struct SphereDef {
+    pub r: DimensionedConstant,
+}
+
+impl SphereDef {
+    /// The shape, for the geometric operations on [`Ellipsoid3`].
+    pub fn ellipsoid3(&self, world: &World) -> Result<Ellipsoid3> {
+        let r = self.r.value.evaluate(world)?.to_f64()?;
+        Ellipsoid3::new(r, r, r)
+    }
+}
+
+/// A triaxial ellipsoid, with semi-axes `a ≥ b` in the equatorial plane along x and y, and `c`
+/// along the polar z axis. Used for the Moon and small bodies.
+#[derive(Clone, Debug, Display)]
+#[display("TriaxialEllipsoidDef {{ a: {a}, b: {b}, c: {c} }}")]
+pub struct TriaxialEllipsoidDef {
+    pub a: DimensionedConstant,
+    pub b: DimensionedConstant,
+    pub c: DimensionedConstant,
+}
+
+impl TriaxialEllipsoidDef {
+    /// The shape, for the geometric operations on [`Ellipsoid3`].
+    pub fn ellipsoid3(&self, world: &World) -> Result<Ellipsoid3> {
+        let semi_axis = |dc: &DimensionedConstant| dc.value.evaluate(world)?.to_f64();
+        Ellipsoid3::new(
+            semi_axis(&self.a)?,
+            semi_axis(&self.b)?,
+            semi_axis(&self.c)?,
+        )
+    }
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// [`hecs::Bundle`] for a sphere in the [`hecs::World`].
+#[derive(Bundle, Clone, Debug, Display)]
+#[display("Ellipsoid3Sphere {{ opt_name: {opt_name:?}, def: {def:?} }}")]
+pub struct Ellipsoid3Sphere {
+    pub opt_name: Option<Name>,
+    pub def: SphereDef,
+}
+
+/// [`hecs::Bundle`] for an ellipsoid of revolution in the [`hecs::World`].
+pub type Ellipsoid3Oblate = OblateSpheroid;
+
+/// [`hecs::Bundle`] for a triaxial ellipsoid in the [`hecs::World`].
+#[derive(Bundle, Clone, Debug, Display)]
+#[display("Ellipsoid3Triaxial {{ opt_name: {opt_name:?}, def: {def:?} }}")]
+pub struct Ellipsoid3Triaxial {
+    pub opt_name: Option<Name>,
+    pub def: TriaxialEllipsoidDef,
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+pub fn ecs_add_sphere<IN, ION>(
+    world: &mut World,
+    e_ns_parent: Entity,
+    opt_name: ION,
+    def: SphereDef,
+) -> Result<Entity>
+where
+    IN: Into<Name>,
+    ION: Into<Option<IN>>,
+{
+    let opt_name: Option<IN> = opt_name.into();
+    let opt_name: Option<Name> = opt_name.map(Into::into);
+
+    world
+        .attach_new::<NamespaceTag, _>(e_ns_parent, Ellipsoid3Sphere { opt_name, def })
+        .context("ecs_add_sphere")
+}
+
+pub fn ecs_add_triaxial_ellipsoid<IN, ION>(
+    world: &mut World,
+    e_ns_parent: Entity,
+    opt_name: ION,
+    def: TriaxialEllipsoidDef,
+) -> Result<Entity>
+where
+    IN: Into<Name>,
+    ION: Into<Option<IN>>,
+{
+    let opt_name: Option<IN> = opt_name.into();
+    let opt_name: Option<Name> = opt_name.map(Into::into);
+
+    world
+        .attach_new::<NamespaceTag, _>(e_ns_parent, Ellipsoid3Triaxial { opt_name, def })
+        .context("ecs_add_triaxial_ellipsoid")
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// The shape of a sphere, oblate spheroid or triaxial ellipsoid centered on the origin with its
+/// semi-axes along x, y and z, evaluated to `f64` for geometry.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ellipsoid3 {
+    pub radii: Vector3,
+}
+
+impl Ellipsoid3 {
+    /// The most rings or segments [`Self::tessellate`] makes. `(rings + 1) * (segments + 1)`
+    /// vertices then fit in a `u32`.
+    pub const MAX_TESSELLATION: u32 = 1 << 15;
+
+    pub fn new(a: f64, b: f64, c: f64) -> Result<Self> {
+        ensure!(
+            [a, b, c].iter().all(|&r| 0.0 < r && r.is_finite()),
+            "semi-axes ({a}, {b}, {c}) are not all positive"
+        );
+        Ok(Self {
+            radii: Vector3::new(a, b, c),
+        })
+    }
+
+    /// The shape of an entity with a [`SphereDef`], [`OblateSpheroidDef`] or
+    /// [`TriaxialEllipsoidDef`].
+    pub fn from_entity(world: &World, e: Entity) -> Result<Self> {
+        if let Ok(def) = world.get::<&SphereDef>(e) {
+            return def.ellipsoid3(world);
+        }
+        if let Ok(def) = world.get::<&OblateSpheroidDef>(e) {
+            return def.ellipsoid3(world);
+        }
+        if let Ok(def) = world.get::<&TriaxialEllipsoidDef>(e) {
+            return def.ellipsoid3(world);
+        }
+        bail!("{e:?} is not an ellipsoid")
+    }
+
+    /// The implicit function `(x/a)² + (y/b)² + (z/c)² - 1`. Negative inside, zero on the
+    /// surface.
+    pub fn implicit(&self, p: Vector3) -> f64 {
+        self.unit_sphere_coords(p).magnitude2() - 1.0
+    }
+
+    /// Outward unit normal at a point on the surface. Off the surface, it's the normal of the
+    /// scaled ellipsoid through the point.
+    pub fn surface_normal(&self, p: Vector3) -> Vector3 {
+        let r2 = self.radii.mul_element_wise(self.radii);
+        p.div_element_wise(r2).normalize()
+    }
+
+    /// The distance `t ≥ 0` along the ray `origin + t dir` to where it first meets the surface,
+    /// if it does. From inside, that's where it leaves. `dir` need not be a unit vector.
+    pub fn ray_intersection(&self, origin: Vector3, dir: Vector3) -> Option<f64> {
+        // Scaled to the unit sphere, this is |o + t d|² = 1.
+        let o = self.unit_sphere_coords(origin);
+        let d = self.unit_sphere_coords(dir);
+
+        let qa = d.magnitude2();
+        let qb = o.dot(d);
+        let qc = o.magnitude2() - 1.0;
+        if qa == 0.0 {
+            return None;
+        }
+        let disc = qb * qb - qa * qc;
+        if disc < 0.0 {
+            return None;
+        }
+
+        // The numerically stable pair of roots.
+        let q = -(qb + disc.sqrt().copysign(qb));
+        let (t0, t1) = if q == 0.0 {
+            (0.0, 0.0)
+        } else {
+            let (r0, r1) = (q / qa, qc / q);
+            (r0.min(r1), r0.max(r1))
+        };
+        [t0, t1].into_iter().find(|&t| 0.0 <= t)
+    }
+
+    /// The closest point on the surface to `p`, from inside or out.
+    ///
+    /// From D. Eberly, "Distance from a Point to an Ellipse, an Ellipsoid, or a Hyperellipsoid",
+    /// which reduces it to finding the root of a monotonic function by bisection.
+    pub fn closest_point(&self, p: Vector3) -> Vector3 {
+        // Work in the first octant, with the semi-axes in decreasing order.
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| self.radii[j].total_cmp(&self.radii[i]));
+        let e = order.map(|i| self.radii[i]);
+        let y = order.map(|i| p[i].abs());
+
+        let x = closest_point_first_octant(e, y);
+
+        let mut result = Vector3::new(0.0, 0.0, 0.0);
+        for (k, &i) in order.iter().enumerate() {
+            result[i] = x[k].copysign(p[i]);
+        }
+        result
+    }
+
+    /// A latitude-longitude mesh of the surface, with `rings` bands from pole to pole and
+    /// `segments` around. Vertices are spaced evenly in parametric latitude and longitude, and
+    /// each ring repeats its first vertex at longitude 360° so textures can wrap. At most
+    /// [`Self::MAX_TESSELLATION`] of each.
+    pub fn tessellate(&self, rings: u32, segments: u32) -> Result<Ellipsoid3Mesh> {
+        const MAX: u32 = Ellipsoid3::MAX_TESSELLATION;
+        ensure!(
+            (2..=MAX).contains(&rings) && (3..=MAX).contains(&segments),
+            "need 2 to {MAX} rings and 3 to {MAX} segments, not {rings} and {segments}"
+        );
+
+        let mut mesh = Ellipsoid3Mesh::default();
+        for i in 0..=rings {
+            let v = f64::from(i) / f64::from(rings);
+            let (sin_beta, cos_beta) = ((v - 0.5) * std::f64::consts::PI).sin_cos();
+            for j in 0..=segments {
+                let u = f64::from(j) / f64::from(segments);
+                let (sin_lon, cos_lon) = (u * std::f64::consts::TAU).sin_cos();
+                let p = Vector3::new(cos_beta * cos_lon, cos_beta * sin_lon, sin_beta)
+                    .mul_element_wise(self.radii);
+                mesh.positions.push(p);
+                mesh.normals.push(self.surface_normal(p));
+                mesh.uvs.push(Vector2::new(u, 1.0 - v));
+            }
+        }
+
+        // Counterclockwise seen from outside. The bands at the poles have one triangle per
+        // segment, the rest two: ring 0 is the south pole, where p00 and p01 coincide, and ring
+        // `rings` the north pole, where p10 and p11 do.
+        let stride = segments + 1;
+        for i in 0..rings {
+            for j in 0..segments {
+                let (p00, p01) = (i * stride + j, i * stride + j + 1);
+                let (p10, p11) = (p00 + stride, p01 + stride);
+                if i + 1 != rings {
+                    mesh.indices.extend([p00, p11, p10]);
+                }
+                if i != 0 {
+                    mesh.indices.extend([p00, p01, p11]);
+                }
+            }
+        }
+        Ok(mesh)
+    }
+
+    fn unit_sphere_coords(&self, v: Vector3) -> Vector3 {
+        v.div_element_wise(self.radii)
+    }
+}
+
+/// Triangle mesh from [`Ellipsoid3::tessellate`]. `indices` has three per triangle.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ellipsoid3Mesh {
+    pub positions: Vec<Vector3>,
+    pub normals: Vec<Vector3>,
+    pub uvs: Vec<Vector2>,
+    pub indices: Vec<u32>,
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// Eberly's case analysis for `e0 ≥ e1 ≥ e2 > 0` and `y ≥ 0`.
+fn closest_point_first_octant(e: [f64; 3], y: [f64; 3]) -> [f64; 3] {
+    let [e0, e1, e2] = e;
+    let [y0, y1, y2] = y;
+
+    if 0.0 < y2 {
+        if 0.0 < y1 {
+            if 0.0 < y0 {
+                let z = [y0 / e0, y1 / e1, y2 / e2];
+                let g = z.iter().map(|z| z * z).sum::<f64>() - 1.0;
+                if g == 0.0 {
+                    return y;
+                }
+                let r = [(e0 / e2).powi(2), (e1 / e2).powi(2), 1.0];
+                let s = bisect_root(&r, &z, g);
+                [0, 1, 2].map(|i| r[i] * y[i] / (s + r[i]))
+            } else {
+                let [x1, x2] = closest_point_first_quadrant([e1, e2], [y1, y2]);
+                [0.0, x1, x2]
+            }
+        } else if 0.0 < y0 {
+            let [x0, x2] = closest_point_first_quadrant([e0, e2], [y0, y2]);
+            [x0, 0.0, x2]
+        } else {
+            [0.0, 0.0, e2]
+        }
+    } else {
+        // In the plane of the two larger axes. Near enough to the center, the closest point is
+        // off that plane.
+        let (denom0, denom1) = (e0 * e0 - e2 * e2, e1 * e1 - e2 * e2);
+        let (numer0, numer1) = (e0 * y0, e1 * y1);
+        if numer0 < denom0 && numer1 < denom1 {
+            let (xde0, xde1) = (numer0 / denom0, numer1 / denom1);
+            let d = 1.0 - xde0 * xde0 - xde1 * xde1;
+            if 0.0 < d {
+                return [e0 * xde0, e1 * xde1, e2 * d.sqrt()];
+            }
+        }
+        let [x0, x1] = closest_point_first_quadrant([e0, e1], [y0, y1]);
+        [x0, x1, 0.0]
+    }
+}
+
+/// The 2-D case of [`closest_point_first_octant`], for an ellipse with `e0 ≥ e1 > 0`.
+fn closest_point_first_quadrant(e: [f64; 2], y: [f64; 2]) -> [f64; 2] {
+    let [e0, e1] = e;
+    let [y0, y1] = y;
+
+    if 0.0 < y1 {
+        if 0.0 < y0 {
+            let z = [y0 / e0, y1 / e1];
+            let g = z[0] * z[0] + z[1] * z[1] - 1.0;
+            if g == 0.0 {
+                return y;
+            }
+            let r = [(e0 / e1).powi(2), 1.0];
+            let s = bisect_root(&r, &z, g);
+            [r[0] * y0 / (s + r[0]), y1 / (s + 1.0)]
+        } else {
+            [0.0, e1]
+        }
+    } else {
+        let (numer0, denom0) = (e0 * y0, e0 * e0 - e1 * e1);
+        if numer0 < denom0 {
+            let xde0 = numer0 / denom0;
+            [e0 * xde0, e1 * (1.0 - xde0 * xde0).sqrt()]
+        } else {
+            [e0, 0.0]
+        }
+    }
+}
+
+/// The root `s` of `Σ (rᵢ zᵢ / (s + rᵢ))² - 1`, where the last `r` is 1 and `g` is its value at
+/// `s = 0`. It's decreasing, and bracketed by `[z_last - 1, |r z| - 1]` outside or
+/// `[z_last - 1, 0]` inside.
+fn bisect_root(r: &[f64], z: &[f64], g: f64) -> f64 {
+    let n: Vec<f64> = r.iter().zip(z).map(|(r, z)| r * z).collect();
+    let last = z.len() - 1;
+
+    let mut s0 = z[last] - 1.0;
+    let mut s1 = if g < 0.0 {
+        0.0
+    } else {
+        n.iter().map(|n| n * n).sum::<f64>().sqrt() - 1.0
+    };
+
+    let mut s = s0;
+    for _ in 0..1100 {
+        s = 0.5 * (s0 + s1);
+        if s == s0 || s == s1 {
+            break;
+        }
+        let g = n
+            .iter()
+            .zip(r)
+            .map(|(n, r)| (n / (s + r)).powi(2))
+            .sum::<f64>()
+            - 1.0;
+        if 0.0 < g {
+            s0 = s;
+        } else if g < 0.0 {
+            s1 = s;
+        } else {
+            break;
+        }
+    }
+    s
+}
+
+//=================================================================================================|
+
+/// A parameter of a reference ellipsoid: a defining decimal literal, a decimal rounded from a
+/// value derived from the defining ones, or a constant in `/consts`.
+enum Param {
+    Decimal(&'static str),
+    Rounded(&'static str),
+    Const(&'static str, &'static str),
+}
+
+impl Param {
+    fn to_ecsnum(&self, world: &World) -> Result<EcsNum> {
+        match *self {
+            Param::Decimal(s) => crate::units::parse_decimal(s),
+
+            // As `F64`, so it isn't taken to be exact.
+            Param::Rounded(s) => Ok(EcsNum::F64(s.parse()?)),
+
+            Param::Const(group, name) => Ok(EcsNum::Entity(DimensionedConstant::find(
+                world, group, name,
+            )?)),
+        }
+    }
+
+    fn exactness(&self, world: &World) -> Result<Exactness> {
+        match *self {
+            Param::Decimal(_) => Ok(Exactness::Exact(ExactReason::ByDefinition)),
+            Param::Rounded(_) => Ok(Exactness::Approximate),
+            Param::Const(group, name) => {
+                let e = DimensionedConstant::find(world, group, name)?;
+                Ok(*world.get::<&Exactness>(e)?)
+            }
+        }
+    }
+}
+
+/// The second parameter of a reference ellipsoid, whichever its definition uses.
+enum Shape {
+    /// Inverse flattening. Zero means a sphere, as in WKT and EPSG.
+    InvF(Param),
+
+    /// Polar semi-axis, in metres.
+    B(Param),
+}
+
+/// The standard reference ellipsoids: name, `a` in metres, and shape.
+#[rustfmt::skip]
+const ELLIPSOIDS: &[(&str, Param, Shape)] = {
+    use Param::*;
+    use Shape::*;
+    &[
+        ("WGS 84",             Const("wgs84", "a"),                           InvF(Const("wgs84", "inv_f"))),
+        // Defined by a, GM, J2 and ω. 1/f is derived, and rounded here.
+        ("GRS 1980",           Const("grs80", "a"),                           InvF(Rounded("298.257222101"))),
+        ("Clarke 1866",        Decimal("6378206.4"),                          B(Decimal("6356583.8"))),
+        ("Airy 1830",          Decimal("6377563.396"),                        InvF(Decimal("299.3249646"))),
+        ("Bessel 1841",        Decimal("6377397.155"),                        InvF(Decimal("299.1528128"))),
+        ("International 1924", Decimal("6378388"),                            InvF(Decimal("297"))),
+    ]
+};
+
+/// Spheres: name and radius in metres.
+#[rustfmt::skip]
+const SPHERES: &[(&str, Param)] = {
+    use Param::*;
+    &[
+        // IAU WGCCRE 2015 mean radius.
+        ("IAU Moon", Decimal("1737400")),
+        // IAU 2015 Resolution B3 nominal solar radius.
+        ("IAU Sun",  Const("astronomical", "nominal_solar_radius")),
+    ]
+};
+
+/// Triaxial shapes of small bodies, from the IAU WGCCRE 2015 report: name and `a`, `b`, `c` in
+/// metres.
+const TRIAXIAL_ELLIPSOIDS: &[(&str, [&str; 3])] = &[
+    ("IAU Phobos", ["13000", "11400", "9100"]),
+    ("IAU Deimos", ["7800", "6000", "5100"]),
+];
+
+fn ellipsoid_def(world: &World, a: &Param, shape: &Shape) -> Result<OblateSpheroidDef> {
+    let a = DimensionedConstant {
+        name: "a".into(),
+        dimension_kind: DimensionKind::LENGTH,
+        exactness: a.exactness(world)?,
+        value: a.to_ecsnum(world)?,
+    };
+
+    let f = match shape {
+        Shape::InvF(inv_f) => {
+            let inv_f = inv_f.to_ecsnum(world)?;
+            if inv_f.evaluate(world)?.is_zero()? {
+                Flattening::F(inv_f)
+            } else {
+                Flattening::F_inv(inv_f)
+            }
+        }
+        Shape::B(b) => {
+            // f = (a - b) / a, exact when both are.
+            let a = a.value.evaluate(world)?;
+            let b = b.to_ecsnum(world)?.evaluate(world)?;
+            Flattening::F(a.sub(&b)?.div(&a)?)
+        }
+    };
+
+    Ok(OblateSpheroidDef { a, f })
+}
+
 pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
     let ns_geometry = ecs_ns_find_or_create(world, NamePathSpec::absolute(["geometry"]))?;
+    let ns_ellipsoids = world
+        .attach_new::<NamespaceTag, _>(ns_geometry, (Name::from("ellipsoids"),))
+        .context("ellipsoids")?;
+
+    for (name, a, shape) in ELLIPSOIDS {
+        let def = ellipsoid_def(world, a, shape).with_context(|| format!("ellipsoid {name:?}"))?;
+        ecs_add_oblatespheroid::<&str, _>(world, ns_ellipsoids, *name, def)?;
+    }
 
-    //?
+    for (name, r) in SPHERES {
+        let r = DimensionedConstant {
+            name: "r".into(),
+            dimension_kind: DimensionKind::LENGTH,
+            exactness: r.exactness(world)?,
+            value: r.to_ecsnum(world)?,
+        };
+        ecs_add_sphere::<&str, _>(world, ns_ellipsoids, *name, SphereDef { r })?;
+    }
+
+    for (name, [a, b, c]) in TRIAXIAL_ELLIPSOIDS {
+        let semi_axis = |name: &str, s| -> Result<DimensionedConstant> {
+            Ok(DimensionedConstant {
+                name: name.into(),
+                dimension_kind: DimensionKind::LENGTH,
+                exactness: Exactness::Approximate,
+                value: crate::units::parse_decimal(s)?,
+            })
+        };
+        let def = TriaxialEllipsoidDef {
+            a: semi_axis("a", a)?,
+            b: semi_axis("b", b)?,
+            c: semi_axis("c", c)?,
+        };
+        ecs_add_triaxial_ellipsoid::<&str, _>(world, ns_ellipsoids, *name, def)?;
+    }
 
     Ok(())
 }
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use crate::gis::geodetic::OblateSpheroidParams;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let mut world = World::new();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let params = |name| {
+            let def = OblateSpheroidRef::find(world, name)
+                .unwrap()
+                .def(world)
+                .unwrap();
+            OblateSpheroidParams::from_def(world, &def).unwrap()
+        };
+
+        // WGS 84 refers to the catalog constants, and stays exact.
+        let wgs84 = OblateSpheroidRef::find(world, "WGS 84")?.def(world)?;
+        assert!(matches!(wgs84.a.value, EcsNum::Entity(_)));
+        let b = wgs84.polar_radius(world)?;
+        assert_ron_snapshot!((b.value.to_f64()?, b.exactness.to_string()), @r#"(6356752.314245179, "ByDefinition")"#);
+        let e2 = wgs84.first_eccentricity_squared(world)?;
+        assert_ron_snapshot!((e2.value.to_f64()?, e2.is_exact()), @"(0.006694379990141317, true)");
+        assert_ron_snapshot!(wgs84.second_eccentricity_squared(world)?.value.to_f64()?, @"0.006739496742276435");
+        assert_ron_snapshot!(wgs84.arithmetic_mean_radius(world)?.value.to_f64()?, @"6371008.77141506");
+
+        let p = params("WGS 84");
+        let round = |x: f64, places: i32| (x * 10f64.powi(places)).round() / 10f64.powi(places);
+        let lat = 45f64.to_radians();
+        let radii = [
+            round(p.second_eccentricity_squared(), 14),
+            round(p.arithmetic_mean_radius(), 4),
+            round(p.authalic_radius(), 4),
+            round(p.volumetric_radius(), 4),
+            round(p.meridional_radius(lat), 4),
+            round(p.prime_vertical_radius(lat), 4),
+        ];
+        assert_ron_snapshot!(format!("{radii:?}"), @r#""[0.00673949674228, 6371008.7714, 6371007.1809, 6371000.79, 6367381.8156, 6388838.2901]""#);
+        assert_ron_snapshot!(round(p.surface_area() / 1e6, 1), @"510065621.7");
+
+        // The others.
+        let summary = |name| {
+            let p = params(name);
+            (round(p.a, 3), round(1.0 / p.f, 9), round(p.b, 4))
+        };
+        let others = [
+            summary("GRS 1980"),
+            summary("Clarke 1866"),
+            summary("Airy 1830"),
+            summary("Bessel 1841"),
+            summary("International 1924"),
+        ];
+        assert_ron_snapshot!(format!("{others:?}"), @r#""[(6378137.0, 298.257222101, 6356752.3141), (6378206.4, 294.978698214, 6356583.8), (6377563.396, 299.3249646, 6356256.9092), (6377397.155, 299.1528128, 6356078.9628), (6378388.0, 297.0, 6356911.9461)]""#);
+
+        // Rounded derived parameters aren't exact.
+        let f_exactness = |name| -> Result<String> {
+            let def = OblateSpheroidRef::find(world, name)?.def(world)?;
+            Ok(def.flattening(world)?.exactness.to_string())
+        };
+        assert_ron_snapshot!([f_exactness("GRS 1980")?, f_exactness("Clarke 1866")?], @r#"("Approximate", "ByDefinition")"#);
+
+        assert!(OblateSpheroidRef::find(world, "Hayford").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn t_ellipsoid3() -> anyhow::Result<()> {
+        let mut world = World::new();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let ns_ellipsoids = ecs_ns_get(world, NamePathSpec::absolute(["geometry", "ellipsoids"]))?;
+        let shape = |name: &str| {
+            let e = world
+                .children::<NamespaceTag>(ns_ellipsoids)
+                .find(|&e| {
+                    world
+                        .get::<&Option<Name>>(e)
+                        .is_ok_and(|n| n.as_ref().is_some_and(|n| n.as_str() == name))
+                })
+                .unwrap();
+            Ellipsoid3::from_entity(world, e).unwrap()
+        };
+        let phobos = shape("IAU Phobos");
+        assert_ron_snapshot!((phobos.radii.x, phobos.radii.y, phobos.radii.z), @"(13000.0, 11400.0, 9100.0)");
+        let wgs84 = shape("WGS 84");
+        assert!(OblateSpheroidRef::find(world, "IAU Phobos").is_err());
+
+        // The Moon and the Sun are spheres, not spheroids with no flattening.
+        let (moon, sun) = (shape("IAU Moon"), shape("IAU Sun"));
+        assert_ron_snapshot!((moon.radii.x, sun.radii.x), @"(1737400.0, 695700000.0)");
+        assert_eq!(moon.radii, Vector3::new(1.0, 1.0, 1.0) * moon.radii.x);
+        assert!(OblateSpheroidRef::find(world, "IAU Moon").is_err());
+
+        let e_sphere = ecs_add_sphere::<&str, _>(
+            world,
+            ns_ellipsoids,
+            "unit",
+            SphereDef {
+                r: DimensionedConstant {
+                    name: "r".into(),
+                    dimension_kind: DimensionKind::LENGTH,
+                    exactness: Exactness::Exact(ExactReason::ByDefinition),
+                    value: EcsNum::RatioU64(RatioU64::from_integer(1)),
+                },
+            },
+        )?;
+        assert_eq!(
+            Ellipsoid3::from_entity(world, e_sphere)?.radii,
+            Vector3::new(1.0, 1.0, 1.0)
+        );
+        assert!(Ellipsoid3::from_entity(world, ns_ellipsoids).is_err());
+        assert!(Ellipsoid3::new(1.0, 0.0, 1.0).is_err());
+
+        // Rays.
+        let [a, b, c] = [phobos.radii.x, phobos.radii.y, phobos.radii.z];
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let near = |t: Option<f64>, expected: f64| t.is_some_and(|t| (t - expected).abs() < 1e-9);
+        assert!(near(
+            phobos.ray_intersection(x * 2.0 * a, -x * 2.0),
+            0.5 * a
+        ));
+        assert_eq!(phobos.ray_intersection(x * 2.0 * a, x), None);
+        assert_eq!(
+            phobos.ray_intersection(x * 2.0 * a, Vector3::new(0.0, 1.0, 0.0)),
+            None
+        );
+        let z = Vector3::new(0.0, 0.0, 1.0);
+        assert!(near(
+            phobos.ray_intersection(Vector3::new(0.0, 0.0, 0.0), z),
+            c
+        ));
+        let t = phobos
+            .ray_intersection(Vector3::new(-a, -b, -c) * 3.0, Vector3::new(a, b, c))
+            .unwrap();
+        let hit = Vector3::new(-a, -b, -c) * (3.0 - t);
+        assert!(phobos.implicit(hit).abs() < 1e-12 && hit.x < 0.0, "{hit:?}");
+
+        // Normals.
+        assert_eq!(phobos.surface_normal(x * a), x);
+        assert_eq!(phobos.surface_normal(-z * c), -z);
+
+        // Closest points, from outside, inside and on the axes, are on the surface and along
+        // the normal there.
+        for p in [
+            Vector3::new(20000.0, -3000.0, 7000.0),
+            Vector3::new(-1000.0, 2000.0, -500.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(100.0, 0.0, 0.0),
+            Vector3::new(0.0, -50000.0, 0.0),
+            Vector3::new(0.0, 300.0, -200.0),
+            Vector3::new(-12000.0, 0.0, 0.0),
+        ] {
+            let q = phobos.closest_point(p);
+            assert!(phobos.implicit(q).abs() < 1e-12, "{p:?} {q:?}");
+            let d = p - q;
+            if 1e-6 < d.magnitude() {
+                let cross = d.normalize().cross(phobos.surface_normal(q));
+                assert!(cross.magnitude() < 1e-9, "{p:?} {q:?}");
+            }
+        }
+        assert_eq!(
+            phobos.closest_point(Vector3::new(0.0, 0.0, 0.0)),
+            Vector3::new(0.0, 0.0, c)
+        );
+
+        // On an oblate spheroid, that's the foot of the geodetic normal.
+        let params = crate::gis::geodetic::OblateSpheroidParams::WGS84;
+        let g = crate::gis::geodetic::Geodetic::from_degrees(37.0, -122.0, 0.0);
+        let above = crate::gis::geodetic::Geodetic::from_degrees(37.0, -122.0, 8000.0);
+        let foot = wgs84.closest_point(params.geodetic_to_ecef(&above));
+        assert!((foot - params.geodetic_to_ecef(&g)).magnitude() < 1e-6);
+
+        // Tessellation.
+        let mesh = phobos.tessellate(4, 8)?;
+        assert_ron_snapshot!((mesh.positions.len(), mesh.indices.len() / 3), @"(45, 48)");
+        assert_eq!((mesh.normals.len(), mesh.uvs.len()), (45, 45));
+        assert!(mesh
+            .positions
+            .iter()
+            .all(|&p| phobos.implicit(p).abs() < 1e-12));
+        let areas = |mesh: &Ellipsoid3Mesh| -> Vec<f64> {
+            let triangles = mesh.indices.chunks(3);
+            triangles
+                .map(|tri| {
+                    let [p0, p1, p2] = [0, 1, 2].map(|k| mesh.positions[tri[k] as usize]);
+                    0.5 * (p1 - p0).cross(p2 - p0).magnitude()
+                })
+                .collect()
+        };
+        let min_area = 1e-3 * areas(&mesh).iter().sum::<f64>() / areas(&mesh).len() as f64;
+        for (tri, area) in mesh.indices.chunks(3).zip(areas(&mesh)) {
+            let [p0, p1, p2] = [0, 1, 2].map(|k| mesh.positions[tri[k] as usize]);
+            let outward = (p1 - p0).cross(p2 - p0).dot(p0 + p1 + p2);
+            assert!(min_area < area && 0.0 < outward, "{tri:?}");
+        }
+        assert!(phobos.tessellate(1, 8).is_err());
+        assert!(phobos.tessellate(u32::MAX, 8).is_err());
+        assert!(phobos.tessellate(4, Ellipsoid3::MAX_TESSELLATION + 1).is_err());
+
+        // Finer meshes approach the surface area, from below.
+        let unit_sphere = (Ellipsoid3::new(1.0, 1.0, 1.0)?, 4.0 * std::f64::consts::PI);
+        let wgs84 = (wgs84, params.surface_area());
+        for (ell, expected) in [unit_sphere, wgs84] {
+            let area: f64 = areas(&ell.tessellate(64, 128)?).iter().sum();
+            let error = (expected - area) / expected;
+            assert!(0.0 < error && error < 1e-3, "{area} {expected}");
+        }
+
+        Ok(())
+    }
+}
@@ -35,22 +35,53 @@ use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
 //? use num_rational::Ratio;
 //? use num_traits::{NumCast, ToPrimitive, Zero};
 //? use once_cell::sync::Lazy;
-//? use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 //? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
 
 use ecs_namespace::NamespaceTag;
+use crate::gis::metadata::AuthorityId;
+use crate::gl::CsUnit;
 use crate::*;
 
 //=================================================================================================|
 
-// The 'Frame reference epoch' for dynamic datums.
+/// The meridian from which longitudes are reckoned.
+#[derive(Clone, Debug, Display, PartialEq, Serialize, Deserialize)]
+#[display("PrimeMeridian {{ {name:?}, {longitude} }}")]
+pub struct PrimeMeridian {
+    pub name: String,
+
+    /// Longitude from Greenwich, in `unit`, or degrees if there is none.
+    pub longitude: f64,
+
+    pub unit: Option<CsUnit>,
+    pub id: Option<AuthorityId>,
+}
+
+impl PrimeMeridian {
+    pub fn greenwich() -> Self {
+        Self {
+            name: "Greenwich".into(),
+            longitude: 0.0,
+            unit: None,
+            id: None,
+        }
+    }
+}
 
 //=================================================================================================|
 
 /// Definition of a [`Datum`].
-#[derive(Clone, Debug, Display)]
+#[derive(Clone, Debug, Default, Display)]
+#[display("DatumDef {{ ellipsoid: {ellipsoid:?}, frame_epoch: {frame_epoch:?} }}")]
 pub struct DatumDef {
     //? TODO official designations, naming authority, notes, etc.
+    pub ellipsoid: Option<OblateSpheroidRef>,
+
+    pub prime_meridian: Option<PrimeMeridian>,
+
+    /// The 'Frame reference epoch' for dynamic datums, as a decimal year.
+    pub frame_epoch: Option<f64>,
 }
 
 //-------------------------------------------------------------------------------------------------|
@@ -67,41 +98,43 @@ pub struct Datum {
 
 /// Reference to a [`Datum`] in the [`hecs::World`].
 #[derive(Clone, Copy, Debug)]
-pub struct DatumRef(Entity);
+pub struct DatumRef(pub(crate) Entity);
 
 impl DatumRef {
     pub fn new(e: Entity, world: &World) -> Self {
         debug_assert!(
-            world.satisfies::<&Datum>(e).unwrap_or_default(),
+            world.satisfies::<&DatumDef>(e).unwrap_or_default(),
             "Although this newtype can't prevent the Datum Entity from being removed from the World, it should probably at least start out that way."
         );
         DatumRef(e)
     }
+
+    pub fn entity(self) -> Entity {
+        self.0
+    }
 }
 
 //-------------------------------------------------------------------------------------------------|
 
-fn ecs_add_datum(world: &mut World, e_ns_parent: Entity, name: &str) -> Result<Entity> {
-    let name: Name = name.into();
-
-    let datum_def = DatumDef {};
-
-    let datum = Datum { name, datum_def };
+pub fn ecs_add_datum(
+    world: &mut World,
+    e_ns_parent: Entity,
+    name: &str,
+    datum_def: DatumDef,
+) -> Result<Entity> {
+    let datum = Datum {
+        name: name.into(),
+        datum_def,
+    };
 
     world
         .attach_new::<NamespaceTag, _>(e_ns_parent, datum)
-        .context("ecs_add_datum({name:?})")
+        .with_context(|| format!("ecs_add_datum({name:?})"))
 }
 
 //=================================================================================================|
 
 pub(crate) fn ecs_add_stuff(world: &mut World, ns_gis: Entity) -> Result<()> {
-    use DimensionKind::*;
-
-    let ns_root = RootNamespace::find_or_create(world)?;
-
-    let ns_datum = world.attach_new::<NamespaceTag, _>(ns_root, (Name::from("datum"),))?;
-
     // From: https://epsg.io/1309-datum
     // "World Geodetic System 1984 (G2139)"
     // "Data source: EPSG"
@@ -118,11 +151,7 @@ pub(crate) fn ecs_add_stuff(world: &mut World, ns_gis: Entity) -> Result<()> {
     //         ],
     //         ID["EPSG",1309]
     //  ]"
-    let earth_datum = ecs_add_datum(
-        world,
-        ns_datum,
-        "earth,WGS 84,G2139,dynamic,EPSG datum 1309",
-    )?;
+    let earth_datum = crate::gis::epsg::lookup_epsg(world, 1309)?;
 
     /*
     let earth_ellipsoid = ecs_add_ellipsoid(world, ns_gl, "earth,WGS84,dynamic,G2139,EPSG1309,from EPSG datum 1309")?;
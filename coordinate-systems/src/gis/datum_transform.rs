@@ -0,0 +1,465 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Transformations between geodetic datums, as entities in the [`World`].
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+use std::collections::VecDeque;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use derive_more::Display;
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+use hecs::{Bundle, Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+
+use crate::gis::epsg::lookup_epsg;
+use crate::gis::{DatumDef, DatumRef, Geodetic, OblateSpheroidParams};
+use crate::*;
+
+//=================================================================================================|
+
+/// Seven Helmert parameters, with rotations in the position vector convention used by IERS
+/// and EPSG method 9606.
+///
+/// `X' = X + T + D X + R × X`, which is the usual small-angle approximation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Helmert7 {
+    /// Translation, meters.
+    pub t: [f64; 3],
+
+    /// Rotation about the X, Y and Z axes, radians.
+    pub r: [f64; 3],
+
+    /// Scale difference, `s - 1`.
+    pub d: f64,
+}
+
+impl Helmert7 {
+    /// In the units EPSG publishes: meters, arc-seconds and parts per million.
+    pub fn position_vector(t: [f64; 3], r_arcsec: [f64; 3], d_ppm: f64) -> Self {
+        Self {
+            t,
+            r: r_arcsec.map(|r| (r / 3600.0).to_radians()),
+            d: d_ppm * 1e-6,
+        }
+    }
+
+    /// As [`position_vector`](Self::position_vector), but the rotations are in the coordinate
+    /// frame convention of EPSG method 9607, which has the opposite sign.
+    pub fn coordinate_frame(t: [f64; 3], r_arcsec: [f64; 3], d_ppm: f64) -> Self {
+        Self::position_vector(t, r_arcsec.map(|r| -r), d_ppm)
+    }
+
+    /// In the units IERS publishes: millimeters, milliarcseconds and parts per billion.
+    /// Rates are in the same units per year.
+    pub fn iers(t_mm: [f64; 3], r_mas: [f64; 3], d_ppb: f64) -> Self {
+        Self::position_vector(
+            t_mm.map(|t| t * 1e-3),
+            r_mas.map(|r| r * 1e-3),
+            d_ppb * 1e-3,
+        )
+    }
+
+    pub fn apply(&self, x: Vector3) -> Vector3 {
+        let t = Vector3::from(self.t);
+        let r = Vector3::from(self.r);
+        x + t + x * self.d + r.cross(x)
+    }
+
+    /// The reverse transformation, to first order, as EPSG specifies for reversing these
+    /// methods.
+    pub fn inverse(&self) -> Self {
+        Self {
+            t: self.t.map(|t| -t),
+            r: self.r.map(|r| -r),
+            d: -self.d,
+        }
+    }
+
+    /// `self + rates dt`.
+    fn propagate(&self, rates: &Self, dt: f64) -> Self {
+        let add = |a: [f64; 3], b: [f64; 3]| [a[0] + b[0] * dt, a[1] + b[1] * dt, a[2] + b[2] * dt];
+        Self {
+            t: add(self.t, rates.t),
+            r: add(self.r, rates.r),
+            d: self.d + rates.d * dt,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// How a [`DatumTransform`] maps coordinates.
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum DatumTransformMethod {
+    /// Seven-parameter Helmert.
+    #[display("Helmert7")]
+    Helmert7(Helmert7),
+
+    /// Time-dependent Helmert, EPSG method 1053. The parameters apply at `reference_epoch`
+    /// and change by `rates` per year.
+    #[display("Helmert14 {{ reference_epoch: {reference_epoch} }}")]
+    Helmert14 {
+        params: Helmert7,
+        rates: Helmert7,
+        reference_epoch: f64,
+    },
+
+    /// Standard Molodensky, EPSG method 9604. Shifts geodetic coordinates by a translation
+    /// `t` in meters and the difference between the datums' ellipsoids.
+    #[display("Molodensky {{ t: {t:?} }}")]
+    Molodensky { t: [f64; 3] },
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// Definition of a [`DatumTransform`].
+#[derive(Clone, Copy, Debug, Display)]
+#[display("DatumTransformDef {{ {method}, accuracy: {accuracy:?} }}")]
+pub struct DatumTransformDef {
+    pub source: DatumRef,
+    pub target: DatumRef,
+    pub method: DatumTransformMethod,
+
+    /// Meters, if known.
+    pub accuracy: Option<f64>,
+}
+
+impl DatumTransformDef {
+    /// Transforms an ECEF position at `epoch`, a decimal year, from `source` to `target`, or
+    /// the other way if `reverse`.
+    pub fn apply(
+        &self,
+        world: &World,
+        ecef: Vector3,
+        epoch: f64,
+        reverse: bool,
+    ) -> Result<Vector3> {
+        let helmert = |params: Helmert7| {
+            if reverse {
+                params.inverse().apply(ecef)
+            } else {
+                params.apply(ecef)
+            }
+        };
+        match self.method {
+            DatumTransformMethod::Helmert7(params) => Ok(helmert(params)),
+            DatumTransformMethod::Helmert14 {
+                params,
+                rates,
+                reference_epoch,
+            } => {
+                ensure!(epoch.is_finite(), "epoch {epoch} is not finite");
+                Ok(helmert(params.propagate(&rates, epoch - reference_epoch)))
+            }
+            DatumTransformMethod::Molodensky { t } => {
+                let (source, target) = if reverse {
+                    (self.target, self.source)
+                } else {
+                    (self.source, self.target)
+                };
+                let from = datum_spheroid(world, source)?;
+                let to = datum_spheroid(world, target)?;
+                let t = if reverse { t.map(|t| -t) } else { t };
+                let g = molodensky(&from, &to, t, &from.ecef_to_geodetic(ecef));
+                Ok(to.geodetic_to_ecef(&g))
+            }
+        }
+    }
+}
+
+/// Standard Molodensky, as given in EPSG Guidance Note 7-2, section 4.4.
+fn molodensky(
+    from: &OblateSpheroidParams,
+    to: &OblateSpheroidParams,
+    t: [f64; 3],
+    g: &Geodetic,
+) -> Geodetic {
+    let [tx, ty, tz] = t;
+    let (a, f, b, e2) = (from.a, from.f, from.b, from.e2);
+    let da = to.a - a;
+    let df = to.f - f;
+    let (sin_lat, cos_lat) = g.lat.sin_cos();
+    let (sin_lon, cos_lon) = g.lon.sin_cos();
+    let w2 = 1.0 - e2 * sin_lat * sin_lat;
+    let rn = a / w2.sqrt();
+    let rm = a * (1.0 - e2) / (w2 * w2.sqrt());
+
+    let d_lat = (-tx * sin_lat * cos_lon - ty * sin_lat * sin_lon
+        + tz * cos_lat
+        + da * rn * e2 * sin_lat * cos_lat / a
+        + df * (rm * a / b + rn * b / a) * sin_lat * cos_lat)
+        / (rm + g.h);
+    let d_lon = (-tx * sin_lon + ty * cos_lon) / ((rn + g.h) * cos_lat);
+    let d_h = tx * cos_lat * cos_lon + ty * cos_lat * sin_lon + tz * sin_lat - da * a / rn
+        + df * b / a * rn * sin_lat * sin_lat;
+
+    Geodetic {
+        lat: g.lat + d_lat,
+        lon: g.lon + d_lon,
+        h: g.h + d_h,
+    }
+}
+
+/// The parameters of a datum's ellipsoid.
+pub fn datum_spheroid(world: &World, datum: DatumRef) -> Result<OblateSpheroidParams> {
+    let ellipsoid = world
+        .get::<&DatumDef>(datum.entity())
+        .context("DatumDef")?
+        .ellipsoid
+        .context("Datum has no ellipsoid")?;
+    OblateSpheroidParams::from_def(world, &ellipsoid.def(world)?)
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// [`hecs::Bundle`] for a [`DatumTransform`] in the [`hecs::World`].
+#[derive(Bundle, Clone, Debug, Display)]
+#[display("DatumTransform {{ Name({:?}), {def} }}", name.as_str())]
+pub struct DatumTransform {
+    pub name: Name,
+    pub def: DatumTransformDef,
+}
+
+/// Reference to a [`DatumTransform`] in the [`hecs::World`].
+#[derive(Clone, Copy, Debug)]
+pub struct DatumTransformRef(Entity);
+
+impl DatumTransformRef {
+    pub fn new(e: Entity, world: &World) -> Self {
+        debug_assert!(
+            world.satisfies::<&DatumTransformDef>(e).unwrap_or_default(),
+            "Although this newtype can't prevent the DatumTransform Entity from being removed from the World, it should probably at least start out that way."
+        );
+        DatumTransformRef(e)
+    }
+
+    pub fn entity(self) -> Entity {
+        self.0
+    }
+}
+
+pub fn ecs_add_datum_transform(
+    world: &mut World,
+    e_ns_parent: Entity,
+    name: &str,
+    def: DatumTransformDef,
+) -> Result<Entity> {
+    ecs_add(
+        world,
+        e_ns_parent,
+        name,
+        DatumTransform {
+            name: name.into(),
+            def,
+        },
+    )
+}
+
+//=================================================================================================|
+
+/// Transforms an ECEF position at `epoch`, a decimal year, from one datum to another through
+/// the fewest [`DatumTransform`]s, each of which may be used in either direction.
+pub fn transform_ecef(
+    world: &World,
+    from: DatumRef,
+    to: DatumRef,
+    ecef: Vector3,
+    epoch: f64,
+) -> Result<Vector3> {
+    let mut ecef = ecef;
+    for (def, reverse) in datum_transform_path(world, from, to)? {
+        ecef = def.apply(world, ecef, epoch, reverse)?;
+    }
+    Ok(ecef)
+}
+
+/// Breadth-first search over the [`DatumTransformDef`]s in the world. Each step is a
+/// transformation and whether it is used in reverse.
+fn datum_transform_path(
+    world: &World,
+    from: DatumRef,
+    to: DatumRef,
+) -> Result<Vec<(DatumTransformDef, bool)>> {
+    let defs: Vec<DatumTransformDef> = world
+        .query::<&DatumTransformDef>()
+        .iter()
+        .map(|(_, def)| *def)
+        .collect();
+
+    // For each datum reached, the step that reached it.
+    let mut reached: Vec<(Entity, Option<(usize, bool)>)> = vec![(from.entity(), None)];
+    let mut queue = VecDeque::from([from.entity()]);
+    while let Some(e) = queue.pop_front() {
+        if e == to.entity() {
+            let mut path = vec![];
+            let mut e = e;
+            while let Some((_, Some((ix, reverse)))) = reached.iter().find(|(e2, _)| *e2 == e) {
+                let def = defs[*ix];
+                path.push((def, *reverse));
+                e = if *reverse { def.target } else { def.source }.entity();
+            }
+            path.reverse();
+            return Ok(path);
+        }
+        for (ix, def) in defs.iter().enumerate() {
+            for (reverse, e_from, e_to) in [
+                (false, def.source.entity(), def.target.entity()),
+                (true, def.target.entity(), def.source.entity()),
+            ] {
+                if e_from == e && !reached.iter().any(|(e2, _)| *e2 == e_to) {
+                    reached.push((e_to, Some((ix, reverse))));
+                    queue.push_back(e_to);
+                }
+            }
+        }
+    }
+    bail!("No datum transformation from {from:?} to {to:?}")
+}
+
+//=================================================================================================|
+
+pub(crate) fn ecs_add_stuff(world: &mut World, ns_gis: Entity) -> Result<()> {
+    let ns = ecs_ns_find_or_create(world, NamePathSpec::relative(ns_gis, ["datum transforms"]))?;
+
+    let mut datum = |code| -> Result<DatumRef> {
+        let e = lookup_epsg(world, code)?;
+        Ok(DatumRef::new(e, world))
+    };
+    let itrf2014 = datum(1165)?;
+    let itrf2020 = datum(1322)?;
+    let igs20 = datum(1333)?;
+    let wgs84_g2139 = datum(1309)?;
+    let wgs84_transit = datum(1166)?;
+    let wgs72 = datum(6322)?;
+    let nad27 = datum(6267)?;
+
+    // From: https://itrf.ign.fr/en/solutions/transformations
+    // "Transformation parameters from ITRF2020 to past ITRFs", epoch 2015.0.
+    let params = Helmert7::iers([-1.4, -0.9, 1.4], [0.0; 3], -0.42);
+    let rates = Helmert7::iers([0.0, -0.1, 0.2], [0.0; 3], 0.0);
+    #[rustfmt::skip]
+    let defs = [
+        ("ITRF2020 to ITRF2014", itrf2020, itrf2014, DatumTransformMethod::Helmert14 { params, rates, reference_epoch: 2015.0 }, None),
+
+        // IGS20 is the IGS realization of ITRF2020, and WGS 84 (G2139) is aligned to IGb14,
+        // the IGS realization of ITRF2014. Neither differs from the ITRF by more than noise.
+        ("ITRF2020 to IGS20", itrf2020, igs20, DatumTransformMethod::Helmert7(Helmert7::default()), None),
+        ("ITRF2014 to WGS 84 (G2139)", itrf2014, wgs84_g2139, DatumTransformMethod::Helmert7(Helmert7::default()), None),
+
+        // EPSG "WGS 72 to WGS 84 (1)".
+        ("WGS 72 to WGS 84 (Transit)", wgs72, wgs84_transit, DatumTransformMethod::Helmert7(Helmert7::position_vector([0.0, 0.0, 4.5], [0.0, 0.0, 0.554], 0.2263)), Some(2.0)),
+
+        // NIMA TR8350.2, table B.1, mean for CONUS.
+        ("NAD27 to WGS 84 (Transit)", nad27, wgs84_transit, DatumTransformMethod::Molodensky { t: [-8.0, 160.0, 176.0] }, Some(10.0)),
+    ];
+    for (name, source, target, method, accuracy) in defs {
+        let def = DatumTransformDef {
+            source,
+            target,
+            method,
+            accuracy,
+        };
+        ecs_add_datum_transform(world, ns, name, def)?;
+    }
+
+    Ok(())
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    fn datum(world: &mut World, code: u32) -> Result<DatumRef> {
+        let e = lookup_epsg(world, code)?;
+        Ok(DatumRef::new(e, world))
+    }
+
+    /// Millimeters, rounded to micrometers.
+    fn mm(v: Vector3) -> [f64; 3] {
+        [v.x, v.y, v.z].map(|x| (x * 1e6).round() / 1e3)
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let mut world = World::default();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let wgs84 = OblateSpheroidParams::WGS84;
+        let p = wgs84.geodetic_to_ecef(&Geodetic::from_degrees(45.0, -120.0, 100.0));
+
+        let h = Helmert7::position_vector([1.0, -2.0, 3.0], [0.1, -0.2, 0.3], 1.5);
+        let back = h.inverse().apply(h.apply(p));
+        assert!(
+            (back - p).x.abs() < 1e-4 && (back - p).y.abs() < 1e-4 && (back - p).z.abs() < 1e-4
+        );
+        let cf = Helmert7::coordinate_frame([0.0; 3], [0.0, 0.0, 1.0], 0.0);
+        assert_eq!(cf.r[2], -(1.0_f64 / 3600.0).to_radians());
+
+        // WGS 84 (G2139) to IGS20 goes through ITRF2014 and ITRF2020.
+        let g2139 = datum(world, 1309)?;
+        let igs20 = datum(world, 1333)?;
+        let itrf2014 = datum(world, 1165)?;
+        let x = Vector3::new(wgs84.a, 0.0, 0.0);
+        let d = transform_ecef(world, g2139, igs20, x, 2020.0)? - x;
+        assert_ron_snapshot!(mm(d), @"(4.079, 1.4, -2.4)");
+        let d = transform_ecef(world, g2139, igs20, x, 2015.0)? - x;
+        assert_ron_snapshot!(mm(d), @"(4.079, 0.9, -1.4)");
+        let back = transform_ecef(world, igs20, itrf2014, x + d, 2015.0)?;
+        assert!(mm(back - x).iter().all(|d| d.abs() < 1e-3), "{back:?}");
+        assert_eq!(transform_ecef(world, igs20, igs20, x, 2015.0)?, x);
+
+        // Mostly a Z shift.
+        let wgs72 = datum(world, 6322)?;
+        let transit = datum(world, 1166)?;
+        let pole = Vector3::new(0.0, 0.0, 6_356_750.5);
+        let d = transform_ecef(world, wgs72, transit, pole, 2000.0)? - pole;
+        assert_ron_snapshot!(mm(d), @"(0.0, 0.0, 5938.533)");
+
+        // Molodensky approximates a geocentric translation to within centimeters.
+        let nad27 = datum(world, 6267)?;
+        let clarke1866 = OblateSpheroidParams::new(6_378_206.4, 1.0 / 294.978_698_213_901);
+        let p = clarke1866.geodetic_to_ecef(&Geodetic::from_degrees(39.0, -98.0, 500.0));
+        let q = transform_ecef(world, nad27, transit, p, 1984.0)?;
+        let err = q - (p + Vector3::new(-8.0, 160.0, 176.0));
+        assert!(
+            err.x.abs() < 0.05 && err.y.abs() < 0.05 && err.z.abs() < 0.05,
+            "{err:?}"
+        );
+        let back = transform_ecef(world, transit, nad27, q, 1984.0)?;
+        assert!(
+            (back - p).x.abs() < 0.01 && (back - p).z.abs() < 0.01,
+            "{back:?}"
+        );
+
+        assert!(transform_ecef(world, nad27, igs20, p, 2020.0).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,599 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! A small subset of the EPSG Geodetic Parameter Dataset, built into the crate.
+//!
+//! Entries are added to the [`World`] under `/gis/epsg/<code>` the first time they are looked
+//! up, along with whatever they depend on. EPSG reuses names, e.g. for the geocentric,
+//! geographic 3D and 2D CRSs of a datum, so the code keeps the name paths apart. Transcribed from EPSG Dataset v12.054.
+//!
+//! The WGS 84 (4326, 4978, 4979) and ETRS89 datum ensembles are not supported. Use a
+//! realization such as WGS 84 (G2139) instead.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::{Deref, DerefMut, Display, From, Into};
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+use hecs::{Entity, World};
+use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+
+use crate::gis::datum::ecs_add_datum;
+use crate::gis::metadata::{AuthorityId, RevisionDate, Usage};
+use crate::gis::wkt::crs::{decimal_to_ecsnum, insert_opt, oblatespheroid_def};
+use crate::gis::{DatumDef, DatumRef};
+use crate::gl::{Axis, CoordinateSystemDef, CoordinateSystemRef, CrsKind, CsType, CsUnit};
+use crate::*;
+use ecs_namespace::NamespaceTag;
+
+//=================================================================================================|
+
+/// One record of the embedded registry.
+#[derive(Clone, Copy, Debug)]
+pub struct EpsgEntry {
+    pub code: u32,
+    pub name: &'static str,
+    pub def: EpsgDef,
+    pub usage: Option<&'static EpsgUsage>,
+    pub revision_date: Option<RevisionDate>,
+}
+
+/// What an [`EpsgEntry`] defines. Other entries are referred to by code.
+#[derive(Clone, Copy, Debug)]
+pub enum EpsgDef {
+    /// Semi-major axis in metres and inverse flattening, as EPSG writes them.
+    Ellipsoid {
+        a: &'static str,
+        inv_f: &'static str,
+    },
+
+    /// Frame reference epoch for dynamic datums, as a decimal year.
+    Datum {
+        ellipsoid: u32,
+        frame_epoch: Option<f64>,
+    },
+
+    Cs {
+        cs_type: CsType,
+        axes: &'static [EpsgAxis],
+    },
+
+    Crs {
+        kind: CrsKind,
+        datum: u32,
+        cs: u32,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EpsgAxis {
+    pub name: &'static str,
+    pub direction: &'static str,
+
+    /// EPSG unit of measure code.
+    pub unit: u32,
+}
+
+/// Source of a [`Usage`] component.
+#[derive(Clone, Copy, Debug)]
+pub struct EpsgUsage {
+    pub scope: &'static str,
+    pub area: &'static str,
+
+    /// South, west, north and east bounds in degrees.
+    pub bbox: [f64; 4],
+}
+
+impl EpsgEntry {
+    const fn new(code: u32, name: &'static str, def: EpsgDef) -> Self {
+        Self {
+            code,
+            name,
+            def,
+            usage: None,
+            revision_date: None,
+        }
+    }
+
+    const fn ellipsoid(
+        code: u32,
+        name: &'static str,
+        a: &'static str,
+        inv_f: &'static str,
+    ) -> Self {
+        Self::new(code, name, EpsgDef::Ellipsoid { a, inv_f })
+    }
+
+    const fn datum(
+        code: u32,
+        name: &'static str,
+        ellipsoid: u32,
+        frame_epoch: Option<f64>,
+    ) -> Self {
+        Self::new(
+            code,
+            name,
+            EpsgDef::Datum {
+                ellipsoid,
+                frame_epoch,
+            },
+        )
+    }
+
+    const fn cs(code: u32, name: &'static str, cs_type: CsType, axes: &'static [EpsgAxis]) -> Self {
+        Self::new(code, name, EpsgDef::Cs { cs_type, axes })
+    }
+
+    const fn crs(code: u32, name: &'static str, kind: CrsKind, datum: u32, cs: u32) -> Self {
+        Self::new(code, name, EpsgDef::Crs { kind, datum, cs })
+    }
+
+    const fn usage(mut self, usage: &'static EpsgUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    const fn revised(mut self, year: u16, month: u8, day: u8) -> Self {
+        self.revision_date = Some(RevisionDate { year, month, day });
+        self
+    }
+}
+
+impl EpsgUsage {
+    fn to_usage(self) -> Usage {
+        Usage {
+            scope: self.scope.into(),
+            area: Some(self.area.into()),
+            bbox: Some(self.bbox),
+        }
+    }
+}
+
+//=================================================================================================|
+
+/// The registry entry for an EPSG code, if it is one of the few that are built in.
+pub fn epsg_entry(code: u32) -> Option<&'static EpsgEntry> {
+    ENTRIES.iter().find(|entry| entry.code == code)
+}
+
+/// Finds the entity for an EPSG code, creating it and its dependencies under
+/// `/gis/epsg/<code>` if this is the first lookup.
+///
+/// The entity has an [`AuthorityId`] component, and [`Usage`] and [`RevisionDate`]
+/// components where the registry has them.
+pub fn lookup_epsg(world: &mut World, code: u32) -> Result<Entity> {
+    let code_str = code.to_string();
+    let ns_epsg = ecs_ns_find_or_create(
+        world,
+        NamePathSpec::absolute(["gis", "epsg", code_str.as_str()]),
+    )?;
+    let id = AuthorityId::epsg(code);
+
+    let opt_e = world
+        .children::<NamespaceTag>(ns_epsg)
+        .find(|&e| world.get::<&AuthorityId>(e).is_ok_and(|e_id| *e_id == id));
+    if let Some(e) = opt_e {
+        return Ok(e);
+    }
+
+    ensure!(
+        !ENSEMBLES.contains(&code),
+        "EPSG:{code} is a datum ensemble, which is not supported. Use a realization instead."
+    );
+    let entry = epsg_entry(code).with_context(|| format!("EPSG:{code} is not in the registry"))?;
+
+    let e = match entry.def {
+        EpsgDef::Ellipsoid { a, inv_f } => {
+            let def = oblatespheroid_def(decimal_to_ecsnum(a)?, decimal_to_ecsnum(inv_f)?)?;
+            let e = ecs_add_oblatespheroid::<&str, _>(world, ns_epsg, entry.name, def)?;
+            world.insert_one(e, cs_unit(METRE)?)?;
+            e
+        }
+        EpsgDef::Datum {
+            ellipsoid,
+            frame_epoch,
+        } => {
+            let e_ellipsoid = lookup_epsg(world, ellipsoid)?;
+            let datum_def = DatumDef {
+                ellipsoid: Some(OblateSpheroidRef::new(e_ellipsoid, world)),
+                prime_meridian: None,
+                frame_epoch,
+            };
+            ecs_add_datum(world, ns_epsg, entry.name, datum_def)?
+        }
+        EpsgDef::Cs { cs_type, axes } => {
+            let axes = axes
+                .iter()
+                .map(|axis| {
+                    Ok(Axis {
+                        name: axis.name.into(),
+                        direction: axis.direction.into(),
+                        unit: Some(cs_unit(axis.unit)?),
+                        min_value: None,
+                        max_value: None,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            ecs_add_cs(
+                world,
+                ns_epsg,
+                entry.name,
+                CoordinateSystemDef { cs_type, axes },
+            )?
+        }
+        EpsgDef::Crs { kind, datum, cs } => {
+            let e_datum = lookup_epsg(world, datum)?;
+            let e_cs = lookup_epsg(world, cs)?;
+            let datum = DatumRef::new(e_datum, world);
+            let cs = CoordinateSystemRef::new(e_cs, world);
+            ecs_add_crs(world, ns_epsg, entry.name, kind, datum, cs)?
+        }
+    };
+
+    world.insert_one(e, id)?;
+    insert_opt(world, e, entry.usage.map(|usage| usage.to_usage()))?;
+    insert_opt(world, e, entry.revision_date)?;
+
+    Ok(e)
+}
+
+const METRE: u32 = 9001;
+const DEGREE: u32 = 9102;
+
+fn cs_unit(code: u32) -> Result<CsUnit> {
+    let unit = match code {
+        METRE => CsUnit::metre(),
+        DEGREE => CsUnit::degree(),
+        _ => bail!("EPSG unit {code} is not in the registry"),
+    };
+    Ok(CsUnit {
+        id: Some(AuthorityId::epsg(code)),
+        ..unit
+    })
+}
+
+//=================================================================================================|
+
+/// WGS 84 and ETRS89 ensembles, and their geodetic, geographic 2D and 3D CRSs.
+const ENSEMBLES: &[u32] = &[6326, 4978, 4326, 4979, 6258, 4936, 4258, 4937];
+
+const WORLD_GPS: EpsgUsage = EpsgUsage {
+    scope: "Geodesy. Navigation and positioning using GPS satellite system.",
+    area: "World.",
+    bbox: [-90.0, -180.0, 90.0, 180.0],
+};
+
+const WORLD_GEODESY: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "World.",
+    bbox: [-90.0, -180.0, 90.0, 180.0],
+};
+
+const EUROPE: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "Europe - onshore and offshore.",
+    bbox: [32.88, -16.1, 84.73, 40.18],
+};
+
+const NAD27: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "North and central America - onshore and offshore.",
+    bbox: [7.15, 167.65, 83.17, -47.74],
+};
+
+const NAD83: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "North America - onshore and offshore: Canada, Puerto Rico, United States (USA).",
+    bbox: [14.92, 167.65, 86.46, -40.73],
+};
+
+const ED50: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "Europe - west and central, onshore and offshore.",
+    bbox: [25.71, -16.1, 84.73, 48.61],
+};
+
+const OSGB36: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area:
+        "United Kingdom (UK) - offshore to boundary of UKCS; onshore Great Britain and Isle of Man.",
+    bbox: [49.75, -9.01, 61.01, 2.01],
+};
+
+const GDA94: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "Australia including Lord Howe Island, Macquarie Island, Ashmore and Cartier Islands, Christmas Island, Cocos (Keeling) Islands, Norfolk Island.",
+    bbox: [-60.56, 93.41, -8.47, 173.35],
+};
+
+const GDA2020: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "Australia including Lord Howe Island, Macquarie Island, Ashmore and Cartier Islands, Christmas Island, Cocos (Keeling) Islands, Norfolk Island.",
+    bbox: [-60.55, 93.41, -8.47, 173.35],
+};
+
+const JGD2011: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "Japan - onshore and offshore.",
+    bbox: [17.09, 122.38, 46.05, 157.65],
+};
+
+const SIRGAS2000: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "Latin America - Central America and South America - onshore and offshore.",
+    bbox: [-59.87, -122.19, 32.72, -25.28],
+};
+
+const CGCS2000: EpsgUsage = EpsgUsage {
+    scope: "Geodesy.",
+    area: "China - onshore and offshore.",
+    bbox: [16.7, 73.62, 53.56, 134.77],
+};
+
+const LAT: EpsgAxis = EpsgAxis {
+    name: "Geodetic latitude (Lat)",
+    direction: "north",
+    unit: DEGREE,
+};
+
+const LON: EpsgAxis = EpsgAxis {
+    name: "Geodetic longitude (Lon)",
+    direction: "east",
+    unit: DEGREE,
+};
+
+const H: EpsgAxis = EpsgAxis {
+    name: "Ellipsoidal height (h)",
+    direction: "up",
+    unit: METRE,
+};
+
+const X: EpsgAxis = EpsgAxis {
+    name: "Geocentric X (X)",
+    direction: "geocentricX",
+    unit: METRE,
+};
+
+const Y: EpsgAxis = EpsgAxis {
+    name: "Geocentric Y (Y)",
+    direction: "geocentricY",
+    unit: METRE,
+};
+
+const Z: EpsgAxis = EpsgAxis {
+    name: "Geocentric Z (Z)",
+    direction: "geocentricZ",
+    unit: METRE,
+};
+
+use CrsKind::{Geodetic, Geographic};
+
+#[rustfmt::skip]
+const ENTRIES: &[EpsgEntry] = &[
+    //--- Ellipsoids
+    EpsgEntry::ellipsoid(1024, "CGCS2000", "6378137", "298.257222101"),
+    EpsgEntry::ellipsoid(7001, "Airy 1830", "6377563.396", "299.3249646"),
+    EpsgEntry::ellipsoid(7008, "Clarke 1866", "6378206.4", "294.978698213901"),
+    EpsgEntry::ellipsoid(7019, "GRS 1980", "6378137", "298.257222101"),
+    EpsgEntry::ellipsoid(7022, "International 1924", "6378388", "297"),
+    EpsgEntry::ellipsoid(7030, "WGS 84", "6378137", "298.257223563"),
+    EpsgEntry::ellipsoid(7043, "WGS 72", "6378135", "298.26"),
+    //--- Datums
+    EpsgEntry::datum(1043, "China 2000", 1024, None).usage(&CGCS2000),
+    EpsgEntry::datum(1061, "International Terrestrial Reference Frame 2008", 7019, Some(2005.0)).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(1128, "Japanese Geodetic Datum 2011", 7019, None).usage(&JGD2011),
+    EpsgEntry::datum(1141, "IGS08", 7019, Some(2005.0)).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(1152, "World Geodetic System 1984 (G730)", 7030, Some(1994.0)).usage(&WORLD_GPS),
+    EpsgEntry::datum(1153, "World Geodetic System 1984 (G873)", 7030, Some(1997.0)).usage(&WORLD_GPS),
+    EpsgEntry::datum(1154, "World Geodetic System 1984 (G1150)", 7030, Some(2001.0)).usage(&WORLD_GPS),
+    EpsgEntry::datum(1155, "World Geodetic System 1984 (G1674)", 7030, Some(2005.0)).usage(&WORLD_GPS),
+    EpsgEntry::datum(1156, "World Geodetic System 1984 (G1762)", 7030, Some(2005.0)).usage(&WORLD_GPS),
+    EpsgEntry::datum(1165, "International Terrestrial Reference Frame 2014", 7019, Some(2010.0)).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(1166, "World Geodetic System 1984 (Transit)", 7030, Some(1984.0)).usage(&WORLD_GPS),
+    EpsgEntry::datum(1168, "Geocentric Datum of Australia 2020", 7019, None).usage(&GDA2020),
+    EpsgEntry::datum(1186, "European Terrestrial Reference Frame 2000", 7019, Some(1989.0)).usage(&EUROPE),
+    EpsgEntry::datum(1191, "IGS14", 7019, Some(2010.0)).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(1272, "IGb14", 7019, Some(2010.0)).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(1309, "World Geodetic System 1984 (G2139)", 7030, Some(2016.0)).usage(&WORLD_GPS).revised(2021, 11, 4),
+    EpsgEntry::datum(1322, "International Terrestrial Reference Frame 2020", 7019, Some(2015.0)).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(1333, "IGS20", 7019, Some(2015.0)).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(1383, "World Geodetic System 1984 (G2296)", 7030, Some(2024.0)).usage(&WORLD_GPS),
+    EpsgEntry::datum(6230, "European Datum 1950", 7022, None).usage(&ED50),
+    EpsgEntry::datum(6267, "North American Datum 1927", 7008, None).usage(&NAD27),
+    EpsgEntry::datum(6269, "North American Datum 1983", 7019, None).usage(&NAD83),
+    EpsgEntry::datum(6277, "Ordnance Survey of Great Britain 1936", 7001, None).usage(&OSGB36),
+    EpsgEntry::datum(6283, "Geocentric Datum of Australia 1994", 7019, None).usage(&GDA94),
+    EpsgEntry::datum(6322, "World Geodetic System 1972", 7043, None).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(6656, "International Terrestrial Reference Frame 2000", 7019, Some(1997.0)).usage(&WORLD_GEODESY),
+    EpsgEntry::datum(6674, "Sistema de Referencia Geocentrico para las AmericaS 2000", 7019, None).usage(&SIRGAS2000),
+    EpsgEntry::datum(6896, "International Terrestrial Reference Frame 2005", 7019, Some(2000.0)).usage(&WORLD_GEODESY),
+    //--- WGS 84 realizations
+    EpsgEntry::crs(7815, "WGS 84 (Transit)", Geodetic, 1166, 6500).usage(&WORLD_GPS),
+    EpsgEntry::crs(7816, "WGS 84 (Transit)", Geographic, 1166, 6423).usage(&WORLD_GPS),
+    EpsgEntry::crs(8888, "WGS 84 (Transit)", Geographic, 1166, 6422).usage(&WORLD_GPS),
+    EpsgEntry::crs(7656, "WGS 84 (G730)", Geodetic, 1152, 6500).usage(&WORLD_GPS),
+    EpsgEntry::crs(7657, "WGS 84 (G730)", Geographic, 1152, 6423).usage(&WORLD_GPS),
+    EpsgEntry::crs(9053, "WGS 84 (G730)", Geographic, 1152, 6422).usage(&WORLD_GPS),
+    EpsgEntry::crs(7658, "WGS 84 (G873)", Geodetic, 1153, 6500).usage(&WORLD_GPS),
+    EpsgEntry::crs(7659, "WGS 84 (G873)", Geographic, 1153, 6423).usage(&WORLD_GPS),
+    EpsgEntry::crs(9054, "WGS 84 (G873)", Geographic, 1153, 6422).usage(&WORLD_GPS),
+    EpsgEntry::crs(7660, "WGS 84 (G1150)", Geodetic, 1154, 6500).usage(&WORLD_GPS),
+    EpsgEntry::crs(7661, "WGS 84 (G1150)", Geographic, 1154, 6423).usage(&WORLD_GPS),
+    EpsgEntry::crs(9055, "WGS 84 (G1150)", Geographic, 1154, 6422).usage(&WORLD_GPS),
+    EpsgEntry::crs(7662, "WGS 84 (G1674)", Geodetic, 1155, 6500).usage(&WORLD_GPS),
+    EpsgEntry::crs(7663, "WGS 84 (G1674)", Geographic, 1155, 6423).usage(&WORLD_GPS),
+    EpsgEntry::crs(9056, "WGS 84 (G1674)", Geographic, 1155, 6422).usage(&WORLD_GPS),
+    EpsgEntry::crs(7664, "WGS 84 (G1762)", Geodetic, 1156, 6500).usage(&WORLD_GPS),
+    EpsgEntry::crs(7665, "WGS 84 (G1762)", Geographic, 1156, 6423).usage(&WORLD_GPS),
+    EpsgEntry::crs(9057, "WGS 84 (G1762)", Geographic, 1156, 6422).usage(&WORLD_GPS),
+    EpsgEntry::crs(9753, "WGS 84 (G2139)", Geodetic, 1309, 6500).usage(&WORLD_GPS),
+    EpsgEntry::crs(9754, "WGS 84 (G2139)", Geographic, 1309, 6423).usage(&WORLD_GPS),
+    EpsgEntry::crs(9755, "WGS 84 (G2139)", Geographic, 1309, 6422).usage(&WORLD_GPS),
+    EpsgEntry::crs(10604, "WGS 84 (G2296)", Geodetic, 1383, 6500).usage(&WORLD_GPS),
+    EpsgEntry::crs(10605, "WGS 84 (G2296)", Geographic, 1383, 6423).usage(&WORLD_GPS),
+    EpsgEntry::crs(10606, "WGS 84 (G2296)", Geographic, 1383, 6422).usage(&WORLD_GPS),
+    //--- ITRF
+    EpsgEntry::crs(4919, "ITRF2000", Geodetic, 6656, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(7909, "ITRF2000", Geographic, 6656, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(8997, "ITRF2000", Geographic, 6656, 6422).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(4896, "ITRF2005", Geodetic, 6896, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(7910, "ITRF2005", Geographic, 6896, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(8998, "ITRF2005", Geographic, 6896, 6422).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(5332, "ITRF2008", Geodetic, 1061, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(7911, "ITRF2008", Geographic, 1061, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(8999, "ITRF2008", Geographic, 1061, 6422).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(7789, "ITRF2014", Geodetic, 1165, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(7912, "ITRF2014", Geographic, 1165, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9000, "ITRF2014", Geographic, 1165, 6422).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9988, "ITRF2020", Geodetic, 1322, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9989, "ITRF2020", Geographic, 1322, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9990, "ITRF2020", Geographic, 1322, 6422).usage(&WORLD_GEODESY),
+    //--- IGS
+    EpsgEntry::crs(6934, "IGS08", Geodetic, 1141, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9013, "IGS08", Geographic, 1141, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9014, "IGS08", Geographic, 1141, 6422).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(8227, "IGS14", Geodetic, 1191, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9018, "IGS14", Geographic, 1191, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9019, "IGS14", Geographic, 1191, 6422).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9378, "IGb14", Geodetic, 1272, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9379, "IGb14", Geographic, 1272, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(9380, "IGb14", Geographic, 1272, 6422).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(10176, "IGS20", Geodetic, 1333, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(10177, "IGS20", Geographic, 1333, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(10178, "IGS20", Geographic, 1333, 6422).usage(&WORLD_GEODESY),
+    //--- ETRF2000
+    EpsgEntry::crs(7930, "ETRF2000", Geodetic, 1186, 6500).usage(&EUROPE),
+    EpsgEntry::crs(7931, "ETRF2000", Geographic, 1186, 6423).usage(&EUROPE),
+    EpsgEntry::crs(9067, "ETRF2000", Geographic, 1186, 6422).usage(&EUROPE),
+    //--- WGS 72
+    EpsgEntry::crs(4984, "WGS 72", Geodetic, 6322, 6500).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(4985, "WGS 72", Geographic, 6322, 6423).usage(&WORLD_GEODESY),
+    EpsgEntry::crs(4322, "WGS 72", Geographic, 6322, 6422).usage(&WORLD_GEODESY),
+    //--- NAD27
+    EpsgEntry::crs(4267, "NAD27", Geographic, 6267, 6422).usage(&NAD27),
+    //--- NAD83
+    EpsgEntry::crs(4269, "NAD83", Geographic, 6269, 6422).usage(&NAD83),
+    //--- ED50
+    EpsgEntry::crs(4230, "ED50", Geographic, 6230, 6422).usage(&ED50),
+    //--- OSGB36
+    EpsgEntry::crs(4277, "OSGB36", Geographic, 6277, 6422).usage(&OSGB36),
+    //--- GDA94
+    EpsgEntry::crs(4938, "GDA94", Geodetic, 6283, 6500).usage(&GDA94),
+    EpsgEntry::crs(4939, "GDA94", Geographic, 6283, 6423).usage(&GDA94),
+    EpsgEntry::crs(4283, "GDA94", Geographic, 6283, 6422).usage(&GDA94),
+    //--- GDA2020
+    EpsgEntry::crs(7842, "GDA2020", Geodetic, 1168, 6500).usage(&GDA2020),
+    EpsgEntry::crs(7843, "GDA2020", Geographic, 1168, 6423).usage(&GDA2020),
+    EpsgEntry::crs(7844, "GDA2020", Geographic, 1168, 6422).usage(&GDA2020),
+    //--- JGD2011
+    EpsgEntry::crs(6666, "JGD2011", Geodetic, 1128, 6500).usage(&JGD2011),
+    EpsgEntry::crs(6667, "JGD2011", Geographic, 1128, 6423).usage(&JGD2011),
+    EpsgEntry::crs(6668, "JGD2011", Geographic, 1128, 6422).usage(&JGD2011),
+    //--- SIRGAS 2000
+    EpsgEntry::crs(4988, "SIRGAS 2000", Geodetic, 6674, 6500).usage(&SIRGAS2000),
+    EpsgEntry::crs(4989, "SIRGAS 2000", Geographic, 6674, 6423).usage(&SIRGAS2000),
+    EpsgEntry::crs(4674, "SIRGAS 2000", Geographic, 6674, 6422).usage(&SIRGAS2000),
+    //--- CGCS2000
+    EpsgEntry::crs(4490, "China Geodetic Coordinate System 2000", Geographic, 1043, 6422).usage(&CGCS2000),
+    //--- Coordinate systems
+    EpsgEntry::cs(6422, "Ellipsoidal 2D CS. Axes: latitude, longitude. Orientations: north, east. UoM: degree", CsType::Ellipsoidal, &[LAT, LON]),
+    EpsgEntry::cs(6423, "Ellipsoidal 3D CS. Axes: latitude, longitude, ellipsoidal height. Orientations: north, east, up. UoM: degree, degree, metre.", CsType::Ellipsoidal, &[LAT, LON, H]),
+    EpsgEntry::cs(6500, "Cartesian 3D CS (geocentric). Axes: geocentric X,Y,Z. Orientations: X and Y in equatorial plane, X positive through intersection with prime meridian, Y through 0°N 90°E. Z axis parallel to mean earth rotation axis and positive towards North Pole. UoM: m.", CsType::Cartesian, &[X, Y, Z]),
+];
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use crate::gis::crs_to_wkt;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let mut world = World::default();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let e_crs = lookup_epsg(world, 9754)?;
+        assert_eq!(*world.get::<&CrsKind>(e_crs)?, CrsKind::Geographic);
+        assert_eq!(world.get::<&AuthorityId>(e_crs)?.to_string(), "EPSG:9754");
+        assert_eq!(
+            world.get::<&Usage>(e_crs)?.bbox,
+            Some([-90.0, -180.0, 90.0, 180.0])
+        );
+        assert_eq!(lookup_epsg(world, 9754)?, e_crs);
+
+        let e_datum = world.get::<&DatumRef>(e_crs)?.entity();
+        assert_eq!(e_datum, lookup_epsg(world, 1309)?);
+        assert_eq!(world.get::<&DatumDef>(e_datum)?.frame_epoch, Some(2016.0));
+        assert_eq!(
+            world.get::<&RevisionDate>(e_datum)?.to_string(),
+            "2021-11-04"
+        );
+
+        // The geocentric CRS shares the datum and ellipsoid.
+        let e_crs_ecef = lookup_epsg(world, 9753)?;
+        assert_eq!(world.get::<&DatumRef>(e_crs_ecef)?.entity(), e_datum);
+
+        let wkt = crs_to_wkt(world, e_crs)?;
+        for s in [
+            r#"GEOGCRS["WGS 84 (G2139)",DYNAMIC[FRAMEEPOCH[2016]]"#,
+            r#"ELLIPSOID["WGS 84",6378137,298.257223563,LENGTHUNIT["metre",1,ID["EPSG",9001]],ID["EPSG",7030]]"#,
+            r#"AXIS["Ellipsoidal height (h)",up,ORDER[3],LENGTHUNIT["metre",1,ID["EPSG",9001]]]"#,
+            r#"ID["EPSG",9754]]"#,
+        ] {
+            assert!(wkt.contains(s), "{wkt}");
+        }
+
+        // Every entry can be built.
+        for entry in ENTRIES {
+            lookup_epsg(world, entry.code)?;
+        }
+        let e_crs_osgb36 = lookup_epsg(world, 4277)?;
+        let e_osgb36 = world.get::<&DatumRef>(e_crs_osgb36)?.entity();
+        let ellipsoid = world
+            .get::<&DatumDef>(e_osgb36)?
+            .ellipsoid
+            .context("ellipsoid")?;
+        assert_eq!(ellipsoid.def(world)?.a.value.to_f64()?, 6377563.396);
+
+        // Names repeat, paths don't.
+        let (e_igs08_datum, e_igs08_crs) = (lookup_epsg(world, 1141)?, lookup_epsg(world, 6934)?);
+        let path = |code: &str, name: &str| {
+            ecs_ns_get(world, NamePathSpec::absolute(["gis", "epsg", code, name]))
+        };
+        assert_eq!(path("9753", "WGS 84 (G2139)")?, e_crs_ecef);
+        assert_eq!(path("9754", "WGS 84 (G2139)")?, e_crs);
+        assert_eq!(path("1141", "IGS08")?, e_igs08_datum);
+        assert_eq!(path("6934", "IGS08")?, e_igs08_crs);
+
+        assert!(lookup_epsg(world, 4326).is_err());
+        assert!(lookup_epsg(world, 1).is_err());
+
+        Ok(())
+    }
+}
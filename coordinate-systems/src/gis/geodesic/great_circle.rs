@@ -0,0 +1,127 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Great circles on a sphere, for comparison with the ellipsoidal geodesics. On the earth these
+//! are in error by up to about 0.5% in distance.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::sync::Arc;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+
+use crate::gis::geodetic::OblateSpheroidParams;
+
+use super::series::{ang_diff, ang_normalize, atan2d, sincosd};
+
+//=================================================================================================|
+
+/// A sphere of radius `r`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GreatCircle {
+    pub r: f64,
+}
+
+/// Distance and azimuths along a great circle, in degrees clockwise from north.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GreatCircleCourse {
+    pub s12: f64,
+    pub azi1: f64,
+
+    /// Forward azimuth at the second point.
+    pub azi2: f64,
+}
+
+impl GreatCircle {
+    /// The sphere of the ellipsoid's mean radius, `(2a + b)/3`.
+    pub fn mean_radius(ellipsoid: &OblateSpheroidParams) -> Self {
+        Self {
+            r: (2.0 * ellipsoid.a + ellipsoid.b) / 3.0,
+        }
+    }
+
+    /// Distance by Vincenty's formula for the sphere, which unlike the haversine holds its
+    /// accuracy for antipodal points.
+    pub fn inverse(&self, lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> GreatCircleCourse {
+        let (lon12, _) = ang_diff(lon1, lon2);
+        let (slam, clam) = sincosd(lon12);
+        let (sphi1, cphi1) = sincosd(lat1);
+        let (sphi2, cphi2) = sincosd(lat2);
+
+        let y1 = cphi2 * slam;
+        let x1 = cphi1 * sphi2 - sphi1 * cphi2 * clam;
+        let y2 = cphi1 * slam;
+        let x2 = -sphi1 * cphi2 + cphi1 * sphi2 * clam;
+        let sig12 = y1.hypot(x1).atan2(sphi1 * sphi2 + cphi1 * cphi2 * clam);
+
+        GreatCircleCourse {
+            s12: self.r * sig12,
+            azi1: atan2d(y1, x1),
+            azi2: atan2d(y2, x2),
+        }
+    }
+
+    /// The point at distance `s12` from a point at an azimuth, and the forward azimuth there.
+    pub fn direct(&self, lat1: f64, lon1: f64, azi1: f64, s12: f64) -> (f64, f64, f64) {
+        let sig12 = (s12 / self.r).to_degrees();
+        let (ssig, csig) = sincosd(sig12);
+        let (sphi1, cphi1) = sincosd(lat1);
+        let (salp1, calp1) = sincosd(azi1);
+
+        let sphi2 = sphi1 * csig + cphi1 * ssig * calp1;
+        let y = salp1 * ssig * cphi1;
+        let x = csig - sphi1 * sphi2;
+        let cphi2 = (salp1 * ssig).hypot(cphi1 * csig - sphi1 * ssig * calp1);
+        let lat2 = atan2d(sphi2, cphi2);
+        let lon2 = ang_normalize(lon1 + atan2d(y, x));
+        let azi2 = atan2d(salp1 * cphi1, cphi1 * csig * calp1 - sphi1 * ssig);
+        (lat2, lon2, azi2)
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use crate::gis::geodesic::Geodesic;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        let wgs84 = OblateSpheroidParams::WGS84;
+        let sphere = GreatCircle::mean_radius(&wgs84);
+        let geod = Geodesic::new(&wgs84);
+
+        // JFK to LHR.
+        let gc = sphere.inverse(40.64, -73.78, 51.47, -0.45);
+        let inv = geod.inverse(40.64, -73.78, 51.47, -0.45);
+        assert!((gc.s12 / inv.s12 - 1.0).abs() < 0.005);
+        assert_ron_snapshot!(((gc.s12 * 1e3).round() / 1e3, (gc.azi1 * 1e9).round() / 1e9), @"(5540517.785, 51.350106323)");
+
+        let (lat2, lon2, azi2) = sphere.direct(40.64, -73.78, gc.azi1, gc.s12);
+        assert!((lat2 - 51.47).abs() < 1e-12 && (lon2 + 0.45).abs() < 1e-12);
+        assert!((azi2 - gc.azi2).abs() < 1e-12);
+
+        // Antipodes.
+        let gc = sphere.inverse(10.0, 20.0, -10.0, -160.0);
+        assert!((gc.s12 - std::f64::consts::PI * sphere.r).abs() < 1e-6);
+    }
+}
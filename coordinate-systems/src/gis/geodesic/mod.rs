@@ -0,0 +1,1010 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Geodesics on an ellipsoid of revolution: the direct and inverse problems, reduced length,
+//! and the area between a geodesic and the equator. This follows Karney (2013), "Algorithms
+//! for geodesics", J. Geod. 87:43, and GeographicLib's implementation of it, which converges
+//! everywhere, including the nearly antipodal cases where Vincenty's method fails.
+//!
+//! Angles are in degrees, as in GeographicLib; reducing them exactly in degrees is part of what
+//! makes the polar and antipodal cases work. Lengths are in the unit of the equatorial radius.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::{Deref, DerefMut, Display, From, Into};
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+use hecs::World;
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+//? use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use crate::gis::geodetic::OblateSpheroidParams;
+use crate::*;
+
+mod series;
+use series::*;
+
+pub mod polygon;
+pub use crate::gis::geodesic::polygon::PolygonArea;
+
+pub mod great_circle;
+
+pub mod rhumb;
+
+//=================================================================================================|
+
+/// `sqrt(f64::MIN_POSITIVE)`, 2⁻⁵¹¹.
+const TINY: f64 = 1.491_668_146_240_041_3e-154;
+const TOL0: f64 = f64::EPSILON;
+const TOL1: f64 = 200.0 * TOL0;
+/// `sqrt(TOL0)`, 2⁻²⁶.
+const TOL2: f64 = 1.490_116_119_384_765_6e-8;
+const TOLB: f64 = TOL0 * TOL2;
+const XTHRESH: f64 = 1000.0 * TOL2;
+const MAXIT1: usize = 20;
+const MAXIT2: usize = MAXIT1 + f64::MANTISSA_DIGITS as usize + 10;
+
+/// Solves geodesic problems on one ellipsoid. Construction evaluates the series coefficients
+/// that depend only on the flattening.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geodesic {
+    a: f64,
+    f: f64,
+    f1: f64,
+    e2: f64,
+    ep2: f64,
+    n: f64,
+    b: f64,
+
+    /// Authalic radius squared.
+    c2: f64,
+
+    etol2: f64,
+    a3x: [f64; ORDER],
+    c3x: [f64; N_C3X],
+    c4x: [f64; N_C4X],
+}
+
+/// Solution of the inverse problem between two points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeodesicInverse {
+    /// Distance.
+    pub s12: f64,
+
+    /// Azimuth at the first point, degrees clockwise from north.
+    pub azi1: f64,
+
+    /// Forward azimuth at the second point.
+    pub azi2: f64,
+
+    /// Arc length on the auxiliary sphere, degrees.
+    pub a12: f64,
+
+    /// Reduced length.
+    pub m12: f64,
+
+    /// Area between the geodesic and the equator, counter-clockwise positive.
+    pub S12: f64,
+}
+
+/// A point along a geodesic, the solution of the direct problem.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeodesicPosition {
+    pub lat2: f64,
+
+    /// In `(-180, 180]`.
+    pub lon2: f64,
+
+    /// Forward azimuth at the point.
+    pub azi2: f64,
+
+    pub s12: f64,
+    pub a12: f64,
+    pub m12: f64,
+    pub S12: f64,
+}
+
+struct Lengths {
+    s12b: f64,
+    m12b: f64,
+    m0: f64,
+    M12: f64,
+    M21: f64,
+}
+
+struct Lambda12 {
+    lam12: f64,
+    salp2: f64,
+    calp2: f64,
+    sig12: f64,
+    ssig1: f64,
+    csig1: f64,
+    ssig2: f64,
+    csig2: f64,
+    eps: f64,
+    domg12: f64,
+    dlam12: f64,
+}
+
+impl Geodesic {
+    /// Oblate ellipsoids and spheres only; `f` must be in `[0, 1)`.
+    pub fn new(ellipsoid: &OblateSpheroidParams) -> Self {
+        let OblateSpheroidParams { a, f, b, e2 } = *ellipsoid;
+        let f1 = 1.0 - f;
+        let ep2 = e2 / sq(f1);
+        let n = f / (2.0 - f);
+        let e = e2.sqrt();
+        let c2 = (sq(a) + sq(b) * if e2 == 0.0 { 1.0 } else { e.atanh() / e }) / 2.0;
+        let etol2 = 0.1 * TOL2 / (f.max(0.001) * (1.0 - f / 2.0).min(1.0) / 2.0).sqrt();
+        Self {
+            a,
+            f,
+            f1,
+            e2,
+            ep2,
+            n,
+            b,
+            c2,
+            etol2,
+            a3x: a3_coeffs(n),
+            c3x: c3_coeffs(n),
+            c4x: c4_coeffs(n),
+        }
+    }
+
+    /// For an [`OblateSpheroid`] entity.
+    pub fn for_entity(world: &World, oblate_spheroid: OblateSpheroidRef) -> Result<Self> {
+        let def = oblate_spheroid.def(world)?;
+        Ok(Self::new(&OblateSpheroidParams::from_def(world, &def)?))
+    }
+
+    /// Surface area of the whole ellipsoid.
+    pub fn ellipsoid_area(&self) -> f64 {
+        4.0 * PI * self.c2
+    }
+
+    fn a3f(&self, eps: f64) -> f64 {
+        polyval(ORDER - 1, &self.a3x, eps)
+    }
+
+    fn c3f(&self, eps: f64, c: &mut [f64; ORDER]) {
+        let mut mult = 1.0;
+        let mut o = 0;
+        for (l, c_l) in c.iter_mut().enumerate().skip(1) {
+            let m = ORDER - l - 1;
+            mult *= eps;
+            *c_l = mult * polyval(m, &self.c3x[o..], eps);
+            o += m + 1;
+        }
+    }
+
+    fn c4f(&self, eps: f64, c: &mut [f64; ORDER]) {
+        let mut mult = 1.0;
+        let mut o = 0;
+        for (l, c_l) in c.iter_mut().enumerate() {
+            let m = ORDER - l - 1;
+            *c_l = mult * polyval(m, &self.c4x[o..], eps);
+            o += m + 1;
+            mult *= eps;
+        }
+    }
+
+    /// Distance, reduced length and geodesic scales, in units of `b`.
+    fn lengths(
+        &self,
+        eps: f64,
+        sig12: f64,
+        (ssig1, csig1, dn1): (f64, f64, f64),
+        (ssig2, csig2, dn2): (f64, f64, f64),
+        cbet1: f64,
+        cbet2: f64,
+    ) -> Lengths {
+        let mut c1a = [0.0; ORDER + 1];
+        let mut c2a = [0.0; ORDER + 1];
+        let a1 = a1m1f(eps);
+        c1f(eps, &mut c1a);
+        let a2 = a2m1f(eps);
+        c2f(eps, &mut c2a);
+        let m0x = a1 - a2;
+        let (a1, a2) = (a1 + 1.0, a2 + 1.0);
+
+        let b1 =
+            sin_cos_series(true, ssig2, csig2, &c1a) - sin_cos_series(true, ssig1, csig1, &c1a);
+        let s12b = a1 * (sig12 + b1);
+        let b2 =
+            sin_cos_series(true, ssig2, csig2, &c2a) - sin_cos_series(true, ssig1, csig1, &c2a);
+        let j12 = m0x * sig12 + (a1 * b1 - a2 * b2);
+
+        let m12b = dn2 * (csig1 * ssig2) - dn1 * (ssig1 * csig2) - csig1 * csig2 * j12;
+        let csig12 = csig1 * csig2 + ssig1 * ssig2;
+        let t = self.ep2 * (cbet1 - cbet2) * (cbet1 + cbet2) / (dn1 + dn2);
+        Lengths {
+            s12b,
+            m12b,
+            m0: m0x,
+            M12: csig12 + (t * ssig2 - csig2 * j12) * ssig1 / dn1,
+            M21: csig12 - (t * ssig1 - csig1 * j12) * ssig2 / dn2,
+        }
+    }
+
+    /// Starting azimuth for Newton's method. Returns `sig12 >= 0` with the final answer if the
+    /// points are close enough that a sphere-like approximation is exact to roundoff.
+    fn inverse_start(
+        &self,
+        (sbet1, cbet1): (f64, f64),
+        (sbet2, cbet2): (f64, f64),
+        lam12: f64,
+        slam12: f64,
+        clam12: f64,
+    ) -> (f64, f64, f64, f64, f64, f64) {
+        let mut sig12 = -1.0;
+        let (mut salp2, mut calp2, mut dnm) = (f64::NAN, f64::NAN, f64::NAN);
+
+        let sbet12 = sbet2 * cbet1 - cbet2 * sbet1;
+        let cbet12 = cbet2 * cbet1 + sbet2 * sbet1;
+        let sbet12a = sbet2 * cbet1 + cbet2 * sbet1;
+
+        let shortline = 0.0 <= cbet12 && sbet12 < 0.5 && cbet2 * lam12 < 0.5;
+        let (mut somg12, mut comg12) = if shortline {
+            let sbetm2 = sq(sbet1 + sbet2);
+            let sbetm2 = sbetm2 / (sbetm2 + sq(cbet1 + cbet2));
+            dnm = (1.0 + self.ep2 * sbetm2).sqrt();
+            (lam12 / (self.f1 * dnm)).sin_cos()
+        } else {
+            (slam12, clam12)
+        };
+
+        let mut salp1 = cbet2 * somg12;
+        let mut calp1 = if 0.0 <= comg12 {
+            sbet12 + cbet2 * sbet1 * sq(somg12) / (1.0 + comg12)
+        } else {
+            sbet12a - cbet2 * sbet1 * sq(somg12) / (1.0 - comg12)
+        };
+
+        let ssig12 = salp1.hypot(calp1);
+        let csig12 = sbet1 * sbet2 + cbet1 * cbet2 * comg12;
+
+        if shortline && ssig12 < self.etol2 {
+            // Really short lines.
+            let s = cbet1 * somg12;
+            let c = sbet12
+                - cbet1
+                    * sbet2
+                    * if 0.0 <= comg12 {
+                        sq(somg12) / (1.0 + comg12)
+                    } else {
+                        1.0 - comg12
+                    };
+            (salp2, calp2) = norm2(s, c);
+            sig12 = ssig12.atan2(csig12);
+        } else if 0.1 < self.n.abs()
+            || 0.0 <= csig12
+            || 6.0 * self.n.abs() * PI * sq(cbet1) <= ssig12
+        {
+            // Nothing to do: the zeroth-order spherical approximation is good enough.
+        } else {
+            // Nearly antipodal: scale to the astroid problem.
+            let lam12x = (-slam12).atan2(-clam12);
+            let k2 = sq(sbet1) * self.ep2;
+            let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+            let lamscale = self.f * cbet1 * self.a3f(eps) * PI;
+            let betscale = lamscale * cbet1;
+            let x = lam12x / lamscale;
+            let y = sbet12a / betscale;
+
+            if -TOL1 < y && -1.0 - XTHRESH < x {
+                salp1 = (-x).min(1.0);
+                calp1 = -(1.0 - sq(salp1)).sqrt();
+            } else {
+                let k = astroid(x, y);
+                let omg12a = lamscale * (-x * k / (1.0 + k));
+                (somg12, comg12) = omg12a.sin_cos();
+                comg12 = -comg12;
+                salp1 = cbet2 * somg12;
+                calp1 = sbet12a - cbet2 * sbet1 * sq(somg12) / (1.0 - comg12);
+            }
+        }
+
+        if 0.0 < salp1 || salp1.is_nan() {
+            (salp1, calp1) = norm2(salp1, calp1);
+        } else {
+            (salp1, calp1) = (1.0, 0.0);
+        }
+        (sig12, salp1, calp1, salp2, calp2, dnm)
+    }
+
+    /// The longitude difference reached from point 1 at azimuth `alp1`, relative to the
+    /// target, and its derivative with respect to `alp1`.
+    fn lambda12(
+        &self,
+        (sbet1, cbet1, dn1): (f64, f64, f64),
+        (sbet2, cbet2, dn2): (f64, f64, f64),
+        salp1: f64,
+        calp1: f64,
+        slam120: f64,
+        clam120: f64,
+        diffp: bool,
+    ) -> Lambda12 {
+        let calp1 = if sbet1 == 0.0 && calp1 == 0.0 {
+            -TINY
+        } else {
+            calp1
+        };
+
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+
+        let somg1 = salp0 * sbet1;
+        let comg1 = calp1 * cbet1;
+        let (ssig1, csig1) = norm2(sbet1, comg1);
+
+        let salp2 = if cbet2 != cbet1 { salp0 / cbet2 } else { salp1 };
+        let calp2 = if cbet2 != cbet1 || sbet2.abs() != -sbet1 {
+            (sq(calp1 * cbet1)
+                + if cbet1 < -sbet1 {
+                    (cbet2 - cbet1) * (cbet1 + cbet2)
+                } else {
+                    (sbet1 - sbet2) * (sbet1 + sbet2)
+                })
+            .sqrt()
+                / cbet2
+        } else {
+            calp1.abs()
+        };
+        let somg2 = salp0 * sbet2;
+        let comg2 = calp2 * cbet2;
+        let (ssig2, csig2) = norm2(sbet2, comg2);
+
+        let sig12 = (csig1 * ssig2 - ssig1 * csig2)
+            .max(0.0)
+            .atan2(csig1 * csig2 + ssig1 * ssig2);
+        let somg12 = (comg1 * somg2 - somg1 * comg2).max(0.0);
+        let comg12 = comg1 * comg2 + somg1 * somg2;
+        let eta = (somg12 * clam120 - comg12 * slam120).atan2(comg12 * clam120 + somg12 * slam120);
+
+        let k2 = sq(calp0) * self.ep2;
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+        let mut c3a = [0.0; ORDER];
+        self.c3f(eps, &mut c3a);
+        let b312 =
+            sin_cos_series(true, ssig2, csig2, &c3a) - sin_cos_series(true, ssig1, csig1, &c3a);
+        let domg12 = -self.f * self.a3f(eps) * salp0 * (sig12 + b312);
+        let lam12 = eta + domg12;
+
+        let dlam12 = if !diffp {
+            f64::NAN
+        } else if calp2 == 0.0 {
+            -2.0 * self.f1 * dn1 / sbet1
+        } else {
+            let l = self.lengths(
+                eps,
+                sig12,
+                (ssig1, csig1, dn1),
+                (ssig2, csig2, dn2),
+                cbet1,
+                cbet2,
+            );
+            l.m12b * self.f1 / (calp2 * cbet2)
+        };
+
+        Lambda12 {
+            lam12,
+            salp2,
+            calp2,
+            sig12,
+            ssig1,
+            csig1,
+            ssig2,
+            csig2,
+            eps,
+            domg12,
+            dlam12,
+        }
+    }
+
+    /// The shortest geodesic between two points.
+    pub fn inverse(&self, lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> GeodesicInverse {
+        // Reduce to lon12 in [0, 180], |lat1| >= |lat2|, lat1 <= 0.
+        let (lon12, lon12s) = ang_diff(lon1, lon2);
+        let mut lonsign = if 0.0 <= lon12 { 1.0 } else { -1.0 };
+        let lon12 = lonsign * ang_round(lon12);
+        let lon12s = ang_round((180.0 - lon12) - lonsign * lon12s);
+        let lam12 = lon12.to_radians();
+        let (slam12, clam12) = if 90.0 < lon12 {
+            let (s, c) = sincosd(lon12s);
+            (s, -c)
+        } else {
+            sincosd(lon12)
+        };
+
+        let mut lat1 = ang_round(lat_fix(lat1));
+        let mut lat2 = ang_round(lat_fix(lat2));
+        let swapp = if lat1.abs() < lat2.abs() { -1.0 } else { 1.0 };
+        if swapp < 0.0 {
+            lonsign = -lonsign;
+            std::mem::swap(&mut lat1, &mut lat2);
+        }
+        let latsign = if lat1 < 0.0 { 1.0 } else { -1.0 };
+        lat1 *= latsign;
+        lat2 *= latsign;
+
+        // Reduced latitudes.
+        let (s, c) = sincosd(lat1);
+        let (sbet1, cbet1) = norm2(self.f1 * s, c);
+        let cbet1 = cbet1.max(TINY);
+        let (s, c) = sincosd(lat2);
+        let (mut sbet2, cbet2) = norm2(self.f1 * s, c);
+        let mut cbet2 = cbet2.max(TINY);
+
+        // Make the symmetries exact.
+        if cbet1 < -sbet1 {
+            if cbet2 == cbet1 {
+                sbet2 = if sbet2 < 0.0 { sbet1 } else { -sbet1 };
+            }
+        } else if sbet2.abs() == -sbet1 {
+            cbet2 = cbet1;
+        }
+
+        let dn1 = (1.0 + self.ep2 * sq(sbet1)).sqrt();
+        let dn2 = (1.0 + self.ep2 * sq(sbet2)).sqrt();
+
+        let (mut salp1, mut calp1, mut salp2, mut calp2) = (0.0, 0.0, 0.0, 0.0);
+        let mut sig12;
+        let (mut s12x, mut m12x, mut a12) = (0.0, 0.0, 0.0);
+        let (mut somg12, mut comg12, mut omg12) = (2.0, 0.0, 0.0);
+
+        let mut meridian = lat1 == -90.0 || slam12 == 0.0;
+        if meridian {
+            // Along a meridian, or from a pole: the azimuths are known.
+            (salp1, calp1) = (slam12, clam12);
+            (salp2, calp2) = (0.0, 1.0);
+            let (ssig1, csig1) = (sbet1, calp1 * cbet1);
+            let (ssig2, csig2) = (sbet2, calp2 * cbet2);
+            sig12 = (csig1 * ssig2 - ssig1 * csig2)
+                .max(0.0)
+                .atan2(csig1 * csig2 + ssig1 * ssig2);
+            let l = self.lengths(
+                self.n,
+                sig12,
+                (ssig1, csig1, dn1),
+                (ssig2, csig2, dn2),
+                cbet1,
+                cbet2,
+            );
+            (s12x, m12x) = (l.s12b, l.m12b);
+
+            // A meridian that goes past a pole is the shortest line only if it's short.
+            if sig12 < 1.0 || 0.0 <= m12x {
+                if sig12 < 3.0 * TINY {
+                    (sig12, m12x, s12x) = (0.0, 0.0, 0.0);
+                }
+                m12x *= self.b;
+                s12x *= self.b;
+                a12 = sig12.to_degrees();
+            } else {
+                meridian = false;
+            }
+        }
+
+        if !meridian && sbet1 == 0.0 && (self.f <= 0.0 || self.f * 180.0 <= lon12s) {
+            // Along the equator.
+            (salp1, calp1, salp2, calp2) = (1.0, 0.0, 1.0, 0.0);
+            s12x = self.a * lam12;
+            sig12 = lam12 / self.f1;
+            omg12 = sig12;
+            m12x = self.b * sig12.sin();
+            a12 = lon12 / self.f1;
+        } else if !meridian {
+            let (sig12_start, s1, c1, s2, c2, dnm) =
+                self.inverse_start((sbet1, cbet1), (sbet2, cbet2), lam12, slam12, clam12);
+            (sig12, salp1, calp1, salp2, calp2) = (sig12_start, s1, c1, s2, c2);
+
+            if 0.0 <= sig12 {
+                // Short lines, solved directly.
+                s12x = sig12 * self.b * dnm;
+                m12x = sq(dnm) * self.b * (sig12 / dnm).sin();
+                a12 = sig12.to_degrees();
+                omg12 = lam12 / (self.f1 * dnm);
+            } else {
+                // Newton's method on alp1, falling back to bisection.
+                let (mut tripn, mut tripb) = (false, false);
+                let (mut salp1a, mut calp1a) = (TINY, 1.0);
+                let (mut salp1b, mut calp1b) = (TINY, -1.0);
+                let mut l = None;
+                for numit in 0..MAXIT2 {
+                    let l12 = self.lambda12(
+                        (sbet1, cbet1, dn1),
+                        (sbet2, cbet2, dn2),
+                        salp1,
+                        calp1,
+                        slam12,
+                        clam12,
+                        numit < MAXIT1,
+                    );
+                    let v = l12.lam12;
+                    let dv = l12.dlam12;
+                    (salp2, calp2) = (l12.salp2, l12.calp2);
+                    l = Some(l12);
+
+                    if tripb || v.abs() < if tripn { 8.0 } else { 1.0 } * TOL0 || v.is_nan() {
+                        break;
+                    }
+                    // Keep the bracket [alp1a, alp1b] around the root.
+                    if 0.0 < v && (MAXIT1 < numit || calp1b / salp1b < calp1 / salp1) {
+                        (salp1b, calp1b) = (salp1, calp1);
+                    } else if v < 0.0 && (MAXIT1 < numit || calp1 / salp1 < calp1a / salp1a) {
+                        (salp1a, calp1a) = (salp1, calp1);
+                    }
+                    if numit < MAXIT1 && 0.0 < dv {
+                        let dalp1 = -v / dv;
+                        let (sdalp1, cdalp1) = dalp1.sin_cos();
+                        let nsalp1 = salp1 * cdalp1 + calp1 * sdalp1;
+                        if 0.0 < nsalp1 && dalp1.abs() < PI {
+                            (salp1, calp1) = norm2(nsalp1, calp1 * cdalp1 - salp1 * sdalp1);
+                            tripn = v.abs() <= 16.0 * TOL0;
+                            continue;
+                        }
+                    }
+                    (salp1, calp1) = norm2((salp1a + salp1b) / 2.0, (calp1a + calp1b) / 2.0);
+                    tripn = false;
+                    tripb = (salp1a - salp1).abs() + (calp1a - calp1) < TOLB
+                        || (salp1 - salp1b).abs() + (calp1 - calp1b) < TOLB;
+                }
+                let l12 = l.expect("MAXIT2 > 0");
+                sig12 = l12.sig12;
+                let lengths = self.lengths(
+                    l12.eps,
+                    sig12,
+                    (l12.ssig1, l12.csig1, dn1),
+                    (l12.ssig2, l12.csig2, dn2),
+                    cbet1,
+                    cbet2,
+                );
+                m12x = lengths.m12b * self.b;
+                s12x = lengths.s12b * self.b;
+                a12 = sig12.to_degrees();
+
+                let (sdomg12, cdomg12) = l12.domg12.sin_cos();
+                somg12 = slam12 * cdomg12 - clam12 * sdomg12;
+                comg12 = clam12 * cdomg12 + slam12 * sdomg12;
+            }
+        }
+
+        // Area, from the equator to the geodesic.
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+        let mut S12 = if calp0 != 0.0 && salp0 != 0.0 {
+            let (ssig1, csig1) = norm2(sbet1, calp1 * cbet1);
+            let (ssig2, csig2) = norm2(sbet2, calp2 * cbet2);
+            let k2 = sq(calp0) * self.ep2;
+            let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+            let a4 = sq(self.a) * calp0 * salp0 * self.e2;
+            let mut c4a = [0.0; ORDER];
+            self.c4f(eps, &mut c4a);
+            let b41 = sin_cos_series(false, ssig1, csig1, &c4a);
+            let b42 = sin_cos_series(false, ssig2, csig2, &c4a);
+            a4 * (b42 - b41)
+        } else {
+            0.0
+        };
+        if !meridian && 1.0 < somg12 {
+            (somg12, comg12) = omg12.sin_cos();
+        }
+        let alp12 = if !meridian && -FRAC_1_SQRT_2 < comg12 && sbet2 - sbet1 < 1.75 {
+            // Use tan(Ω/2) = tan(ω/2) (sin β₁ + sin β₂)/(1 + cos(β₁ + β₂)), Karney eq. (C1).
+            let domg12 = 1.0 + comg12;
+            let dbet1 = 1.0 + cbet1;
+            let dbet2 = 1.0 + cbet2;
+            2.0 * (somg12 * (sbet1 * dbet2 + sbet2 * dbet1))
+                .atan2(domg12 * (sbet1 * sbet2 + dbet1 * dbet2))
+        } else {
+            let mut salp12 = salp2 * calp1 - calp2 * salp1;
+            let mut calp12 = calp2 * calp1 + salp2 * salp1;
+            if salp12 == 0.0 && calp12 < 0.0 {
+                salp12 = TINY * calp1;
+                calp12 = -1.0;
+            }
+            salp12.atan2(calp12)
+        };
+        S12 += self.c2 * alp12;
+        S12 *= swapp * lonsign * latsign;
+        S12 += 0.0;
+
+        // Undo the reductions.
+        if swapp < 0.0 {
+            std::mem::swap(&mut salp1, &mut salp2);
+            std::mem::swap(&mut calp1, &mut calp2);
+        }
+        salp1 *= swapp * lonsign;
+        calp1 *= swapp * latsign;
+        salp2 *= swapp * lonsign;
+        calp2 *= swapp * latsign;
+
+        GeodesicInverse {
+            s12: 0.0 + s12x,
+            azi1: atan2d(salp1, calp1),
+            azi2: atan2d(salp2, calp2),
+            a12,
+            m12: 0.0 + m12x,
+            S12,
+        }
+    }
+
+    /// The geodesic from a point at an azimuth.
+    pub fn line(&self, lat1: f64, lon1: f64, azi1: f64) -> GeodesicLine {
+        GeodesicLine::new(self, lat1, lon1, azi1)
+    }
+
+    /// The point at distance `s12` (which may be negative) along the geodesic from a point at an
+    /// azimuth.
+    pub fn direct(&self, lat1: f64, lon1: f64, azi1: f64, s12: f64) -> GeodesicPosition {
+        self.line(lat1, lon1, azi1).position(s12)
+    }
+}
+
+//=================================================================================================|
+
+/// A geodesic from a starting point and azimuth, for finding points along it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeodesicLine {
+    lat1: f64,
+    lon1: f64,
+    azi1: f64,
+    a: f64,
+    b: f64,
+    c2: f64,
+    f: f64,
+    f1: f64,
+    salp1: f64,
+    calp1: f64,
+    salp0: f64,
+    calp0: f64,
+    ssig1: f64,
+    csig1: f64,
+    somg1: f64,
+    comg1: f64,
+    dn1: f64,
+    k2: f64,
+    stau1: f64,
+    ctau1: f64,
+    a1m1: f64,
+    a2m1: f64,
+    a3c: f64,
+    a4: f64,
+    b11: f64,
+    b21: f64,
+    b31: f64,
+    b41: f64,
+    c1a: [f64; ORDER + 1],
+    c1pa: [f64; ORDER + 1],
+    c2a: [f64; ORDER + 1],
+    c3a: [f64; ORDER],
+    c4a: [f64; ORDER],
+}
+
+impl GeodesicLine {
+    fn new(geod: &Geodesic, lat1: f64, lon1: f64, azi1: f64) -> Self {
+        let azi1 = ang_normalize(azi1);
+        let (salp1, calp1) = sincosd(ang_round(azi1));
+        let lat1 = lat_fix(lat1);
+
+        let (s, c) = sincosd(ang_round(lat1));
+        let (sbet1, cbet1) = norm2(geod.f1 * s, c);
+        let cbet1 = cbet1.max(TINY);
+        let dn1 = (1.0 + geod.ep2 * sq(sbet1)).sqrt();
+
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+        let somg1 = salp0 * sbet1;
+        let csig1 = if sbet1 != 0.0 || calp1 != 0.0 {
+            cbet1 * calp1
+        } else {
+            1.0
+        };
+        let comg1 = csig1;
+        let (ssig1, csig1) = norm2(sbet1, csig1);
+
+        let k2 = sq(calp0) * geod.ep2;
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+
+        let a1m1 = a1m1f(eps);
+        let mut c1a = [0.0; ORDER + 1];
+        c1f(eps, &mut c1a);
+        let b11 = sin_cos_series(true, ssig1, csig1, &c1a);
+        let (s, c) = b11.sin_cos();
+        let stau1 = ssig1 * c + csig1 * s;
+        let ctau1 = csig1 * c - ssig1 * s;
+
+        let mut c1pa = [0.0; ORDER + 1];
+        c1pf(eps, &mut c1pa);
+
+        let a2m1 = a2m1f(eps);
+        let mut c2a = [0.0; ORDER + 1];
+        c2f(eps, &mut c2a);
+        let b21 = sin_cos_series(true, ssig1, csig1, &c2a);
+
+        let mut c3a = [0.0; ORDER];
+        geod.c3f(eps, &mut c3a);
+        let a3c = -geod.f * salp0 * geod.a3f(eps);
+        let b31 = sin_cos_series(true, ssig1, csig1, &c3a);
+
+        let mut c4a = [0.0; ORDER];
+        geod.c4f(eps, &mut c4a);
+        let a4 = sq(geod.a) * calp0 * salp0 * geod.e2;
+        let b41 = sin_cos_series(false, ssig1, csig1, &c4a);
+
+        Self {
+            lat1,
+            lon1,
+            azi1,
+            a: geod.a,
+            b: geod.b,
+            c2: geod.c2,
+            f: geod.f,
+            f1: geod.f1,
+            salp1,
+            calp1,
+            salp0,
+            calp0,
+            ssig1,
+            csig1,
+            somg1,
+            comg1,
+            dn1,
+            k2,
+            stau1,
+            ctau1,
+            a1m1,
+            a2m1,
+            a3c,
+            a4,
+            b11,
+            b21,
+            b31,
+            b41,
+            c1a,
+            c1pa,
+            c2a,
+            c3a,
+            c4a,
+        }
+    }
+
+    /// The point at distance `s12` along the line.
+    pub fn position(&self, s12: f64) -> GeodesicPosition {
+        let tau12 = s12 / (self.b * (1.0 + self.a1m1));
+        let (s, c) = tau12.sin_cos();
+        let b12 = -sin_cos_series(
+            true,
+            self.stau1 * c + self.ctau1 * s,
+            self.ctau1 * c - self.stau1 * s,
+            &self.c1pa,
+        );
+        let mut sig12 = tau12 - (b12 - self.b11);
+        let (mut ssig12, mut csig12) = sig12.sin_cos();
+        if 0.01 < self.f.abs() {
+            // The reverted series isn't accurate enough; take one Newton step.
+            let ssig2 = self.ssig1 * csig12 + self.csig1 * ssig12;
+            let csig2 = self.csig1 * csig12 - self.ssig1 * ssig12;
+            let b12 = sin_cos_series(true, ssig2, csig2, &self.c1a);
+            let serr = (1.0 + self.a1m1) * (sig12 + (b12 - self.b11)) - s12 / self.b;
+            sig12 -= serr / (1.0 + self.k2 * sq(ssig2)).sqrt();
+            (ssig12, csig12) = sig12.sin_cos();
+        }
+        self.position_at_sigma(sig12, ssig12, csig12, s12)
+    }
+
+    /// The point at arc length `a12` degrees on the auxiliary sphere along the line.
+    pub fn arc_position(&self, a12: f64) -> GeodesicPosition {
+        let (ssig12, csig12) = sincosd(a12);
+        let sig12 = a12.to_radians();
+        let ssig2 = self.ssig1 * csig12 + self.csig1 * ssig12;
+        let csig2 = self.csig1 * csig12 - self.ssig1 * ssig12;
+        let b12 = sin_cos_series(true, ssig2, csig2, &self.c1a);
+        let s12 = self.b * ((1.0 + self.a1m1) * sig12 + (1.0 + self.a1m1) * (b12 - self.b11));
+        GeodesicPosition {
+            a12,
+            ..self.position_at_sigma(sig12, ssig12, csig12, s12)
+        }
+    }
+
+    fn position_at_sigma(
+        &self,
+        sig12: f64,
+        ssig12: f64,
+        csig12: f64,
+        s12: f64,
+    ) -> GeodesicPosition {
+        let ssig2 = self.ssig1 * csig12 + self.csig1 * ssig12;
+        let mut csig2 = self.csig1 * csig12 - self.ssig1 * ssig12;
+        let dn2 = (1.0 + self.k2 * sq(ssig2)).sqrt();
+        let b12 = sin_cos_series(true, ssig2, csig2, &self.c1a);
+        let ab1 = (1.0 + self.a1m1) * (b12 - self.b11);
+
+        let sbet2 = self.calp0 * ssig2;
+        let mut cbet2 = self.salp0.hypot(self.calp0 * csig2);
+        if cbet2 == 0.0 {
+            // The point is at a pole.
+            cbet2 = TINY;
+            csig2 = TINY;
+        }
+        let salp2 = self.salp0;
+        let calp2 = self.calp0 * csig2;
+
+        let somg2 = self.salp0 * ssig2;
+        let comg2 = csig2;
+        let omg12 = (somg2 * self.comg1 - comg2 * self.somg1)
+            .atan2(comg2 * self.comg1 + somg2 * self.somg1);
+        let lam12 =
+            omg12 + self.a3c * (sig12 + (sin_cos_series(true, ssig2, csig2, &self.c3a) - self.b31));
+        let lon2 = ang_normalize(ang_normalize(self.lon1) + ang_normalize(lam12.to_degrees()));
+
+        let b22 = sin_cos_series(true, ssig2, csig2, &self.c2a);
+        let ab2 = (1.0 + self.a2m1) * (b22 - self.b21);
+        let j12 = (self.a1m1 - self.a2m1) * sig12 + (ab1 - ab2);
+        let m12 = self.b
+            * ((dn2 * (self.csig1 * ssig2) - self.dn1 * (self.ssig1 * csig2))
+                - self.csig1 * csig2 * j12);
+
+        let b42 = sin_cos_series(false, ssig2, csig2, &self.c4a);
+        let (salp12, calp12) = if self.calp0 == 0.0 || self.salp0 == 0.0 {
+            (
+                salp2 * self.calp1 - calp2 * self.salp1,
+                calp2 * self.calp1 + salp2 * self.salp1,
+            )
+        } else {
+            // tan(alp2 - alp1) without the cancellation, Karney eq. (C2).
+            (
+                self.calp0
+                    * self.salp0
+                    * if csig12 <= 0.0 {
+                        self.csig1 * (1.0 - csig12) + ssig12 * self.ssig1
+                    } else {
+                        ssig12 * (self.csig1 * ssig12 / (1.0 + csig12) + self.ssig1)
+                    },
+                sq(self.salp0) + sq(self.calp0) * self.csig1 * csig2,
+            )
+        };
+        let S12 = self.c2 * salp12.atan2(calp12) + self.a4 * (b42 - self.b41);
+
+        GeodesicPosition {
+            lat2: atan2d(sbet2, self.f1 * cbet2),
+            lon2,
+            azi2: atan2d(salp2, calp2),
+            s12,
+            a12: sig12.to_degrees(),
+            m12,
+            S12,
+        }
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    /// 100 lines from Karney's GeodTest.dat (doi:10.5281/zenodo.32156), one per case:
+    /// lat1 lon1 azi1 lat2 lon2 azi2 s12 a12 m12 S12, on WGS 84.
+    const GEOD_TEST: &str = include_str!("GeodTest-100.dat");
+
+    fn azi_diff(a: f64, b: f64) -> f64 {
+        ang_diff(a, b).0.abs()
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let geod = Geodesic::new(&OblateSpheroidParams::WGS84);
+        assert_ron_snapshot!((geod.ellipsoid_area() / 1e6).round(), @"510065622.0");
+
+        let mut n = 0;
+        for line in GEOD_TEST.lines() {
+            let v: Vec<f64> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()?;
+            let [lat1, lon1, azi1, lat2, lon2, azi2, s12, a12, m12, S12] = v[..] else {
+                bail!("bad line {line:?}");
+            };
+
+            // Near conjugate points (m12 ~ 0) the azimuths are ill-conditioned, so check the
+            // displacement an azimuth error would make, allowing 10 nm. Near a pole, longitude
+            // and azimuth turn with tiny displacements. The area goes with the azimuths.
+            let pole_dist = geod.a * lat2.to_radians().cos();
+            let S12_tol =
+                0.1 + 1e-14 * S12.abs() + geod.c2 * 1e-8 * (1.0 / m12.abs() + 1.0 / pole_dist);
+
+            let inv = geod.inverse(lat1, lon1, lat2, lon2);
+            ensure!((inv.s12 - s12).abs() < 1e-8, "{line}: s12 {}", inv.s12);
+            ensure!(
+                azi_diff(inv.azi1, azi1).to_radians() * m12.abs() < 1e-8,
+                "{line}: azi1 {}",
+                inv.azi1
+            );
+            ensure!(
+                azi_diff(inv.azi2, azi2).to_radians() * m12.abs() < 1e-8,
+                "{line}: azi2 {}",
+                inv.azi2
+            );
+            ensure!((inv.a12 - a12).abs() < 1e-11, "{line}: a12 {}", inv.a12);
+            ensure!((inv.m12 - m12).abs() < 1e-5, "{line}: m12 {}", inv.m12);
+            ensure!((inv.S12 - S12).abs() < S12_tol, "{line}: S12 {}", inv.S12);
+
+            let back = geod.direct(lat1, lon1, inv.azi1, inv.s12);
+            ensure!(
+                geod.inverse(back.lat2, back.lon2, lat2, lon2).s12 < 1e-6,
+                "{line}: round trip"
+            );
+
+            let dir = geod.direct(lat1, lon1, azi1, s12);
+            ensure!((dir.lat2 - lat2).abs() < 1e-13, "{line}: lat2 {}", dir.lat2);
+            ensure!(
+                azi_diff(dir.lon2, lon2).to_radians() * pole_dist < 1e-8,
+                "{line}: lon2 {}",
+                dir.lon2
+            );
+            ensure!(
+                azi_diff(dir.azi2, azi2).to_radians() * pole_dist < 1e-8,
+                "{line}: azi2 {}",
+                dir.azi2
+            );
+            ensure!((dir.a12 - a12).abs() < 1e-13, "{line}: a12 {}", dir.a12);
+            ensure!((dir.m12 - m12).abs() < 1e-8, "{line}: m12 {}", dir.m12);
+            ensure!((dir.S12 - S12).abs() < S12_tol, "{line}: S12 {}", dir.S12);
+            n += 1;
+        }
+        assert_eq!(n, 100);
+
+        // Nearly antipodal points on the equator, where Vincenty doesn't converge. The shortest
+        // path goes over a pole.
+        let inv = geod.inverse(0.0, 0.0, 0.5, 179.7);
+        assert_ron_snapshot!(((inv.s12 * 1e3).round() / 1e3, (inv.azi1 * 1e9).round() / 1e9), @"(19944127.421, 15.556882793)");
+        let inv = geod.inverse(0.0, 0.0, 0.0, 180.0);
+        assert_ron_snapshot!(((inv.s12 * 1e3).round() / 1e3, inv.azi1, inv.azi2), @"(20003931.459, 0.0, 180.0)");
+
+        // The meridian from pole to pole.
+        let inv = geod.inverse(90.0, 0.0, -90.0, 0.0);
+        assert_ron_snapshot!((inv.s12 * 1e3).round() / 1e3, @"20003931.459");
+
+        // Points along a line.
+        let line = geod.line(40.64, -73.78, 45.0);
+        let p = line.position(10e6);
+        assert_ron_snapshot!(((p.lat2 * 1e9).round() / 1e9, (p.lon2 * 1e9).round() / 1e9), @"(32.621100464, 49.052487093)");
+        let p_arc = line.arc_position(p.a12);
+        assert!((p_arc.s12 - 10e6).abs() < 1e-6 && (p_arc.lat2 - p.lat2).abs() < 1e-12);
+        Ok(())
+    }
+}
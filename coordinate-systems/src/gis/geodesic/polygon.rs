@@ -0,0 +1,241 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Perimeter and area of polygons whose edges are geodesics, as in GeographicLib's
+//! `PolygonArea`. The area is the sum of the areas between each edge and the equator, with
+//! a correction for each time an edge crosses the antimeridian so that polygons around a pole
+//! come out right.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::sync::Arc;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+
+use super::series::{ang_diff, ang_normalize, two_sum};
+use super::Geodesic;
+
+//=================================================================================================|
+
+/// A sum carried with its rounding error, so that adding many edge areas of opposite sign
+/// doesn't lose the small result.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Accumulator {
+    s: f64,
+    t: f64,
+}
+
+impl Accumulator {
+    fn add(&mut self, y: f64) {
+        let (u, err) = two_sum(y, self.t);
+        let (s, t) = two_sum(u, self.s);
+        self.s = s;
+        self.t = if s == 0.0 { u } else { t + err };
+    }
+
+    fn sum(&self, y: f64) -> f64 {
+        let mut a = *self;
+        a.add(y);
+        a.s
+    }
+}
+
+/// Perimeter and area of a polygon, and number of vertices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PolygonResult {
+    pub num: usize,
+    pub perimeter: f64,
+
+    /// NaN for a polyline.
+    pub area: f64,
+}
+
+/// Accumulates the vertices of a polygon (or polyline) on an ellipsoid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PolygonArea {
+    geod: Geodesic,
+    polyline: bool,
+
+    /// Area of the whole ellipsoid.
+    area0: f64,
+
+    num: usize,
+    crossings: i32,
+    areasum: Accumulator,
+    perimetersum: Accumulator,
+    lat0: f64,
+    lon0: f64,
+    lat1: f64,
+    lon1: f64,
+}
+
+impl PolygonArea {
+    /// If `polyline`, the perimeter is of an open path and there is no area.
+    pub fn new(geod: &Geodesic, polyline: bool) -> Self {
+        Self {
+            geod: *geod,
+            polyline,
+            area0: geod.ellipsoid_area(),
+            num: 0,
+            crossings: 0,
+            areasum: Accumulator::default(),
+            perimetersum: Accumulator::default(),
+            lat0: f64::NAN,
+            lon0: f64::NAN,
+            lat1: f64::NAN,
+            lon1: f64::NAN,
+        }
+    }
+
+    /// Adds a vertex, in degrees.
+    pub fn add_point(&mut self, lat: f64, lon: f64) {
+        if self.num == 0 {
+            (self.lat0, self.lon0) = (lat, lon);
+        } else {
+            let inv = self.geod.inverse(self.lat1, self.lon1, lat, lon);
+            self.perimetersum.add(inv.s12);
+            if !self.polyline {
+                self.areasum.add(inv.S12);
+                self.crossings += transit(self.lon1, lon);
+            }
+        }
+        (self.lat1, self.lon1) = (lat, lon);
+        self.num += 1;
+    }
+
+    /// The perimeter and area so far, closing the polygon back to the first vertex. The area is
+    /// positive if the vertices go counter-clockwise, or clockwise if `reverse`. If `signed`, it
+    /// is in `(-area0/2, area0/2]`, otherwise in `[0, area0)`.
+    pub fn compute(&self, reverse: bool, signed: bool) -> PolygonResult {
+        let num = self.num;
+        if num < 2 {
+            return PolygonResult {
+                num,
+                perimeter: 0.0,
+                area: if self.polyline { f64::NAN } else { 0.0 },
+            };
+        }
+        if self.polyline {
+            return PolygonResult {
+                num,
+                perimeter: self.perimetersum.sum(0.0),
+                area: f64::NAN,
+            };
+        }
+
+        let inv = self
+            .geod
+            .inverse(self.lat1, self.lon1, self.lat0, self.lon0);
+        let crossings = self.crossings + transit(self.lon1, self.lon0);
+        PolygonResult {
+            num,
+            perimeter: self.perimetersum.sum(inv.s12),
+            area: self.reduce_area(self.areasum.sum(inv.S12), crossings, reverse, signed),
+        }
+    }
+
+    fn reduce_area(&self, area: f64, crossings: i32, reverse: bool, signed: bool) -> f64 {
+        let area0 = self.area0;
+        let mut area = area % area0;
+        if area0 / 2.0 < area.abs() {
+            area -= area0.copysign(area);
+        }
+        if crossings & 1 != 0 {
+            area += if area < 0.0 {
+                area0 / 2.0
+            } else {
+                -area0 / 2.0
+            };
+        }
+        // The sum is clockwise positive.
+        if !reverse {
+            area = -area;
+        }
+        if signed {
+            if area0 / 2.0 < area {
+                area -= area0;
+            } else if area <= -area0 / 2.0 {
+                area += area0;
+            }
+        } else if area0 <= area {
+            area -= area0;
+        } else if area < 0.0 {
+            area += area0;
+        }
+        area + 0.0
+    }
+}
+
+/// `+1` for an eastward crossing of the antimeridian, `-1` for westward, else `0`.
+fn transit(lon1: f64, lon2: f64) -> i32 {
+    let (lon12, _) = ang_diff(lon1, lon2);
+    let lon1 = ang_normalize(lon1);
+    let lon2 = ang_normalize(lon2);
+    if 0.0 < lon12 && ((lon1 < 0.0 && 0.0 <= lon2) || (0.0 < lon1 && lon2 == 0.0)) {
+        1
+    } else if lon12 < 0.0 && 0.0 <= lon1 && lon2 < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use crate::gis::geodetic::OblateSpheroidParams;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        let geod = Geodesic::new(&OblateSpheroidParams::WGS84);
+
+        // One octant of the ellipsoid, by symmetry an eighth of its area.
+        let mut poly = PolygonArea::new(&geod, false);
+        for (lat, lon) in [(0.0, 0.0), (0.0, 90.0), (90.0, 0.0)] {
+            poly.add_point(lat, lon);
+        }
+        let r = poly.compute(false, true);
+        assert_eq!(r.num, 3);
+        assert!((r.area - geod.ellipsoid_area() / 8.0).abs() < 1.0);
+        assert_ron_snapshot!((r.perimeter * 1e3).round() / 1e3, @"30022685.63");
+
+        // Counted clockwise, the same polygon has the complementary area.
+        let r = poly.compute(true, false);
+        assert!((r.area - 7.0 * geod.ellipsoid_area() / 8.0).abs() < 1.0);
+
+        // Around the north pole, crossing the antimeridian.
+        let mut poly = PolygonArea::new(&geod, false);
+        for lon in [0.0, 90.0, 180.0, -90.0] {
+            poly.add_point(80.0, lon);
+        }
+        let r = poly.compute(false, true);
+        assert_ron_snapshot!((r.area / 1e6).round(), @"2507270.0");
+
+        let mut line = PolygonArea::new(&geod, true);
+        line.add_point(0.0, 0.0);
+        line.add_point(0.0, 1.0);
+        let r = line.compute(false, true);
+        assert!(r.area.is_nan());
+        assert!((r.perimeter - 111_319.490_793).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Rhumb lines (loxodromes) on the ellipsoid: paths of constant azimuth, which are straight on
+//! a Mercator map. The azimuth is `atan2(Δλ, Δψ)` with `ψ` the isometric latitude, and the
+//! distance is the meridian distance over the cosine of the azimuth. Meridian distances come
+//! from the geodesic along the meridian, so they are as accurate as the geodesics are.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::sync::Arc;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use log::{debug, error, info, trace, warn};
+
+use crate::gis::geodetic::OblateSpheroidParams;
+
+use super::series::{ang_diff, ang_normalize, atan2d, sincosd, sq};
+use super::Geodesic;
+
+//=================================================================================================|
+
+/// Below this `|Δψ|` the distance is computed along the parallel instead.
+const DPSI_TINY: f64 = 1e-12;
+
+/// Solves rhumb line problems on one ellipsoid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rhumb {
+    geod: Geodesic,
+}
+
+/// Distance and constant azimuth of the rhumb line between two points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RhumbInverse {
+    pub s12: f64,
+
+    /// Degrees clockwise from north.
+    pub azi12: f64,
+}
+
+impl Rhumb {
+    pub fn new(ellipsoid: &OblateSpheroidParams) -> Self {
+        Self {
+            geod: Geodesic::new(ellipsoid),
+        }
+    }
+
+    /// Isometric latitude, radians, of a latitude in degrees.
+    pub fn isometric_latitude(&self, lat: f64) -> f64 {
+        let (s, c) = sincosd(lat);
+        let e = self.geod.e2.sqrt();
+        (s / c).asinh() - e * (e * s).atanh()
+    }
+
+    /// Signed distance along the meridian from the equator.
+    pub fn meridian_distance(&self, lat: f64) -> f64 {
+        let s = self.geod.inverse(0.0, 0.0, lat.abs(), 0.0).s12;
+        s.copysign(lat)
+    }
+
+    /// Radius of the parallel at a latitude in degrees.
+    fn parallel_radius(&self, lat: f64) -> f64 {
+        let (s, c) = sincosd(lat);
+        self.geod.a * c / (1.0 - self.geod.e2 * sq(s)).sqrt()
+    }
+
+    /// `ΔM/Δψ`, or its limit `dM/dψ` along a parallel.
+    fn scale(&self, lat1: f64, lat2: f64, dpsi: f64) -> f64 {
+        if DPSI_TINY < dpsi.abs() {
+            (self.meridian_distance(lat2) - self.meridian_distance(lat1)) / dpsi
+        } else {
+            self.parallel_radius(lat1)
+        }
+    }
+
+    /// The rhumb line between two points, going the short way in longitude.
+    pub fn inverse(&self, lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> RhumbInverse {
+        let lam12 = ang_diff(lon1, lon2).0.to_radians();
+        let dpsi = self.isometric_latitude(lat2) - self.isometric_latitude(lat1);
+        RhumbInverse {
+            s12: lam12.hypot(dpsi) * self.scale(lat1, lat2, dpsi),
+            azi12: lam12.atan2(dpsi).to_degrees(),
+        }
+    }
+
+    /// The point at distance `s12` along the rhumb line from a point at an azimuth. The latitude
+    /// is NaN if the line would reach a pole first.
+    pub fn direct(&self, lat1: f64, lon1: f64, azi12: f64, s12: f64) -> (f64, f64) {
+        let (salp, calp) = sincosd(azi12);
+        let m2 = self.meridian_distance(lat1) + s12 * calp;
+        let quarter = self.meridian_distance(90.0);
+        if quarter < m2.abs() {
+            return (f64::NAN, f64::NAN);
+        }
+        let lat2 = self.geod.direct(0.0, 0.0, 0.0, m2).lat2;
+        let dpsi = self.isometric_latitude(lat2) - self.isometric_latitude(lat1);
+        let lam12 = if DPSI_TINY < dpsi.abs() {
+            dpsi * salp / calp
+        } else {
+            s12 * salp / self.parallel_radius(lat1)
+        };
+        (lat2, ang_normalize(lon1 + lam12.to_degrees()))
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        let wgs84 = OblateSpheroidParams::WGS84;
+        let rhumb = Rhumb::new(&wgs84);
+        let geod = Geodesic::new(&wgs84);
+
+        // JFK to LHR: the rhumb line is longer than the geodesic.
+        let rh = rhumb.inverse(40.64, -73.78, 51.47, -0.45);
+        let inv = geod.inverse(40.64, -73.78, 51.47, -0.45);
+        assert!(inv.s12 < rh.s12);
+        assert_ron_snapshot!(((rh.s12 * 1e3).round() / 1e3, (rh.azi12 * 1e9).round() / 1e9), @"(5774746.236, 77.968126049)");
+
+        let (lat2, lon2) = rhumb.direct(40.64, -73.78, rh.azi12, rh.s12);
+        assert!((lat2 - 51.47).abs() < 1e-9 && (lon2 + 0.45).abs() < 1e-9);
+
+        // Along the equator and along a meridian, the rhumb line is the geodesic.
+        let rh = rhumb.inverse(0.0, 10.0, 0.0, 40.0);
+        let inv = geod.inverse(0.0, 10.0, 0.0, 40.0);
+        assert!((rh.s12 - inv.s12).abs() < 1e-6 && rh.azi12 == 90.0);
+        let rh = rhumb.inverse(-20.0, 10.0, 60.0, 10.0);
+        let inv = geod.inverse(-20.0, 10.0, 60.0, 10.0);
+        assert!((rh.s12 - inv.s12).abs() < 1e-6 && rh.azi12 == 0.0);
+
+        // Along a parallel.
+        let (lat2, lon2) = rhumb.direct(45.0, 0.0, 90.0, 100e3);
+        assert!((lat2 - 45.0).abs() < 1e-12);
+        assert_ron_snapshot!((lon2 * 1e9).round() / 1e9, @"1.268281725");
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Angle reductions and the series of Karney (2013), "Algorithms for geodesics", J. Geod.
+//! 87:43, to sixth order in the third flattening. Coefficient tables are from GeographicLib
+//! (MIT license); each is a run of polynomials, highest power first, each followed by its
+//! denominator.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::{Deref, DerefMut, Display, From, Into};
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+//? use hecs::{Bundle, Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+//? use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+//=================================================================================================|
+
+/// Order of the series expansions.
+pub(super) const ORDER: usize = 6;
+pub(super) const N_C3X: usize = 15;
+pub(super) const N_C4X: usize = 21;
+
+pub(super) fn sq(x: f64) -> f64 {
+    x * x
+}
+
+/// Scales `(x, y)` to unit length.
+pub(super) fn norm2(x: f64, y: f64) -> (f64, f64) {
+    let r = x.hypot(y);
+    (x / r, y / r)
+}
+
+/// Error-free sum: `u + v = s + t` exactly.
+pub(super) fn two_sum(u: f64, v: f64) -> (f64, f64) {
+    let s = u + v;
+    let up = s - v;
+    let vpp = s - up;
+    let t = -((up - u) + (vpp - v));
+    (s, t)
+}
+
+/// Evaluates the polynomial of degree `n` with coefficients `p[0..=n]`, highest power first.
+pub(super) fn polyval(n: usize, p: &[f64], x: f64) -> f64 {
+    p[1..=n].iter().fold(p[0], |y, &c| y * x + c)
+}
+
+/// Rounds tiny angles to multiples of 2⁻⁵⁷ degrees (about 0.7 pm on the earth), so that
+/// nearly-singular cases like 1e-200 become exactly zero.
+pub(super) fn ang_round(x: f64) -> f64 {
+    const Z: f64 = 1.0 / 16.0;
+    let y = x.abs();
+    let y = if y < Z { Z - (Z - y) } else { y };
+    if x == 0.0 {
+        0.0
+    } else {
+        y.copysign(x)
+    }
+}
+
+/// Reduces an angle in degrees to `(-180, 180]`.
+pub(super) fn ang_normalize(x: f64) -> f64 {
+    let y = x % 360.0;
+    let y = if y < -180.0 {
+        y + 360.0
+    } else if 180.0 < y {
+        y - 360.0
+    } else {
+        y
+    };
+    if y == -180.0 {
+        180.0
+    } else {
+        y
+    }
+}
+
+/// Latitudes outside `[-90, 90]` become NaN.
+pub(super) fn lat_fix(x: f64) -> f64 {
+    if 90.0 < x.abs() {
+        f64::NAN
+    } else {
+        x
+    }
+}
+
+/// `y - x` reduced to `[-180, 180]`, as a value and its rounding error.
+pub(super) fn ang_diff(x: f64, y: f64) -> (f64, f64) {
+    let (d, t) = two_sum(ang_normalize(-x), ang_normalize(y));
+    let d = ang_normalize(d);
+    if d == 180.0 && 0.0 < t {
+        two_sum(-180.0, t)
+    } else {
+        two_sum(d, t)
+    }
+}
+
+/// Sine and cosine of an angle in degrees, exact at multiples of 90°.
+pub(super) fn sincosd(x: f64) -> (f64, f64) {
+    let r = x % 360.0;
+    let q = if r.is_finite() {
+        (r / 90.0).round()
+    } else {
+        0.0
+    };
+    let (s, c) = (r - 90.0 * q).to_radians().sin_cos();
+    let (s, c) = match (q as i64).rem_euclid(4) {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    };
+    // No negative zeros from the cosine; keep the sign of x on a zero sine.
+    let c = c + 0.0;
+    let s = if s == 0.0 { s.copysign(x) } else { s };
+    (s, c)
+}
+
+/// `atan2` in degrees, exact for the axes.
+pub(super) fn atan2d(y: f64, x: f64) -> f64 {
+    let (mut x, mut y) = (x, y);
+    let mut q = 0;
+    if x.abs() < y.abs() {
+        std::mem::swap(&mut x, &mut y);
+        q = 2;
+    }
+    if x < 0.0 {
+        q += 1;
+        x = -x;
+    }
+    let ang = y.atan2(x).to_degrees();
+    match q {
+        1 => (if y.is_sign_negative() { -180.0 } else { 180.0 }) - ang,
+        2 => 90.0 - ang,
+        3 => -90.0 + ang,
+        _ => ang,
+    }
+}
+
+/// Clenshaw summation of `sum c[l] sin(2 l x)` (l = 1..) if `sinp`, else
+/// `sum c[l] cos((2 l + 1) x)` (l = 0..).
+pub(super) fn sin_cos_series(sinp: bool, sinx: f64, cosx: f64, c: &[f64]) -> f64 {
+    let mut k = c.len();
+    let mut n = k - usize::from(sinp);
+    let ar = 2.0 * (cosx - sinx) * (cosx + sinx);
+    let mut y1 = 0.0;
+    let mut y0 = if n % 2 == 1 {
+        k -= 1;
+        c[k]
+    } else {
+        0.0
+    };
+    n /= 2;
+    while 0 < n {
+        n -= 1;
+        k -= 1;
+        y1 = ar * y0 - y1 + c[k];
+        k -= 1;
+        y0 = ar * y1 - y0 + c[k];
+    }
+    if sinp {
+        2.0 * sinx * cosx * y0
+    } else {
+        cosx * (y0 - y1)
+    }
+}
+
+/// The positive root `k` of `k⁴ + 2k³ - (x² + y² - 1)k² - 2y²k - y² = 0`, which gives the
+/// starting point for nearly antipodal inverse problems.
+pub(super) fn astroid(x: f64, y: f64) -> f64 {
+    let p = sq(x);
+    let q = sq(y);
+    let r = (p + q - 1.0) / 6.0;
+    if q == 0.0 && r <= 0.0 {
+        return 0.0;
+    }
+    let s = p * q / 4.0;
+    let r2 = sq(r);
+    let r3 = r * r2;
+    let disc = s * (s + 2.0 * r3);
+    let mut u = r;
+    if 0.0 <= disc {
+        let t3 = s + r3;
+        let t3 = t3 + disc.sqrt().copysign(t3);
+        let t = t3.cbrt();
+        u += t + if t != 0.0 { r2 / t } else { 0.0 };
+    } else {
+        let ang = (-disc).sqrt().atan2(-(s + r3));
+        u += 2.0 * r * (ang / 3.0).cos();
+    }
+    let v = (sq(u) + q).sqrt();
+    let uv = if u < 0.0 { q / (v - u) } else { u + v };
+    let w = (uv - q) / (2.0 * v);
+    uv / ((uv + sq(w)).sqrt() + w)
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// Fills `c[1..=ORDER]` with `eps^l` times the polynomials in `eps²` from `coeff`.
+fn fill_eps_series(coeff: &[f64], eps: f64, c: &mut [f64; ORDER + 1]) {
+    let eps2 = sq(eps);
+    let mut d = eps;
+    let mut o = 0;
+    for (l, c_l) in c.iter_mut().enumerate().skip(1) {
+        let m = (ORDER - l) / 2;
+        *c_l = d * polyval(m, &coeff[o..], eps2) / coeff[o + m + 1];
+        o += m + 2;
+        d *= eps;
+    }
+}
+
+/// `A1 - 1`, Karney eq. (17).
+pub(super) fn a1m1f(eps: f64) -> f64 {
+    const COEFF: [f64; 5] = [1.0, 4.0, 64.0, 0.0, 256.0];
+    let m = ORDER / 2;
+    let t = polyval(m, &COEFF, sq(eps)) / COEFF[m + 1];
+    (t + eps) / (1.0 - eps)
+}
+
+/// `C1l`, eq. (18).
+pub(super) fn c1f(eps: f64, c: &mut [f64; ORDER + 1]) {
+    const COEFF: [f64; 18] = [
+        -1.0, 6.0, -16.0, 32.0, -9.0, 64.0, -128.0, 2048.0, 9.0, -16.0, 768.0, 3.0, -5.0, 512.0,
+        -7.0, 1280.0, -7.0, 2048.0,
+    ];
+    fill_eps_series(&COEFF, eps, c);
+}
+
+/// `C1'l`, eq. (21), for the reversion of the distance series.
+pub(super) fn c1pf(eps: f64, c: &mut [f64; ORDER + 1]) {
+    const COEFF: [f64; 18] = [
+        205.0, -432.0, 768.0, 1536.0, 4005.0, -4736.0, 3840.0, 12288.0, -225.0, 116.0, 384.0,
+        -7173.0, 2695.0, 7680.0, 3467.0, 7680.0, 38081.0, 61440.0,
+    ];
+    fill_eps_series(&COEFF, eps, c);
+}
+
+/// `A2 - 1`, eq. (42).
+pub(super) fn a2m1f(eps: f64) -> f64 {
+    const COEFF: [f64; 5] = [-11.0, -28.0, -192.0, 0.0, 256.0];
+    let m = ORDER / 2;
+    let t = polyval(m, &COEFF, sq(eps)) / COEFF[m + 1];
+    (t - eps) / (1.0 + eps)
+}
+
+/// `C2l`, eq. (43).
+pub(super) fn c2f(eps: f64, c: &mut [f64; ORDER + 1]) {
+    const COEFF: [f64; 18] = [
+        1.0, 2.0, 16.0, 32.0, 35.0, 64.0, 384.0, 2048.0, 15.0, 80.0, 768.0, 7.0, 35.0, 512.0, 63.0,
+        1280.0, 77.0, 2048.0,
+    ];
+    fill_eps_series(&COEFF, eps, c);
+}
+
+/// `A3` as a polynomial in `eps`, with coefficients depending on the third flattening `n`.
+pub(super) fn a3_coeffs(n: f64) -> [f64; ORDER] {
+    const COEFF: [f64; 18] = [
+        -3.0, 128.0, -2.0, -3.0, 64.0, -1.0, -3.0, -1.0, 16.0, 3.0, -1.0, -2.0, 8.0, 1.0, -1.0,
+        2.0, 1.0, 1.0,
+    ];
+    let mut a3x = [0.0; ORDER];
+    let mut o = 0;
+    for (k, j) in (0..ORDER).rev().enumerate() {
+        let m = j.min(ORDER - j - 1);
+        a3x[k] = polyval(m, &COEFF[o..], n) / COEFF[o + m + 1];
+        o += m + 2;
+    }
+    a3x
+}
+
+/// `C3l`, eq. (25), as polynomials in `eps` with coefficients depending on `n`.
+pub(super) fn c3_coeffs(n: f64) -> [f64; N_C3X] {
+    const COEFF: [f64; 45] = [
+        3.0, 128.0, 2.0, 5.0, 128.0, -1.0, 3.0, 3.0, 64.0, -1.0, 0.0, 1.0, 8.0, -1.0, 1.0, 4.0,
+        5.0, 256.0, 1.0, 3.0, 128.0, -3.0, -2.0, 3.0, 64.0, 1.0, -3.0, 2.0, 32.0, 7.0, 512.0,
+        -10.0, 9.0, 384.0, 5.0, -9.0, 5.0, 192.0, 7.0, 512.0, -14.0, 7.0, 512.0, 21.0, 2560.0,
+    ];
+    let mut c3x = [0.0; N_C3X];
+    let (mut o, mut k) = (0, 0);
+    for l in 1..ORDER {
+        for j in (l..ORDER).rev() {
+            let m = j.min(ORDER - j - 1);
+            c3x[k] = polyval(m, &COEFF[o..], n) / COEFF[o + m + 1];
+            k += 1;
+            o += m + 2;
+        }
+    }
+    c3x
+}
+
+/// `C4l`, eq. (64) of Karney (2013), for the area.
+pub(super) fn c4_coeffs(n: f64) -> [f64; N_C4X] {
+    const COEFF: [f64; 77] = [
+        97.0, 15015.0, 1088.0, 156.0, 45045.0, -224.0, -4784.0, 1573.0, 45045.0, -10656.0, 14144.0,
+        -4576.0, -858.0, 45045.0, 64.0, 624.0, -4576.0, 6864.0, -3003.0, 15015.0, 100.0, 208.0,
+        572.0, 3432.0, -12012.0, 30030.0, 45045.0, 1.0, 9009.0, -2944.0, 468.0, 135135.0, 5792.0,
+        1040.0, -1287.0, 135135.0, 5952.0, -11648.0, 9152.0, -2574.0, 135135.0, -64.0, -624.0,
+        4576.0, -6864.0, 3003.0, 135135.0, 8.0, 10725.0, 1856.0, -936.0, 225225.0, -8448.0, 4992.0,
+        -1144.0, 225225.0, -1440.0, 4160.0, -4576.0, 1716.0, 225225.0, -136.0, 63063.0, 1024.0,
+        -208.0, 105105.0, 3584.0, -3328.0, 1144.0, 315315.0, -128.0, 135135.0, -2560.0, 832.0,
+        405405.0, 128.0, 99099.0,
+    ];
+    let mut c4x = [0.0; N_C4X];
+    let (mut o, mut k) = (0, 0);
+    for l in 0..ORDER {
+        for j in (l..ORDER).rev() {
+            let m = ORDER - j - 1;
+            c4x[k] = polyval(m, &COEFF[o..], n) / COEFF[o + m + 1];
+            k += 1;
+            o += m + 2;
+        }
+    }
+    c4x
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() {
+        assert_eq!(sincosd(90.0), (1.0, 0.0));
+        assert_eq!(sincosd(-180.0), (-0.0, -1.0));
+        assert!((sincosd(30.0).0 - 0.5).abs() < 1e-16);
+        assert_eq!(atan2d(1.0, -1.0), 135.0);
+        assert_eq!(atan2d(-0.0, -1.0), -180.0);
+        assert_eq!(
+            (
+                ang_normalize(-180.0),
+                ang_normalize(540.0),
+                ang_normalize(-190.0)
+            ),
+            (180.0, 180.0, 170.0)
+        );
+        assert_eq!(ang_diff(170.0, -170.0), (20.0, 0.0));
+        assert_eq!(ang_round(1e-200), 0.0);
+        assert_ron_snapshot!(astroid(0.5, 0.5), @"0.5290855136357461");
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+use std::f64::consts::{FRAC_PI_2, PI};
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::{Deref, DerefMut, Display, From, Into};
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+use hecs::World;
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+//? use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use crate::geom::Flattening;
+use crate::*;
+
+//=================================================================================================|
+
+/// The numbers of an [`OblateSpheroidDef`] evaluated to `f64`, with the derived quantities the
+/// coordinate conversions need. Lengths are in the unit of `a`, normally meters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OblateSpheroidParams {
+    /// Equatorial radius.
+    pub a: f64,
+
+    /// Flattening, `(a - b)/a`.
+    pub f: f64,
+
+    /// Polar radius.
+    pub b: f64,
+
+    /// First eccentricity squared, `f (2 - f)`.
+    pub e2: f64,
+}
+
+impl OblateSpheroidParams {
+    /// WGS 84, from NIMA TR8350.2.
+    pub const WGS84: Self = Self::new(6_378_137.0, 1.0 / 298.257_223_563);
+
+    pub const fn new(a: f64, f: f64) -> Self {
+        Self {
+            a,
+            f,
+            b: a * (1.0 - f),
+            e2: f * (2.0 - f),
+        }
+    }
+
+    /// Evaluates the definition. Fails if it refers to other entities.
+    pub fn try_from_def(def: &OblateSpheroidDef) -> Result<Self> {
+        let a = def.a.value.to_f64().context("OblateSpheroidDef.a")?;
+        let f = def.f.f()?.to_f64().context("OblateSpheroidDef.f")?;
+        Self::checked(a, f)
+    }
+
+    /// Evaluates the definition, following references to other entities, such as the constants
+    /// in `/consts`.
+    pub fn from_def(world: &World, def: &OblateSpheroidDef) -> Result<Self> {
+        let a = def
+            .a
+            .value
+            .evaluate(world)?
+            .to_f64()
+            .context("OblateSpheroidDef.a")?;
+        let f = def
+            .f
+            .f()?
+            .evaluate(world)?
+            .to_f64()
+            .context("OblateSpheroidDef.f")?;
+        Self::checked(a, f)
+    }
+
+    fn checked(a: f64, f: f64) -> Result<Self> {
+        ensure!(
+            0.0 < a && a.is_finite(),
+            "equatorial radius {a} is not positive"
+        );
+        ensure!((0.0..1.0).contains(&f), "flattening {f} is not in [0, 1)");
+        Ok(Self::new(a, f))
+    }
+
+    /// Second eccentricity squared, `e² / (1 - e²)`.
+    pub fn second_eccentricity_squared(&self) -> f64 {
+        self.e2 / (1.0 - self.e2)
+    }
+
+    /// Radius of curvature in the prime vertical, `N(lat)`.
+    pub fn prime_vertical_radius(&self, lat: f64) -> f64 {
+        self.a / (1.0 - self.e2 * lat.sin().powi(2)).sqrt()
+    }
+
+    /// Radius of curvature in the meridian, `M(lat)`.
+    pub fn meridional_radius(&self, lat: f64) -> f64 {
+        let w2 = 1.0 - self.e2 * lat.sin().powi(2);
+        self.a * (1.0 - self.e2) / (w2 * w2.sqrt())
+    }
+
+    /// The IUGG mean radius `R1 = (2a + b) / 3`.
+    pub fn arithmetic_mean_radius(&self) -> f64 {
+        (2.0 * self.a + self.b) / 3.0
+    }
+
+    /// The radius `R2` of the sphere with the same surface area.
+    pub fn authalic_radius(&self) -> f64 {
+        (self.surface_area() / (4.0 * PI)).sqrt()
+    }
+
+    /// The radius `R3` of the sphere with the same volume.
+    pub fn volumetric_radius(&self) -> f64 {
+        (self.a * self.a * self.b).cbrt()
+    }
+
+    /// Surface area, in the square of the unit of `a`.
+    pub fn surface_area(&self) -> f64 {
+        if self.e2 == 0.0 {
+            return 4.0 * PI * self.a * self.a;
+        }
+        let e = self.e2.sqrt();
+        2.0 * PI * self.a * self.a * (1.0 + (1.0 - self.e2) / e * e.atanh())
+    }
+
+    /// Earth-centered, earth-fixed Cartesian coordinates of a geodetic position.
+    pub fn geodetic_to_ecef(&self, geodetic: &Geodetic) -> Vector3 {
+        let Geodetic { lat, lon, h } = *geodetic;
+        let (sin_lat, cos_lat) = lat.sin_cos();
+        let (sin_lon, cos_lon) = lon.sin_cos();
+        let n = self.prime_vertical_radius(lat);
+        Vector3::new(
+            (n + h) * cos_lat * cos_lon,
+            (n + h) * cos_lat * sin_lon,
+            (n * (1.0 - self.e2) + h) * sin_lat,
+        )
+    }
+
+    /// Geodetic position of an ECEF point, by the closed-form solution of Vermeille (2011), "An
+    /// analytical method to transform geocentric into geodetic coordinates", J. Geod. 85:105,
+    /// arranged as in GeographicLib's `Geocentric::IntReverse` to hold its accuracy at the
+    /// poles, on the equator, and near the center.
+    pub fn ecef_to_geodetic(&self, ecef: Vector3) -> Geodetic {
+        let Vector3 { x, y, z } = ecef;
+        let r = x.hypot(y);
+        let lon = if r == 0.0 { 0.0 } else { y.atan2(x) };
+
+        if self.e2 == 0.0 {
+            let lat = if r == 0.0 && z == 0.0 {
+                FRAC_PI_2
+            } else {
+                z.atan2(r)
+            };
+            return Geodetic {
+                lat,
+                lon,
+                h: r.hypot(z) - self.a,
+            };
+        }
+
+        let a = self.a;
+        let e2 = self.e2;
+        let e2m = 1.0 - e2;
+        let e4 = e2 * e2;
+
+        let p = (r / a).powi(2);
+        let q = e2m * (z / a).powi(2);
+        let rr = (p + q - e4) / 6.0;
+
+        let (sin_lat, cos_lat, h) = if !(e4 * q == 0.0 && rr <= 0.0) {
+            // Multiplied through by rr^3 and rr so that rr = 0 doesn't divide by zero.
+            let s = e4 * p * q / 4.0;
+            let r2 = rr * rr;
+            let r3 = rr * r2;
+            let disc = s * (2.0 * r3 + s);
+            let mut u = rr;
+            if 0.0 <= disc {
+                // Take the sign of the root that maximizes |t3|.
+                let t3 = s + r3;
+                let t3 = t3 + disc.sqrt().copysign(t3);
+                let t = t3.cbrt();
+                u += t + if t != 0.0 { r2 / t } else { 0.0 };
+            } else {
+                // Inside the evolute: three real roots, take the one without cancellation.
+                let ang = (-disc).sqrt().atan2(-(s + r3));
+                u += 2.0 * rr * (ang / 3.0).cos();
+            }
+            let v = (u * u + e4 * q).sqrt();
+            let uv = if u < 0.0 { e4 * q / (v - u) } else { u + v };
+            let w = (e2 * (uv - q) / (2.0 * v)).max(0.0);
+            let k = uv / ((uv + w * w).sqrt() + w);
+            let d = k * r / (k + e2);
+            let hyp = (z / k).hypot(r / (k + e2));
+            let h = (1.0 - e2m / k) * d.hypot(z);
+            ((z / k) / hyp, (r / (k + e2)) / hyp, h)
+        } else {
+            // In the equatorial plane within the evolute, where k -> 0.
+            let zz = ((e4 - p) / e2m).sqrt();
+            let xx = p.sqrt();
+            let hyp = zz.hypot(xx);
+            let h = -a * e2m * hyp / e2;
+            ((zz / hyp).copysign(z), xx / hyp, h)
+        };
+
+        Geodetic {
+            lat: sin_lat.atan2(cos_lat),
+            lon,
+            h,
+        }
+    }
+}
+
+impl TryFrom<&OblateSpheroidDef> for OblateSpheroidParams {
+    type Error = anyhow::Error;
+
+    fn try_from(def: &OblateSpheroidDef) -> Result<Self> {
+        Self::try_from_def(def)
+    }
+}
+
+//=================================================================================================|
+
+/// Geodetic latitude and longitude (radians, east positive) and height above the ellipsoid
+/// (in the unit of its equatorial radius).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Geodetic {
+    pub lat: f64,
+    pub lon: f64,
+    pub h: f64,
+}
+
+impl Geodetic {
+    pub fn from_degrees(lat_deg: f64, lon_deg: f64, h: f64) -> Self {
+        Self {
+            lat: lat_deg.to_radians(),
+            lon: lon_deg.to_radians(),
+            h,
+        }
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    const WGS84: OblateSpheroidParams = OblateSpheroidParams::WGS84;
+
+    fn round_trip(g: Geodetic) -> (f64, f64, f64) {
+        let back = WGS84.ecef_to_geodetic(WGS84.geodetic_to_ecef(&g));
+        // Position errors in meters.
+        let n = WGS84.prime_vertical_radius(g.lat) + g.h;
+        (
+            (back.lat - g.lat).abs() * n,
+            (back.lon - g.lon).abs() * n * g.lat.cos(),
+            (back.h - g.h).abs(),
+        )
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        assert_ron_snapshot!(WGS84.b, @"6356752.314245179");
+        assert_ron_snapshot!(WGS84.e2, @"0.0066943799901413165");
+
+        let def = OblateSpheroidDef {
+            a: DimensionedConstant {
+                name: "a".into(),
+                dimension_kind: DimensionKind::LENGTH,
+                exactness: Exactness::Exact(crate::core::ExactReason::ByDefinition),
+                value: EcsNum::RatioU64(RatioU64::from_integer(6378137)),
+            },
+            f: Flattening::F_inv(EcsNum::RatioU64(RatioU64::new(298257223563, 1000000000))),
+        };
+        let params = OblateSpheroidParams::try_from(&def)?;
+        assert!((params.f - WGS84.f).abs() < 1e-18 && params.a == WGS84.a);
+
+        // Equator, prime meridian; north pole.
+        let p = WGS84.geodetic_to_ecef(&Geodetic::from_degrees(0.0, 0.0, 0.0));
+        assert_eq!((p.x, p.y, p.z), (6_378_137.0, 0.0, 0.0));
+        let p = WGS84.geodetic_to_ecef(&Geodetic::from_degrees(90.0, 0.0, 0.0));
+        assert_ron_snapshot!(((p.x * 1e6).round(), (p.z * 1e3).round() / 1e3), @"(0.0, 6356752.314)");
+
+        let g = WGS84.ecef_to_geodetic(Vector3::new(0.0, 0.0, -WGS84.b - 100.0));
+        assert_eq!(
+            (g.lat.to_degrees(), g.lon, (g.h * 1e6).round() / 1e6),
+            (-90.0, 0.0, 100.0)
+        );
+
+        // Near the center, inside the evolute, the closest surface point in the equatorial plane
+        // is off the equator.
+        for ecef in [
+            Vector3::new(1000.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1000.0),
+        ] {
+            let g = WGS84.ecef_to_geodetic(ecef);
+            let back = WGS84.geodetic_to_ecef(&g);
+            assert!(
+                (back.x - ecef.x).abs() < 1e-6 && (back.z - ecef.z).abs() < 1e-6,
+                "{g:?}"
+            );
+        }
+        let g = WGS84.ecef_to_geodetic(Vector3::new(0.0, 0.0, 1000.0));
+        assert_eq!(g.lat.to_degrees(), 90.0);
+        assert!((g.h - (1000.0 - WGS84.b)).abs() < 1e-6);
+
+        for lat_deg in [
+            -90.0,
+            -89.999_999,
+            -45.0,
+            -1e-9,
+            0.0,
+            1e-9,
+            30.0,
+            60.0,
+            89.9,
+            90.0,
+        ] {
+            for lon_deg in [-180.0, -90.0, 0.0, 45.0, 179.999] {
+                for h in [
+                    -6_000_000.0,
+                    -10_000.0,
+                    0.0,
+                    8848.0,
+                    400_000.0,
+                    35_786_000.0,
+                    1e9,
+                ] {
+                    let (d_lat, d_lon, d_h) =
+                        round_trip(Geodetic::from_degrees(lat_deg, lon_deg, h));
+                    let tol = 1e-8 * (1.0 + h.abs() / WGS84.a);
+                    assert!(
+                        d_lat < tol && d_lon < tol && d_h < tol,
+                        "({lat_deg}, {lon_deg}, {h}): {d_lat:e} {d_lon:e} {d_h:e}"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
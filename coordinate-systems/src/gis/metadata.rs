@@ -0,0 +1,81 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+use derive_more::Display;
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+//? use hecs::{Bundle, Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+
+//=================================================================================================|
+
+/// Component: an identifier assigned by an authority, e.g. `EPSG:4326`.
+#[derive(Clone, Debug, Display, PartialEq, Eq, Serialize, Deserialize)]
+#[display("{authority}:{code}")]
+pub struct AuthorityId {
+    pub authority: String,
+
+    /// Usually a number, but not always.
+    pub code: String,
+}
+
+impl AuthorityId {
+    pub fn epsg(code: u32) -> Self {
+        Self {
+            authority: "EPSG".into(),
+            code: code.to_string(),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// Component: what a CRS or datum is for and where it applies.
+#[derive(Clone, Debug, Display, PartialEq)]
+#[display("Usage {{ {scope:?} }}")]
+pub struct Usage {
+    pub scope: String,
+
+    /// Description of the area of use.
+    pub area: Option<String>,
+
+    /// South, west, north and east bounds in degrees. West may exceed east across the
+    /// antimeridian.
+    pub bbox: Option<[f64; 4]>,
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// Component: when an authority last revised its record of an entity.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, PartialOrd, Ord)]
+#[display("{year:04}-{month:02}-{day:02}")]
+pub struct RevisionDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
@@ -39,7 +39,42 @@ use hecs::{Bundle, Entity, World};
 //? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
 
 pub mod datum;
-pub use crate::gis::datum::{Datum, DatumDef, DatumRef};
+pub use crate::gis::datum::{Datum, DatumDef, DatumRef, PrimeMeridian};
+
+pub mod datum_transform;
+pub use crate::gis::datum_transform::{
+    datum_spheroid, transform_ecef, DatumTransform, DatumTransformDef, DatumTransformMethod,
+    DatumTransformRef, Helmert7,
+};
+
+pub mod epsg;
+pub use crate::gis::epsg::{epsg_entry, lookup_epsg};
+
+pub mod geodesic;
+pub use crate::gis::geodesic::{Geodesic, GeodesicInverse, GeodesicLine, GeodesicPosition};
+
+pub mod metadata;
+pub use crate::gis::metadata::{AuthorityId, RevisionDate, Usage};
+
+pub mod geodetic;
+pub use crate::gis::geodetic::{Geodetic, OblateSpheroidParams};
+
+pub mod plate_motion;
+pub use crate::gis::plate_motion::{
+    EpochCoordinate, PlateMotionModel, PlatePole, Station, StationVelocity, ITRF2014_PMM,
+};
+
+pub mod projection;
+pub use crate::gis::projection::{
+    ecs_add_map_projection, ecs_add_utm, MapProjection, Projection, TransverseMercatorParams,
+    UtmZone,
+};
+
+pub mod topocentric;
+pub use crate::gis::topocentric::{Aer, LocalEnuFrame};
+
+pub mod wkt;
+pub use crate::gis::wkt::{crs_to_wkt, ecs_add_crs_from_wkt};
 
 use crate::*;
 
@@ -55,6 +90,8 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
     let ns_gis = ecs_ns_find_or_create_gis(world)?;
 
     crate::gis::datum::ecs_add_stuff(world, ns_gis)?;
+    crate::gis::datum_transform::ecs_add_stuff(world, ns_gis)?;
+    crate::gis::projection::ecs_add_stuff(world, ns_gis)?;
 
     Ok(())
 }
@@ -0,0 +1,228 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Tectonic plate motion, for carrying station positions in a dynamic CRS from one
+//! coordinate epoch to another.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use derive_more::Display;
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+use hecs::{Bundle, Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+
+use crate::gl::CoordinateReferenceSystemRef;
+use crate::*;
+
+//=================================================================================================|
+
+/// Rotation rate of one plate, as a Cartesian vector.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlatePole {
+    /// Four-letter abbreviation, e.g. `"EURA"`.
+    pub plate: &'static str,
+
+    pub name: &'static str,
+
+    /// Milliarcseconds per year about the X, Y and Z axes.
+    pub omega: [f64; 3],
+}
+
+/// A set of plate rotation poles in some terrestrial reference frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlateMotionModel {
+    pub name: &'static str,
+    pub poles: &'static [PlatePole],
+
+    /// Velocity added to every point for the motion of the frame's origin, millimeters per year.
+    pub origin_rate_bias: [f64; 3],
+}
+
+/// From Altamimi et al. (2017), "ITRF2014 plate motion model", Geophys. J. Int. 209(3),
+/// table 1.
+pub const ITRF2014_PMM: PlateMotionModel = PlateMotionModel {
+    name: "ITRF2014-PMM",
+    #[rustfmt::skip]
+    poles: &[
+        PlatePole { plate: "ANTA", name: "Antarctica", omega: [-0.248, -0.324, 0.675] },
+        PlatePole { plate: "ARAB", name: "Arabia", omega: [1.154, -0.136, 1.444] },
+        PlatePole { plate: "AUST", name: "Australia", omega: [1.510, 1.182, 1.215] },
+        PlatePole { plate: "EURA", name: "Eurasia", omega: [-0.085, -0.531, 0.770] },
+        PlatePole { plate: "INDI", name: "India", omega: [1.154, -0.005, 1.454] },
+        PlatePole { plate: "NAZC", name: "Nazca", omega: [-0.333, -1.544, 1.623] },
+        PlatePole { plate: "NOAM", name: "North America", omega: [0.024, -0.694, -0.063] },
+        PlatePole { plate: "NUBI", name: "Nubia", omega: [0.099, -0.614, 0.733] },
+        PlatePole { plate: "PCFC", name: "Pacific", omega: [-0.409, 1.047, -2.169] },
+        PlatePole { plate: "SOAM", name: "South America", omega: [-0.270, -0.301, -0.140] },
+        PlatePole { plate: "SOMA", name: "Somalia", omega: [-0.121, -0.794, 0.884] },
+    ],
+    origin_rate_bias: [0.37, 0.35, 0.74],
+};
+
+impl PlateMotionModel {
+    pub fn pole(&self, plate: &str) -> Result<&PlatePole> {
+        self.poles
+            .iter()
+            .find(|pole| pole.plate == plate)
+            .with_context(|| format!("{} has no plate {plate:?}", self.name))
+    }
+
+    /// Velocity in meters per year of an ECEF point on `plate`.
+    pub fn velocity(&self, plate: &str, ecef: Vector3) -> Result<Vector3> {
+        let omega = Vector3::from(
+            self.pole(plate)?
+                .omega
+                .map(|w| (w * 1e-3 / 3600.0).to_radians()),
+        );
+        let origin_rate_bias = Vector3::from(self.origin_rate_bias.map(|v| v * 1e-3));
+        Ok(omega.cross(ecef) + origin_rate_bias)
+    }
+}
+
+//=================================================================================================|
+
+/// Component: an ECEF position at a coordinate epoch, which a dynamic CRS needs to pin a
+/// moving point down.
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[display("EpochCoordinate {{ {ecef:?} at {epoch} }}")]
+pub struct EpochCoordinate {
+    pub ecef: Vector3,
+
+    /// Decimal year.
+    pub epoch: f64,
+}
+
+impl EpochCoordinate {
+    /// The position at another epoch, moving at a constant `velocity` in meters per year.
+    pub fn propagate(&self, velocity: Vector3, epoch: f64) -> Self {
+        Self {
+            ecef: self.ecef + velocity * (epoch - self.epoch),
+            epoch,
+        }
+    }
+}
+
+/// Component: a station's velocity in meters per year, in the same CRS as its
+/// [`EpochCoordinate`].
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[display("StationVelocity({_0:?})")]
+pub struct StationVelocity(pub Vector3);
+
+/// [`hecs::Bundle`] for a ground station in the [`hecs::World`].
+#[derive(Bundle, Clone, Debug, Display)]
+#[display("Station {{ Name({:?}), {coord}, {velocity} }}", name.as_str())]
+pub struct Station {
+    pub name: Name,
+    pub crs: CoordinateReferenceSystemRef,
+    pub coord: EpochCoordinate,
+    pub velocity: StationVelocity,
+}
+
+pub fn ecs_add_station(
+    world: &mut World,
+    e_ns_parent: Entity,
+    name: &str,
+    crs: CoordinateReferenceSystemRef,
+    coord: EpochCoordinate,
+    velocity: StationVelocity,
+) -> Result<Entity> {
+    ecs_add(
+        world,
+        e_ns_parent,
+        name,
+        Station {
+            name: name.into(),
+            crs,
+            coord,
+            velocity,
+        },
+    )
+}
+
+/// The position of a station at `epoch`.
+pub fn station_at_epoch(world: &World, e_station: Entity, epoch: f64) -> Result<EpochCoordinate> {
+    let coord = *world
+        .get::<&EpochCoordinate>(e_station)
+        .context("EpochCoordinate")?;
+    let velocity = world
+        .get::<&StationVelocity>(e_station)
+        .context("StationVelocity")?
+        .0;
+    Ok(coord.propagate(velocity, epoch))
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use crate::gis::{lookup_epsg, Geodetic, LocalEnuFrame, OblateSpheroidParams};
+    use cgmath::InnerSpace;
+    use insta::assert_ron_snapshot;
+
+    /// East, north and up, millimeters per year.
+    fn enu_mm(ecef: Vector3, velocity: Vector3) -> [f64; 3] {
+        let grs80 = OblateSpheroidParams::new(6_378_137.0, 1.0 / 298.257_222_101);
+        let enu = LocalEnuFrame::new(&grs80, grs80.ecef_to_geodetic(ecef));
+        let v = enu.ecef_to_enu * velocity;
+        [v.x, v.y, v.z].map(|x| (x * 1e4).round() / 10.0)
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let mut world = World::default();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+        let ns = ecs_ns_find_or_create_gis(world)?;
+
+        let grs80 = OblateSpheroidParams::new(6_378_137.0, 1.0 / 298.257_222_101);
+        let potsdam = grs80.geodetic_to_ecef(&Geodetic::from_degrees(52.38, 13.07, 144.0));
+        let canberra = grs80.geodetic_to_ecef(&Geodetic::from_degrees(-35.4, 148.98, 689.0));
+
+        let v = ITRF2014_PMM.velocity("EURA", potsdam)?;
+        assert_ron_snapshot!(enu_mm(potsdam, v), @"(19.8, 15.5, 0.9)");
+        let v = ITRF2014_PMM.velocity("AUST", canberra)?;
+        assert_ron_snapshot!(enu_mm(canberra, v), @"(18.0, 55.9, -0.7)");
+
+        // Surveyed in 1994, compared in 2024.
+        let e_crs = lookup_epsg(world, 7789)?;
+        let crs = CoordinateReferenceSystemRef::new(e_crs, world);
+        let coord = EpochCoordinate {
+            ecef: canberra,
+            epoch: 1994.0,
+        };
+        let e = ecs_add_station(world, ns, "Canberra", crs, coord, StationVelocity(v))?;
+        let moved = station_at_epoch(world, e, 2024.0)?;
+        assert_eq!(moved.epoch, 2024.0);
+        assert_ron_snapshot!(((moved.ecef - canberra).magnitude() * 1e3).round() / 1e3, @"1.76");
+        assert_eq!(moved.propagate(v, 1994.0).ecef, coord.ecef);
+
+        assert!(ITRF2014_PMM.velocity("ATLA", canberra).is_err());
+
+        Ok(())
+    }
+}
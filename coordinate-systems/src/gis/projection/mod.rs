@@ -0,0 +1,451 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Map projections from an ellipsoid to the plane, forward and inverse, and as coordinate
+//! systems in the ECS. A [`MapProjection`] component on a [`CoordinateSystem`] entity says how
+//! its easting and northing relate to latitude and longitude, which is what a 2D map view needs.
+//!
+//! Angles are in radians. Eastings, northings and distances are in the unit of the ellipsoid's
+//! equatorial radius. Heights are ignored by the forward projections and are zero from the
+//! inverse ones.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use derive_more::Display;
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+use hecs::{Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+//? use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use cgmath::{InnerSpace, Matrix};
+
+use crate::gis::geodesic::Geodesic;
+use crate::gis::geodetic::{Geodetic, OblateSpheroidParams};
+use crate::gis::topocentric::LocalEnuFrame;
+use crate::gl::{Axis, CoordinateSystemDef, CsType, CsUnit};
+use crate::*;
+
+pub mod utm;
+pub use crate::gis::projection::utm::{TransverseMercatorParams, UtmZone};
+
+//=================================================================================================|
+
+/// Reduces a longitude to `[-π, π)`.
+pub(crate) fn wrap_lon(lon: f64) -> f64 {
+    (lon + PI).rem_euclid(TAU) - PI
+}
+
+/// Distance along the meridian from the equator to latitude `lat`, by Helmert's series in the
+/// third flattening.
+pub fn meridian_arc(ellipsoid: &OblateSpheroidParams, lat: f64) -> f64 {
+    let n = ellipsoid.f / (2.0 - ellipsoid.f);
+    let (n2, n3) = (n * n, n * n * n);
+    let n4 = n2 * n2;
+    ellipsoid.a / (1.0 + n)
+        * ((1.0 + n2 / 4.0 + n4 / 64.0) * lat - 1.5 * (n - n3 / 8.0) * (2.0 * lat).sin()
+            + 15.0 / 16.0 * (n2 - n4 / 4.0) * (4.0 * lat).sin()
+            - 35.0 / 48.0 * n3 * (6.0 * lat).sin()
+            + 315.0 / 512.0 * n4 * (8.0 * lat).sin())
+}
+
+//=================================================================================================|
+
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum Projection {
+    /// Equidistant cylindrical, EPSG method 1028. Scale is true along the meridians and along
+    /// the standard parallels at ±`lat_ts`.
+    #[display("Equirectangular {{ lat_ts: {lat_ts}, lon0: {lon0} }}")]
+    Equirectangular { lat_ts: f64, lon0: f64 },
+
+    /// Mercator (variant A), EPSG method 9804. Conformal, with scale `k0` on the equator.
+    #[display("Mercator {{ lon0: {lon0}, k0: {k0} }}")]
+    Mercator { lon0: f64, k0: f64 },
+
+    /// Transverse Mercator, EPSG method 9807, of which UTM is a family.
+    #[display("TransverseMercator {_0:?}")]
+    TransverseMercator(TransverseMercatorParams),
+
+    /// Distance and azimuth from `center` are true, by way of the geodesics. Good for "what's
+    /// how far from me" views centred on an observer. The point antipodal to the center is a
+    /// circle, so the inverse is only unique inside it.
+    #[display("AzimuthalEquidistant {{ center: {center:?} }}")]
+    AzimuthalEquidistant { center: Geodetic },
+
+    /// The view from infinitely far above `center`, along its ellipsoid normal, EPSG method
+    /// 9840. Only the near side of the ellipsoid is in the projection.
+    #[display("Orthographic {{ center: {center:?} }}")]
+    Orthographic { center: Geodetic },
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// A projection of a particular ellipsoid. As a component, this goes on the entity of the
+/// projected [`CoordinateSystem`].
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[display("MapProjection {{ {projection} }}")]
+pub struct MapProjection {
+    pub ellipsoid: OblateSpheroidParams,
+    pub projection: Projection,
+}
+
+impl MapProjection {
+    pub fn new(ellipsoid: OblateSpheroidParams, projection: Projection) -> Self {
+        Self {
+            ellipsoid,
+            projection,
+        }
+    }
+
+    pub fn utm(ellipsoid: OblateSpheroidParams, zone: UtmZone) -> Self {
+        Self::new(
+            ellipsoid,
+            Projection::TransverseMercator(zone.transverse_mercator()),
+        )
+    }
+
+    /// Easting and northing of a point on the ellipsoid.
+    pub fn forward(&self, g: &Geodetic) -> Result<Vector2> {
+        use Projection::*;
+        let ell = &self.ellipsoid;
+        match self.projection {
+            Equirectangular { lat_ts, lon0 } => {
+                let dlon = wrap_lon(g.lon - lon0);
+                let x = ell.prime_vertical_radius(lat_ts) * lat_ts.cos() * dlon;
+                Ok(Vector2::new(x, meridian_arc(ell, g.lat)))
+            }
+            Mercator { lon0, k0 } => {
+                ensure!(
+                    g.lat.abs() < FRAC_PI_2,
+                    "The poles are at infinity in the Mercator projection"
+                );
+                let e = ell.e2.sqrt();
+                let psi = g.lat.tan().asinh() - e * (e * g.lat.sin()).atanh();
+                Ok(Vector2::new(wrap_lon(g.lon - lon0), psi) * (ell.a * k0))
+            }
+            TransverseMercator(tm) => tm.forward(ell, g),
+            AzimuthalEquidistant { center } => {
+                let inv = Geodesic::new(ell).inverse(
+                    center.lat.to_degrees(),
+                    center.lon.to_degrees(),
+                    g.lat.to_degrees(),
+                    g.lon.to_degrees(),
+                );
+                let (sin_azi, cos_azi) = inv.azi1.to_radians().sin_cos();
+                Ok(Vector2::new(sin_azi, cos_azi) * inv.s12)
+            }
+            Orthographic { center } => {
+                let frame = LocalEnuFrame::new(ell, Geodetic { h: 0.0, ..center });
+                let p = ell.geodetic_to_ecef(&Geodetic { h: 0.0, ..*g });
+                let up = frame.ecef_to_enu.transpose().z;
+                // The gradient of the ellipsoid's implicit equation is its outward normal.
+                let normal = Vector3::new(p.x, p.y, p.z * ell.a * ell.a / (ell.b * ell.b));
+                ensure!(
+                    0.0 <= normal.dot(up),
+                    "Latitude {:.6}°, longitude {:.6}° is on the far side of the orthographic projection",
+                    g.lat.to_degrees(),
+                    g.lon.to_degrees()
+                );
+                let enu = frame.ecef_to_enu(p);
+                Ok(Vector2::new(enu.x, enu.y))
+            }
+        }
+    }
+
+    /// The point on the ellipsoid with an easting and northing.
+    pub fn inverse(&self, en: Vector2) -> Result<Geodetic> {
+        use Projection::*;
+        let ell = &self.ellipsoid;
+        let g = match self.projection {
+            Equirectangular { lat_ts, lon0 } => {
+                let quarter_meridian = meridian_arc(ell, FRAC_PI_2);
+                ensure!(
+                    en.y.abs() <= quarter_meridian,
+                    "Northing {} is beyond the pole",
+                    en.y
+                );
+                let mut lat = en.y / quarter_meridian * FRAC_PI_2;
+                for _ in 0..10 {
+                    let dlat = (meridian_arc(ell, lat) - en.y) / ell.meridional_radius(lat);
+                    lat -= dlat;
+                    if dlat.abs() < 1e-15 {
+                        break;
+                    }
+                }
+                let dlon = en.x / (ell.prime_vertical_radius(lat_ts) * lat_ts.cos());
+                Geodetic {
+                    lat: lat.clamp(-FRAC_PI_2, FRAC_PI_2),
+                    lon: wrap_lon(lon0 + dlon),
+                    h: 0.0,
+                }
+            }
+            Mercator { lon0, k0 } => {
+                let e = ell.e2.sqrt();
+                let psi = en.y / (ell.a * k0);
+                // Fixed-point iteration on the isometric latitude, which converges by a factor
+                // of about e² each time.
+                let mut lat = psi.sinh().atan();
+                for _ in 0..30 {
+                    let next = (psi + e * (e * lat.sin()).atanh()).sinh().atan();
+                    let done = (next - lat).abs() < 1e-15;
+                    lat = next;
+                    if done {
+                        break;
+                    }
+                }
+                Geodetic {
+                    lat,
+                    lon: wrap_lon(lon0 + en.x / (ell.a * k0)),
+                    h: 0.0,
+                }
+            }
+            TransverseMercator(tm) => tm.inverse(ell, en)?,
+            AzimuthalEquidistant { center } => {
+                let azi = en.x.atan2(en.y).to_degrees();
+                let pos = Geodesic::new(ell).direct(
+                    center.lat.to_degrees(),
+                    center.lon.to_degrees(),
+                    azi,
+                    en.magnitude(),
+                );
+                Geodetic::from_degrees(pos.lat2, pos.lon2, 0.0)
+            }
+            Orthographic { center } => {
+                let frame = LocalEnuFrame::new(ell, Geodetic { h: 0.0, ..center });
+                let q = frame.enu_to_ecef(Vector3::new(en.x, en.y, 0.0));
+                let up = frame.ecef_to_enu.transpose().z;
+
+                // Scale z to make the ellipsoid a sphere of radius a, then intersect the line
+                // q + t·up with it, taking the intersection nearer the viewer.
+                let squash = |v: Vector3| Vector3::new(v.x, v.y, v.z * ell.a / ell.b);
+                let (q_s, up_s) = (squash(q), squash(up));
+                let (a, b, c) = (
+                    up_s.magnitude2(),
+                    2.0 * q_s.dot(up_s),
+                    q_s.magnitude2() - ell.a * ell.a,
+                );
+                let discriminant = b * b - 4.0 * a * c;
+                ensure!(
+                    0.0 <= discriminant,
+                    "({}, {}) is outside the disk of the orthographic projection",
+                    en.x,
+                    en.y
+                );
+                let t = (-b + discriminant.sqrt()) / (2.0 * a);
+                Geodetic {
+                    h: 0.0,
+                    ..ell.ecef_to_geodetic(q + up * t)
+                }
+            }
+        };
+        ensure!(
+            g.lat.is_finite() && g.lon.is_finite(),
+            "Not a point of the {} projection: {en:?}",
+            self.projection
+        );
+        Ok(g)
+    }
+
+    /// The 2D Cartesian coordinate system of a projected CRS.
+    pub fn cs_def() -> CoordinateSystemDef {
+        let axis = |name: &str, direction: &str| Axis {
+            name: name.into(),
+            direction: direction.into(),
+            unit: Some(CsUnit::metre()),
+            min_value: None,
+            max_value: None,
+        };
+        CoordinateSystemDef {
+            cs_type: CsType::Cartesian,
+            axes: vec![axis("easting (E)", "east"), axis("northing (N)", "north")],
+        }
+    }
+}
+
+//=================================================================================================|
+
+/// Adds a 2D Cartesian [`CoordinateSystem`] with the [`MapProjection`] as an extra component.
+pub fn ecs_add_map_projection(
+    world: &mut World,
+    e_ns_parent: Entity,
+    name: &str,
+    map_projection: MapProjection,
+) -> Result<Entity> {
+    let e = ecs_add_cs(world, e_ns_parent, name, MapProjection::cs_def())?;
+    world.insert_one(e, map_projection)?;
+    Ok(e)
+}
+
+/// Adds the projection for a UTM zone, named e.g. `UTM zone 33N`.
+pub fn ecs_add_utm(
+    world: &mut World,
+    e_ns_parent: Entity,
+    ellipsoid: OblateSpheroidParams,
+    zone: UtmZone,
+) -> Result<Entity> {
+    ecs_add_map_projection(
+        world,
+        e_ns_parent,
+        &zone.to_string(),
+        MapProjection::utm(ellipsoid, zone),
+    )
+}
+
+pub(crate) fn ecs_add_stuff(world: &mut World, ns_gis: Entity) -> Result<()> {
+    let ns = ecs_ns_find_or_create(world, NamePathSpec::relative(ns_gis, ["projections"]))?;
+
+    let wgs84 = OblateSpheroidParams::WGS84;
+
+    // The projections of EPSG 4087 "WGS 84 / World Equidistant Cylindrical" and 3395
+    // "WGS 84 / World Mercator".
+    #[rustfmt::skip]
+    let defs = [
+        ("World Equidistant Cylindrical", Projection::Equirectangular { lat_ts: 0.0, lon0: 0.0 }),
+        ("World Mercator", Projection::Mercator { lon0: 0.0, k0: 1.0 }),
+    ];
+    for (name, projection) in defs {
+        ecs_add_map_projection(world, ns, name, MapProjection::new(wgs84, projection))?;
+    }
+
+    Ok(())
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    const WGS84: OblateSpheroidParams = OblateSpheroidParams::WGS84;
+
+    fn r(x: f64) -> f64 {
+        (x * 1e3).round() / 1e3 + 0.0
+    }
+
+    /// Distance in meters between two points on the ellipsoid surface, for small differences.
+    fn err(a: &Geodetic, b: &Geodetic) -> f64 {
+        WGS84.a * (a.lat - b.lat).hypot(wrap_lon(a.lon - b.lon) * a.lat.cos())
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        // Quarter meridian of WGS 84.
+        assert_ron_snapshot!(r(meridian_arc(&WGS84, FRAC_PI_2)), @"10001965.729");
+
+        let seattle = Geodetic::from_degrees(47.6, -122.3, 0.0);
+        let projections = [
+            Projection::Equirectangular {
+                lat_ts: 0.0,
+                lon0: 0.0,
+            },
+            Projection::Equirectangular {
+                lat_ts: 45f64.to_radians(),
+                lon0: -100f64.to_radians(),
+            },
+            Projection::Mercator { lon0: 0.0, k0: 1.0 },
+            Projection::TransverseMercator(UtmZone::containing(&seattle)?.transverse_mercator()),
+            Projection::AzimuthalEquidistant { center: seattle },
+            Projection::Orthographic { center: seattle },
+        ];
+        let points = [
+            (47.6, -122.3),
+            (45.0, -120.0),
+            (49.0, -123.5),
+            (37.8, -122.4),
+            (61.2, -149.9),
+            (-0.5, -125.0),
+        ];
+        for projection in projections {
+            let mp = MapProjection::new(WGS84, projection);
+            for (lat, lon) in points {
+                let g = Geodetic::from_degrees(lat, lon, 0.0);
+                let back = mp.inverse(mp.forward(&g)?)?;
+                assert!(
+                    err(&g, &back) < 1e-3,
+                    "{projection}: {lat} {lon}: {} m",
+                    err(&g, &back)
+                );
+            }
+        }
+
+        // EPSG 3395 "WGS 84 / World Mercator".
+        let mercator = MapProjection::new(WGS84, Projection::Mercator { lon0: 0.0, k0: 1.0 });
+        let en = mercator.forward(&Geodetic::from_degrees(45.0, 90.0, 0.0))?;
+        assert_ron_snapshot!((r(en.x), r(en.y)), @"(10018754.171, 5591295.919)");
+        assert!(mercator
+            .forward(&Geodetic::from_degrees(90.0, 0.0, 0.0))
+            .is_err());
+
+        // Distances and azimuths from the center are true in the azimuthal equidistant.
+        let aeqd = MapProjection::new(WGS84, Projection::AzimuthalEquidistant { center: seattle });
+        let honolulu = Geodetic::from_degrees(21.3, -157.9, 0.0);
+        let en = aeqd.forward(&honolulu)?;
+        let inv = Geodesic::new(&WGS84).inverse(47.6, -122.3, 21.3, -157.9);
+        assert!((en.magnitude() - inv.s12).abs() < 1e-6);
+        assert_ron_snapshot!((r(en.magnitude() / 1e3), r(en.x.atan2(en.y).to_degrees())), @"(4317.611, -119.981)");
+        // A point due north is straight up the map, and far away points still come back.
+        let en = aeqd.forward(&Geodetic::from_degrees(80.0, -122.3, 0.0))?;
+        assert!(en.x.abs() < 1e-6 && 0.0 < en.y);
+        let sydney = Geodetic::from_degrees(-33.9, 151.2, 0.0);
+        assert!(err(&sydney, &aeqd.inverse(aeqd.forward(&sydney)?)?) < 1e-6);
+
+        // The orthographic sees only the near side.
+        let ortho = MapProjection::new(WGS84, Projection::Orthographic { center: seattle });
+        let en = ortho.forward(&seattle)?;
+        assert!(en.magnitude() < 1e-6);
+        assert!(ortho
+            .forward(&Geodetic::from_degrees(-47.6, 57.7, 0.0))
+            .is_err());
+        assert!(ortho.inverse(Vector2::new(7e6, 0.0)).is_err());
+
+        // As coordinate systems in the ECS.
+        let mut world = World::new();
+        crate::ecs_add_stuff(&mut world);
+        let e = ecs_ns_find_or_create(
+            &mut world,
+            NamePathSpec::absolute(["gis", "projections", "World Mercator"]),
+        )?;
+        assert_eq!(*world.get::<&MapProjection>(e)?, mercator);
+        assert_ron_snapshot!(world.get::<&CoordinateSystemDef>(e)?.to_string(), @r#""CoordinateSystemDef { Cartesian, 2 axes }""#);
+
+        let ns = ecs_ns_find_or_create(&mut world, NamePathSpec::absolute(["gis", "projections"]))?;
+        let e = ecs_add_utm(&mut world, ns, WGS84, UtmZone::new(10, true)?)?;
+        assert_ron_snapshot!(world.get::<&Name>(e)?.as_str(), @r#""UTM zone 10N""#);
+        let en = world.get::<&MapProjection>(e)?.forward(&seattle)?;
+        assert_ron_snapshot!((r(en.x), r(en.y)), @"(552619.097, 5272080.811)");
+
+        Ok(())
+    }
+}
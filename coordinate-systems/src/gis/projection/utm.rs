@@ -0,0 +1,332 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! The transverse Mercator projection by Krüger's series in the third flattening, to n³, which
+//! is good to better than a millimetre within a few thousand kilometres of the central meridian.
+//! The formulas are those in the Wikipedia article "Universal Transverse Mercator coordinate
+//! system", after Karney (2011), "Transverse Mercator with an accuracy of a few nanometers".
+//! And the UTM zones, with their exceptions around Norway and Svalbard.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use derive_more::Display;
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+//? use hecs::{Bundle, Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+//? use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use std::f64::consts::FRAC_PI_2;
+
+use crate::gis::geodetic::{Geodetic, OblateSpheroidParams};
+use crate::gis::projection::wrap_lon;
+use crate::*;
+
+//=================================================================================================|
+
+/// Parameters of a transverse Mercator projection, EPSG method 9807. Angles in radians,
+/// lengths in the unit of the ellipsoid's equatorial radius.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransverseMercatorParams {
+    /// Latitude of natural origin.
+    pub lat0: f64,
+
+    /// Central meridian.
+    pub lon0: f64,
+
+    /// Scale factor on the central meridian.
+    pub k0: f64,
+
+    pub false_easting: f64,
+    pub false_northing: f64,
+}
+
+/// The series coefficients, which depend only on the ellipsoid.
+struct Kruger {
+    /// Radius of the rectifying sphere, a quarter meridian over π/2.
+    A: f64,
+
+    /// `2√n / (1 + n)`, which is the first eccentricity.
+    e: f64,
+
+    alpha: [f64; 3],
+    beta: [f64; 3],
+    delta: [f64; 3],
+}
+
+impl Kruger {
+    fn new(ellipsoid: &OblateSpheroidParams) -> Self {
+        let n = ellipsoid.f / (2.0 - ellipsoid.f);
+        let (n2, n3) = (n * n, n * n * n);
+        Self {
+            A: ellipsoid.a / (1.0 + n) * (1.0 + n2 / 4.0 + n2 * n2 / 64.0),
+            e: 2.0 * n.sqrt() / (1.0 + n),
+            alpha: [
+                n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0,
+                13.0 * n2 / 48.0 - 3.0 * n3 / 5.0,
+                61.0 * n3 / 240.0,
+            ],
+            beta: [
+                n / 2.0 - 2.0 * n2 / 3.0 + 37.0 * n3 / 96.0,
+                n2 / 48.0 + n3 / 15.0,
+                17.0 * n3 / 480.0,
+            ],
+            delta: [
+                2.0 * n - 2.0 * n2 / 3.0 - 2.0 * n3,
+                7.0 * n2 / 3.0 - 8.0 * n3 / 5.0,
+                56.0 * n3 / 15.0,
+            ],
+        }
+    }
+
+    /// Conformal latitude.
+    fn conformal(&self, lat: f64) -> f64 {
+        let sin_lat = lat.sin();
+        (sin_lat.atanh() - self.e * (self.e * sin_lat).atanh())
+            .sinh()
+            .atan()
+    }
+
+    /// `(ξ, η)` on the rectifying sphere for a longitude `dlon` from the central meridian.
+    fn forward(&self, lat: f64, dlon: f64) -> (f64, f64) {
+        let t = self.conformal(lat).tan();
+        let xi_ = t.atan2(dlon.cos());
+        let eta_ = (dlon.sin() / (1.0 + t * t).sqrt()).atanh();
+
+        let (mut xi, mut eta) = (xi_, eta_);
+        for (j, alpha) in (1..).zip(self.alpha) {
+            let j2 = 2.0 * j as f64;
+            xi += alpha * (j2 * xi_).sin() * (j2 * eta_).cosh();
+            eta += alpha * (j2 * xi_).cos() * (j2 * eta_).sinh();
+        }
+        (xi, eta)
+    }
+
+    /// `(lat, dlon)` from `(ξ, η)`.
+    fn inverse(&self, xi: f64, eta: f64) -> (f64, f64) {
+        let (mut xi_, mut eta_) = (xi, eta);
+        for (j, beta) in (1..).zip(self.beta) {
+            let j2 = 2.0 * j as f64;
+            xi_ -= beta * (j2 * xi).sin() * (j2 * eta).cosh();
+            eta_ -= beta * (j2 * xi).cos() * (j2 * eta).sinh();
+        }
+
+        let chi = (xi_.sin() / eta_.cosh()).asin();
+        let mut lat = chi;
+        for (j, delta) in (1..).zip(self.delta) {
+            lat += delta * (2.0 * j as f64 * chi).sin();
+        }
+        (lat, eta_.sinh().atan2(xi_.cos()))
+    }
+}
+
+impl TransverseMercatorParams {
+    /// Easting and northing of a point. Errors beyond 90° of longitude from the central
+    /// meridian, where the projection goes to infinity, but the series loses accuracy long
+    /// before then.
+    pub fn forward(&self, ellipsoid: &OblateSpheroidParams, g: &Geodetic) -> Result<Vector2> {
+        let dlon = wrap_lon(g.lon - self.lon0);
+        ensure!(
+            dlon.abs() < FRAC_PI_2,
+            "Longitude {:.6}° is 90° or more from the central meridian {:.6}°",
+            g.lon.to_degrees(),
+            self.lon0.to_degrees()
+        );
+
+        let kruger = Kruger::new(ellipsoid);
+        let (xi0, _) = kruger.forward(self.lat0, 0.0);
+        let (xi, eta) = kruger.forward(g.lat, dlon);
+        let k0_A = self.k0 * kruger.A;
+        Ok(Vector2::new(
+            self.false_easting + k0_A * eta,
+            self.false_northing + k0_A * (xi - xi0),
+        ))
+    }
+
+    pub fn inverse(&self, ellipsoid: &OblateSpheroidParams, en: Vector2) -> Result<Geodetic> {
+        let kruger = Kruger::new(ellipsoid);
+        let (xi0, _) = kruger.forward(self.lat0, 0.0);
+        let k0_A = self.k0 * kruger.A;
+        let xi = (en.y - self.false_northing) / k0_A + xi0;
+        let eta = (en.x - self.false_easting) / k0_A;
+        ensure!(
+            xi.is_finite() && eta.is_finite(),
+            "Not a point of the projection: {en:?}"
+        );
+
+        let (lat, dlon) = kruger.inverse(xi, eta);
+        Ok(Geodetic {
+            lat,
+            lon: wrap_lon(self.lon0 + dlon),
+            h: 0.0,
+        })
+    }
+}
+
+//=================================================================================================|
+
+/// A zone of the Universal Transverse Mercator system: 6° of longitude, and a hemisphere.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+#[display("UTM zone {zone}{}", if *north { "N" } else { "S" })]
+pub struct UtmZone {
+    /// 1 through 60, eastward from 180°.
+    pub zone: u8,
+    pub north: bool,
+}
+
+impl UtmZone {
+    pub const K0: f64 = 0.9996;
+    pub const FALSE_EASTING: f64 = 500_000.0;
+
+    /// Of the southern hemisphere zones.
+    pub const FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+    pub fn new(zone: u8, north: bool) -> Result<Self> {
+        ensure!((1..=60).contains(&zone), "No UTM zone {zone}");
+        Ok(Self { zone, north })
+    }
+
+    /// The zone in which UTM places a point, including the wider zones 32V over southwest
+    /// Norway and 31X, 33X, 35X and 37X over Svalbard. UTM stops at 80° S and 84° N; UPS
+    /// covers the poles.
+    pub fn containing(g: &Geodetic) -> Result<Self> {
+        let lat = g.lat.to_degrees();
+        let lon = wrap_lon(g.lon).to_degrees();
+        ensure!(
+            (-80.0..=84.0).contains(&lat),
+            "Latitude {lat:.6}° is outside UTM, which covers 80° S to 84° N"
+        );
+
+        let mut zone = (((lon + 180.0) / 6.0).floor() as u8 + 1).min(60);
+        if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+            zone = 32;
+        } else if 72.0 <= lat && (0.0..42.0).contains(&lon) {
+            zone = match lon {
+                lon if lon < 9.0 => 31,
+                lon if lon < 21.0 => 33,
+                lon if lon < 33.0 => 35,
+                _ => 37,
+            };
+        }
+        Ok(Self {
+            zone,
+            north: 0.0 <= lat,
+        })
+    }
+
+    /// In radians.
+    pub fn central_meridian(&self) -> f64 {
+        (6.0 * self.zone as f64 - 183.0).to_radians()
+    }
+
+    pub fn transverse_mercator(&self) -> TransverseMercatorParams {
+        TransverseMercatorParams {
+            lat0: 0.0,
+            lon0: self.central_meridian(),
+            k0: Self::K0,
+            false_easting: Self::FALSE_EASTING,
+            false_northing: if self.north {
+                0.0
+            } else {
+                Self::FALSE_NORTHING_SOUTH
+            },
+        }
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    const WGS84: OblateSpheroidParams = OblateSpheroidParams::WGS84;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let zone = |lat, lon| {
+            UtmZone::containing(&Geodetic::from_degrees(lat, lon, 0.0)).map(|z| z.to_string())
+        };
+        assert_ron_snapshot!(zone(47.6, -122.3)?, @r#""UTM zone 10N""#);
+        assert_ron_snapshot!(zone(-33.9, 151.2)?, @r#""UTM zone 56S""#);
+        assert_ron_snapshot!(zone(60.4, 5.3)?, @r#""UTM zone 32N""#);
+        assert_ron_snapshot!(zone(78.2, 15.6)?, @r#""UTM zone 33N""#);
+        assert_ron_snapshot!(zone(0.0, 180.0)?, @r#""UTM zone 1N""#);
+        assert!(zone(84.5, 0.0).is_err());
+        assert!(UtmZone::new(61, true).is_err());
+
+        // From the GeoConvert documentation: 33.3° N 44.4° E is 38N 444140.54 3684706.36.
+        let g = Geodetic::from_degrees(33.3, 44.4, 0.0);
+        let tm = UtmZone::containing(&g)?.transverse_mercator();
+        let en = tm.forward(&WGS84, &g)?;
+        let r = |x: f64| (x * 100.0).round() / 100.0;
+        assert_ron_snapshot!((r(en.x), r(en.y)), @"(444140.54, 3684706.36)");
+
+        // Round trips, north and south, near and far from the central meridian.
+        for (lat, lon) in [
+            (33.3, 44.4),
+            (-45.0, 170.9),
+            (0.0, 3.0),
+            (83.9, -60.0),
+            (-79.9, 8.0),
+            (10.0, 30.0),
+        ] {
+            let g = Geodetic::from_degrees(lat, lon, 0.0);
+            let tm = UtmZone::containing(&g)?.transverse_mercator();
+            let back = tm.inverse(&WGS84, tm.forward(&WGS84, &g)?)?;
+            let err = WGS84.a * (back.lat - g.lat).hypot((back.lon - g.lon) * g.lat.cos());
+            assert!(err < 1e-3, "{lat} {lon}: {err} m");
+        }
+
+        // Outside the zone, the series still works some way out, but not at 90°.
+        let tm = UtmZone::new(31, true)?.transverse_mercator();
+        assert!(tm
+            .forward(&WGS84, &Geodetic::from_degrees(0.0, 93.0, 0.0))
+            .is_err());
+
+        // A nonzero latitude of origin: British National Grid on the Airy ellipsoid, whose
+        // true origin 49° N 2° W is at (400000, -100000).
+        let airy = OblateSpheroidParams::new(6_377_563.396, 1.0 / 299.324_964_6);
+        let bng = TransverseMercatorParams {
+            lat0: 49f64.to_radians(),
+            lon0: -2f64.to_radians(),
+            k0: 0.999_601_271_7,
+            false_easting: 400_000.0,
+            false_northing: -100_000.0,
+        };
+        let en = bng.forward(&airy, &Geodetic::from_degrees(49.0, -2.0, 0.0))?;
+        assert_ron_snapshot!((r(en.x), r(en.y)), @"(400000.0, -100000.0)");
+
+        Ok(())
+    }
+}
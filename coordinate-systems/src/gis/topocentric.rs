@@ -0,0 +1,213 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+use std::f64::consts::TAU;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+//? use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::{Deref, DerefMut, Display, From, Into};
+//? use enumflags2::{bitflags, make_bitflags, BitFlags};
+//? use hecs::{Bundle, Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+//? use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use cgmath::{InnerSpace, Matrix};
+
+use crate::gis::geodetic::{Geodetic, OblateSpheroidParams};
+use crate::*;
+
+//=================================================================================================|
+
+/// The local East-North-Up frame of an observer on an ellipsoid. Up is along the ellipsoid
+/// normal, so it differs from the geocentric radial direction by up to about 0.19° on WGS 84.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocalEnuFrame {
+    pub origin: Geodetic,
+
+    /// ECEF position of the origin.
+    pub origin_ecef: Vector3,
+
+    /// Rotation taking ECEF vectors to ENU. Its rows are the east, north and up unit vectors.
+    pub ecef_to_enu: Matrix3,
+}
+
+impl LocalEnuFrame {
+    pub fn new(ellipsoid: &OblateSpheroidParams, origin: Geodetic) -> Self {
+        let (sin_lat, cos_lat) = origin.lat.sin_cos();
+        let (sin_lon, cos_lon) = origin.lon.sin_cos();
+
+        // cgmath matrices are column-major, so build the transpose from the rows.
+        let east = Vector3::new(-sin_lon, cos_lon, 0.0);
+        let north = Vector3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat);
+        let up = Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat);
+        let ecef_to_enu = Matrix3::from_cols(east, north, up).transpose();
+
+        Self {
+            origin,
+            origin_ecef: ellipsoid.geodetic_to_ecef(&origin),
+            ecef_to_enu,
+        }
+    }
+
+    /// ENU coordinates of an ECEF point.
+    pub fn ecef_to_enu(&self, ecef: Vector3) -> Vector3 {
+        self.ecef_to_enu * (ecef - self.origin_ecef)
+    }
+
+    /// ECEF coordinates of an ENU point.
+    pub fn enu_to_ecef(&self, enu: Vector3) -> Vector3 {
+        self.origin_ecef + self.ecef_to_enu.transpose() * enu
+    }
+
+    pub fn ecef_to_aer(&self, ecef: Vector3) -> Aer {
+        Aer::from_enu(self.ecef_to_enu(ecef))
+    }
+
+    pub fn aer_to_ecef(&self, aer: &Aer) -> Vector3 {
+        self.enu_to_ecef(aer.to_enu())
+    }
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// Azimuth (radians clockwise from north, `[0, τ)`), elevation above the local horizontal
+/// plane (radians), and slant range.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Aer {
+    pub az: f64,
+    pub el: f64,
+    pub range: f64,
+}
+
+impl Aer {
+    /// Azimuth is zero straight up or down, where it's undefined.
+    pub fn from_enu(enu: Vector3) -> Self {
+        let Vector3 { x: e, y: n, z: u } = enu;
+        let horizontal = e.hypot(n);
+        let az = if horizontal == 0.0 {
+            0.0
+        } else {
+            e.atan2(n).rem_euclid(TAU)
+        };
+        Self {
+            az: if az == TAU { 0.0 } else { az },
+            el: u.atan2(horizontal),
+            range: enu.magnitude(),
+        }
+    }
+
+    pub fn to_enu(&self) -> Vector3 {
+        let (sin_az, cos_az) = self.az.sin_cos();
+        let (sin_el, cos_el) = self.el.sin_cos();
+        self.range * Vector3::new(cos_el * sin_az, cos_el * cos_az, sin_el)
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    const WGS84: OblateSpheroidParams = OblateSpheroidParams::WGS84;
+
+    fn rounded(v: Vector3) -> (f64, f64, f64) {
+        let r = |x: f64| (x * 1e6).round() / 1e6 + 0.0;
+        (r(v.x), r(v.y), r(v.z))
+    }
+
+    #[test]
+    fn t() {
+        // At the equator and prime meridian, east is +y, north is +z, up is +x.
+        let frame = LocalEnuFrame::new(&WGS84, Geodetic::from_degrees(0.0, 0.0, 0.0));
+        assert_ron_snapshot!(rounded(frame.ecef_to_enu(Vector3::new(WGS84.a + 1.0, 2.0, 3.0))), @"(2.0, 3.0, 1.0)");
+
+        // At the north pole, north is toward the prime meridian's antipode.
+        let frame = LocalEnuFrame::new(&WGS84, Geodetic::from_degrees(90.0, 0.0, 0.0));
+        assert_ron_snapshot!(rounded(frame.ecef_to_enu(Vector3::new(-1000.0, 0.0, WGS84.b))), @"(0.0, 1000.0, 0.0)");
+
+        // A point 10 km up the ellipsoid normal from an observer is at the zenith.
+        let observer = Geodetic::from_degrees(47.6, -122.3, 50.0);
+        let frame = LocalEnuFrame::new(&WGS84, observer);
+        let above = WGS84.geodetic_to_ecef(&Geodetic {
+            h: observer.h + 10_000.0,
+            ..observer
+        });
+        let aer = frame.ecef_to_aer(above);
+        assert!(90.0 - 1e-9 < aer.el.to_degrees());
+        assert!((aer.range - 10_000.0).abs() < 1e-6);
+
+        // A geostationary satellite due south, seen from 47.6° N.
+        let sat = WGS84.geodetic_to_ecef(&Geodetic::from_degrees(0.0, -122.3, 35_786_000.0));
+        let aer = frame.ecef_to_aer(sat);
+        let r = |x: f64| (x * 1e3).round() / 1e3;
+        assert_ron_snapshot!((r(aer.az.to_degrees()), r(aer.el.to_degrees()), r(aer.range / 1e3)), @"(180.0, 35.342, 38144.496)");
+
+        // Round trips through ENU and AER, at the poles, equator and altitude.
+        for (lat_deg, lon_deg, h) in [
+            (90.0, 0.0, 0.0),
+            (-90.0, 45.0, 0.0),
+            (0.0, 180.0, 0.0),
+            (35.0, 139.0, 400_000.0),
+        ] {
+            let frame = LocalEnuFrame::new(&WGS84, Geodetic::from_degrees(lat_deg, lon_deg, h));
+            for aer in [
+                Aer {
+                    az: 0.0,
+                    el: 0.0,
+                    range: 1000.0,
+                },
+                Aer {
+                    az: 1.0,
+                    el: 0.5,
+                    range: 20_000_000.0,
+                },
+                Aer {
+                    az: 6.0,
+                    el: -0.7,
+                    range: 12.5,
+                },
+            ] {
+                let back = frame.ecef_to_aer(frame.aer_to_ecef(&aer));
+                assert!(
+                    (back.az - aer.az).abs() < 1e-9
+                        && (back.el - aer.el).abs() < 1e-9
+                        && (back.range - aer.range).abs() < 1e-6 * (1.0 + aer.range / 1e6),
+                    "{lat_deg} {lon_deg} {h}: {aer:?} -> {back:?}"
+                );
+                let enu_back = frame.ecef_to_enu(frame.enu_to_ecef(aer.to_enu()));
+                assert!((enu_back - aer.to_enu()).magnitude() < 1e-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,843 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Geodetic and geographic CRSs between WKT and the [`hecs::World`].
+//!
+//! Reading accepts WKT2 `GEODCRS`/`GEOGCRS` and WKT1 `GEOCCS`/`GEOGCS`. The ellipsoid,
+//! datum, coordinate system and CRS each become an entity under the given namespace parent.
+//! Elements that would carry meaning we have nowhere to put, such as datum ensembles or
+//! `TOWGS84`, are an error rather than being dropped.
+//!
+//! Writing always produces WKT2 in the single-line style of the EPSG registry.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use hecs::{Entity, World};
+use hecs_hierarchy::HierarchyMut;
+//? use log::{debug, error, info, trace, warn};
+
+use ecs_namespace::NamespaceTag;
+use crate::core::ExactReason;
+use crate::geom::Flattening;
+use crate::gis::datum::ecs_add_datum;
+use crate::gis::metadata::{AuthorityId, Usage};
+use crate::gis::wkt::{parse_wkt, WktAttr, WktNode};
+use crate::gis::{DatumDef, DatumRef, PrimeMeridian};
+use crate::gl::{Axis, CoordinateSystemDef, CoordinateSystemRef, CrsKind, CsType, CsUnit};
+use crate::*;
+
+//=================================================================================================|
+
+/// Everything read from the WKT, before any entities are made.
+struct CrsParts {
+    name: String,
+    kind: CrsKind,
+    datum: DatumParts,
+    prime_meridian: Option<PrimeMeridian>,
+    frame_epoch: Option<f64>,
+    cs: CoordinateSystemDef,
+    usage: Option<Usage>,
+    id: Option<AuthorityId>,
+}
+
+struct DatumParts {
+    name: String,
+    ellipsoid: EllipsoidParts,
+    id: Option<AuthorityId>,
+}
+
+struct EllipsoidParts {
+    name: String,
+    a: EcsNum,
+    inv_f: EcsNum,
+    unit: Option<CsUnit>,
+    id: Option<AuthorityId>,
+}
+
+//=================================================================================================|
+
+/// Reads a geodetic or geographic CRS from WKT1 or WKT2 and adds its entities under
+/// `e_ns_parent`. Returns the CRS entity.
+pub fn ecs_add_crs_from_wkt(world: &mut World, e_ns_parent: Entity, wkt: &str) -> Result<Entity> {
+    let node = parse_wkt(wkt)?;
+    let parts = match node.keyword.as_str() {
+        "GEODCRS" | "GEODETICCRS" => crs_parts_wkt2(&node, CrsKind::Geodetic),
+        "GEOGCRS" | "GEOGRAPHICCRS" => crs_parts_wkt2(&node, CrsKind::Geographic),
+        "GEOCCS" => crs_parts_wkt1(&node, CrsKind::Geodetic),
+        "GEOGCS" => crs_parts_wkt1(&node, CrsKind::Geographic),
+        kw => bail!("WKT {kw} is not a geodetic or geographic CRS"),
+    }
+    .with_context(|| format!("WKT {}", node.keyword))?;
+
+    ecs_add_crs_parts(world, e_ns_parent, parts)
+}
+
+/// Adds the ellipsoid, datum, CS and CRS, or none of them.
+fn ecs_add_crs_parts(world: &mut World, e_ns_parent: Entity, parts: CrsParts) -> Result<Entity> {
+    let mut added = vec![];
+    let result = ecs_add_crs_parts_impl(world, e_ns_parent, parts, &mut added);
+    if result.is_err() {
+        for &e in added.iter().rev() {
+            let _ = world.detach::<NamespaceTag>(e);
+            let _ = world.despawn(e);
+        }
+    }
+    result
+}
+
+fn ecs_add_crs_parts_impl(
+    world: &mut World,
+    e_ns_parent: Entity,
+    parts: CrsParts,
+    added: &mut Vec<Entity>,
+) -> Result<Entity> {
+    let ell = parts.datum.ellipsoid;
+    let def = oblatespheroid_def(ell.a, ell.inv_f)?;
+    let e_ellipsoid =
+        ecs_add_oblatespheroid::<&str, _>(world, e_ns_parent, ell.name.as_str(), def)?;
+    added.push(e_ellipsoid);
+    insert_opt(world, e_ellipsoid, ell.unit)?;
+    insert_opt(world, e_ellipsoid, ell.id)?;
+
+    let datum_def = DatumDef {
+        ellipsoid: Some(OblateSpheroidRef::new(e_ellipsoid, world)),
+        prime_meridian: parts.prime_meridian,
+        frame_epoch: parts.frame_epoch,
+    };
+    let e_datum = ecs_add_datum(world, e_ns_parent, &parts.datum.name, datum_def)?;
+    added.push(e_datum);
+    insert_opt(world, e_datum, parts.datum.id)?;
+
+    let e_cs = ecs_add_cs(world, e_ns_parent, &format!("{} CS", parts.name), parts.cs)?;
+    added.push(e_cs);
+
+    let datum = DatumRef::new(e_datum, world);
+    let cs = CoordinateSystemRef::new(e_cs, world);
+    let e_crs = ecs_add_crs(world, e_ns_parent, &parts.name, parts.kind, datum, cs)?;
+    added.push(e_crs);
+    insert_opt(world, e_crs, parts.usage)?;
+    insert_opt(world, e_crs, parts.id)?;
+
+    Ok(e_crs)
+}
+
+/// An inverse flattening of zero means a sphere, as in WKT and EPSG.
+pub(crate) fn oblatespheroid_def(a: EcsNum, inv_f: EcsNum) -> Result<OblateSpheroidDef> {
+    let f = if inv_f.to_f64()? == 0.0 {
+        Flattening::F(EcsNum::RatioU64(RatioU64::from_integer(0)))
+    } else {
+        Flattening::F_inv(inv_f)
+    };
+    Ok(OblateSpheroidDef {
+        a: DimensionedConstant {
+            name: "a".into(),
+            dimension_kind: DimensionKind::LENGTH,
+            exactness: Exactness::Exact(ExactReason::ByDefinition),
+            value: a,
+        },
+        f,
+    })
+}
+
+pub(crate) fn insert_opt<T: hecs::Component>(world: &mut World, e: Entity, opt: Option<T>) -> Result<()> {
+    if let Some(c) = opt {
+        world.insert_one(e, c).context("insert_one")?;
+    }
+    Ok(())
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+fn crs_parts_wkt2(node: &WktNode, kind: CrsKind) -> Result<CrsParts> {
+    let name = node.text(0)?.to_string();
+    let mut frame_epoch = None;
+    let mut datum = None;
+    let mut prime_meridian = None;
+    let mut cs_node = None;
+    let mut axes = vec![];
+    let mut cs_unit = None;
+    let mut usage = None;
+    let mut id = None;
+
+    for n in child_nodes(node, 1)? {
+        match n.keyword.as_str() {
+            "DYNAMIC" => set_once(&mut frame_epoch, dynamic(n)?)?,
+            "DATUM" | "TRF" | "GEODETICDATUM" => set_once(&mut datum, datum_wkt2(n)?)?,
+            "ENSEMBLE" => bail!("datum ensembles are not supported"),
+            "PRIMEM" | "PRIMEMERIDIAN" => set_once(&mut prime_meridian, primem(n, None)?)?,
+            "CS" => set_once(&mut cs_node, n)?,
+            "AXIS" => axes.push(axis_wkt2(n)?),
+            "LENGTHUNIT" | "ANGLEUNIT" | "SCALEUNIT" | "UNIT" => {
+                set_once(&mut cs_unit, unit(n, None)?)?
+            }
+            "USAGE" => set_once(&mut usage, usage_of(n)?)?,
+            "ID" => set_once(&mut id, authority_id(n)?)?,
+            kw => bail!("{kw} is not supported here"),
+        }
+    }
+
+    let cs_node = cs_node.context("no CS")?;
+    let cs_type = CsType::from_wkt_name(cs_node.enum_str(0)?)
+        .with_context(|| format!("CS type {:?}", cs_node.enum_str(0)))?;
+    let dim = cs_node.number(1)?;
+    ensure!(
+        dim == axes.len() as f64,
+        "CS has dimension {dim} but {} axes",
+        axes.len()
+    );
+    for axis in &mut axes {
+        if axis.unit.is_none() {
+            axis.unit.clone_from(&cs_unit);
+        }
+    }
+
+    Ok(CrsParts {
+        name,
+        kind,
+        datum: datum.context("no DATUM")?,
+        prime_meridian,
+        frame_epoch,
+        cs: CoordinateSystemDef { cs_type, axes },
+        usage,
+        id,
+    })
+}
+
+/// The frame reference epoch of `DYNAMIC[FRAMEEPOCH[...]]`.
+fn dynamic(n: &WktNode) -> Result<f64> {
+    let mut epoch = None;
+    for n in child_nodes(n, 0)? {
+        match n.keyword.as_str() {
+            "FRAMEEPOCH" => set_once(&mut epoch, n.number(0)?)?,
+            "MODEL" | "VELOCITYGRID" => bail!("deformation models are not supported"),
+            kw => bail!("{kw} is not supported in DYNAMIC"),
+        }
+    }
+    epoch.context("DYNAMIC has no FRAMEEPOCH")
+}
+
+fn datum_wkt2(n: &WktNode) -> Result<DatumParts> {
+    let name = n.text(0)?.to_string();
+    let mut ellipsoid = None;
+    let mut id = None;
+    for n in child_nodes(n, 1)? {
+        match n.keyword.as_str() {
+            "ELLIPSOID" | "SPHEROID" => set_once(&mut ellipsoid, ellipsoid_of(n)?)?,
+            "ID" => set_once(&mut id, authority_id(n)?)?,
+            kw => bail!("{kw} is not supported in DATUM"),
+        }
+    }
+    Ok(DatumParts {
+        name,
+        ellipsoid: ellipsoid.context("DATUM has no ELLIPSOID")?,
+        id,
+    })
+}
+
+fn axis_wkt2(n: &WktNode) -> Result<Axis> {
+    let mut unit_ = None;
+    let mut min_value = None;
+    let mut max_value = None;
+    for c in child_nodes(n, 2)? {
+        match c.keyword.as_str() {
+            // The axes are taken in the order written.
+            "ORDER" => {}
+            "LENGTHUNIT" | "ANGLEUNIT" | "SCALEUNIT" | "UNIT" => {
+                set_once(&mut unit_, unit(c, None)?)?
+            }
+            "AXISMINVALUE" => set_once(&mut min_value, c.number(0)?)?,
+            "AXISMAXVALUE" => set_once(&mut max_value, c.number(0)?)?,
+            kw => bail!("{kw} is not supported in AXIS"),
+        }
+    }
+    Ok(Axis {
+        name: n.text(0)?.to_string(),
+        direction: n.enum_str(1)?.to_string(),
+        unit: unit_,
+        min_value,
+        max_value,
+    })
+}
+
+fn usage_of(n: &WktNode) -> Result<Usage> {
+    let mut scope = None;
+    let mut area = None;
+    let mut bbox = None;
+    for n in child_nodes(n, 0)? {
+        match n.keyword.as_str() {
+            "SCOPE" => set_once(&mut scope, n.text(0)?.to_string())?,
+            "AREA" => set_once(&mut area, n.text(0)?.to_string())?,
+            "BBOX" => set_once(
+                &mut bbox,
+                [n.number(0)?, n.number(1)?, n.number(2)?, n.number(3)?],
+            )?,
+            kw => bail!("{kw} is not supported in USAGE"),
+        }
+    }
+    Ok(Usage {
+        scope: scope.context("USAGE has no SCOPE")?,
+        area,
+        bbox,
+    })
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+fn crs_parts_wkt1(node: &WktNode, kind: CrsKind) -> Result<CrsParts> {
+    let name = node.text(0)?.to_string();
+    let mut datum = None;
+    let mut prime_meridian = None;
+    let mut cs_unit = None;
+    let mut axes = vec![];
+    let mut id = None;
+
+    let unit_kind = match kind {
+        CrsKind::Geodetic => DimensionKind::LENGTH,
+        CrsKind::Geographic => DimensionKind::ANGLE,
+    };
+
+    for n in child_nodes(node, 1)? {
+        match n.keyword.as_str() {
+            "DATUM" => set_once(&mut datum, datum_wkt1(n)?)?,
+            "PRIMEM" => set_once(&mut prime_meridian, n)?,
+            "UNIT" => set_once(&mut cs_unit, unit(n, Some(unit_kind))?)?,
+            "AXIS" => axes.push((n.text(0)?, n.enum_str(1)?.to_ascii_lowercase())),
+            "AUTHORITY" => set_once(&mut id, authority_id(n)?)?,
+            kw => bail!("{kw} is not supported here"),
+        }
+    }
+    let cs_unit = cs_unit.context("no UNIT")?;
+
+    // OGC 01-009 gives these defaults when no axes are written.
+    if axes.is_empty() {
+        axes = match kind {
+            CrsKind::Geodetic => vec![("X", "other"), ("Y", "east"), ("Z", "north")],
+            CrsKind::Geographic => vec![("Lon", "east"), ("Lat", "north")],
+        }
+        .into_iter()
+        .map(|(name, dir)| (name, dir.to_string()))
+        .collect();
+    }
+
+    let (cs_type, axes) = match kind {
+        CrsKind::Geodetic => {
+            ensure!(axes.len() == 3, "GEOCCS has {} axes", axes.len());
+            let axes = axes
+                .into_iter()
+                .zip(["geocentricX", "geocentricY", "geocentricZ"])
+                .map(|((name, _), direction)| Axis {
+                    name: name.to_string(),
+                    direction: direction.to_string(),
+                    unit: Some(cs_unit.clone()),
+                    min_value: None,
+                    max_value: None,
+                })
+                .collect();
+            (CsType::Cartesian, axes)
+        }
+        CrsKind::Geographic => {
+            let axes = axes
+                .into_iter()
+                .map(|(name, direction)| {
+                    // Heights are in metres; WKT1 has no way to say otherwise.
+                    let unit = if direction == "up" || direction == "down" {
+                        CsUnit::metre()
+                    } else {
+                        cs_unit.clone()
+                    };
+                    Axis {
+                        name: name.to_string(),
+                        direction,
+                        unit: Some(unit),
+                        min_value: None,
+                        max_value: None,
+                    }
+                })
+                .collect();
+            (CsType::Ellipsoidal, axes)
+        }
+    };
+
+    // The prime meridian is in the angular unit of a GEOGCS, and in degrees for a GEOCCS.
+    let pm_unit = match kind {
+        CrsKind::Geodetic => None,
+        CrsKind::Geographic => Some(cs_unit),
+    };
+    let prime_meridian = prime_meridian.map(|n| primem(n, pm_unit)).transpose()?;
+
+    Ok(CrsParts {
+        name,
+        kind,
+        datum: datum.context("no DATUM")?,
+        prime_meridian,
+        frame_epoch: None,
+        cs: CoordinateSystemDef { cs_type, axes },
+        usage: None,
+        id,
+    })
+}
+
+fn datum_wkt1(n: &WktNode) -> Result<DatumParts> {
+    let name = n.text(0)?.to_string();
+    let mut ellipsoid = None;
+    let mut id = None;
+    for n in child_nodes(n, 1)? {
+        match n.keyword.as_str() {
+            "SPHEROID" => set_once(&mut ellipsoid, ellipsoid_of(n)?)?,
+            "AUTHORITY" => set_once(&mut id, authority_id(n)?)?,
+            //? TODO TOWGS84 once there are datum transformations to put it in
+            kw => bail!("{kw} is not supported in DATUM"),
+        }
+    }
+    Ok(DatumParts {
+        name,
+        ellipsoid: ellipsoid.context("DATUM has no SPHEROID")?,
+        id,
+    })
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+fn ellipsoid_of(n: &WktNode) -> Result<EllipsoidParts> {
+    let mut unit_ = None;
+    let mut id = None;
+    for c in child_nodes(n, 3)? {
+        match c.keyword.as_str() {
+            "LENGTHUNIT" | "UNIT" => set_once(&mut unit_, unit(c, Some(DimensionKind::LENGTH))?)?,
+            "ID" | "AUTHORITY" => set_once(&mut id, authority_id(c)?)?,
+            kw => bail!("{kw} is not supported in {}", n.keyword),
+        }
+    }
+    Ok(EllipsoidParts {
+        name: n.text(0)?.to_string(),
+        a: decimal_to_ecsnum(n.number_str(1)?)?,
+        inv_f: decimal_to_ecsnum(n.number_str(2)?)?,
+        unit: unit_,
+        id,
+    })
+}
+
+/// `unit` applies if the `PRIMEM` doesn't give its own.
+fn primem(n: &WktNode, unit_: Option<CsUnit>) -> Result<PrimeMeridian> {
+    let mut pm = PrimeMeridian {
+        name: n.text(0)?.to_string(),
+        longitude: n.number(1)?,
+        unit: None,
+        id: None,
+    };
+    for c in child_nodes(n, 2)? {
+        match c.keyword.as_str() {
+            "ANGLEUNIT" | "UNIT" => set_once(&mut pm.unit, unit(c, Some(DimensionKind::ANGLE))?)?,
+            "ID" | "AUTHORITY" => set_once(&mut pm.id, authority_id(c)?)?,
+            kw => bail!("{kw} is not supported in PRIMEM"),
+        }
+    }
+    if pm.unit.is_none() {
+        pm.unit = unit_;
+    }
+    Ok(pm)
+}
+
+/// The dimension of a bare `UNIT` comes from where it appears.
+fn unit(n: &WktNode, dimension_kind: Option<DimensionKind>) -> Result<CsUnit> {
+    let dimension_kind = match n.keyword.as_str() {
+        "LENGTHUNIT" => DimensionKind::LENGTH,
+        "ANGLEUNIT" => DimensionKind::ANGLE,
+        "SCALEUNIT" => DimensionKind::SCALE,
+        "TIMEUNIT" => DimensionKind::TIME,
+        _ => dimension_kind.with_context(|| format!("{} of unknown dimension", n.keyword))?,
+    };
+    let mut id = None;
+    for c in child_nodes(n, 2)? {
+        match c.keyword.as_str() {
+            "ID" | "AUTHORITY" => set_once(&mut id, authority_id(c)?)?,
+            kw => bail!("{kw} is not supported in {}", n.keyword),
+        }
+    }
+    Ok(CsUnit {
+        dimension_kind,
+        name: n.text(0)?.to_string(),
+        factor: n.number(1)?,
+        id,
+    })
+}
+
+/// `ID` or `AUTHORITY`. Any version, citation or URI in an `ID` is not kept.
+fn authority_id(n: &WktNode) -> Result<AuthorityId> {
+    Ok(AuthorityId {
+        authority: n.text(0)?.to_string(),
+        code: n.enum_str(1)?.to_string(),
+    })
+}
+
+/// The child nodes from attribute `ix` on, which must all be nodes.
+fn child_nodes(n: &WktNode, ix: usize) -> Result<Vec<&WktNode>> {
+    n.attrs
+        .iter()
+        .skip(ix)
+        .map(|a| match a {
+            WktAttr::Node(c) => Ok(c),
+            a => Err(anyhow!("{} has unexpected attribute {a}", n.keyword)),
+        })
+        .collect()
+}
+
+fn set_once<T>(opt: &mut Option<T>, value: T) -> Result<()> {
+    ensure!(opt.is_none(), "element appears more than once");
+    *opt = Some(value);
+    Ok(())
+}
+
+/// A plain decimal like `298.257223563` is exact. Anything with a sign or exponent, or too
+/// many digits, becomes an `f64`.
+pub(crate) fn decimal_to_ecsnum(s: &str) -> Result<EcsNum> {
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    let digits = format!("{int}{frac}");
+    if !digits.is_empty() && digits.bytes().all(|c| c.is_ascii_digit()) && frac.len() < 20 {
+        if let Ok(numer) = digits.parse::<u64>() {
+            let denom = 10_u64.pow(frac.len() as u32);
+            return Ok(EcsNum::RatioU64(RatioU64::new(numer, denom)));
+        }
+    }
+    let f: f64 = s.parse().with_context(|| format!("number {s:?}"))?;
+    Ok(EcsNum::F64(f))
+}
+
+//=================================================================================================|
+
+/// Writes a geodetic or geographic CRS entity as WKT2.
+pub fn crs_to_wkt(world: &World, e_crs: Entity) -> Result<String> {
+    Ok(crs_node(world, e_crs)?.to_string())
+}
+
+fn crs_node(world: &World, e_crs: Entity) -> Result<WktNode> {
+    let name = world
+        .get::<&Name>(e_crs)
+        .context("CRS Name")?
+        .as_str()
+        .to_string();
+    let kind = *world.get::<&CrsKind>(e_crs).context("CrsKind")?;
+    let e_datum = world.get::<&DatumRef>(e_crs).context("DatumRef")?.entity();
+    let e_cs = world
+        .get::<&CoordinateSystemRef>(e_crs)
+        .context("CoordinateSystemRef")?
+        .entity();
+    let datum_def = (*world.get::<&DatumDef>(e_datum).context("DatumDef")?).clone();
+    let cs_def = (*world
+        .get::<&CoordinateSystemDef>(e_cs)
+        .context("CoordinateSystemDef")?)
+    .clone();
+
+    let mut attrs = vec![text(&name)];
+    if let Some(epoch) = datum_def.frame_epoch {
+        attrs.push(node(
+            "DYNAMIC",
+            vec![node("FRAMEEPOCH", vec![number(epoch)])],
+        ));
+    }
+    attrs.push(datum_node(world, e_datum, &datum_def)?);
+    if let Some(pm) = &datum_def.prime_meridian {
+        let mut pm_attrs = vec![text(&pm.name), number(pm.longitude)];
+        pm_attrs.extend(pm.unit.as_ref().map(unit_node));
+        pm_attrs.extend(pm.id.as_ref().map(id_node));
+        attrs.push(node("PRIMEM", pm_attrs));
+    }
+
+    attrs.push(node(
+        "CS",
+        vec![
+            WktAttr::Enum(cs_def.cs_type.wkt_name().to_string()),
+            WktAttr::Number(cs_def.axes.len().to_string()),
+        ],
+    ));
+    for (ix, axis) in cs_def.axes.iter().enumerate() {
+        let mut axis_attrs = vec![
+            text(&axis.name),
+            WktAttr::Enum(axis.direction.clone()),
+            node("ORDER", vec![WktAttr::Number((ix + 1).to_string())]),
+        ];
+        axis_attrs.extend(axis.unit.as_ref().map(unit_node));
+        if let Some(x) = axis.min_value {
+            axis_attrs.push(node("AXISMINVALUE", vec![number(x)]));
+        }
+        if let Some(x) = axis.max_value {
+            axis_attrs.push(node("AXISMAXVALUE", vec![number(x)]));
+        }
+        attrs.push(node("AXIS", axis_attrs));
+    }
+
+    if let Ok(usage) = world.get::<&Usage>(e_crs) {
+        let mut usage_attrs = vec![node("SCOPE", vec![text(&usage.scope)])];
+        if let Some(area) = &usage.area {
+            usage_attrs.push(node("AREA", vec![text(area)]));
+        }
+        if let Some(bbox) = usage.bbox {
+            usage_attrs.push(node("BBOX", bbox.into_iter().map(number).collect()));
+        }
+        attrs.push(node("USAGE", usage_attrs));
+    }
+    if let Ok(id) = world.get::<&AuthorityId>(e_crs) {
+        attrs.push(id_node(&id));
+    }
+
+    let keyword = match kind {
+        CrsKind::Geodetic => "GEODCRS",
+        CrsKind::Geographic => "GEOGCRS",
+    };
+    Ok(WktNode::new(keyword, attrs))
+}
+
+fn datum_node(world: &World, e_datum: Entity, datum_def: &DatumDef) -> Result<WktAttr> {
+    let name = world
+        .get::<&Name>(e_datum)
+        .context("Datum Name")?
+        .as_str()
+        .to_string();
+    let ellipsoid = datum_def.ellipsoid.context("Datum has no ellipsoid")?;
+    let e_ellipsoid = ellipsoid.entity();
+    let def = ellipsoid.def(world)?;
+
+    let ellipsoid_name = world
+        .get::<&Option<Name>>(e_ellipsoid)
+        .context("OblateSpheroid name")?
+        .as_ref()
+        .map(|name| name.as_str().to_string())
+        .unwrap_or_default();
+    let inv_f = match &def.f {
+        Flattening::F_inv(inv_f) => ecsnum_to_number(inv_f)?,
+        Flattening::F(f) if f.to_f64()? == 0.0 => WktAttr::Number("0".into()),
+        Flattening::F(f) => ecsnum_to_number(&f.recip()?)?,
+    };
+    let mut ellipsoid_attrs = vec![
+        text(&ellipsoid_name),
+        ecsnum_to_number(&def.a.value)?,
+        inv_f,
+    ];
+    if let Ok(unit) = world.get::<&CsUnit>(e_ellipsoid) {
+        ellipsoid_attrs.push(unit_node(&unit));
+    }
+    if let Ok(id) = world.get::<&AuthorityId>(e_ellipsoid) {
+        ellipsoid_attrs.push(id_node(&id));
+    }
+
+    let mut attrs = vec![text(&name), node("ELLIPSOID", ellipsoid_attrs)];
+    if let Ok(id) = world.get::<&AuthorityId>(e_datum) {
+        attrs.push(id_node(&id));
+    }
+    Ok(node("DATUM", attrs))
+}
+
+fn unit_node(unit: &CsUnit) -> WktAttr {
+    let keyword = match unit.dimension_kind {
+        DimensionKind::LENGTH => "LENGTHUNIT",
+        DimensionKind::ANGLE => "ANGLEUNIT",
+        DimensionKind::SCALE => "SCALEUNIT",
+        DimensionKind::TIME => "TIMEUNIT",
+        _ => "UNIT",
+    };
+    let mut attrs = vec![text(&unit.name), number(unit.factor)];
+    attrs.extend(unit.id.as_ref().map(id_node));
+    node(keyword, attrs)
+}
+
+/// Codes that are all digits are written as numbers, as EPSG does.
+fn id_node(id: &AuthorityId) -> WktAttr {
+    let code = if !id.code.is_empty() && id.code.bytes().all(|c| c.is_ascii_digit()) {
+        WktAttr::Number(id.code.clone())
+    } else {
+        text(&id.code)
+    };
+    node("ID", vec![text(&id.authority), code])
+}
+
+fn node(keyword: &str, attrs: Vec<WktAttr>) -> WktAttr {
+    WktAttr::Node(WktNode::new(keyword, attrs))
+}
+
+fn text(s: &str) -> WktAttr {
+    WktAttr::Text(s.to_string())
+}
+
+fn number(x: f64) -> WktAttr {
+    WktAttr::Number(x.to_string())
+}
+
+/// Exact decimals stay exact. Other values go through `f64`.
+fn ecsnum_to_number(n: &EcsNum) -> Result<WktAttr> {
+    if let EcsNum::RatioU64(r) = n {
+        let (numer, denom) = (*r.numer() as u128, *r.denom() as u128);
+        let mut scale: u128 = 1;
+        let mut digits = 0_usize;
+        while scale % denom != 0 && digits < 19 {
+            scale *= 10;
+            digits += 1;
+        }
+        if scale % denom == 0 {
+            let v = numer * (scale / denom);
+            let (int, frac) = (v / scale, v % scale);
+            if frac == 0 {
+                return Ok(WktAttr::Number(int.to_string()));
+            }
+            let frac = format!("{frac:0digits$}");
+            return Ok(WktAttr::Number(format!(
+                "{int}.{}",
+                frac.trim_end_matches('0')
+            )));
+        }
+    }
+    Ok(number(n.to_f64()?))
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    const EPSG_9754: &str = r#"GEOGCRS["WGS 84 (G2139)",
+        DYNAMIC[FRAMEEPOCH[2016]],
+        DATUM["World Geodetic System 1984 (G2139)",
+            ELLIPSOID["WGS 84",6378137,298.257223563,LENGTHUNIT["metre",1]]],
+        PRIMEM["Greenwich",0,ANGLEUNIT["degree",0.0174532925199433]],
+        CS[ellipsoidal,3],
+            AXIS["geodetic latitude (Lat)",north,ORDER[1],ANGLEUNIT["degree",0.0174532925199433]],
+            AXIS["geodetic longitude (Lon)",east,ORDER[2],ANGLEUNIT["degree",0.0174532925199433]],
+            AXIS["ellipsoidal height (h)",up,ORDER[3],LENGTHUNIT["metre",1]],
+        USAGE[
+            SCOPE["Geodesy. Navigation and positioning using GPS satellite system."],
+            AREA["World."],
+            BBOX[-90,-180,90,180]],
+        ID["EPSG",9754]]"#;
+
+    const EPSG_9753: &str = r#"GEODCRS["WGS 84 (G2139)",
+        DYNAMIC[FRAMEEPOCH[2016]],
+        DATUM["World Geodetic System 1984 (G2139)",
+            ELLIPSOID["WGS 84",6378137,298.257223563,LENGTHUNIT["metre",1]]],
+        PRIMEM["Greenwich",0,ANGLEUNIT["degree",0.0174532925199433]],
+        CS[Cartesian,3],
+            AXIS["(X)",geocentricX,ORDER[1],LENGTHUNIT["metre",1]],
+            AXIS["(Y)",geocentricY,ORDER[2],LENGTHUNIT["metre",1]],
+            AXIS["(Z)",geocentricZ,ORDER[3],LENGTHUNIT["metre",1]],
+        USAGE[
+            SCOPE["Geodesy. Navigation and positioning using GPS satellite system."],
+            AREA["World."],
+            BBOX[-90,-180,90,180]],
+        ID["EPSG",9753]]"#;
+
+    const EPSG_4979_WKT1: &str = r#"GEOGCS["WGS 84",
+        DATUM["World Geodetic System 1984",
+            SPHEROID["WGS 84",6378137.0,298.257223563,AUTHORITY["EPSG","7030"]],
+            AUTHORITY["EPSG","6326"]],
+        PRIMEM["Greenwich",0.0,AUTHORITY["EPSG","8901"]],
+        UNIT["degree",0.017453292519943295],
+        AXIS["Geodetic latitude",NORTH],
+        AXIS["Geodetic longitude",EAST],
+        AXIS["Ellipsoidal height",UP],
+        AUTHORITY["EPSG","4979"]]"#;
+
+    const EPSG_4978_WKT1: &str = r#"GEOCCS["WGS 84",
+        DATUM["World Geodetic System 1984",
+            SPHEROID["WGS 84",6378137.0,298.257223563,AUTHORITY["EPSG","7030"]],
+            AUTHORITY["EPSG","6326"]],
+        PRIMEM["Greenwich",0.0,AUTHORITY["EPSG","8901"]],
+        UNIT["m",1.0],
+        AXIS["Geocentric X",OTHER],
+        AXIS["Geocentric Y",EAST],
+        AXIS["Geocentric Z",NORTH],
+        AUTHORITY["EPSG","4978"]]"#;
+
+    /// As PostGIS has it.
+    const EPSG_4326_WKT1: &str = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4326"]]"#;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let mut world = World::default();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+        let ns = ecs_ns_find_or_create_gis(world)?;
+
+        // WKT2 comes back out as it went in.
+        for wkt in [EPSG_9754, EPSG_9753] {
+            let e_crs = ecs_add_crs_from_wkt(world, ns, wkt)?;
+            assert_eq!(crs_to_wkt(world, e_crs)?, parse_wkt(wkt)?.to_string());
+        }
+
+        let e_crs = ecs_add_crs_from_wkt(world, ns, EPSG_9754)?;
+        assert_eq!(*world.get::<&CrsKind>(e_crs)?, CrsKind::Geographic);
+        assert_eq!(world.get::<&AuthorityId>(e_crs)?.to_string(), "EPSG:9754");
+        let e_datum = world.get::<&DatumRef>(e_crs)?.entity();
+        let datum_def = (*world.get::<&DatumDef>(e_datum)?).clone();
+        assert_eq!(datum_def.frame_epoch, Some(2016.0));
+        let ellipsoid = datum_def.ellipsoid.context("ellipsoid")?.def(world)?;
+        assert_eq!(ellipsoid.a.value.to_f64()?, 6378137.0);
+        match &ellipsoid.f {
+            Flattening::F_inv(EcsNum::RatioU64(r)) => {
+                assert_eq!(*r, RatioU64::new(298_257_223_563, 1_000_000_000))
+            }
+            f => panic!("{f:?}"),
+        }
+
+        // WKT1 reads into the same entities, and writes as WKT2 that reads back the same.
+        for wkt in [EPSG_4979_WKT1, EPSG_4978_WKT1, EPSG_4326_WKT1] {
+            let e_crs = ecs_add_crs_from_wkt(world, ns, wkt)?;
+            let wkt2 = crs_to_wkt(world, e_crs)?;
+            let e_crs2 = ecs_add_crs_from_wkt(world, ns, &wkt2)?;
+            assert_eq!(crs_to_wkt(world, e_crs2)?, wkt2);
+        }
+
+        let e_crs = ecs_add_crs_from_wkt(world, ns, EPSG_4978_WKT1)?;
+        assert_eq!(
+            crs_to_wkt(world, e_crs)?,
+            r#"GEODCRS["WGS 84",DATUM["World Geodetic System 1984",ELLIPSOID["WGS 84",6378137,298.257223563,ID["EPSG",7030]],ID["EPSG",6326]],PRIMEM["Greenwich",0,ID["EPSG",8901]],CS[Cartesian,3],AXIS["Geocentric X",geocentricX,ORDER[1],LENGTHUNIT["m",1]],AXIS["Geocentric Y",geocentricY,ORDER[2],LENGTHUNIT["m",1]],AXIS["Geocentric Z",geocentricZ,ORDER[3],LENGTHUNIT["m",1]],ID["EPSG",4978]]"#
+        );
+
+        let e_crs = ecs_add_crs_from_wkt(world, ns, EPSG_4326_WKT1)?;
+        let e_cs = world.get::<&CoordinateSystemRef>(e_crs)?.entity();
+        let cs_def = (*world.get::<&CoordinateSystemDef>(e_cs)?).clone();
+        assert_eq!(cs_def.cs_type, CsType::Ellipsoidal);
+        let directions: Vec<_> = cs_def.axes.iter().map(|a| a.direction.as_str()).collect();
+        assert_eq!(directions, ["east", "north"]);
+        let unit = cs_def.axes[0].unit.clone().context("unit")?;
+        assert_eq!(unit.id, Some(AuthorityId::epsg(9122)));
+
+        // Not supported, or not valid.
+        for wkt in [
+            r#"PROJCRS["x"]"#,
+            r#"GEOGCRS["x",ENSEMBLE["WGS 84 ensemble"]]"#,
+            r#"GEOGCRS["x",DYNAMIC[FRAMEEPOCH[2016],MODEL["m"]]]"#,
+            r#"GEOGCS["x",DATUM["d",SPHEROID["s",1,0],TOWGS84[0,0,0]],UNIT["degree",1]]"#,
+            r#"GEOGCS["x",DATUM["d",SPHEROID["s",1,0]],UNIT["degree",1],AUTHORITY["a","1"],AUTHORITY["a","2"]]"#,
+            EPSG_9754
+                .replace("CS[ellipsoidal,3]", "CS[ellipsoidal,2]")
+                .as_str(),
+        ] {
+            assert!(ecs_add_crs_from_wkt(world, ns, wkt).is_err(), "{wkt}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,341 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Well-known text for coordinate reference systems: WKT2 ([ISO 19162:2019](
+//! https://docs.ogc.org/is/18-010r7/18-010r7.html)) and the older WKT1 of OGC 01-009.
+//!
+//! The text is first parsed into a tree of [`WktNode`]s without regard to what the keywords
+//! mean; [`crs`] turns the tree into entities in the [`hecs::World`] and back. Numbers keep
+//! the text they were written with, so a tree writes back out exactly as it came in, minus
+//! the whitespace.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::collections::BTreeMap;
+//? use std::convert::From;
+use std::fmt::{Display, Write};
+//? use std::ops::{RangeBounds, RangeInclusive};
+//? use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::{Deref, DerefMut, Display, From, Into};
+//? use log::{debug, error, info, trace, warn};
+
+pub mod crs;
+pub use crate::gis::wkt::crs::{crs_to_wkt, ecs_add_crs_from_wkt};
+
+//=================================================================================================|
+
+/// A keyword and its bracketed attributes, e.g. `ID["EPSG",4326]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WktNode {
+    /// Upper case. WKT keywords are case-insensitive.
+    pub keyword: String,
+
+    pub attrs: Vec<WktAttr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WktAttr {
+    /// Quoted text, with any doubled quotes undone.
+    Text(String),
+
+    /// A number as written.
+    Number(String),
+
+    /// An unquoted value such as `north` or `ellipsoidal`.
+    Enum(String),
+
+    Node(WktNode),
+}
+
+impl WktNode {
+    pub fn new<S: Into<String>>(keyword: S, attrs: Vec<WktAttr>) -> Self {
+        Self {
+            keyword: keyword.into(),
+            attrs,
+        }
+    }
+
+    /// The attribute at `ix`.
+    pub fn attr(&self, ix: usize) -> Result<&WktAttr> {
+        self.attrs
+            .get(ix)
+            .ok_or_else(|| anyhow!("{} has no attribute {ix}", self.keyword))
+    }
+
+    pub fn text(&self, ix: usize) -> Result<&str> {
+        match self.attr(ix)? {
+            WktAttr::Text(s) => Ok(s),
+            a => bail!("{} attribute {ix} is not text: {a}", self.keyword),
+        }
+    }
+
+    /// Number as written.
+    pub fn number_str(&self, ix: usize) -> Result<&str> {
+        match self.attr(ix)? {
+            WktAttr::Number(s) => Ok(s),
+            a => bail!("{} attribute {ix} is not a number: {a}", self.keyword),
+        }
+    }
+
+    pub fn number(&self, ix: usize) -> Result<f64> {
+        let s = self.number_str(ix)?;
+        s.parse()
+            .with_context(|| format!("{} attribute {ix}: {s:?}", self.keyword))
+    }
+
+    /// Enumeration value, or a number or text written where one was expected.
+    pub fn enum_str(&self, ix: usize) -> Result<&str> {
+        match self.attr(ix)? {
+            WktAttr::Enum(s) | WktAttr::Number(s) | WktAttr::Text(s) => Ok(s),
+            a => bail!("{} attribute {ix} is not a value: {a}", self.keyword),
+        }
+    }
+
+    /// The child nodes, in order.
+    pub fn nodes(&self) -> impl Iterator<Item = &WktNode> {
+        self.attrs.iter().filter_map(|a| match a {
+            WktAttr::Node(n) => Some(n),
+            _ => None,
+        })
+    }
+
+    /// The first child node with one of the keywords.
+    pub fn node(&self, keywords: &[&str]) -> Option<&WktNode> {
+        self.nodes()
+            .find(|n| keywords.contains(&n.keyword.as_str()))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+impl Display for WktAttr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WktAttr::Text(s) => {
+                f.write_char('"')?;
+                f.write_str(&s.replace('"', "\"\""))?;
+                f.write_char('"')
+            }
+            WktAttr::Number(s) | WktAttr::Enum(s) => f.write_str(s),
+            WktAttr::Node(n) => n.fmt(f),
+        }
+    }
+}
+
+/// Writes the single-line form, with square brackets.
+impl Display for WktNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.keyword)?;
+        f.write_char('[')?;
+        for (ix, a) in self.attrs.iter().enumerate() {
+            if ix != 0 {
+                f.write_char(',')?;
+            }
+            a.fmt(f)?;
+        }
+        f.write_char(']')
+    }
+}
+
+//=================================================================================================|
+
+/// Parses one WKT element. Either square brackets or parentheses may be used.
+pub fn parse_wkt(s: &str) -> Result<WktNode> {
+    let mut p = Parser {
+        s: s.as_bytes(),
+        pos: 0,
+        depth: 0,
+    };
+    p.skip_ws();
+    let kw = p.identifier()?;
+    let node = p.node(kw)?;
+    p.skip_ws();
+    ensure!(p.pos == s.len(), "WKT has trailing text at byte {}", p.pos);
+    Ok(node)
+}
+
+/// Real WKT nests a handful of levels deep. The limit keeps hostile input from exhausting the
+/// stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+
+    /// Nodes open at `pos`.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        // Only ASCII is taken, so this is on a char boundary.
+        std::str::from_utf8(&self.s[start..self.pos]).unwrap_or_default()
+    }
+
+    fn identifier(&mut self) -> Result<&'a str> {
+        let id = self.take_while(|c| c.is_ascii_alphanumeric() || c == b'_');
+        ensure!(
+            !id.is_empty(),
+            "WKT expected a keyword at byte {}",
+            self.pos
+        );
+        Ok(id)
+    }
+
+    /// The bracketed part of a node, after its keyword.
+    fn node(&mut self, keyword: &str) -> Result<WktNode> {
+        self.skip_ws();
+        let close = match self.peek() {
+            Some(b'[') => b']',
+            Some(b'(') => b')',
+            _ => bail!("WKT expected '[' after {keyword} at byte {}", self.pos),
+        };
+        ensure!(
+            self.depth < MAX_DEPTH,
+            "WKT nests deeper than {MAX_DEPTH} at byte {}",
+            self.pos
+        );
+        self.depth += 1;
+        self.pos += 1;
+
+        let mut attrs = vec![];
+        loop {
+            self.skip_ws();
+            if attrs.is_empty() && self.peek() == Some(close) {
+                break;
+            }
+            attrs.push(self.attr()?);
+            self.skip_ws();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(c) if c == close => break,
+                _ => bail!("WKT expected ',' or ']' in {keyword} at byte {}", self.pos),
+            }
+        }
+        self.pos += 1;
+        self.depth -= 1;
+
+        Ok(WktNode::new(keyword.to_ascii_uppercase(), attrs))
+    }
+
+    fn attr(&mut self) -> Result<WktAttr> {
+        match self.peek() {
+            Some(b'"') => {
+                self.pos += 1;
+                let mut bytes = vec![];
+                loop {
+                    match self.peek() {
+                        None => bail!("WKT has unterminated text"),
+                        Some(b'"') if self.s.get(self.pos + 1) == Some(&b'"') => {
+                            bytes.push(b'"');
+                            self.pos += 2;
+                        }
+                        Some(b'"') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        Some(c) => {
+                            bytes.push(c);
+                            self.pos += 1;
+                        }
+                    }
+                }
+                Ok(WktAttr::Text(
+                    String::from_utf8(bytes).context("WKT text is not UTF-8")?,
+                ))
+            }
+            Some(c) if c.is_ascii_digit() || c == b'-' || c == b'+' || c == b'.' => {
+                let s = self.take_while(|c| {
+                    c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.' | b'e' | b'E')
+                });
+                s.parse::<f64>()
+                    .with_context(|| format!("WKT number {s:?}"))?;
+                Ok(WktAttr::Number(s.to_string()))
+            }
+            _ => {
+                let id = self.identifier()?;
+                self.skip_ws();
+                if matches!(self.peek(), Some(b'[' | b'(')) {
+                    Ok(WktAttr::Node(self.node(id)?))
+                } else {
+                    Ok(WktAttr::Enum(id.to_string()))
+                }
+            }
+        }
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let node = parse_wkt(
+            r#" geogcrs [ "WGS 84 (G2139)",
+                CS(ellipsoidal, 2),
+                AXIS["say ""hi""", north, ORDER[1]],
+                BBOX[-90, -180.0, 9e1, 180] ]"#,
+        )?;
+        assert_eq!(node.keyword, "GEOGCRS");
+        assert_eq!(node.text(0)?, "WGS 84 (G2139)");
+        let cs = node.node(&["CS"]).context("CS")?;
+        assert_eq!((cs.enum_str(0)?, cs.number(1)?), ("ellipsoidal", 2.0));
+        let axis = node.node(&["AXIS"]).context("AXIS")?;
+        assert_eq!(axis.text(0)?, r#"say "hi""#);
+        assert_eq!(node.node(&["BBOX"]).context("BBOX")?.number(2)?, 90.0);
+        assert_eq!(
+            node.to_string(),
+            r#"GEOGCRS["WGS 84 (G2139)",CS[ellipsoidal,2],AXIS["say ""hi""",north,ORDER[1]],BBOX[-90,-180.0,9e1,180]]"#
+        );
+        assert_eq!(parse_wkt(&node.to_string())?, node);
+
+        assert!(parse_wkt("ID[\"EPSG\",4326").is_err());
+        assert!(parse_wkt("ID[\"EPSG\",4326]]").is_err());
+        assert!(parse_wkt("ID[\"EPSG,4326]").is_err());
+        assert!(parse_wkt("ID[\"EPSG\" 4326]").is_err());
+
+        // Deep nesting is an error, not a stack overflow.
+        let deep = |n: usize| format!("{}1{}", "A[".repeat(n), "]".repeat(n));
+        assert!(parse_wkt(&deep(MAX_DEPTH)).is_ok());
+        assert!(parse_wkt(&deep(MAX_DEPTH + 1)).is_err());
+        assert!(parse_wkt(&deep(1_000_000)).is_err());
+        assert_eq!(parse_wkt("REMARK[]")?.attrs, vec![]);
+        Ok(())
+    }
+}
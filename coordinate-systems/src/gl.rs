@@ -35,19 +35,184 @@ use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
 //? use num_rational::Ratio;
 //? use num_traits::{NumCast, ToPrimitive, Zero};
 //? use once_cell::sync::Lazy;
-//? use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 //? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
 
+use cgmath::InnerSpace;
+
+use crate::gis::metadata::AuthorityId;
 use crate::gis::*;
 use ecs_namespace::NamespaceTag;
 use crate::*;
 
 //=================================================================================================|
 
-#[derive(Clone, Debug, Display)]
+/// A unit of measure as a coordinate system or datum refers to it, by name and conversion
+/// factor to the SI unit (metre, radian, unity or second).
+#[derive(Clone, Debug, Display, PartialEq, Serialize, Deserialize)]
+#[display("CsUnit {{ {dimension_kind}, {name:?}, {factor} }}")]
+pub struct CsUnit {
+    pub dimension_kind: DimensionKind,
+    pub name: String,
+    pub factor: f64,
+    pub id: Option<AuthorityId>,
+}
+
+impl CsUnit {
+    pub fn metre() -> Self {
+        Self {
+            dimension_kind: DimensionKind::LENGTH,
+            name: "metre".into(),
+            factor: 1.0,
+            id: None,
+        }
+    }
+
+    pub fn degree() -> Self {
+        Self {
+            dimension_kind: DimensionKind::ANGLE,
+            name: "degree".into(),
+            factor: 0.017_453_292_519_943_3,
+            id: None,
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// The kinds of coordinate system in ISO 19111.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CsType {
+    Affine,
+    Cartesian,
+    Cylindrical,
+    Ellipsoidal,
+    Linear,
+    Parametric,
+    Polar,
+    Spherical,
+    Vertical,
+    TemporalCount,
+    TemporalMeasure,
+    Ordinal,
+    TemporalDateTime,
+}
+
+impl CsType {
+    const ALL: [CsType; 13] = [
+        CsType::Affine,
+        CsType::Cartesian,
+        CsType::Cylindrical,
+        CsType::Ellipsoidal,
+        CsType::Linear,
+        CsType::Parametric,
+        CsType::Polar,
+        CsType::Spherical,
+        CsType::Vertical,
+        CsType::TemporalCount,
+        CsType::TemporalMeasure,
+        CsType::Ordinal,
+        CsType::TemporalDateTime,
+    ];
+
+    /// The name as written in WKT2, e.g. `ellipsoidal`.
+    pub fn wkt_name(self) -> &'static str {
+        use CsType::*;
+        match self {
+            Affine => "affine",
+            Cartesian => "Cartesian",
+            Cylindrical => "cylindrical",
+            Ellipsoidal => "ellipsoidal",
+            Linear => "linear",
+            Parametric => "parametric",
+            Polar => "polar",
+            Spherical => "spherical",
+            Vertical => "vertical",
+            TemporalCount => "temporalCount",
+            TemporalMeasure => "temporalMeasure",
+            Ordinal => "ordinal",
+            TemporalDateTime => "temporalDateTime",
+        }
+    }
+
+    /// Case-insensitive.
+    pub fn from_wkt_name(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|cs_type| cs_type.wkt_name().eq_ignore_ascii_case(s))
+    }
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// One axis of a [`CoordinateSystem`].
+#[derive(Clone, Debug, Display, PartialEq, Serialize, Deserialize)]
+#[display("Axis {{ {name:?}, {direction} }}")]
+pub struct Axis {
+    /// Name, with the abbreviation in parentheses as WKT2 writes it, e.g.
+    /// `"geodetic latitude (Lat)"`.
+    pub name: String,
+
+    /// Direction as named in ISO 19111, e.g. `north` or `geocentricX`.
+    pub direction: String,
+
+    pub unit: Option<CsUnit>,
+
+    /// The extent of the axis, as WKT2 `AXISMINVALUE` and `AXISMAXVALUE`.
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+#[derive(Clone, Debug, Display, Serialize, Deserialize)]
+#[display("CoordinateSystemDef {{ {cs_type}, {} axes }}", axes.len())]
 pub struct CoordinateSystemDef {
     //? TODO official designations?
-    //? TODO number and nature of axes
+    pub cs_type: CsType,
+
+    /// In order.
+    pub axes: Vec<Axis>,
+}
+
+impl CoordinateSystemDef {
+    /// Whether the first three axes form a left- or right-handed system, if their directions are
+    /// ones this knows how to orient, such as `east`, `geocentricX` or `displayRight`.
+    pub fn handedness(&self) -> Option<Handedness> {
+        let [x, y, z] = match self.axes.as_slice() {
+            [x, y, z, ..] => [x, y, z].map(|axis| direction_vector(&axis.direction)),
+            _ => return None,
+        };
+        let det = x?.cross(y?).dot(z?);
+        if 0.5 < det {
+            Some(Handedness::Right)
+        } else if det < -0.5 {
+            Some(Handedness::Left)
+        } else {
+            None
+        }
+    }
+}
+
+/// A unit vector for an ISO 19111 axis direction, in a frame of its own. The display directions
+/// are x right, y up and z towards the viewer.
+fn direction_vector(direction: &str) -> Option<Vector3> {
+    let v = |x, y, z| Some(Vector3::new(x, y, z));
+    match direction {
+        "east" | "geocentricX" | "displayRight" | "columnPositive" => v(1.0, 0.0, 0.0),
+        "west" | "displayLeft" | "columnNegative" => v(-1.0, 0.0, 0.0),
+        "north" | "geocentricY" | "displayUp" | "rowNegative" => v(0.0, 1.0, 0.0),
+        "south" | "displayDown" | "rowPositive" => v(0.0, -1.0, 0.0),
+        "up" | "geocentricZ" | "towards" => v(0.0, 0.0, 1.0),
+        "down" | "awayFrom" => v(0.0, 0.0, -1.0),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum Handedness {
+    Left,
+    Right,
 }
 
 #[derive(Bundle, Clone, Debug, Display)]
@@ -58,39 +223,58 @@ pub struct CoordinateSystem {
 }
 
 #[derive(Clone, Copy, Debug)]
-pub struct CoordinateSystemRef(Entity);
+pub struct CoordinateSystemRef(pub(crate) Entity);
 
 impl CoordinateSystemRef {
     pub fn new(e: Entity, world: &World) -> Self {
         debug_assert!(
-            world.satisfies::<&CoordinateSystem>(e).unwrap_or_default(),
+            world.satisfies::<&CoordinateSystemDef>(e).unwrap_or_default(),
             "Although this newtype can't prevent the CoordinateSystem Entity from being removed from the World, it should probably at least start out that way."
         );
         CoordinateSystemRef(e)
     }
+
+    pub fn entity(self) -> Entity {
+        self.0
+    }
 }
 
 //=================================================================================================|
 
+/// The kinds of CRS that WKT distinguishes.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrsKind {
+    /// Cartesian or spherical coordinates about the center of a body, `GEODCRS`.
+    Geodetic,
+
+    /// Latitude, longitude and perhaps height on an ellipsoid, `GEOGCRS`.
+    Geographic,
+}
+
 #[derive(Bundle, Clone, Debug, Display)]
-#[display("CoordinateReferenceSystem {{ Name({:?}), {cs:?}, {datum:?} }}", name.as_str())]
+#[display("CoordinateReferenceSystem {{ Name({:?}), {kind}, {cs:?}, {datum:?} }}", name.as_str())]
 pub struct CoordinateReferenceSystem {
     pub name: Name,
+    pub kind: CrsKind,
     pub cs: CoordinateSystemRef,
     pub datum: DatumRef,
 }
 
 #[derive(Clone, Copy, Debug)]
-struct CoordinateReferenceSystemRef(Entity);
+pub struct CoordinateReferenceSystemRef(Entity);
 
 impl CoordinateReferenceSystemRef {
     pub fn new(e: Entity, world: &World) -> Self {
         debug_assert!(
-            world.satisfies::<&CoordinateSystem>(e).unwrap_or_default(),
+            world.satisfies::<&CrsKind>(e).unwrap_or_default(),
             "Although this newtype can't prevent the CoordinateReferenceSystem Entity from being removed from the World, it should probably at least start out that way."
         );
         CoordinateReferenceSystemRef(e)
     }
+
+    pub fn entity(self) -> Entity {
+        self.0
+    }
 }
 
 //=================================================================================================|
@@ -116,6 +300,7 @@ pub fn ecs_add_crs(
     world: &mut World,
     e_ns_parent: Entity,
     name: &str,
+    kind: CrsKind,
     datum: DatumRef,
     cs: CoordinateSystemRef,
 ) -> Result<Entity> {
@@ -125,15 +310,190 @@ pub fn ecs_add_crs(
         name,
         CoordinateReferenceSystem {
             name: name.into(),
+            kind,
             datum,
             cs,
         },
     )
 }
 
-pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
-    use DimensionKind::*;
+//=================================================================================================|
+
+/// A perspective projection from a right-handed view space, looking down -z with y up as in
+/// cgmath and three-d, to WebGPU clip space. After the divide by `w`, depth is 0 at `near` and 1
+/// at `far`. `fov_y` is the full vertical field of view in radians, `aspect` is width / height.
+pub fn perspective(fov_y: f64, aspect: f64, near: f64, far: f64) -> Result<Matrix4> {
+    ensure!(
+        0.0 < fov_y && fov_y < std::f64::consts::PI,
+        "field of view {fov_y} is not in (0, π)"
+    );
+    ensure!(
+        0.0 < aspect && aspect.is_finite(),
+        "aspect ratio {aspect} is not positive"
+    );
+    ensure!(
+        0.0 < near && near < far && far.is_finite(),
+        "depth range {near}..{far} is not positive and increasing"
+    );
+
+    let f = 1.0 / (0.5 * fov_y).tan();
+    let d = near - far;
+
+    // Column-major.
+    #[rustfmt::skip]
+    let m = Matrix4::new(
+        f / aspect, 0.0, 0.0,             0.0,
+        0.0,        f,   0.0,             0.0,
+        0.0,        0.0, far / d,        -1.0,
+        0.0,        0.0, near * far / d,  0.0,
+    );
+    Ok(m)
+}
+
+/// Clip coordinates to normalized device coordinates, by the divide by `w`.
+pub fn clip_to_ndc(clip: Vector4) -> Result<Vector3> {
+    ensure!(clip.w != 0.0, "clip coordinates {clip:?} have w = 0");
+    Ok(clip.truncate() / clip.w)
+}
+
+/// From normalized device coordinates to viewport coordinates, as set by WebGPU
+/// `setViewport(x, y, width, height, minDepth, maxDepth)`. NDC y is up and viewport y is down.
+pub fn viewport_transform(
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    min_depth: f64,
+    max_depth: f64,
+) -> Result<Matrix4> {
+    ensure!(
+        0.0 < width && 0.0 < height,
+        "viewport size {width} x {height} is not positive"
+    );
+    ensure!(
+        (0.0..=1.0).contains(&min_depth) && (min_depth..=1.0).contains(&max_depth),
+        "depth range {min_depth}..{max_depth} is not within 0..1"
+    );
+
+    let (hw, hh) = (0.5 * width, 0.5 * height);
+
+    // Column-major.
+    #[rustfmt::skip]
+    let m = Matrix4::new(
+        hw,     0.0,    0.0,                   0.0,
+        0.0,    -hh,    0.0,                   0.0,
+        0.0,    0.0,    max_depth - min_depth, 0.0,
+        x + hw, y + hh, min_depth,             1.0,
+    );
+    Ok(m)
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// Adds the coordinate systems of the WebGPU rendering pipeline to `/gl`. The conventions are
+/// quoted from <https://gpuweb.github.io/gpuweb/#coordinate-systems>.
+fn ecs_add_rendering_cs(world: &mut World, ns_gl: Entity) -> Result<()> {
+    let axis = |name: &str, direction: &str, range: Option<(f64, f64)>| Axis {
+        name: name.into(),
+        direction: direction.into(),
+        unit: None,
+        min_value: range.map(|r| r.0),
+        max_value: range.map(|r| r.1),
+    };
+    let unbounded = None;
+    let unit_interval = Some((0.0, 1.0));
+    let signed_unit_interval = Some((-1.0, 1.0));
+
+    //w3c Clip space coordinates have four dimensions: (x, y, z, w)
+    //w3c Clip space coordinates are used for the the clip position of a vertex
+    //w3c (i.e. the position output of a vertex shader), and for the clip volume.
+    // Vertex shaders use clip space for @builtin(position).
+    // They're homogeneous, so not Cartesian. See [`perspective`].
+    let cs_def = CoordinateSystemDef {
+        cs_type: CsType::Affine,
+        axes: vec![
+            axis("x", "displayRight", unbounded),
+            axis("y", "displayUp", unbounded),
+            axis("z", "awayFrom", unbounded),
+            axis("w", "unspecified", unbounded),
+        ],
+    };
+    ecs_add_cs(world, ns_gl, "clip", cs_def)?;
+
+    //w3c Normalized device coordinates and clip space coordinates are related as follows:
+    //w3c If point p = (p.x, p.y, p.z, p.w) is in the clip volume, then the
+    //w3c NDC are (p.x ÷ p.w, p.y ÷ p.w, p.z ÷ p.w).
+    // See [`clip_to_ndc`].
+
+    //w3c Normalized device coordinates (or NDC) have three dimensions, where:
+    //w3c -1.0 ≤ x ≤ 1.0
+    //w3c -1.0 ≤ y ≤ 1.0
+    //w3c 0.0 ≤ z ≤ 1.0
+    //w3c The bottom-left corner is at (-1.0, -1.0, z).
+    // Left-handed.
+    let cs_def = CoordinateSystemDef {
+        cs_type: CsType::Cartesian,
+        axes: vec![
+            axis("x", "displayRight", signed_unit_interval),
+            axis("y", "displayUp", signed_unit_interval),
+            axis("z", "awayFrom", unit_interval),
+        ],
+    };
+    ecs_add_cs(world, ns_gl, "NDC", cs_def)?;
+
+    //w3c Framebuffer coordinates address the pixels in the framebuffer
+    //w3c They have two dimensions.
+    //w3c Each pixel extends 1 unit in x and y dimensions.
+    //w3c The top-left corner is at (0.0, 0.0).
+    //w3c x increases to the right.
+    //w3c y increases down.
+    // Fragment shaders use framebuffer coordinates space for @builtin(position).
+    //w3c Window coordinates, or present coordinates, match framebuffer coordinates, and are
+    //w3c used when interacting with an external display or conceptually similar interface.
+    let cs_def = CoordinateSystemDef {
+        cs_type: CsType::Cartesian,
+        axes: vec![
+            axis("x", "displayRight", unbounded),
+            axis("y", "displayDown", unbounded),
+        ],
+    };
+    ecs_add_cs(world, ns_gl, "framebuffer", cs_def)?;
+
+    //w3c Viewport coordinates combine framebuffer coordinates in x and y dimensions, with depth in z.
+    //w3c Normally 0.0 ≤ z ≤ 1.0, but this can be modified by setting [[viewport]].minDepth and maxDepth via setViewport()
+    //w3c Fragment coordinates match viewport coordinates.
+    // Right-handed. See [`viewport_transform`].
+    let cs_def = CoordinateSystemDef {
+        cs_type: CsType::Cartesian,
+        axes: vec![
+            axis("x", "displayRight", unbounded),
+            axis("y", "displayDown", unbounded),
+            axis("z", "awayFrom", unit_interval),
+        ],
+    };
+    ecs_add_cs(world, ns_gl, "viewport", cs_def)?;
+
+    //w3c UV coordinates are used to sample textures, and have two dimensions:
+    //w3c 0 ≤ u ≤ 1.0
+    //w3c 0 ≤ v ≤ 1.0
+    //w3c (0.0, 0.0) is in the first texel in texture memory address order.
+    //w3c (1.0, 1.0) is in the last texel texture memory address order.
+    // Rows are stored top to bottom, so v increases down the image.
+    let cs_def = CoordinateSystemDef {
+        cs_type: CsType::Cartesian,
+        axes: vec![
+            axis("u", "columnPositive", unit_interval),
+            axis("v", "rowPositive", unit_interval),
+        ],
+    };
+    ecs_add_cs(world, ns_gl, "UV", cs_def)?;
 
+    Ok(())
+}
+
+//=================================================================================================|
+
+pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
     let ns_gl = ecs_ns_find_or_create(world, NamePathSpec::absolute(["gl"]))?;
 
     // From: https://epsg.io/1309-datum
@@ -156,7 +516,7 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
     let e_earth_wgs84_equatorial_radius: Entity = {
         let dc_earth_wgs84_equatorial_radius = DimensionedConstant {
             name: "earth_wgs84_equatorial_radius".into(),
-            dimension_kind: DimensionKind::Length,
+            dimension_kind: DimensionKind::LENGTH,
             // notes: "This is value is only 'Exact' in the sense of the definition of the WGS 84 reference shape."
             exactness: Exactness::Exact(crate::core::ExactReason::ByDefinition),
             value: EcsNum::RatioU64(RatioU64::from_integer(6378137)),
@@ -168,78 +528,88 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
     };
 
     /*
-    let _earth_wgs84_earth_ellipsoid = {
+    let oblate_spheroid_geodetic_cs = ecs_add_cs(world, ns_gl, "oblate spheroid")?;
 
-        let f_inv_n: u64 = 298257223563;
-        let f_inv_d: u64 = 1000000000;
-        let f_inv_ratiou64 = RatioU64::new_raw(f_inv_n, f_inv_d);
-        let f_inv_ecsnum = EcsNum::RatioU64(f_inv_ratiou64);
+    let earth_geodetic_crs = ecs_add_datum(world, ns_gl, "Earth")?;
+    */
 
-        let obs_def = OblateSpheroidDef {
-            a: e_earth_wgs84_equatorial_radius,
-            f: geom::Flattening::F_inv(f_inv_ecsnum)
-        };
+    ecs_add_rendering_cs(world, ns_gl)?;
 
-        crate::geom::ecs_add_oblatespheroid(
-            world, ns_gl, "earth,ellipsoid,WGS84,dynamic,G2139,EPSG-1309-datum",
-            obs_def
-        )?
-    };
-    */
+    Ok(())
+}
 
-    /*
-    let oblate_spheroid_geodetic_cs = ecs_add_cs(world, ns_gl, "oblate spheroid")?;
+//=================================================================================================|
 
-    let earth_geodetic_crs = ecs_add_datum(world, ns_gl, "Earth")?;
-    */
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
 
-    //? TODO: Perspective projection (<https://en.wikipedia.org/wiki/Transformation_matrix#Perspective_projection>)
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let mut world = World::new();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
 
-    //? TODO: normalized device coordinates (<https://en.wikipedia.org/wiki/Clip_coordinates>)
-    // [](<https://gpuweb.github.io/gpuweb/#coordinate-systems>)
-    //w3c Normalized device coordinates (or NDC) have three dimensions, where:
-    //w3c -1.0 ≤ x ≤ 1.0
-    //w3c -1.0 ≤ y ≤ 1.0
-    //w3c 0.0 ≤ z ≤ 1.0
-    //w3c The bottom-left corner is at (-1.0, -1.0, z).
-    
-    //? TODO: clip coordinates (<https://en.wikipedia.org/wiki/Clip_coordinates>)
-    // [](<https://gpuweb.github.io/gpuweb/#coordinate-systems>)
-    //w3c Clip space coordinates have four dimensions: (x, y, z, w)
-    //w3c Clip space coordinates are used for the the clip position of a vertex
-    //w3c (i.e. the position output of a vertex shader), and for the clip volume.
-    // Vertex shaders use clip space for @builtin(position).
-    
-    //w3c Normalized device coordinates and clip space coordinates are related as follows:
-    //w3c If point p = (p.x, p.y, p.z, p.w) is in the clip volume, then the
-    //w3c NDC are (p.x ÷ p.w, p.y ÷ p.w, p.z ÷ p.w).
-    
-    // [](<https://gpuweb.github.io/gpuweb/#coordinate-systems>)
-    //w3c Framebuffer coordinates address the pixels in the framebuffer
-    //w3c They have two dimensions.
-    //w3c Each pixel extends 1 unit in x and y dimensions.
-    //w3c The top-left corner is at (0.0, 0.0).
-    //w3c x increases to the right.
-    //w3c y increases down.
-    // Fragment shaders use framebuffer coordinates space for @builtin(position).
-    
-    // [](<https://gpuweb.github.io/gpuweb/#coordinate-systems>)
-    //w3c Viewport coordinates combine framebuffer coordinates in x and y dimensions, with depth in z.
-    //w3c Normally 0.0 ≤ z ≤ 1.0, but this can be modified by setting [[viewport]].minDepth and maxDepth via setViewport()
-    
-    // [](<https://gpuweb.github.io/gpuweb/#coordinate-systems>)
-    //w3c Fragment coordinates match viewport coordinates.
-    
-    // [](<https://gpuweb.github.io/gpuweb/#coordinate-systems>)
-    //w3c UV coordinates are used to sample textures, and have two dimensions:
-    //w3c 0 ≤ u ≤ 1.0
-    //w3c 0 ≤ v ≤ 1.0
-    //w3c (0.0, 0.0) is in the first texel in texture memory address order.
-    //w3c (1.0, 1.0) is in the last texel texture memory address order.
-    
-    // [](<https://gpuweb.github.io/gpuweb/#coordinate-systems>)
-    //w3c Window coordinates, or present coordinates, match framebuffer coordinates, and are
-    //w3c used when interacting with an external display or conceptually similar interface.
+        let cs_def = |name: &str| {
+            let e = ecs_ns_get(world, NamePathSpec::absolute(["gl", name])).unwrap();
+            (*world.get::<&CoordinateSystemDef>(e).unwrap()).clone()
+        };
+        let handedness = |name| cs_def(name).handedness().map(|h| h.to_string());
+        assert_ron_snapshot!(
+            ["clip", "NDC", "framebuffer", "viewport", "UV"].map(handedness),
+            @r#"(Some("Left"), Some("Left"), None, Some("Right"), None)"#
+        );
+        let ndc = cs_def("NDC");
+        let ranges: Vec<_> = ndc
+            .axes
+            .iter()
+            .map(|a| (a.min_value, a.max_value))
+            .collect();
+        assert_ron_snapshot!(format!("{ranges:?}"), @r#""[(Some(-1.0), Some(1.0)), (Some(-1.0), Some(1.0)), (Some(0.0), Some(1.0))]""#);
 
-    Ok(())
+        // East-north-up is right-handed, latitude-longitude-height isn't.
+        let axis = |direction: &str| Axis {
+            name: direction.into(),
+            direction: direction.into(),
+            unit: None,
+            min_value: None,
+            max_value: None,
+        };
+        let cs = |directions: [&str; 3]| CoordinateSystemDef {
+            cs_type: CsType::Cartesian,
+            axes: directions.map(axis).to_vec(),
+        };
+        assert_eq!(
+            cs(["east", "north", "up"]).handedness(),
+            Some(Handedness::Right)
+        );
+        assert_eq!(
+            cs(["north", "east", "up"]).handedness(),
+            Some(Handedness::Left)
+        );
+        assert_eq!(cs(["east", "east", "up"]).handedness(), None);
+        assert_eq!(cs(["east", "north", "future"]).handedness(), None);
+
+        // A point on the near plane at the top right of the view, and one on the far plane at
+        // the center, through to an 800 x 600 viewport.
+        let proj = perspective(60f64.to_radians(), 4.0 / 3.0, 1.0, 100.0)?;
+        let vp = viewport_transform(0.0, 0.0, 800.0, 600.0, 0.0, 1.0)?;
+        let to_viewport = |p: Vector3| -> Result<Vector3> {
+            let ndc = clip_to_ndc(proj * p.extend(1.0))?;
+            Ok((vp * ndc.extend(1.0)).truncate())
+        };
+        let top = 30f64.to_radians().tan();
+        let round = |v: Vector3| [v.x, v.y, v.z].map(|x| (x * 1e9).round() / 1e9);
+        assert_ron_snapshot!(round(to_viewport(Vector3::new(top * 4.0 / 3.0, top, -1.0))?), @"(800.0, 0.0, 0.0)");
+        assert_ron_snapshot!(round(to_viewport(Vector3::new(0.0, 0.0, -100.0))?), @"(400.0, 300.0, 1.0)");
+
+        assert!(clip_to_ndc(Vector4::new(1.0, 0.0, 0.0, 0.0)).is_err());
+        assert!(perspective(0.0, 1.0, 1.0, 100.0).is_err());
+        assert!(perspective(1.0, 1.0, 0.0, 100.0).is_err());
+        assert!(viewport_transform(0.0, 0.0, 800.0, 600.0, 0.5, 0.25).is_err());
+
+        Ok(())
+    }
 }
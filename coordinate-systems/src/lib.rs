@@ -46,38 +46,56 @@ pub use num_rational::BigRational;
 //pub type Point3 = cgmath::Point3<f64>;
 
 //pub type Vector1 = cgmath::Vector1<f64>;
-//pub type Vector2 = cgmath::Vector2<f64>;
-//pub type Vector3 = cgmath::Vector3<f64>;
-//pub type Vector4 = cgmath::Vector4<f64>;
+pub type Vector2 = cgmath::Vector2<f64>;
+pub type Vector3 = cgmath::Vector3<f64>;
+pub type Vector4 = cgmath::Vector4<f64>;
 
 //pub type Matrix2 = cgmath::Matrix2<f64>;
-//pub type Matrix3 = cgmath::Matrix3<f64>;
-//pub type Matrix4 = cgmath::Matrix4<f64>;
+pub type Matrix3 = cgmath::Matrix3<f64>;
+pub type Matrix4 = cgmath::Matrix4<f64>;
 
 pub mod core;
-pub use crate::core::{Abbr, DimensionKind, EcsNum, EcsNumRef, Exactness};
+pub use crate::core::{
+    Abbr, BaseDimension, DimensionKind, EcsNum, EcsNumRef, ExactReason, Exactness, Uncertainty,
+};
+
+pub mod eval;
+pub use crate::eval::Estimate;
 
 pub mod consts;
-pub use crate::consts::DimensionedConstant;
+pub use crate::consts::{ConstantSource, ConstantUnit, DimensionedConstant};
 
 pub mod units;
-pub use crate::units::{Unit, UnitDef};
+pub use crate::units::{convert, Measure, Quantity, Unit, UnitDef};
+
+pub mod sexagesimal;
+pub use crate::sexagesimal::{SexagesimalFormat, SexagesimalStyle};
 
 pub mod geom;
 pub use crate::geom::{
     ecs_add_oblatespheroid, OblateSpheroid, OblateSpheroidDef, OblateSpheroidRef,
 };
-//pub use crate::geom::{Ellipsoid3Sphere, Ellipsoid3Oblate, Ellipsoid3Triaxial};
+pub use crate::geom::{
+    ecs_add_sphere, ecs_add_triaxial_ellipsoid, Ellipsoid3, Ellipsoid3Mesh, Ellipsoid3Oblate,
+    Ellipsoid3Sphere, Ellipsoid3Triaxial, SphereDef, TriaxialEllipsoidDef,
+};
 
 pub mod gis;
 pub use crate::gis::ecs_ns_find_or_create_gis;
 
 pub mod gl;
-pub use crate::gl::ecs_add_cs;
+pub use crate::gl::{
+    clip_to_ndc, ecs_add_crs, ecs_add_cs, perspective, viewport_transform,
+    CoordinateReferenceSystem, CoordinateSystem, Handedness,
+};
+
+pub mod persist;
+pub use crate::persist::{EntityPath, LoadReport, NamespaceDoc};
 
 pub fn ecs_add_stuff(world: &mut hecs::World) {
     crate::consts::ecs_add_stuff(world).unwrap();
     crate::units::ecs_add_stuff(world).unwrap();
+    crate::consts::ecs_add_catalog(world).unwrap();
     crate::geom::ecs_add_stuff(world).unwrap();
     crate::gis::ecs_add_stuff(world).unwrap();
     crate::gl::ecs_add_stuff(world).unwrap();
@@ -0,0 +1,828 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Saving the namespace tree of a [`hecs::World`] to a document, and loading it back, so
+//! user-defined units, constants, datums and coordinate systems survive restarts and can be
+//! shared as RON or JSON files.
+//!
+//! [`hecs::Entity`] ids aren't stable from one run to the next, so an entity is referred to by
+//! its [`EntityPath`] from the namespace root, e.g. `["consts", "wgs84", "a"]`. Loading a
+//! document keeps the entities the world already has at those paths, such as the ones
+//! [`crate::ecs_add_stuff`] adds, and creates the rest.
+//!
+//! Only the components below are saved. An entity with others still appears, so that paths and
+//! references through it hold, but without them.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::Display;
+use hecs::{Entity, World};
+use hecs_hierarchy::{Hierarchy, HierarchyMut};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use ecs_namespace::{NamespaceTag, RootNamespace};
+use crate::core::EcsNumRef;
+use crate::geom::{Flattening, SphereDef, TriaxialEllipsoidDef};
+use crate::gis::metadata::AuthorityId;
+use crate::gis::{DatumDef, DatumRef, PrimeMeridian};
+use crate::gl::{CoordinateSystemDef, CoordinateSystemRef, CrsKind, CsUnit};
+use crate::*;
+
+//=================================================================================================|
+
+/// The path of an entity from the namespace root, one [`Name`] per level. An entity without a
+/// name, or whose name an earlier sibling already has, is `"#<index>"`, its position among its
+/// siblings.
+pub type EntityPath = Vec<String>;
+
+/// An [`EcsNum`], with entity references as paths.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum NumDoc {
+    Ratio(u64, u64),
+
+    /// As `numer/denom`.
+    BigRational(String),
+
+    F64(f64),
+    Entity(EntityPath),
+    Inverse(EntityPath),
+    Ref(EntityPath),
+}
+
+/// A [`DimensionedConstant`] held inside another component.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DimensionedConstantDoc {
+    pub name: String,
+    pub dimension_kind: DimensionKind,
+    pub exactness: Exactness,
+    pub value: NumDoc,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+pub enum FlatteningDoc {
+    F(NumDoc),
+    F_inv(NumDoc),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum UnitDefDoc {
+    BaseUnit,
+    ScaleToBaseUnit {
+        scale: NumDoc,
+        base_unit: EntityPath,
+    },
+    Product {
+        factors: Vec<(EntityPath, i8)>,
+    },
+}
+
+/// One [`hecs::Component`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ComponentDoc {
+    Name(String),
+
+    /// The `Option<Name>` of an ellipsoid.
+    OptName(Option<String>),
+
+    Abbr(String),
+    DimensionKind(DimensionKind),
+    Exactness(Exactness),
+    EcsNum(NumDoc),
+    UnitDef(UnitDefDoc),
+    ConstantUnit(EntityPath),
+    ConstantSource(ConstantSource),
+    AuthorityId(AuthorityId),
+    CsUnit(CsUnit),
+    DatumDef {
+        ellipsoid: Option<EntityPath>,
+        prime_meridian: Option<PrimeMeridian>,
+        frame_epoch: Option<f64>,
+    },
+    OblateSpheroidDef {
+        a: DimensionedConstantDoc,
+        f: FlatteningDoc,
+    },
+    SphereDef {
+        r: DimensionedConstantDoc,
+    },
+    TriaxialEllipsoidDef {
+        a: DimensionedConstantDoc,
+        b: DimensionedConstantDoc,
+        c: DimensionedConstantDoc,
+    },
+    CoordinateSystemDef(CoordinateSystemDef),
+    CrsKind(CrsKind),
+    CoordinateSystemRef(EntityPath),
+    DatumRef(EntityPath),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntityDoc {
+    pub path: EntityPath,
+    pub components: Vec<ComponentDoc>,
+}
+
+/// What [`NamespaceDoc::load`] did.
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    /// The entities added, parents before children.
+    pub created: Vec<Entity>,
+
+    /// The entities that couldn't be loaded, and why. Their descendants were skipped with them.
+    pub skipped: Vec<(EntityPath, anyhow::Error)>,
+}
+
+/// A subtree of the namespace. Parents come before their children.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NamespaceDoc {
+    pub version: u32,
+    pub entities: Vec<EntityDoc>,
+}
+
+//=================================================================================================|
+
+impl NamespaceDoc {
+    pub const VERSION: u32 = 1;
+
+    /// The namespace under `e_top`, and `e_top` itself unless it's the root.
+    pub fn save(world: &World, e_top: Entity) -> Result<Self> {
+        let paths = entity_paths(world)?;
+        let path_of = |e: Entity| {
+            paths
+                .get(&e)
+                .cloned()
+                .with_context(|| format!("{e:?} is not in the namespace"))
+        };
+
+        let mut es = vec![];
+        if world.get::<&RootNamespace>(e_top).is_err() {
+            path_of(e_top)?;
+            es.push(e_top);
+        }
+        es.extend(world.descendants_depth_first::<NamespaceTag>(e_top));
+
+        let entities = es
+            .into_iter()
+            .map(|e| {
+                Ok(EntityDoc {
+                    path: path_of(e)?,
+                    components: save_components(world, e, &path_of)
+                        .with_context(|| format!("saving {:?}", paths[&e]))?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            version: Self::VERSION,
+            entities,
+        })
+    }
+
+    /// Adds the entities the world doesn't already have at their paths. References may be to
+    /// entities in the document or in the world.
+    ///
+    /// An entity the world already has at a path keeps its components, whatever the document
+    /// says, so what the code adds on start takes precedence over what was saved. One that's
+    /// addressed by position, `"#<index>"`, has to match the document though, since it may be an
+    /// unrelated entity that happens to be there.
+    ///
+    /// An entity that can't be loaded is skipped, with its descendants and whatever refers to
+    /// it, and the rest are loaded without it. Only an unsupported version is an error.
+    pub fn load(&self, world: &mut World) -> Result<LoadReport> {
+        ensure!(
+            self.version == Self::VERSION,
+            "NamespaceDoc version {} is not supported",
+            self.version
+        );
+        let had_root = RootNamespace::find(world).is_ok();
+
+        // Each failed attempt is undone and tried again without the entity that failed, so
+        // nothing is left referring to it.
+        let mut skipped: Vec<(EntityPath, anyhow::Error)> = vec![];
+        loop {
+            let mut new_entities = vec![];
+            let mut failed = None;
+            let skip: Vec<&EntityPath> = skipped.iter().map(|(path, _)| path).collect();
+            let result = self.load_impl(world, &skip, &mut new_entities, &mut failed);
+            let Err(e) = result else {
+                return Ok(LoadReport {
+                    created: new_entities,
+                    skipped,
+                });
+            };
+
+            // Children before their parents.
+            for &e in new_entities.iter().rev() {
+                let _ = world.detach::<NamespaceTag>(e);
+                let _ = world.despawn(e);
+            }
+            if !had_root {
+                if let Ok(e_root) = RootNamespace::find(world) {
+                    let _ = world.despawn(e_root);
+                }
+            }
+            match failed {
+                Some(path) => skipped.push((path, e)),
+                None => return Err(e),
+            }
+        }
+    }
+
+    /// Sets `failed` to the path of each entity as it works on it, for [`Self::load`] to skip
+    /// if there's an error.
+    fn load_impl(
+        &self,
+        world: &mut World,
+        skip: &[&EntityPath],
+        new_entities: &mut Vec<Entity>,
+        failed: &mut Option<EntityPath>,
+    ) -> Result<()> {
+        RootNamespace::find_or_create(world)?;
+        let existing_paths = entity_paths(world)?;
+        let existing_path_of = |e: Entity| {
+            existing_paths
+                .get(&e)
+                .cloned()
+                .with_context(|| format!("{e:?} is not in the namespace"))
+        };
+
+        // First the entities with their names, so that paths to them resolve, then the rest of
+        // the components, which may refer to any of them.
+        let mut created = BTreeMap::new();
+        let mut new_docs = vec![];
+        for doc in &self.entities {
+            if skip.iter().any(|path| doc.path.starts_with(path)) {
+                continue;
+            }
+            *failed = Some(doc.path.clone());
+            let Some((element, parent_path)) = doc.path.split_last() else {
+                bail!("An entity has an empty path");
+            };
+            if let Ok(e) = resolve(world, &created, &doc.path) {
+                if element.starts_with('#') {
+                    let existing = save_components(world, e, &existing_path_of)?;
+                    ensure!(
+                        serde_json::to_value(&existing)? == serde_json::to_value(&doc.components)?,
+                        "loading {:?}: the entity there is a different one",
+                        doc.path
+                    );
+                }
+                continue;
+            }
+            let e_parent = resolve(world, &created, parent_path)
+                .with_context(|| format!("loading {:?}", doc.path))?;
+
+            let e = world
+                .attach_new::<NamespaceTag, _>(e_parent, ())
+                .map_err(|err| anyhow!("loading {:?}: {err}", doc.path))?;
+            new_entities.push(e);
+            for c in &doc.components {
+                match c {
+                    ComponentDoc::Name(name) => world.insert_one(e, Name(name.clone()))?,
+                    ComponentDoc::OptName(opt_name) => {
+                        world.insert_one(e, opt_name.clone().map(Name))?
+                    }
+                    _ => {}
+                }
+            }
+            created.insert(doc.path.clone(), e);
+            new_docs.push((e, doc));
+        }
+
+        for (e, doc) in new_docs {
+            *failed = Some(doc.path.clone());
+            for c in &doc.components {
+                load_component(world, &created, e, c)
+                    .with_context(|| format!("loading {:?}", doc.path))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_ron(&self) -> Result<String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .context("NamespaceDoc::to_ron")
+    }
+
+    pub fn from_ron(s: &str) -> Result<Self> {
+        ron::from_str(s).context("NamespaceDoc::from_ron")
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("NamespaceDoc::to_json")
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).context("NamespaceDoc::from_json")
+    }
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// The label a path uses for an entity, before disambiguation.
+fn label(world: &World, e: Entity) -> Option<String> {
+    if let Ok(name) = world.get::<&Name>(e) {
+        return Some(name.0.clone());
+    }
+    let opt_name = world.get::<&Option<Name>>(e).ok()?;
+    opt_name.as_ref().map(|name| name.0.clone())
+}
+
+/// The children of `e_parent`, each with its path element.
+fn child_elements(world: &World, e_parent: Entity) -> Vec<(Entity, String)> {
+    let mut seen = BTreeSet::new();
+    world
+        .children::<NamespaceTag>(e_parent)
+        .enumerate()
+        .map(|(ix, e)| match label(world, e) {
+            Some(s) if !s.starts_with('#') && seen.insert(s.clone()) => (e, s),
+            _ => (e, format!("#{ix}")),
+        })
+        .collect()
+}
+
+fn entity_paths(world: &World) -> Result<BTreeMap<Entity, EntityPath>> {
+    fn walk(
+        world: &World,
+        e_parent: Entity,
+        path: &mut EntityPath,
+        paths: &mut BTreeMap<Entity, EntityPath>,
+    ) {
+        for (e, element) in child_elements(world, e_parent) {
+            path.push(element);
+            paths.insert(e, path.clone());
+            walk(world, e, path, paths);
+            path.pop();
+        }
+    }
+
+    let mut paths = BTreeMap::new();
+    walk(world, RootNamespace::find(world)?, &mut vec![], &mut paths);
+    Ok(paths)
+}
+
+/// Entities created by this load first, then what's in the world.
+fn resolve(
+    world: &World,
+    created: &BTreeMap<EntityPath, Entity>,
+    path: &[String],
+) -> Result<Entity> {
+    let mut e = RootNamespace::find(world)?;
+    for (depth, element) in path.iter().enumerate() {
+        e = match created.get(&path[..=depth]) {
+            Some(&e) => e,
+            None => child_elements(world, e)
+                .into_iter()
+                .find(|(_, s)| s == element)
+                .map(|(e, _)| e)
+                .with_context(|| format!("No entity at {path:?}"))?,
+        };
+    }
+    Ok(e)
+}
+
+//=================================================================================================|
+
+fn save_components(
+    world: &World,
+    e: Entity,
+    path_of: &dyn Fn(Entity) -> Result<EntityPath>,
+) -> Result<Vec<ComponentDoc>> {
+    let num = |n: &EcsNum| save_num(n, path_of);
+    let constant = |dc: &DimensionedConstant| -> Result<DimensionedConstantDoc> {
+        Ok(DimensionedConstantDoc {
+            name: dc.name.0.clone(),
+            dimension_kind: dc.dimension_kind,
+            exactness: dc.exactness,
+            value: num(&dc.value)?,
+        })
+    };
+
+    let mut v = vec![];
+    macro_rules! plain {
+        ($t:ty, $variant:ident) => {
+            if let Ok(c) = world.get::<&$t>(e) {
+                v.push(ComponentDoc::$variant((*c).clone()));
+            }
+        };
+    }
+
+    if let Ok(name) = world.get::<&Name>(e) {
+        v.push(ComponentDoc::Name(name.0.clone()));
+    }
+    if let Ok(opt_name) = world.get::<&Option<Name>>(e) {
+        v.push(ComponentDoc::OptName(
+            opt_name.as_ref().map(|n| n.0.clone()),
+        ));
+    }
+    if let Ok(abbr) = world.get::<&Abbr>(e) {
+        v.push(ComponentDoc::Abbr(abbr.0.clone()));
+    }
+    plain!(DimensionKind, DimensionKind);
+    plain!(Exactness, Exactness);
+    if let Ok(n) = world.get::<&EcsNum>(e) {
+        v.push(ComponentDoc::EcsNum(num(&n)?));
+    }
+    if let Ok(unit_def) = world.get::<&UnitDef>(e) {
+        let unit_def = match &*unit_def {
+            UnitDef::BaseUnit => UnitDefDoc::BaseUnit,
+            UnitDef::ScaleToBaseUnit { scale, base_unit } => UnitDefDoc::ScaleToBaseUnit {
+                scale: num(scale)?,
+                base_unit: path_of(*base_unit)?,
+            },
+            UnitDef::Product { factors } => UnitDefDoc::Product {
+                factors: factors
+                    .iter()
+                    .map(|&(e, exp)| Ok((path_of(e)?, exp)))
+                    .collect::<Result<_>>()?,
+            },
+        };
+        v.push(ComponentDoc::UnitDef(unit_def));
+    }
+    if let Ok(unit) = world.get::<&ConstantUnit>(e) {
+        v.push(ComponentDoc::ConstantUnit(path_of(unit.0)?));
+    }
+    plain!(ConstantSource, ConstantSource);
+    plain!(AuthorityId, AuthorityId);
+    plain!(CsUnit, CsUnit);
+    if let Ok(def) = world.get::<&DatumDef>(e) {
+        v.push(ComponentDoc::DatumDef {
+            ellipsoid: def.ellipsoid.map(|r| path_of(r.entity())).transpose()?,
+            prime_meridian: def.prime_meridian.clone(),
+            frame_epoch: def.frame_epoch,
+        });
+    }
+    if let Ok(def) = world.get::<&OblateSpheroidDef>(e) {
+        let f = match &def.f {
+            Flattening::F(f) => FlatteningDoc::F(num(f)?),
+            Flattening::F_inv(f_inv) => FlatteningDoc::F_inv(num(f_inv)?),
+        };
+        v.push(ComponentDoc::OblateSpheroidDef {
+            a: constant(&def.a)?,
+            f,
+        });
+    }
+    if let Ok(def) = world.get::<&SphereDef>(e) {
+        v.push(ComponentDoc::SphereDef {
+            r: constant(&def.r)?,
+        });
+    }
+    if let Ok(def) = world.get::<&TriaxialEllipsoidDef>(e) {
+        v.push(ComponentDoc::TriaxialEllipsoidDef {
+            a: constant(&def.a)?,
+            b: constant(&def.b)?,
+            c: constant(&def.c)?,
+        });
+    }
+    plain!(CoordinateSystemDef, CoordinateSystemDef);
+    plain!(CrsKind, CrsKind);
+    if let Ok(r) = world.get::<&CoordinateSystemRef>(e) {
+        v.push(ComponentDoc::CoordinateSystemRef(path_of(r.entity())?));
+    }
+    if let Ok(r) = world.get::<&DatumRef>(e) {
+        v.push(ComponentDoc::DatumRef(path_of(r.entity())?));
+    }
+
+    Ok(v)
+}
+
+fn save_num(n: &EcsNum, path_of: &dyn Fn(Entity) -> Result<EntityPath>) -> Result<NumDoc> {
+    Ok(match n {
+        EcsNum::RatioU64(r) => NumDoc::Ratio(*r.numer(), *r.denom()),
+        EcsNum::BigRational(r) => NumDoc::BigRational(r.to_string()),
+        EcsNum::F64(f) => NumDoc::F64(*f),
+        EcsNum::Entity(e) => NumDoc::Entity(path_of(*e)?),
+        EcsNum::Inverse(r) => NumDoc::Inverse(path_of(r.entity())?),
+        EcsNum::Ref(r) => NumDoc::Ref(path_of(r.entity())?),
+    })
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+fn load_component(
+    world: &mut World,
+    created: &BTreeMap<EntityPath, Entity>,
+    e: Entity,
+    c: &ComponentDoc,
+) -> Result<()> {
+    let w: &World = world;
+    let entity = |path: &EntityPath| resolve(w, created, path);
+    let num = |n: &NumDoc| load_num(w, created, n);
+    let constant = |dc: &DimensionedConstantDoc| -> Result<DimensionedConstant> {
+        Ok(DimensionedConstant {
+            name: Name(dc.name.clone()),
+            dimension_kind: dc.dimension_kind,
+            exactness: dc.exactness,
+            value: num(&dc.value)?,
+        })
+    };
+
+    // Each arm makes the component from the document and the world, then inserts it.
+    macro_rules! insert {
+        ($c:expr) => {{
+            let c = $c;
+            world.insert_one(e, c)?;
+        }};
+    }
+
+    match c {
+        // Added with the entity.
+        ComponentDoc::Name(_) | ComponentDoc::OptName(_) => {}
+
+        ComponentDoc::Abbr(abbr) => insert!(Abbr(abbr.clone())),
+        ComponentDoc::DimensionKind(dk) => insert!(*dk),
+        ComponentDoc::Exactness(exactness) => insert!(*exactness),
+        ComponentDoc::EcsNum(n) => insert!(num(n)?),
+        ComponentDoc::UnitDef(unit_def) => insert!(match unit_def {
+            UnitDefDoc::BaseUnit => UnitDef::BaseUnit,
+            UnitDefDoc::ScaleToBaseUnit { scale, base_unit } => UnitDef::ScaleToBaseUnit {
+                scale: num(scale)?,
+                base_unit: entity(base_unit)?,
+            },
+            UnitDefDoc::Product { factors } => UnitDef::Product {
+                factors: factors
+                    .iter()
+                    .map(|(path, exp)| Ok((entity(path)?, *exp)))
+                    .collect::<Result<_>>()?,
+            },
+        }),
+        ComponentDoc::ConstantUnit(path) => insert!(ConstantUnit(entity(path)?)),
+        ComponentDoc::ConstantSource(source) => insert!(*source),
+        ComponentDoc::AuthorityId(id) => insert!(id.clone()),
+        ComponentDoc::CsUnit(cs_unit) => insert!(cs_unit.clone()),
+        ComponentDoc::DatumDef {
+            ellipsoid,
+            prime_meridian,
+            frame_epoch,
+        } => insert!(DatumDef {
+            ellipsoid: ellipsoid
+                .as_ref()
+                .map(|path| Ok::<_, anyhow::Error>(OblateSpheroidRef(entity(path)?)))
+                .transpose()?,
+            prime_meridian: prime_meridian.clone(),
+            frame_epoch: *frame_epoch,
+        }),
+        ComponentDoc::OblateSpheroidDef { a, f } => insert!(OblateSpheroidDef {
+            a: constant(a)?,
+            f: match f {
+                FlatteningDoc::F(f) => Flattening::F(num(f)?),
+                FlatteningDoc::F_inv(f_inv) => Flattening::F_inv(num(f_inv)?),
+            },
+        }),
+        ComponentDoc::SphereDef { r } => insert!(SphereDef { r: constant(r)? }),
+        ComponentDoc::TriaxialEllipsoidDef { a, b, c } => insert!(TriaxialEllipsoidDef {
+            a: constant(a)?,
+            b: constant(b)?,
+            c: constant(c)?,
+        }),
+        ComponentDoc::CoordinateSystemDef(def) => insert!(def.clone()),
+        ComponentDoc::CrsKind(kind) => insert!(*kind),
+        ComponentDoc::CoordinateSystemRef(path) => insert!(CoordinateSystemRef(entity(path)?)),
+        ComponentDoc::DatumRef(path) => insert!(DatumRef(entity(path)?)),
+    }
+    Ok(())
+}
+
+fn load_num(world: &World, created: &BTreeMap<EntityPath, Entity>, n: &NumDoc) -> Result<EcsNum> {
+    let entity = |path: &EntityPath| resolve(world, created, path);
+    Ok(match n {
+        NumDoc::Ratio(numer, denom) => {
+            ensure!(*denom != 0, "Ratio({numer}, 0)");
+
+            // As saved, which isn't always in lowest terms.
+            EcsNum::RatioU64(RatioU64::new_raw(*numer, *denom))
+        }
+        NumDoc::BigRational(s) => EcsNum::BigRational(
+            s.parse()
+                .map_err(|err| anyhow!("BigRational({s:?}): {err:?}"))?,
+        ),
+        NumDoc::F64(f) => EcsNum::F64(*f),
+        NumDoc::Entity(path) => EcsNum::Entity(entity(path)?),
+        NumDoc::Inverse(path) => EcsNum::Inverse(EcsNumRef(entity(path)?)),
+        NumDoc::Ref(path) => EcsNum::Ref(EcsNumRef(entity(path)?)),
+    })
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use crate::gis::{crs_to_wkt, ecs_add_crs_from_wkt};
+    use crate::units::ecs_add_compound_unit;
+    use insta::assert_ron_snapshot;
+
+    const SITE_CRS: &str = r#"GEOGCRS["Site",DATUM["Site datum",ELLIPSOID["WGS 84",6378137,298.257223563,LENGTHUNIT["metre",1]]],PRIMEM["Greenwich",0,ANGLEUNIT["degree",0.0174532925199433]],CS[ellipsoidal,2],AXIS["latitude",north,ORDER[1],ANGLEUNIT["degree",0.0174532925199433]],AXIS["longitude",east,ORDER[2],ANGLEUNIT["degree",0.0174532925199433]],ID["USER",1]]"#;
+
+    /// What the app starts with, and some things a user might add to it.
+    fn user_world() -> Result<(World, Entity)> {
+        let mut world = World::new();
+        crate::ecs_add_stuff(&mut world);
+        let world_ = &mut world;
+        let ns_user = ecs_ns_find_or_create(world_, NamePathSpec::absolute(["user"]))?;
+
+        let km = Unit::look_up_name_or_abbr(world_, "km")?;
+        let h = Unit::look_up_name_or_abbr(world_, "hour")?;
+        let m = Unit::look_up_name_or_abbr(world_, "m")?;
+        ecs_add_compound_unit(
+            world_,
+            ns_user,
+            "kilometer per hour",
+            "km/h",
+            &[(km, 1), (h, -1)],
+        )?;
+
+        let ns_site = world_.attach_new::<NamespaceTag, _>(ns_user, (Name::from("site"),))?;
+        let dc = DimensionedConstant {
+            name: Name::from("radius"),
+            dimension_kind: DimensionKind::LENGTH,
+            exactness: Exactness::Exact(ExactReason::ByDefinition),
+            value: EcsNum::Entity(DimensionedConstant::find(world_, "wgs84", "a")?),
+        };
+        let e = world_.attach_new::<NamespaceTag, _>(ns_site, dc)?;
+        world_.insert_one(e, ConstantUnit(m))?;
+
+        let datum_def = DatumDef {
+            ellipsoid: Some(OblateSpheroidRef::find(world_, "WGS 84")?),
+            prime_meridian: None,
+            frame_epoch: Some(2020.0),
+        };
+        crate::gis::datum::ecs_add_datum(world_, ns_site, "site datum", datum_def)?;
+        ecs_add_crs_from_wkt(world_, ns_site, SITE_CRS)?;
+
+        Ok((world, ns_user))
+    }
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let (world, ns_user) = user_world()?;
+        let path = |world: &World, path: &[&str]| {
+            let path: EntityPath = path.iter().map(|s| s.to_string()).collect();
+            resolve(world, &BTreeMap::new(), &path)
+        };
+
+        let doc = NamespaceDoc::save(&world, ns_user)?;
+        let paths: Vec<_> = doc.entities.iter().map(|ed| ed.path.join("/")).collect();
+        assert_ron_snapshot!(format!("{paths:?}"), @r#""[\"user\", \"user/kilometer per hour\", \"user/site\", \"user/site/radius\", \"user/site/site datum\", \"user/site/WGS 84\", \"user/site/Site datum\", \"user/site/Site CS\", \"user/site/Site\"]""#);
+        assert_ron_snapshot!(format!("{:?}", doc.entities[1].components), @r#""[Name(\"kilometer per hour\"), Abbr(\"km/h\"), DimensionKind(DimensionKind([1, 0, -1, 0, 0])), UnitDef(Product { factors: [([\"units\", \"length\", \"meter\", \"kilometer\"], 1), ([\"units\", \"time\", \"hour\"], -1)] })]""#);
+        let ron = doc.to_ron()?;
+        let json = doc.to_json()?;
+
+        // Into a world as the app starts with.
+        for doc in [
+            NamespaceDoc::from_ron(&ron)?,
+            NamespaceDoc::from_json(&json)?,
+        ] {
+            let mut world2 = World::new();
+            crate::ecs_add_stuff(&mut world2);
+            let world2 = &mut world2;
+            let report = doc.load(world2)?;
+            assert!(report.skipped.is_empty(), "{:?}", report.skipped);
+            assert_eq!(report.created.len(), doc.entities.len());
+
+            let ns_user2 = path(world2, &["user"])?;
+            assert_eq!(NamespaceDoc::save(world2, ns_user2)?.to_ron()?, ron);
+            assert_eq!(NamespaceDoc::save(world2, ns_user2)?.to_json()?, json);
+
+            // References into the rest of the world resolve there.
+            let unit = |name: &str| Unit::look_up_name_or_abbr(world2, name).unwrap();
+            let v = EcsNum::RatioU64(RatioU64::from_integer(36));
+            assert_eq!(
+                convert(world2, &v, unit("km/h"), unit("m/s"))?.to_string(),
+                "10"
+            );
+            let e_radius = path(world2, &["user", "site", "radius"])?;
+            assert_eq!(
+                world2
+                    .get::<&EcsNum>(e_radius)?
+                    .evaluate(world2)?
+                    .to_string(),
+                "6378137"
+            );
+            let e_crs = path(&world, &["user", "site", "Site"])?;
+            let e_crs2 = path(world2, &["user", "site", "Site"])?;
+            assert_eq!(crs_to_wkt(world2, e_crs2)?, crs_to_wkt(&world, e_crs)?);
+
+            // What's already there stays.
+            assert!(doc.load(world2)?.created.is_empty());
+        }
+
+        // The whole namespace, into an empty world.
+        let doc = NamespaceDoc::save(&world, RootNamespace::find(&world)?)?;
+        let mut world3 = World::new();
+        let report = doc.load(&mut world3)?;
+        assert_eq!(report.created.len(), doc.entities.len());
+        let doc3 = NamespaceDoc::save(&world3, RootNamespace::find(&world3)?)?;
+        assert_eq!(doc3.to_ron()?, doc.to_ron()?);
+
+        // References must be to entities in the namespace.
+        let mut world4 = World::new();
+        let e = world4.spawn((Name::from("stray"),));
+        let ns_root = RootNamespace::find_or_create(&mut world4)?;
+        world4.attach_new::<NamespaceTag, _>(ns_root, (ConstantUnit(e),))?;
+        assert!(NamespaceDoc::save(&world4, ns_root).is_err());
+
+        let doc = NamespaceDoc::from_ron(
+            r#"(version: 1, entities: [(path: ["a", "b"], components: [])])"#,
+        )?;
+        let report = doc.load(&mut World::new())?;
+        assert!(report.created.is_empty() && report.skipped.len() == 1);
+        assert!(
+            NamespaceDoc::from_json(r#"{"version": 2, "entities": []}"#)?
+                .load(&mut World::new())
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn t_load_errors() -> anyhow::Result<()> {
+        let (mut world, ns_user) = user_world()?;
+        let world = &mut world;
+        let save_all = |world: &World| NamespaceDoc::save(world, RootNamespace::find(world)?);
+        let len = world.len();
+
+        // A bad reference skips its entity, what's under it and what refers to it. The rest
+        // loads.
+        let doc = NamespaceDoc::from_ron(
+            r#"(version: 1, entities: [
+                (path: ["more"], components: [Name("more")]),
+                (path: ["more", "c"], components: [Name("c"), ConstantUnit(["nowhere"])]),
+                (path: ["more", "c", "d"], components: [Name("d")]),
+                (path: ["more", "e"], components: [Name("e"), ConstantUnit(["more", "c"])]),
+                (path: ["more", "f"], components: [Name("f")]),
+            ])"#,
+        )?;
+        let skipped_paths = |report: &LoadReport| -> Vec<String> {
+            report
+                .skipped
+                .iter()
+                .map(|(path, _)| path.join("/"))
+                .collect()
+        };
+        let report = doc.load(world)?;
+        assert_eq!(skipped_paths(&report), ["more/c", "more/e"]);
+        assert_eq!(report.created.len(), 2);
+        assert_eq!(world.len(), len + 2);
+        let paths: Vec<_> = save_all(world)?
+            .entities
+            .iter()
+            .map(|ed| ed.path.join("/"))
+            .collect();
+        assert!(paths.contains(&"more/f".to_string()));
+        assert!(!paths
+            .iter()
+            .any(|p| p.starts_with("more/c") || p == "more/e"));
+
+        // In an empty world too.
+        let mut empty = World::new();
+        let report = doc.load(&mut empty)?;
+        assert_eq!(skipped_paths(&report), ["more/c", "more/e"]);
+        assert_eq!(empty.len(), 3);
+
+        // An entity addressed by position has to be the one in the document.
+        world.attach_new::<NamespaceTag, _>(ns_user, (Abbr("x".into()),))?;
+        let doc = NamespaceDoc::save(world, ns_user)?;
+        let unnamed = doc.entities.last().unwrap();
+        assert_ron_snapshot!(format!("{:?}", unnamed.path), @r##""[\"user\", \"#2\"]""##);
+        assert!(doc.load(world)?.created.is_empty());
+        let mut other = doc.clone();
+        other.entities.last_mut().unwrap().components = vec![ComponentDoc::Abbr("y".into())];
+        let report = other.load(world)?;
+        assert_eq!(skipped_paths(&report), ["user/#2"]);
+        assert!(report.created.is_empty());
+
+        Ok(())
+    }
+}
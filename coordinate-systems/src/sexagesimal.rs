@@ -0,0 +1,338 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! Sexagesimal angles: degrees, arcminutes and arcseconds (`-23° 26′ 21.4″`, `N 47°36.5′`), and
+//! hours, minutes and seconds of right ascension (`12h 34m 56.7s`, `12ʰ34ᵐ56.7ˢ`).
+//!
+//! Parsing is exact, in degrees or hours of right ascension, so a formatted value parses back to
+//! the same number to the precision it was formatted with.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::Display;
+use hecs::{Entity, World};
+//? use hecs_hierarchy::{Hierarchy, HierarchyMut, HierarchyQuery};
+//? use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+//? use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use crate::units::{numeric_prefix_len, parse_decimal};
+use crate::*;
+
+//=================================================================================================|
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Family {
+    Degrees,
+    Hours,
+}
+
+impl Family {
+    fn unit(self, world: &World) -> Result<Entity> {
+        let name = match self {
+            Family::Degrees => "degree",
+            Family::Hours => "hour of right ascension",
+        };
+        Unit::look_up(world, DimensionKind::ANGLE, name).ok_or_else(|| anyhow!("No {name} unit"))
+    }
+
+    fn symbols(self) -> [char; 3] {
+        match self {
+            Family::Degrees => ['°', '′', '″'],
+            Family::Hours => ['h', 'm', 's'],
+        }
+    }
+}
+
+/// The family and place, 0 to 2, of a symbol following a number.
+fn symbol_place(symbol: char) -> Option<(Family, u32)> {
+    Some(match symbol {
+        '°' => (Family::Degrees, 0),
+        '′' | '\'' => (Family::Degrees, 1),
+        '″' | '"' => (Family::Degrees, 2),
+        'h' | 'ʰ' => (Family::Hours, 0),
+        'm' | 'ᵐ' => (Family::Hours, 1),
+        's' | 'ˢ' => (Family::Hours, 2),
+        _ => return None,
+    })
+}
+
+/// Whether `s` should be parsed as sexagesimal rather than as a number and a unit. A plain
+/// `"12 h"` is a time, but `"12h 34m"` is a right ascension.
+pub(crate) fn is_sexagesimal(s: &str) -> bool {
+    if s.contains(['°', '′', '″', 'ʰ', 'ᵐ', 'ˢ']) {
+        return true;
+    }
+    let s = s.trim_start_matches(['+', '-']);
+    let len = numeric_prefix_len(s);
+    0 < len
+        && s[len..]
+            .strip_prefix('h')
+            .is_some_and(|rest| rest.trim_start().starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// A leading or trailing `N`, `E`, `S` or `W`, as whether it makes the value negative.
+fn strip_hemisphere(s: &str) -> (Option<bool>, &str) {
+    let negative = |c: char| match c {
+        'N' | 'E' => Some(false),
+        'S' | 'W' => Some(true),
+        _ => None,
+    };
+    if let Some(neg) = s.chars().next().and_then(negative) {
+        return (Some(neg), s[1..].trim_start());
+    }
+    if let Some(neg) = s.chars().next_back().and_then(negative) {
+        return (Some(neg), s[..s.len() - 1].trim_end());
+    }
+    (None, s)
+}
+
+pub(crate) fn parse(world: &World, s: &str) -> Result<Quantity> {
+    let (hemisphere, rest) = strip_hemisphere(s.trim());
+    let (sign, mut rest) = match (rest.strip_prefix('-'), rest.strip_prefix('+')) {
+        (Some(rest), _) => (Some(true), rest),
+        (_, Some(rest)) => (Some(false), rest),
+        _ => (None, rest),
+    };
+    ensure!(
+        hemisphere.is_none() || sign.is_none(),
+        "Both a sign and a hemisphere in {s:?}"
+    );
+    let negative = hemisphere.or(sign).unwrap_or(false);
+
+    let mut family = None;
+    let mut value = EcsNum::RatioU64(RatioU64::from_integer(0));
+    let mut next_place = 0;
+    while !rest.is_empty() {
+        ensure!(!rest.starts_with(['+', '-']), "Misplaced sign in {s:?}");
+        let len = numeric_prefix_len(rest);
+        let number = parse_decimal(&rest[..len])?;
+        rest = rest[len..].trim_start();
+
+        let symbol = rest
+            .chars()
+            .next()
+            .ok_or_else(|| anyhow!("Missing °, ′, ″ or h, m, s in {s:?}"))?;
+        let (symbol_family, place) =
+            symbol_place(symbol).with_context(|| format!("Unexpected {symbol:?} in {s:?}"))?;
+        ensure!(
+            *family.get_or_insert(symbol_family) == symbol_family,
+            "Mixed degrees and hours in {s:?}"
+        );
+        ensure!(next_place <= place, "Out of order {symbol:?} in {s:?}");
+        if 0 < place {
+            let sixty = EcsNum::RatioU64(RatioU64::from_integer(60));
+            ensure!(
+                number.sub(&sixty)?.to_f64()? < 0.0,
+                "{number}{symbol} is out of range in {s:?}"
+            );
+        }
+        next_place = place + 1;
+        rest = rest[symbol.len_utf8()..].trim_start();
+
+        let per_first_place = RatioU64::from_integer(60_u64.pow(place));
+        value = value.add(&number.div(&EcsNum::RatioU64(per_first_place))?)?;
+    }
+    let family = family.with_context(|| format!("No degrees or hours in {s:?}"))?;
+
+    Ok(Quantity {
+        value: if negative { value.neg()? } else { value },
+        unit: family.unit(world)?,
+    })
+}
+
+//=================================================================================================|
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SexagesimalStyle {
+    /// `-23° 26′ 21.4″`
+    Degrees,
+
+    /// `N 47° 36.5′`, or `S`.
+    Latitude,
+
+    /// `E 122° 19′ 55″`, or `W`.
+    Longitude,
+
+    /// `12h 34m 56.7s`
+    Hours,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SexagesimalFormat {
+    pub style: SexagesimalStyle,
+
+    /// 1 for degrees or hours only, 2 to include minutes, 3 to include seconds.
+    pub places: u32,
+
+    /// Digits after the decimal point of the last place.
+    pub decimals: u32,
+}
+
+impl SexagesimalFormat {
+    pub fn new(style: SexagesimalStyle, places: u32, decimals: u32) -> Self {
+        Self {
+            style,
+            places,
+            decimals,
+        }
+    }
+}
+
+impl Quantity {
+    /// Formats an angle in degrees or hours, rounded to the last place shown.
+    pub fn format_sexagesimal(&self, world: &World, format: SexagesimalFormat) -> Result<String> {
+        let SexagesimalFormat {
+            style,
+            places,
+            decimals,
+        } = format;
+        ensure!((1..=3).contains(&places), "{places} sexagesimal places");
+        ensure!(decimals <= 9, "{decimals} decimals");
+
+        let family = match style {
+            SexagesimalStyle::Hours => Family::Hours,
+            _ => Family::Degrees,
+        };
+        let x = self.convert_to(world, family.unit(world)?)?.to_f64()?;
+
+        // Round once, as an integer count of the smallest step, so that carries are exact.
+        let scale = 10_u64.pow(decimals);
+        let steps = (x.abs() * (60_u64.pow(places - 1) * scale) as f64).round();
+        ensure!(
+            steps < 2.0_f64.powi(63),
+            "{x} is out of range for {places} places"
+        );
+        let steps = steps as u64;
+
+        let mut whole = steps / scale;
+        let mut values = [0_u64; 3];
+        for ix in (1..places as usize).rev() {
+            values[ix] = whole % 60;
+            whole /= 60;
+        }
+        values[0] = whole;
+
+        let negative = x < 0.0 && steps != 0;
+        let mut s = match (style, negative) {
+            (SexagesimalStyle::Latitude, false) => "N ".to_string(),
+            (SexagesimalStyle::Latitude, true) => "S ".to_string(),
+            (SexagesimalStyle::Longitude, false) => "E ".to_string(),
+            (SexagesimalStyle::Longitude, true) => "W ".to_string(),
+            (_, true) => "-".to_string(),
+            (_, false) => String::new(),
+        };
+
+        let symbols = family.symbols();
+        for ix in 0..places as usize {
+            if ix == 0 {
+                s += &values[ix].to_string();
+            } else {
+                s += &format!(" {:02}", values[ix]);
+            }
+            if ix + 1 == places as usize && 0 < decimals {
+                s += &format!(".{:0width$}", steps % scale, width = decimals as usize);
+            }
+            s.push(symbols[ix]);
+        }
+
+        Ok(s)
+    }
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use insta::assert_ron_snapshot;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        use SexagesimalStyle::*;
+
+        let mut world = World::new();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let parse = |s: &str| Quantity::parse(world, s);
+        let format = |q: &Quantity, style, places, decimals| {
+            q.format_sexagesimal(world, SexagesimalFormat::new(style, places, decimals))
+        };
+
+        // Right ascension.
+        let ra = parse("12h 34m 56.7s")?;
+        assert_ron_snapshot!(ra.value.to_string(), @r#""150989/12000""#);
+        assert_ron_snapshot!(format(&ra, Hours, 3, 1)?, @r#""12h 34m 56.7s""#);
+        assert_ron_snapshot!(format(&ra, Degrees, 3, 2)?, @r#""188° 44′ 10.50″""#);
+        assert_ron_snapshot!(parse("12ʰ34ᵐ56.7ˢ")?.value.to_string(), @r#""150989/12000""#);
+        assert_ron_snapshot!(parse("12 h")?.convert_to(world, Unit::look_up_name_or_abbr(world, "min")?)?.to_string(), @r#""720""#);
+
+        // Degrees, signed or with a hemisphere.
+        let obliquity = parse("-23° 26′ 21.4″")?;
+        assert_ron_snapshot!(format(&obliquity, Degrees, 3, 1)?, @r#""-23° 26′ 21.4″""#);
+        assert_ron_snapshot!(format(&obliquity, Latitude, 2, 3)?, @r#""S 23° 26.357′""#);
+        let seattle = parse("N 47°36.5′")?;
+        assert_ron_snapshot!(seattle.value.to_string(), @r#""5713/120""#);
+        assert_ron_snapshot!(format(&seattle, Latitude, 2, 1)?, @r#""N 47° 36.5′""#);
+        assert_ron_snapshot!(parse("122°19′55″W")?.value.to_string(), @r#""-88079/720""#);
+
+        // Rounding carries all the way up.
+        assert_ron_snapshot!(format(&parse("29°59′59.96″")?, Degrees, 3, 1)?, @r#""30° 00′ 00.0″""#);
+        assert_ron_snapshot!(format(&parse("-0.00001°")?, Degrees, 3, 0)?, @r#""0° 00′ 00″""#);
+
+        // Formatted values parse back exactly.
+        for s in [
+            "12h 34m 56.7s",
+            "-23° 26′ 21.4″",
+            "N 47° 36.5′",
+            "W 0° 00′ 05.25″",
+        ] {
+            let q = parse(s)?;
+            let (style, places, decimals) = match s.chars().next() {
+                Some('1') => (Hours, 3, 1),
+                Some('N') => (Latitude, 2, 1),
+                Some('W') => (Longitude, 3, 2),
+                _ => (Degrees, 3, 1),
+            };
+            let formatted = format(&q, style, places, decimals)?;
+            assert_eq!(formatted, s);
+            assert_eq!(parse(&formatted)?.value.to_string(), q.value.to_string());
+        }
+
+        // Errors.
+        assert!(parse("12h 34′").is_err());
+        assert!(parse("N -10°").is_err());
+        assert!(parse("10° -5′").is_err());
+        assert!(parse("12h 60m").is_err());
+        assert!(format(&ra, Degrees, 4, 0).is_err());
+
+        Ok(())
+    }
+}
@@ -48,8 +48,17 @@ pub enum UnitDef {
         scale: EcsNum,
         base_unit: Entity,
     },
+
+    /// A product of powers of other units, e.g. meters per second is `[(meter, 1), (second, -1)]`.
+    #[display("Product {{ factors: {:?} }}", factors)]
+    Product {
+        factors: Vec<(Entity, i8)>,
+    },
 }
 
+/// A product of powers of `BaseUnit`s, sorted by Entity.
+pub type BaseUnits = Vec<(Entity, i8)>;
+
 #[derive(Bundle, Debug, Display, Clone)]
 #[display("Unit {{ {:?}, {:?}, {dimension_kind}, {unit_def} }}", name.to_string(), abbr.to_string())]
 pub struct Unit {
@@ -81,6 +90,280 @@ impl Unit {
 
         None
     }
+
+    /// Finds a Unit of any dimension by its name or abbreviation.
+    pub fn look_up_name_or_abbr(world: &World, name_or_abbr: &str) -> Result<Entity> {
+        let mut found = None;
+        for (e, (na, abbr)) in world.query::<(&Name, &Abbr)>().with::<&UnitDef>().iter() {
+            if na.as_str() == name_or_abbr || abbr.as_str() == name_or_abbr {
+                ensure!(found.is_none(), "Ambiguous unit {name_or_abbr:?}");
+                found = Some(e);
+            }
+        }
+        found.ok_or_else(|| anyhow!("No unit named {name_or_abbr:?}"))
+    }
+
+    /// Follows the `ScaleToBaseUnit` chains and `Product` factors from `unit`, returning the
+    /// accumulated scale and the base units they end at.
+    pub fn scale_to_base(world: &World, unit: Entity) -> Result<(EcsNum, BaseUnits)> {
+        Self::scale_to_base_impl(world, unit, &mut Vec::new())
+    }
+
+    fn scale_to_base_impl(
+        world: &World,
+        e: Entity,
+        visiting: &mut Vec<Entity>,
+    ) -> Result<(EcsNum, BaseUnits)> {
+        ensure!(!visiting.contains(&e), "Cycle in units at {e:?}");
+
+        let unit_def = (*world
+            .get::<&UnitDef>(e)
+            .with_context(|| format!("{e:?} is not a Unit"))?)
+        .clone();
+
+        visiting.push(e);
+        let scale_and_base_units = match unit_def {
+            UnitDef::BaseUnit => (one(), vec![(e, 1)]),
+            UnitDef::ScaleToBaseUnit { scale, base_unit } => {
+                let (base_scale, base_units) =
+                    Self::scale_to_base_impl(world, base_unit, visiting)?;
+                (scale.evaluate(world)?.mul(&base_scale)?, base_units)
+            }
+            UnitDef::Product { factors } => {
+                let mut scale = one();
+                let mut base_units = std::collections::BTreeMap::<Entity, i8>::new();
+                for (factor, exp) in factors {
+                    let (factor_scale, factor_base_units) =
+                        Self::scale_to_base_impl(world, factor, visiting)?;
+
+                    let mut factor_scale = factor_scale.pow(&EcsNum::RatioU64(
+                        RatioU64::from_integer(exp.unsigned_abs().into()),
+                    ))?;
+                    if exp < 0 {
+                        factor_scale = factor_scale.recip()?;
+                    }
+                    scale = scale.mul(&factor_scale)?;
+
+                    for (base_unit, base_exp) in factor_base_units {
+                        let acc = base_units.entry(base_unit).or_default();
+                        *acc = base_exp
+                            .checked_mul(exp)
+                            .and_then(|x| acc.checked_add(x))
+                            .ok_or_else(|| anyhow!("Unit exponent overflow in {e:?}"))?;
+                    }
+                }
+                base_units.retain(|_, exp| *exp != 0);
+                (scale, base_units.into_iter().collect())
+            }
+        };
+        visiting.pop();
+
+        Ok(scale_and_base_units)
+    }
+
+    pub fn dimension_kind(world: &World, unit: Entity) -> Result<DimensionKind> {
+        Ok(*world
+            .get::<&DimensionKind>(unit)
+            .with_context(|| format!("{unit:?} is not a Unit"))?)
+    }
+}
+
+fn one() -> EcsNum {
+    EcsNum::RatioU64(RatioU64::from_integer(1))
+}
+
+/// Converts `value` from `from_unit` to `to_unit`. Exact if the value and all the scales along
+/// both chains are.
+pub fn convert(
+    world: &World,
+    value: &EcsNum,
+    from_unit: Entity,
+    to_unit: Entity,
+) -> Result<EcsNum> {
+    let dk_from = Unit::dimension_kind(world, from_unit)?;
+    let dk_to = Unit::dimension_kind(world, to_unit)?;
+    ensure!(dk_from == dk_to, "Can't convert {dk_from} to {dk_to}");
+
+    let (scale_from, base_from) = Unit::scale_to_base(world, from_unit)?;
+    let (scale_to, base_to) = Unit::scale_to_base(world, to_unit)?;
+    ensure!(
+        base_from == base_to,
+        "Units {from_unit:?} and {to_unit:?} have no common base unit"
+    );
+
+    value.evaluate(world)?.mul(&scale_from)?.div(&scale_to)
+}
+
+//=================================================================================================|
+
+/// A value with a unit, as parsed from strings like `"6378.137 km"`, `"23°26′21″"` or
+/// `"12h 34m 56.7s"`.
+#[derive(Debug, Display, Clone)]
+#[display("{value} {:?}", unit)]
+pub struct Quantity {
+    pub value: EcsNum,
+    pub unit: Entity,
+}
+
+impl Quantity {
+    pub fn parse(world: &World, s: &str) -> Result<Quantity> {
+        let s = s.trim();
+        if crate::sexagesimal::is_sexagesimal(s) {
+            return crate::sexagesimal::parse(world, s);
+        }
+
+        let (number, unit) = s.split_at(numeric_prefix_len(s));
+        let unit = unit.trim();
+        ensure!(!unit.is_empty(), "No unit in {s:?}");
+
+        Ok(Quantity {
+            value: parse_decimal(number)?,
+            unit: Unit::look_up_name_or_abbr(world, unit)?,
+        })
+    }
+
+    pub fn convert_to(&self, world: &World, to_unit: Entity) -> Result<EcsNum> {
+        convert(world, &self.value, self.unit, to_unit)
+    }
+
+    pub fn to_measure(&self, world: &World) -> Result<Measure> {
+        let (scale, _) = Unit::scale_to_base(world, self.unit)?;
+        Ok(Measure {
+            value: self.value.evaluate(world)?.mul(&scale)?,
+            dimension_kind: Unit::dimension_kind(world, self.unit)?,
+        })
+    }
+}
+
+//=================================================================================================|
+
+/// A value in base units, tagged with its dimension, so that arithmetic can check dimensions at
+/// runtime. This relies on there being one `BaseUnit` for each [`BaseDimension`].
+#[derive(Debug, Display, Clone)]
+#[display("{value} {dimension_kind}")]
+pub struct Measure {
+    pub value: EcsNum,
+    pub dimension_kind: DimensionKind,
+}
+
+impl Measure {
+    pub fn new(value: EcsNum, dimension_kind: DimensionKind) -> Self {
+        Self {
+            value,
+            dimension_kind,
+        }
+    }
+
+    pub fn add(&self, rhs: &Measure) -> Result<Measure> {
+        self.ensure_same_dimension(rhs, "add")?;
+        Ok(Self::new(self.value.add(&rhs.value)?, self.dimension_kind))
+    }
+
+    pub fn sub(&self, rhs: &Measure) -> Result<Measure> {
+        self.ensure_same_dimension(rhs, "subtract")?;
+        Ok(Self::new(self.value.sub(&rhs.value)?, self.dimension_kind))
+    }
+
+    pub fn mul(&self, rhs: &Measure) -> Result<Measure> {
+        Ok(Self::new(
+            self.value.mul(&rhs.value)?,
+            self.dimension_kind.mul(&rhs.dimension_kind)?,
+        ))
+    }
+
+    pub fn div(&self, rhs: &Measure) -> Result<Measure> {
+        Ok(Self::new(
+            self.value.div(&rhs.value)?,
+            self.dimension_kind.div(&rhs.dimension_kind)?,
+        ))
+    }
+
+    pub fn powi(&self, n: i8) -> Result<Measure> {
+        let mut value = self.value.pow(&EcsNum::RatioU64(RatioU64::from_integer(
+            n.unsigned_abs().into(),
+        )))?;
+        if n < 0 {
+            value = value.recip()?;
+        }
+        Ok(Self::new(value, self.dimension_kind.powi(n)?))
+    }
+
+    /// The value expressed in `unit`, which must be of the same dimension.
+    pub fn in_unit(&self, world: &World, unit: Entity) -> Result<EcsNum> {
+        let dk_unit = Unit::dimension_kind(world, unit)?;
+        ensure!(
+            self.dimension_kind == dk_unit,
+            "Can't express {} in a unit of {dk_unit}",
+            self.dimension_kind
+        );
+        let (scale, _) = Unit::scale_to_base(world, unit)?;
+        self.value.div(&scale)
+    }
+
+    fn ensure_same_dimension(&self, rhs: &Measure, verb: &str) -> Result<()> {
+        ensure!(
+            self.dimension_kind == rhs.dimension_kind,
+            "Can't {verb} {} and {}",
+            self.dimension_kind,
+            rhs.dimension_kind
+        );
+        Ok(())
+    }
+}
+
+/// The length of the leading `[+-]digits[.digits][e[+-]digits]`, if any.
+pub(crate) fn numeric_prefix_len(s: &str) -> usize {
+    let b = s.as_bytes();
+    let digits = |mut i: usize| {
+        while i < b.len() && b[i].is_ascii_digit() {
+            i += 1;
+        }
+        i
+    };
+
+    let mut i = usize::from(matches!(b.first(), Some(b'+' | b'-')));
+    i = digits(i);
+    if b.get(i) == Some(&b'.') {
+        i = digits(i + 1);
+    }
+    if matches!(b.get(i), Some(b'e' | b'E')) {
+        let j = i + 1 + usize::from(matches!(b.get(i + 1), Some(b'+' | b'-')));
+        if b.get(j).is_some_and(u8::is_ascii_digit) {
+            i = digits(j);
+        }
+    }
+    i
+}
+
+/// Parses a decimal number exactly, e.g. `"6378.137"` is `6378137/1000`.
+pub(crate) fn parse_decimal(s: &str) -> Result<EcsNum> {
+    const MAX_EXP10: i64 = 1000;
+
+    let (mantissa, exp10) = match s.find(['e', 'E']) {
+        Some(i) => (&s[..i], s[i + 1..].parse::<i64>().context("Bad exponent")?),
+        None => (s, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let int_digits = int_part.trim_start_matches(['+', '-']);
+    ensure!(
+        (!int_digits.is_empty() || !frac_part.is_empty())
+            && (int_digits.chars().chain(frac_part.chars())).all(|c| c.is_ascii_digit()),
+        "Not a number: {s:?}"
+    );
+
+    let exp10 = exp10 - frac_part.len() as i64;
+    ensure!(exp10.abs() <= MAX_EXP10, "Exponent out of range: {s:?}");
+    let zeros = "0".repeat(exp10.unsigned_abs() as usize);
+    let (numer, denom) = if exp10 < 0 {
+        (format!("{int_part}{frac_part}"), format!("1{zeros}"))
+    } else {
+        (format!("{int_part}{frac_part}{zeros}"), "1".to_string())
+    };
+
+    let r: BigRational = format!("{numer}/{denom}")
+        .parse()
+        .map_err(|_| anyhow!("Not a number: {s:?}"))?;
+    Ok(crate::eval::from_bigrational(r))
 }
 
 fn ecs_add_unit(
@@ -109,6 +392,32 @@ fn ecs_add_unit(
     e
 }
 
+/// Adds a `Product` unit, with the dimension following from its factors.
+pub fn ecs_add_compound_unit(
+    world: &mut World,
+    e_ns_parent: Entity,
+    name: &str,
+    abbr: &str,
+    factors: &[(Entity, i8)],
+) -> Result<Entity> {
+    let mut dimension_kind = DimensionKind::SCALE;
+    for &(factor, exp) in factors {
+        dimension_kind = dimension_kind.mul(&Unit::dimension_kind(world, factor)?.powi(exp)?)?;
+    }
+
+    let unit_def = UnitDef::Product {
+        factors: factors.to_vec(),
+    };
+    Ok(ecs_add_unit(
+        world,
+        e_ns_parent,
+        dimension_kind,
+        name,
+        abbr,
+        unit_def,
+    ))
+}
+
 #[rustfmt::skip]
 #[bitflags]
 #[repr(u32)]
@@ -144,7 +453,7 @@ fn ecs_add_derived_unit_si_prefixes(
         ( "mega",   "M",   6 ),
         ( "kilo",   "k",   3_i32 ),
         ( "hecto",  "h",   2 ),
-        ( "deca",   "a",   1 ),
+        ( "deca",   "da",  1 ),
         //( "",      "",   0 ),
         ( "deci",   "d",  -1 ),
         ( "centi",  "c",  -2 ),
@@ -225,8 +534,6 @@ fn ecs_add_derived_unit_si_prefixes(
 }
 
 pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
-    use DimensionKind::*;
-
     let ns_root = RootNamespace::find_or_create(world)?;
 
     let ns_units = world.attach_new::<NamespaceTag, _>(ns_root, (Name::from("units"),))?;
@@ -235,7 +542,14 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
     {
         let ns_length = world.attach_new::<NamespaceTag, _>(ns_units, (Name::from("length"),))?;
 
-        let meter = ecs_add_unit(world, ns_length, Length, "meter", "m", UnitDef::BaseUnit);
+        let meter = ecs_add_unit(
+            world,
+            ns_length,
+            DimensionKind::LENGTH,
+            "meter",
+            "m",
+            UnitDef::BaseUnit,
+        );
 
         ecs_add_derived_unit_si_prefixes(
             world,
@@ -245,12 +559,12 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
         )?;
 
         let millimeter =
-            Unit::look_up(world, DimensionKind::Length, "millimeter").ok_or_else(|| anyhow!(""))?;
+            Unit::look_up(world, DimensionKind::LENGTH, "millimeter").ok_or_else(|| anyhow!(""))?;
 
         let inch = ecs_add_unit(
             world,
             meter,
-            Length,
+            DimensionKind::LENGTH,
             "inch",
             "in",
             UnitDef::ScaleToBaseUnit {
@@ -262,7 +576,7 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
         let foot = ecs_add_unit(
             world,
             meter,
-            Length,
+            DimensionKind::LENGTH,
             "foot",
             "ft",
             UnitDef::ScaleToBaseUnit {
@@ -274,7 +588,7 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
         let _mile = ecs_add_unit(
             world,
             meter,
-            Length,
+            DimensionKind::LENGTH,
             "mile",
             "mi",
             UnitDef::ScaleToBaseUnit {
@@ -288,7 +602,14 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
     {
         let ns_time = world.attach_new::<NamespaceTag, _>(ns_units, (Name::from("time"),))?;
 
-        let s = ecs_add_unit(world, ns_time, Time, "second", "s", UnitDef::BaseUnit);
+        let s = ecs_add_unit(
+            world,
+            ns_time,
+            DimensionKind::TIME,
+            "second",
+            "s",
+            UnitDef::BaseUnit,
+        );
 
         ecs_add_derived_unit_si_prefixes(
             world,
@@ -296,19 +617,108 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
             s,
             make_bitflags!(WhichPrefixes::{ExcludePosExp}),
         )?;
-        // hertz	Hz	frequency	s−1
+
+        let scaled = |n: u64, base_unit: Entity| UnitDef::ScaleToBaseUnit {
+            scale: EcsNum::RatioU64(RatioU64::from_integer(n)),
+            base_unit,
+        };
+        let min = ecs_add_unit(
+            world,
+            ns_time,
+            DimensionKind::TIME,
+            "minute",
+            "min",
+            scaled(60, s),
+        );
+        let h = ecs_add_unit(
+            world,
+            ns_time,
+            DimensionKind::TIME,
+            "hour",
+            "h",
+            scaled(60, min),
+        );
+        let d = ecs_add_unit(
+            world,
+            ns_time,
+            DimensionKind::TIME,
+            "day",
+            "d",
+            scaled(24, h),
+        );
+
+        // The IAU's Julian year, of exactly 365.25 days.
+        ecs_add_unit(
+            world,
+            ns_time,
+            DimensionKind::TIME,
+            "julian year",
+            "yr",
+            UnitDef::ScaleToBaseUnit {
+                scale: EcsNum::RatioU64(RatioU64::new_raw(1461, 4)),
+                base_unit: d,
+            },
+        );
+    }
+
+    // Mass units
+    {
+        let ns_mass = world.attach_new::<NamespaceTag, _>(ns_units, (Name::from("mass"),))?;
+
+        let kg = ecs_add_unit(
+            world,
+            ns_mass,
+            DimensionKind::MASS,
+            "kilogram",
+            "kg",
+            UnitDef::BaseUnit,
+        );
+
+        ecs_add_unit(
+            world,
+            ns_mass,
+            DimensionKind::MASS,
+            "gram",
+            "g",
+            UnitDef::ScaleToBaseUnit {
+                scale: EcsNum::RatioU64(RatioU64::new_raw(1, 1000)),
+                base_unit: kg,
+            },
+        );
+    }
+
+    // Temperature units
+    {
+        let ns_temperature =
+            world.attach_new::<NamespaceTag, _>(ns_units, (Name::from("temperature"),))?;
+
+        ecs_add_unit(
+            world,
+            ns_temperature,
+            DimensionKind::TEMPERATURE,
+            "kelvin",
+            "K",
+            UnitDef::BaseUnit,
+        );
     }
 
     // Angle units
     {
         let angle = world.attach_new::<NamespaceTag, _>(ns_units, (Name::from("angle"),))?;
 
-        let _turn = ecs_add_unit(world, angle, Angle, "turn", "tr", UnitDef::BaseUnit);
+        let _turn = ecs_add_unit(
+            world,
+            angle,
+            DimensionKind::ANGLE,
+            "turn",
+            "tr",
+            UnitDef::BaseUnit,
+        );
 
-        ecs_add_unit(
+        let degree = ecs_add_unit(
             world,
             angle,
-            Angle,
+            DimensionKind::ANGLE,
             "degree",
             "°",
             UnitDef::ScaleToBaseUnit {
@@ -318,12 +728,12 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
         );
 
         if let Some(e_tau_inv) =
-            DimensionedConstant::look_up(world, DimensionKind::Scale, "tau_inv")
+            DimensionedConstant::look_up(world, DimensionKind::SCALE, "tau_inv")
         {
             ecs_add_unit(
                 world,
                 angle,
-                Angle,
+                DimensionKind::ANGLE,
                 "radian",
                 "rad",
                 UnitDef::ScaleToBaseUnit {
@@ -332,6 +742,139 @@ pub(crate) fn ecs_add_stuff(world: &mut World) -> Result<()> {
                 },
             );
         }
+
+        let mut add_fraction = |name, abbr, denom, base_unit| {
+            ecs_add_unit(
+                world,
+                angle,
+                DimensionKind::ANGLE,
+                name,
+                abbr,
+                UnitDef::ScaleToBaseUnit {
+                    scale: EcsNum::RatioU64(RatioU64::new_raw(1, denom)),
+                    base_unit,
+                },
+            )
+        };
+
+        let arcmin = add_fraction("arcminute", "′", 60, degree);
+        let arcsec = add_fraction("arcsecond", "″", 60, arcmin);
+        let mas = add_fraction("milliarcsecond", "mas", 1000, arcsec);
+        add_fraction("microarcsecond", "μas", 1000, mas);
+
+        add_fraction("gradian", "gon", 400, _turn);
+
+        // NATO mils.
+        add_fraction("mil", "mil", 6400, _turn);
+
+        let ra_h = add_fraction("hour of right ascension", "ʰ", 24, _turn);
+        let ra_m = add_fraction("minute of right ascension", "ᵐ", 60, ra_h);
+        add_fraction("second of right ascension", "ˢ", 60, ra_m);
+    }
+
+    // Derived units
+    {
+        let ns_derived = world.attach_new::<NamespaceTag, _>(ns_units, (Name::from("derived"),))?;
+
+        let unit = |world: &World, dimension_kind, name| {
+            Unit::look_up(world, dimension_kind, name).ok_or_else(|| anyhow!("No unit {name:?}"))
+        };
+        let m = unit(world, DimensionKind::LENGTH, "meter")?;
+        let km = unit(world, DimensionKind::LENGTH, "kilometer")?;
+        let s = unit(world, DimensionKind::TIME, "second")?;
+        let kg = unit(world, DimensionKind::MASS, "kilogram")?;
+        let deg = unit(world, DimensionKind::ANGLE, "degree")?;
+
+        ecs_add_compound_unit(world, ns_derived, "hertz", "Hz", &[(s, -1)])?;
+
+        ecs_add_compound_unit(world, ns_derived, "square meter", "m²", &[(m, 2)])?;
+        ecs_add_compound_unit(world, ns_derived, "square kilometer", "km²", &[(km, 2)])?;
+
+        ecs_add_compound_unit(
+            world,
+            ns_derived,
+            "meter per second",
+            "m/s",
+            &[(m, 1), (s, -1)],
+        )?;
+        ecs_add_compound_unit(
+            world,
+            ns_derived,
+            "kilometer per second",
+            "km/s",
+            &[(km, 1), (s, -1)],
+        )?;
+
+        ecs_add_compound_unit(
+            world,
+            ns_derived,
+            "degree per second",
+            "°/s",
+            &[(deg, 1), (s, -1)],
+        )?;
+        if let Some(rad) = Unit::look_up(world, DimensionKind::ANGLE, "radian") {
+            ecs_add_compound_unit(
+                world,
+                ns_derived,
+                "radian per second",
+                "rad/s",
+                &[(rad, 1), (s, -1)],
+            )?;
+        }
+
+        // Gravitational parameters, and the constant of gravitation.
+        ecs_add_compound_unit(
+            world,
+            ns_derived,
+            "cubic meter per second squared",
+            "m³/s²",
+            &[(m, 3), (s, -2)],
+        )?;
+        ecs_add_compound_unit(
+            world,
+            ns_derived,
+            "cubic meter per kilogram second squared",
+            "m³/(kg·s²)",
+            &[(m, 3), (kg, -1), (s, -2)],
+        )?;
+
+        // Proper motions.
+        let arcsec = unit(world, DimensionKind::ANGLE, "arcsecond")?;
+        let mas = unit(world, DimensionKind::ANGLE, "milliarcsecond")?;
+        let yr = unit(world, DimensionKind::TIME, "julian year")?;
+        ecs_add_compound_unit(
+            world,
+            ns_derived,
+            "arcsecond per julian year",
+            "″/yr",
+            &[(arcsec, 1), (yr, -1)],
+        )?;
+        ecs_add_compound_unit(
+            world,
+            ns_derived,
+            "milliarcsecond per julian year",
+            "mas/yr",
+            &[(mas, 1), (yr, -1)],
+        )?;
+
+        let pa = ecs_add_compound_unit(
+            world,
+            ns_derived,
+            "pascal",
+            "Pa",
+            &[(kg, 1), (m, -1), (s, -2)],
+        )?;
+        ecs_add_unit(
+            world,
+            ns_derived,
+            DimensionKind::PRESSURE,
+            "hectopascal",
+            "hPa",
+            UnitDef::ScaleToBaseUnit {
+                scale: EcsNum::RatioU64(RatioU64::from_integer(100)),
+                base_unit: pa,
+            },
+        );
     }
 
     Ok(())
@@ -363,4 +906,101 @@ mod t {
         //assert_ron_snapshot!(, @"");
         Ok(())
     }
+
+    #[test]
+    fn t_convert() -> anyhow::Result<()> {
+        let mut world = World::default();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let unit = |name: &str| Unit::look_up_name_or_abbr(world, name).unwrap();
+        let parse = |s: &str| Quantity::parse(world, s);
+
+        // Exact, along chains of different lengths.
+        let one = EcsNum::RatioU64(RatioU64::from_integer(1));
+        assert_ron_snapshot!(convert(world, &one, unit("mile"), unit("km"))?.to_string(), @r#""25146/15625""#);
+        assert_ron_snapshot!(convert(world, &one, unit("ft"), unit("in"))?.to_string(), @r#""12""#);
+        assert_ron_snapshot!(convert(world, &one, unit("ms"), unit("μs"))?.to_string(), @r#""1000""#);
+
+        // Quantity strings.
+        let a = parse("6378.137 km")?;
+        assert_ron_snapshot!(a.value.to_string(), @r#""6378137/1000""#);
+        assert_ron_snapshot!(a.convert_to(world, unit("meter"))?.to_string(), @r#""6378137""#);
+        assert_ron_snapshot!(parse("-0.5e-3 s")?.convert_to(world, unit("ms"))?.to_string(), @r#""-1/2""#);
+        assert_ron_snapshot!(parse("12in")?.convert_to(world, unit("foot"))?.to_string(), @r#""1""#);
+
+        // Sexagesimal degrees.
+        let obliquity = parse("23°26′21″")?;
+        assert_ron_snapshot!(obliquity.value.to_string(), @r#""28127/1200""#);
+        let rad = obliquity.convert_to(world, unit("radian"))?.to_f64()?;
+        assert_ron_snapshot!((rad * 1e12).round() / 1e12, @"0.409090632257");
+        assert_ron_snapshot!(parse("-12° 30' 36\"")?.value.to_string(), @r#""-1251/100""#);
+        assert_ron_snapshot!(parse("90°")?.convert_to(world, unit("turn"))?.to_string(), @r#""1/4""#);
+
+        // The rest of the angle family.
+        assert_ron_snapshot!(convert(world, &one, unit("°"), unit("mas"))?.to_string(), @r#""3600000""#);
+        assert_ron_snapshot!(convert(world, &one, unit("ʰ"), unit("″"))?.to_string(), @r#""54000""#);
+        assert_ron_snapshot!(parse("100 gon")?.convert_to(world, unit("°"))?.to_string(), @r#""90""#);
+        assert_ron_snapshot!(parse("1600 mil")?.convert_to(world, unit("gon"))?.to_string(), @r#""100""#);
+        assert_ron_snapshot!(parse("250 mas/yr")?.convert_to(world, unit("″/yr"))?.to_string(), @r#""1/4""#);
+
+        // Errors.
+        assert!(convert(world, &one, unit("meter"), unit("second")).is_err());
+        assert!(parse("6378.137").is_err());
+        assert!(parse("1.2.3 m").is_err());
+        assert!(parse("5 furlong").is_err());
+        assert!(parse("10°75′").is_err());
+        assert!(parse("10″20°").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn t_compound() -> anyhow::Result<()> {
+        let mut world = World::default();
+        let world = &mut world;
+        crate::ecs_add_stuff(world);
+
+        let kmh = {
+            let km = Unit::look_up_name_or_abbr(world, "km")?;
+            let h = Unit::look_up_name_or_abbr(world, "hour")?;
+            let e_ns_units = ecs_ns_find_or_create(world, NamePathSpec::absolute(["units"]))?;
+            let factors = [(km, 1), (h, -1)];
+            ecs_add_compound_unit(world, e_ns_units, "kilometer per hour", "km/h", &factors)?
+        };
+
+        let unit = |name: &str| Unit::look_up_name_or_abbr(world, name).unwrap();
+        let parse = |s: &str| Quantity::parse(world, s);
+        let dk = |name: &str| Unit::dimension_kind(world, unit(name)).unwrap().to_string();
+
+        assert_ron_snapshot!([dk("m/s"), dk("rad/s"), dk("Hz"), dk("hPa"), dk("km²")], @r#"("Velocity", "AngularRate", "Frequency", "Pressure", "Area")"#);
+        assert_ron_snapshot!(DimensionKind::LENGTH.div(&DimensionKind::TIME)?.powi(2)?.to_string(), @r#""L²·T⁻²""#);
+        assert_ron_snapshot!(DimensionKind([1, 0, -2, 0, 0]).to_string(), @r#""L·T⁻²""#);
+
+        // Compound units convert along all their factors.
+        assert_ron_snapshot!(parse("1 km/s")?.convert_to(world, unit("m/s"))?.to_string(), @r#""1000""#);
+        assert_ron_snapshot!(parse("1013.25 hPa")?.convert_to(world, unit("Pa"))?.to_string(), @r#""101325""#);
+        assert_ron_snapshot!(parse("3 km²")?.convert_to(world, unit("m²"))?.to_string(), @r#""3000000""#);
+        assert_ron_snapshot!(parse("1 yr")?.convert_to(world, unit("s"))?.to_string(), @r#""31557600""#);
+        assert!(parse("1 m/s")
+            .and_then(|q| q.convert_to(world, unit("Hz")))
+            .is_err());
+
+        // A user-defined unit, in terms of others.
+        assert_ron_snapshot!(parse("36 km/h")?.convert_to(world, unit("m/s"))?.to_string(), @r#""10""#);
+
+        // Measures check dimensions as they go.
+        let distance = parse("1 mi")?.to_measure(world)?;
+        let time = parse("2 min")?.to_measure(world)?;
+        let speed = distance.div(&time)?;
+        assert_ron_snapshot!(speed.to_string(), @r#""8382/625 Velocity""#);
+        assert_ron_snapshot!(speed.in_unit(world, kmh)?.to_string(), @r#""150876/3125""#);
+        assert!(speed.add(&distance).is_err());
+        assert!(speed.in_unit(world, unit("m")).is_err());
+        let area = distance.powi(2)?;
+        assert_ron_snapshot!(area.in_unit(world, unit("km²"))?.to_string(), @r#""632321316/244140625""#);
+        assert_ron_snapshot!(time.powi(-1)?.in_unit(world, unit("Hz"))?.to_string(), @r#""1/120""#);
+
+        Ok(())
+    }
 }
@@ -196,12 +196,13 @@ where
 
     let mut e = nps.start.to_entity_or_create(world)?;
 
-    let mut it_components = nps.components.into_iter().fuse();
+    let mut it_components = nps.components.into_iter().map(Into::into).fuse();
 
-    #[allow(clippy::while_let_on_iterator)]
-    'next_path_component: while let Some(path_component) = it_components.next() {
-        let name: Name = path_component.into();
+    // The first name path component that wasn't found.
+    let mut opt_name_not_found: Option<Name> = None;
 
+    #[allow(clippy::while_let_on_iterator)]
+    'next_path_component: while let Some(name) = it_components.next() {
         for e_child in world.children::<NamespaceTag>(e) {
             let opt_ref_child_name = Name::opt_from_entity(world, e_child);
             if let Some(ref_child_name) = opt_ref_child_name {
@@ -212,12 +213,12 @@ where
             }
         }
 
+        opt_name_not_found = Some(name);
         break;
     }
 
-    // Create any remaining name path components.
-    for pc_into_name in it_components {
-        let name: Name = pc_into_name.into();
+    // Create it and any remaining name path components.
+    for name in opt_name_not_found.into_iter().chain(it_components) {
         e = world.attach_new::<NamespaceTag, _>(e, (name,))?;
     }
 
@@ -226,12 +227,33 @@ where
 
 //-------------------------------------------------------------------------------------------------|
 
-pub fn ecs_ns_get<N, II>(world: &mut World, nps: NamePathSpec<N, II>) -> Result<Entity>
+/// Like [`ecs_ns_find_or_create`], but fails instead of creating anything.
+pub fn ecs_ns_get<N, II>(world: &World, nps: NamePathSpec<N, II>) -> Result<Entity>
 where
     N: Into<Name>,
     II: IntoIterator<Item = N>,
 {
-    bail!("TODO"); //? TODO
+    let mut e = nps.start.try_to_entity(world)?;
+
+    'next_path_component: for name in nps.components.into_iter().map(Into::into) {
+        for e_child in world.children::<NamespaceTag>(e) {
+            let opt_ref_child_name = Name::opt_from_entity(world, e_child);
+            if let Some(ref_child_name) = opt_ref_child_name {
+                if name == *ref_child_name {
+                    e = e_child;
+                    continue 'next_path_component;
+                }
+            }
+        }
+
+        bail!(
+            "No {:?} in namespace {:?}",
+            name.as_str(),
+            Name::entity_to_name_string(world, e)
+        );
+    }
+
+    Ok(e)
 }
 
 //=================================================================================================|
@@ -448,4 +470,17 @@ mod t {
         //? assert_ron_snapshot!(, @"");
         Ok(())
     }
+
+    #[test]
+    fn t4() -> anyhow::Result<()> {
+        let mut world = World::default();
+        let e_b = ecs_ns_find_or_create(&mut world, NamePathSpec::absolute(["a", "b"]))?;
+        let e_a = ecs_ns_get(&world, NamePathSpec::absolute(["a"]))?;
+        assert_eq!(ecs_ns_get(&world, NamePathSpec::absolute(["a", "b"]))?, e_b);
+        assert_eq!(ecs_ns_get(&world, NamePathSpec::relative(e_a, ["b"]))?, e_b);
+
+        let err = ecs_ns_get(&world, NamePathSpec::absolute(["a", "c"])).unwrap_err();
+        assert_ron_snapshot!(err.to_string(), @r#""No \"c\" in namespace \"a\"""#);
+        Ok(())
+    }
 }
@@ -48,6 +48,24 @@ impl DateTimeTai {
         Into::into(self)
     }
 
+    /// Julian date on the TAI time scale.
+    #[must_use]
+    pub fn to_jd_tai(self) -> f64 {
+        ndt_to_jd(self.ndt)
+    }
+
+    /// Julian date on the TT time scale, which is what ephemerides want.
+    #[must_use]
+    pub fn to_jd_tt(self) -> f64 {
+        self.to_jd_tai() + astro::julian::TT_MINUS_TAI_SECONDS / astro::julian::SECONDS_PER_DAY
+    }
+
+    /// Julian date on the UTC time scale. Also used as UT1, which is within a second of it.
+    #[must_use]
+    pub fn to_jd_utc(self) -> f64 {
+        ndt_to_jd(self.to_utc().naive_utc())
+    }
+
     /// Adds some number of days.
     #[must_use]
     pub fn checked_add_days(self, days: chrono::Days) -> Option<Self> {
@@ -77,6 +95,15 @@ impl DateTimeTai {
     };
 }
 
+/// Julian date of 1970-01-01 00:00:00.
+const JD_UNIX_EPOCH: f64 = 2_440_587.5;
+
+fn ndt_to_jd(ndt: NaiveDateTime) -> f64 {
+    let utc = ndt.and_utc();
+    let secs = utc.timestamp() as f64 + utc.timestamp_subsec_nanos() as f64 * 1e-9;
+    JD_UNIX_EPOCH + secs / astro::julian::SECONDS_PER_DAY
+}
+
 impl std::fmt::Display for DateTimeTai {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} TAI", self.ndt)
@@ -5,4 +5,5 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+pub mod sky_dome;
 pub mod threedapp;
@@ -0,0 +1,369 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! The sky as seen by an observer: a dome with altitude and azimuth grid lines, the horizon,
+//! the cardinal points, and the sun, moon and stars where they appear.
+//!
+//! Scene axes are x east, y up and z south, which keeps three-d's right-handed, y-up
+//! convention with the observer at the origin.
+
+//? use std::any::Any;
+//? use std::fmt::Display;
+//? use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use log::{debug, error, info, trace, warn};
+//? use serde::{Deserialize, Serialize};
+
+use three_d::material::ColorMaterial;
+use three_d::renderer::{Camera, Gm};
+use three_d::{
+    degrees, vec3, Context, CpuMesh, InnerSpace, InstancedMesh, Mat4, Quat, RenderTarget,
+    ScissorBox, Srgba, Vec3, Viewport,
+};
+
+use astro::angle::norm_0_tau;
+use astro::ephemeris::{Vsop87Ephemeris, WithLowPrecisionMoon};
+use astro::frames::gast;
+use astro::horizontal::equatorial_to_horizontal;
+use astro::nutation::iau2000b;
+use astro::stars::{color_from_b_v, relative_size};
+use astro::{ApparentPlace, ApparentPlaceCalc, Horizontal, NaifId, Observer, StarCatalog};
+
+use crate::view_state::SkyViewState;
+use crate::world_state::WorldState;
+
+/// Radius of the dome. Inside `z_far`.
+const DOME_RADIUS: f32 = 50.0;
+
+const Z_NEAR: f32 = 0.1;
+const Z_FAR: f32 = 100.0;
+
+/// Altitude circles, degrees. The horizon is drawn separately.
+const ALTITUDE_CIRCLES_DEG: [f64; 5] = [15.0, 30.0, 45.0, 60.0, 75.0];
+
+/// Azimuth lines are drawn from the horizon toward, but not into, the zenith.
+const AZIMUTH_LINE_STEP_DEG: f64 = 30.0;
+const AZIMUTH_LINE_TOP_DEG: f64 = 80.0;
+
+/// Circles are drawn as this many straight segments.
+const CIRCLE_SEGMENTS: usize = 120;
+
+/// Line radii, as a fraction of the dome radius.
+const GRID_LINE_RADIUS: f32 = 0.0006;
+const HORIZON_LINE_RADIUS: f32 = 0.0012;
+const LABEL_LINE_RADIUS: f32 = 0.0010;
+
+/// Height of the cardinal letters, and the elevation of their baseline, degrees.
+const LABEL_HEIGHT_DEG: f64 = 2.5;
+const LABEL_EL_DEG: f64 = 1.0;
+
+const GRID_COLOR: Srgba = Srgba::new_opaque(50, 70, 110);
+const HORIZON_COLOR: Srgba = Srgba::new_opaque(150, 150, 150);
+const LABEL_COLOR: Srgba = Srgba::new_opaque(255, 200, 80);
+const SUN_COLOR: Srgba = Srgba::new_opaque(255, 240, 180);
+const MOON_COLOR: Srgba = Srgba::new_opaque(200, 200, 210);
+
+/// Angular diameters, degrees. Drawn at twice these so they can be found at a wide field of
+/// view.
+const SUN_DIAMETER_DEG: f64 = 0.53;
+const MOON_DIAMETER_DEG: f64 = 0.52;
+const BODY_SIZE_EXAGGERATION: f64 = 2.0;
+
+/// Radius of a star of `STAR_REFERENCE_VMAG`, as a fraction of the dome radius.
+const STAR_RADIUS: f32 = 0.002;
+const STAR_REFERENCE_VMAG: f64 = 1.0;
+
+/// The sky turns 0.04° in this long, which is about where it starts to look jerky.
+const BODIES_RECOMPUTE_DAYS: f64 = 10.0 / 86_400.0;
+
+//=================================================================================================|
+
+/// Unit vector in the scene toward an azimuth and elevation, radians.
+pub fn scene_from_horizontal(hor: &Horizontal) -> Vec3 {
+    let (sin_az, cos_az) = hor.azimuth.sin_cos();
+    let (sin_el, cos_el) = hor.elevation.sin_cos();
+    vec3(
+        (sin_az * cos_el) as f32,
+        sin_el as f32,
+        (-cos_az * cos_el) as f32,
+    )
+}
+
+fn scene_from_degrees(az_deg: f64, el_deg: f64) -> Vec3 {
+    scene_from_horizontal(&Horizontal {
+        azimuth: az_deg.to_radians(),
+        elevation: el_deg.to_radians(),
+    })
+}
+
+/// Takes the unit cylinder of `CpuMesh::cylinder`, along x from 0 to 1, to a line from `p0` to
+/// `p1`.
+fn segment_transformation(p0: Vec3, p1: Vec3, radius: f32) -> Mat4 {
+    let d = p1 - p0;
+    Mat4::from_translation(p0)
+        * Mat4::from(Quat::from_arc(vec3(1.0, 0.0, 0.0), d.normalize(), None))
+        * Mat4::from_nonuniform_scale(d.magnitude(), radius, radius)
+}
+
+/// Strokes of a capital letter in a unit box, x right and y up, as polylines.
+fn letter_strokes(letter: char) -> &'static [&'static [(f32, f32)]] {
+    match letter {
+        'N' => &[&[(0.0, 0.0), (0.0, 1.0), (0.7, 0.0), (0.7, 1.0)]],
+        'E' => &[
+            &[(0.6, 0.0), (0.0, 0.0), (0.0, 1.0), (0.6, 1.0)],
+            &[(0.0, 0.5), (0.45, 0.5)],
+        ],
+        'S' => &[&[
+            (0.6, 1.0),
+            (0.0, 1.0),
+            (0.0, 0.5),
+            (0.6, 0.5),
+            (0.6, 0.0),
+            (0.0, 0.0),
+        ]],
+        'W' => &[&[(0.0, 1.0), (0.2, 0.0), (0.45, 0.6), (0.7, 0.0), (0.9, 1.0)]],
+        _ => &[],
+    }
+}
+
+//=================================================================================================|
+
+/// Builds the line instances of the dome, which don't change.
+fn grid_instances() -> three_d::renderer::geometry::Instances {
+    let mut transformations = Vec::new();
+    let mut colors = Vec::new();
+    let mut line = |p0: Vec3, p1: Vec3, radius: f32, color: Srgba| {
+        transformations.push(segment_transformation(
+            p0 * DOME_RADIUS,
+            p1 * DOME_RADIUS,
+            radius * DOME_RADIUS,
+        ));
+        colors.push(color);
+    };
+
+    // The horizon and altitude circles.
+    let circle_step_deg = 360.0 / CIRCLE_SEGMENTS as f64;
+    let circles = std::iter::once((0.0, HORIZON_LINE_RADIUS, HORIZON_COLOR)).chain(
+        ALTITUDE_CIRCLES_DEG
+            .iter()
+            .map(|&el| (el, GRID_LINE_RADIUS, GRID_COLOR)),
+    );
+    for (el_deg, radius, color) in circles {
+        for ix in 0..CIRCLE_SEGMENTS {
+            let az0 = ix as f64 * circle_step_deg;
+            line(
+                scene_from_degrees(az0, el_deg),
+                scene_from_degrees(az0 + circle_step_deg, el_deg),
+                radius,
+                color,
+            );
+        }
+    }
+
+    // Azimuth lines.
+    let el_steps = (AZIMUTH_LINE_TOP_DEG / circle_step_deg).ceil() as usize;
+    let el_step_deg = AZIMUTH_LINE_TOP_DEG / el_steps as f64;
+    let mut az_deg = 0.0;
+    while az_deg < 360.0 {
+        for ix in 0..el_steps {
+            let el0 = ix as f64 * el_step_deg;
+            line(
+                scene_from_degrees(az_deg, el0),
+                scene_from_degrees(az_deg, el0 + el_step_deg),
+                GRID_LINE_RADIUS,
+                GRID_COLOR,
+            );
+        }
+        az_deg += AZIMUTH_LINE_STEP_DEG;
+    }
+
+    // Cardinal letters, standing on the horizon and facing the observer.
+    for (az_deg, letter) in [(0.0, 'N'), (90.0, 'E'), (180.0, 'S'), (270.0, 'W')] {
+        let az = f64::to_radians(az_deg);
+        let right = vec3(az.cos() as f32, 0.0, az.sin() as f32);
+        let up = vec3(0.0, 1.0, 0.0);
+        let height = LABEL_HEIGHT_DEG.to_radians() as f32;
+        let origin = scene_from_degrees(az_deg, LABEL_EL_DEG) - right * (0.35 * height);
+        let to_scene = |(x, y): (f32, f32)| origin + right * (x * height) + up * (y * height);
+
+        for stroke in letter_strokes(letter) {
+            for pair in stroke.windows(2) {
+                line(
+                    to_scene(pair[0]),
+                    to_scene(pair[1]),
+                    LABEL_LINE_RADIUS,
+                    LABEL_COLOR,
+                );
+            }
+        }
+    }
+
+    three_d::renderer::geometry::Instances {
+        transformations,
+        colors: Some(colors),
+        ..Default::default()
+    }
+}
+
+/// One sphere each for the sun, the moon and the stars that are above the horizon, at their
+/// apparent places in the observer's sky. Aberration and nutation are applied to all of them,
+/// and the parallax, which is up to a degree for the moon.
+fn bodies_instances(
+    star_catalog: &StarCatalog,
+    observer: &Observer,
+    jd_tt: f64,
+    jd_ut1: f64,
+) -> Result<three_d::renderer::geometry::Instances> {
+    let eph = WithLowPrecisionMoon::new(Vsop87Ephemeris::abridged());
+    let calc = ApparentPlaceCalc {
+        observer: Some(*observer),
+        ..ApparentPlaceCalc::new(&eph)
+    };
+
+    // Apparent places are on the true equator and equinox of date, so the sidereal time is
+    // the apparent one.
+    let last = norm_0_tau(gast(jd_ut1, jd_tt, iau2000b(jd_tt)) + observer.lon_deg.to_radians());
+    let lat = observer.lat_deg.to_radians();
+    let horizontal =
+        |place: ApparentPlace| equatorial_to_horizontal(place.ra, place.dec, last, lat);
+
+    let mut transformations = Vec::with_capacity(star_catalog.stars.len() + 2);
+    let mut colors = Vec::with_capacity(star_catalog.stars.len() + 2);
+    let mut body = |hor: Horizontal, radius: f32, color: Srgba| {
+        if 0.0 <= hor.elevation {
+            let pos = scene_from_horizontal(&hor) * DOME_RADIUS;
+            transformations.push(Mat4::from_translation(pos) * Mat4::from_scale(radius));
+            colors.push(color);
+        }
+    };
+
+    // Angular radius, exaggerated, to a radius on the dome.
+    let disk_radius = |diameter_deg: f64| {
+        DOME_RADIUS
+            * (BODY_SIZE_EXAGGERATION * diameter_deg / 2.0)
+                .to_radians()
+                .tan() as f32
+    };
+
+    let sun = calc.place(NaifId::SUN, jd_tt, jd_ut1)?;
+    body(horizontal(sun), disk_radius(SUN_DIAMETER_DEG), SUN_COLOR);
+
+    let moon = calc.place(NaifId::MOON, jd_tt, jd_ut1)?;
+    body(horizontal(moon), disk_radius(MOON_DIAMETER_DEG), MOON_COLOR);
+
+    for star in &star_catalog.stars {
+        let hor = horizontal(calc.star_place(star, jd_tt, jd_ut1)?);
+        let radius =
+            DOME_RADIUS * STAR_RADIUS * relative_size(star.vmag, STAR_REFERENCE_VMAG) as f32;
+        let [r, g, b] = color_from_b_v(star.b_v.unwrap_or_default());
+        body(hor, radius, Srgba::new_opaque(r, g, b));
+    }
+
+    Ok(three_d::renderer::geometry::Instances {
+        transformations,
+        colors: Some(colors),
+        ..Default::default()
+    })
+}
+
+//=================================================================================================|
+
+pub struct SkyDome {
+    gm_grid: Gm<InstancedMesh, ColorMaterial>,
+    opt_gm_bodies: Option<Gm<InstancedMesh, ColorMaterial>>,
+
+    /// What the bodies were last placed for.
+    bodies_observer: Observer,
+    bodies_jd_ut1: f64,
+}
+
+impl SkyDome {
+    pub fn new(context: &Context) -> Self {
+        let gm_grid = Gm::new(
+            InstancedMesh::new(context, &grid_instances(), &CpuMesh::cylinder(6)),
+            ColorMaterial::default(),
+        );
+
+        Self {
+            gm_grid,
+            opt_gm_bodies: None,
+            bodies_observer: Observer::default(),
+            bodies_jd_ut1: f64::NAN,
+        }
+    }
+
+    /// Places the bodies for the time and observer of `world_state`, if they have moved enough
+    /// to notice.
+    pub fn update(
+        &mut self,
+        context: &Context,
+        star_catalog: &StarCatalog,
+        world_state: &WorldState,
+    ) {
+        let observer = world_state.observer_position.observer;
+        let tai = world_state.time.tai;
+        let jd_tt = tai.to_jd_tt();
+
+        // UT1 - UTC is under a second.
+        let jd_ut1 = tai.to_jd_utc();
+
+        if self.opt_gm_bodies.is_some()
+            && observer == self.bodies_observer
+            && (jd_ut1 - self.bodies_jd_ut1).abs() < BODIES_RECOMPUTE_DAYS
+        {
+            return;
+        }
+
+        let instances = match bodies_instances(star_catalog, &observer, jd_tt, jd_ut1) {
+            Ok(instances) => instances,
+            Err(e) => {
+                error!("placing the sky dome bodies: {e:#}");
+                return;
+            }
+        };
+        self.opt_gm_bodies = Some(Gm::new(
+            InstancedMesh::new(context, &instances, &CpuMesh::sphere(8)),
+            ColorMaterial::default(),
+        ));
+        self.bodies_observer = observer;
+        self.bodies_jd_ut1 = jd_ut1;
+    }
+
+    /// Points `camera` from the observer in the direction of `sky_view`.
+    pub fn set_camera(camera: &mut Camera, viewport: Viewport, sky_view: &SkyViewState) {
+        camera.set_viewport(viewport);
+        camera.set_view(
+            vec3(0.0, 0.0, 0.0),
+            scene_from_degrees(sky_view.look_az_deg, sky_view.look_el_deg),
+            vec3(0.0, 1.0, 0.0),
+        );
+        camera.set_perspective_projection(degrees(sky_view.fov_y_deg as f32), Z_NEAR, Z_FAR);
+    }
+
+    pub fn render(
+        &self,
+        render_target: &RenderTarget<'_>,
+        scissor_box: ScissorBox,
+        camera: &Camera,
+    ) {
+        render_target.render_partially(scissor_box, camera, [&self.gm_grid], &[]);
+
+        if let Some(gm_bodies) = self.opt_gm_bodies.as_ref() {
+            render_target.render_partially(scissor_box, camera, [gm_bodies], &[]);
+        }
+    }
+}
@@ -36,8 +36,12 @@ use three_d::{
 };
 use three_d_asset::{Matrix4, PbrMaterial};
 
+use astro::stars::{color_from_b_v, relative_size};
+use astro::StarCatalog;
+
 use crate::tai::DateTimeTai;
-use crate::view_state::{AnimationState, ViewState};
+use crate::threed::sky_dome::SkyDome;
+use crate::view_state::{AnimationState, SkyViewState, ViewState};
 use crate::world_state::WorldState;
 
 pub fn with_three_d_app<R>(
@@ -73,9 +77,26 @@ pub struct ThreeDApp {
     opt_object_triangle: Option<Gm<Mesh, ColorMaterial>>,
     opt_gm_mesh_color: Option<Gm<Mesh, ColorMaterial>>,
     opt_gm_mesh_phys: Option<Gm<Mesh, PhysicalMaterial>>,
+    star_catalog: StarCatalog,
+    opt_gm_stars: Option<Gm<InstancedMesh, ColorMaterial>>,
+    stars_jd_tt: f64,
+    sky_dome: SkyDome,
+
+    /// Looks out from the observer. Kept apart from `camera`, which looks in at the sphere.
+    sky_camera: Camera,
     triangle_rotate: Deg<f32>,
 }
 
+/// Radius of the sphere the stars are drawn on. Inside `z_far`.
+const STAR_SPHERE_RADIUS: f32 = 50.0;
+
+/// Radius of a star of `STAR_REFERENCE_VMAG`.
+const STAR_RADIUS: f32 = 0.15;
+const STAR_REFERENCE_VMAG: f64 = 1.0;
+
+/// Star positions are recomputed when the date moves by more than this many days.
+const STAR_RECOMPUTE_DAYS: f64 = 1.0;
+
 impl ThreeDApp {
     pub fn new(arc_glow_context: Arc<glow::Context>) -> Self {
         debug!("ThreeDApp::new(...)");
@@ -121,6 +142,17 @@ impl ThreeDApp {
         let opt_object_triangle = Some(Self::make_basic_triangle_model(&core_context));
         let opt_gm_mesh_color = Some(Self::make_sphere_model(&core_context));
         let opt_gm_mesh_phys = Some(Self::make_opaque_model(&core_context));
+        let star_catalog = StarCatalog::bright_stars();
+        let sky_dome = SkyDome::new(&core_context);
+        let sky_camera = Camera::new_perspective(
+            viewport,
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, -1.0),
+            vec3(0.0, 1.0, 0.0),
+            fov_y,
+            z_near,
+            z_far,
+        );
 
         Self {
             core_context,
@@ -128,6 +160,11 @@ impl ThreeDApp {
             opt_object_triangle,
             opt_gm_mesh_color,
             opt_gm_mesh_phys,
+            star_catalog,
+            opt_gm_stars: None,
+            stars_jd_tt: f64::NAN,
+            sky_dome,
+            sky_camera,
             triangle_rotate: degrees(123.0),
         }
     }
@@ -336,6 +373,40 @@ impl ThreeDApp {
         opaque_model
     }
 
+    /// One small sphere per star, at its mean place of date, sized by magnitude and colored by
+    /// B-V. Equatorial x, y, z map to three-d x, z, -y, so the celestial pole is up.
+    fn make_stars_model(
+        context: &Context,
+        star_catalog: &StarCatalog,
+        jd_tt: f64,
+    ) -> Gm<InstancedMesh, ColorMaterial> {
+        let mut transformations = Vec::with_capacity(star_catalog.stars.len());
+        let mut colors = Vec::with_capacity(star_catalog.stars.len());
+
+        for star in &star_catalog.stars {
+            let (ra, dec) = star.mean_of_date(jd_tt);
+            let (x, y, z) = (dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin());
+            let pos = vec3(x as f32, z as f32, -y as f32) * STAR_SPHERE_RADIUS;
+
+            let scale = STAR_RADIUS * relative_size(star.vmag, STAR_REFERENCE_VMAG) as f32;
+            transformations.push(Mat4::from_translation(pos) * Mat4::from_scale(scale));
+
+            let [r, g, b] = color_from_b_v(star.b_v.unwrap_or_default());
+            colors.push(Srgba::new_opaque(r, g, b));
+        }
+
+        let instances = three_d::renderer::geometry::Instances {
+            transformations,
+            colors: Some(colors),
+            ..Default::default()
+        };
+
+        Gm::new(
+            InstancedMesh::new(context, &instances, &CpuMesh::sphere(4)),
+            ColorMaterial::default(),
+        )
+    }
+
     fn viewport_from_paint_info(paint_info: &epaint::PaintCallbackInfo) -> three_d::Viewport {
         let vp_px = paint_info.viewport_in_pixels();
 
@@ -379,6 +450,7 @@ impl ThreeDApp {
         egui_glow_painter: &egui_glow::Painter,
         arcrwl_animation_state: Arc<RwLock<AnimationState>>,
         arcrwl_world_state: Arc<RwLock<WorldState>>,
+        arcrwl_sky_view_state: Arc<RwLock<SkyViewState>>,
     ) // -> Option<glow::Framebuffer>
     {
         //? TODO why?
@@ -396,6 +468,15 @@ impl ThreeDApp {
 
         // Figure the world state from tai.
         //let world_state = WorldState::world_at_tai(tai);
+        let jd_tt = arcrwl_world_state.read().unwrap().time.tai.to_jd_tt();
+        if self.opt_gm_stars.is_none() || STAR_RECOMPUTE_DAYS < (jd_tt - self.stars_jd_tt).abs() {
+            self.opt_gm_stars = Some(Self::make_stars_model(
+                &self.core_context,
+                &self.star_catalog,
+                jd_tt,
+            ));
+            self.stars_jd_tt = jd_tt;
+        }
         //debug!("t: {tai},   triangle_rotate: {:?} deg", Deg::<f32>::from(triangle_rotate));
 
         let viewport = Self::viewport_from_paint_info(paint_callback_info);
@@ -415,6 +496,17 @@ impl ThreeDApp {
         //render_target.clear_partially(scissor_box, ClearState::depth(1.0));
         render_target.clear(ClearState::depth(1.0));
 
+        let sky_view = *arcrwl_sky_view_state.read().unwrap();
+        if sky_view.enabled {
+            let world_state = *arcrwl_world_state.read().unwrap();
+            self.sky_dome
+                .update(&self.core_context, &self.star_catalog, &world_state);
+            SkyDome::set_camera(&mut self.sky_camera, viewport, &sky_view);
+            self.sky_dome
+                .render(&render_target, scissor_box, &self.sky_camera);
+            return;
+        }
+
         // /*
         if let Some(object) = self.opt_object_triangle.as_mut() {
             object.set_transformation(Mat4::from_angle_y(tri_rot_y));
@@ -429,6 +521,9 @@ impl ThreeDApp {
             render_target.render_partially(scissor_box, &self.camera, [&object], &[]);
         }
         // */
+        if let Some(object) = self.opt_gm_stars.as_ref() {
+            render_target.render_partially(scissor_box, &self.camera, [object], &[]);
+        }
 
         //render_target.clear(ClearState::depth(1.0));
 
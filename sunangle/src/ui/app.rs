@@ -19,18 +19,21 @@ use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
-use egui::{epaint, Align, Frame, Hyperlink, Layout, Ui};
+use egui::{epaint, Align, Frame, Hyperlink, Layout, Sense, Ui};
 use hecs::World;
 use log::{debug, error, info, trace, warn};
 use serde::{self, Deserialize, Serialize};
 
+use coordinate_systems::NamespaceDoc;
+use ecs_namespace::{ecs_ns_get, NamePathSpec};
+
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
 
 use crate::draw_frame_info::DrawFrameInfo;
 use crate::tai::DateTimeTai;
 use crate::ui;
 use crate::ui::showable::ShowableEguiWindow;
-use crate::view_state::{AnimationState, ViewState};
+use crate::view_state::{AnimationState, SkyViewState, ViewState};
 use crate::world_state::{TimeState, WorldState};
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
@@ -41,6 +44,10 @@ pub struct SunangleApp {
     #[serde(skip)]
     world: World,
 
+    /// The user's part of `world`, under [`Self::USER_NAMESPACE`], as of the last save. It's
+    /// loaded into `world` on start.
+    world_doc: Option<NamespaceDoc>,
+
     // Ephemeral Frame stuff.
     //
     #[serde(skip)]
@@ -52,6 +59,7 @@ pub struct SunangleApp {
     current_time_checkbx: bool,
     animation_checkbx: bool,
     ecs_explore_checkbx: bool,
+    sun_path_checkbx: bool,
 
     #[serde(skip)]
     opt_current_time_ctrl_window: Option<ui::CurrentTimeCtrlWindow>,
@@ -62,8 +70,12 @@ pub struct SunangleApp {
     #[serde(skip)]
     opt_ecs_explore_window: Option<ui::EcsExploreWindow>,
 
+    #[serde(skip)]
+    opt_sun_path_window: Option<ui::SunPathWindow>,
+
     arcrwl_animation_state: Arc<RwLock<AnimationState>>,
     arcrwl_world_state: Arc<RwLock<WorldState>>,
+    arcrwl_sky_view_state: Arc<RwLock<SkyViewState>>,
     //next_frame_number: u64,
     //#[serde(skip)]
 
@@ -75,16 +87,20 @@ impl Default for SunangleApp {
     fn default() -> Self {
         Self {
             world: World::new(),
+            world_doc: None,
             draw_frame_info: DrawFrameInfo::new(),
             ui_settings_checkbox: false,
             current_time_checkbx: true,
             animation_checkbx: true,
             ecs_explore_checkbx: true,
+            sun_path_checkbx: false,
             opt_current_time_ctrl_window: None,
             opt_animation_ctrl_window: None,
             opt_ecs_explore_window: None,
+            opt_sun_path_window: None,
             arcrwl_animation_state: Arc::new(RwLock::new(AnimationState::default())),
             arcrwl_world_state: Arc::new(RwLock::new(WorldState::default())),
+            arcrwl_sky_view_state: Arc::new(RwLock::new(SkyViewState::default())),
             //next_frame_number: 0,
 
             //tai: TimeState::default_tai(),
@@ -95,6 +111,10 @@ impl Default for SunangleApp {
 impl SunangleApp {
     const MAX_ANIMATION_TIME: f32 = 0.250;
 
+    /// Where what the user adds to the world goes. Only this is saved, since the code adds the
+    /// rest on every start.
+    const USER_NAMESPACE: [&'static str; 1] = ["user"];
+
     /// Called once before the first frame.
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         cc.egui_ctx.style_mut(|style| {
@@ -106,8 +126,20 @@ impl SunangleApp {
         // Load previous app state
         let mut self_ = Self::load_from_storage(cc).unwrap_or_default();
 
-        // Initialize the world.
+        // Initialize the world, then add what was saved of it. Entities the code adds win over
+        // saved ones at the same path, so saved changes to them are dropped.
         coordinate_systems::ecs_add_stuff(&mut self_.world);
+        if let Some(world_doc) = self_.world_doc.take() {
+            match world_doc.load(&mut self_.world) {
+                Ok(report) => {
+                    debug!("Loaded {} entities into the world", report.created.len());
+                    for (path, e) in &report.skipped {
+                        warn!("Loading SunangleApp: skipped {path:?}: {e:#}");
+                    }
+                }
+                Err(e) => warn!("Loading SunangleApp: world err: {e:#}"),
+            }
+        }
 
         self_
     }
@@ -165,12 +197,21 @@ impl eframe::App for SunangleApp {
 
     /// Called occasionally, and before shutdown, to persist state.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let world = &self.world;
+        if let Ok(ns_user) = ecs_ns_get(world, NamePathSpec::absolute(Self::USER_NAMESPACE)) {
+            match NamespaceDoc::save(world, ns_user) {
+                Ok(world_doc) => self.world_doc = Some(world_doc),
+                Err(e) => warn!("Saving SunangleApp: world err: {e:#}"),
+            }
+        }
+
         debug!("Saving SunagleApp:\n{}", self.to_string(true));
 
         #[cfg(not(target_arch = "wasm32"))]
         info!("Saving...");
 
         eframe::set_value(storage, eframe::APP_KEY, self);
+        self.world_doc = None;
 
         #[cfg(not(target_arch = "wasm32"))]
         info!("saved.");
@@ -211,6 +252,12 @@ impl SunangleApp {
                 .get_or_insert_with(|| ui::EcsExploreWindow::new())
                 .show(ctx, &mut self.world);
         }
+
+        if self.sun_path_checkbx {
+            self.opt_sun_path_window
+                .get_or_insert_with(|| ui::SunPathWindow::new(self.arcrwl_world_state.clone()))
+                .show(ctx, &mut self.world);
+        }
         Ok(())
     }
 
@@ -242,6 +289,11 @@ impl SunangleApp {
             ui.checkbox(&mut self.ui_settings_checkbox, "UI Settings");
             ui.checkbox(&mut self.current_time_checkbx, "Time");
             ui.checkbox(&mut self.animation_checkbx, "Animation");
+            ui.checkbox(&mut self.sun_path_checkbx, "Sun path");
+            ui.checkbox(
+                &mut self.arcrwl_sky_view_state.write().unwrap().enabled,
+                "Sky dome",
+            );
         });
     }
 
@@ -254,6 +306,9 @@ impl SunangleApp {
     }
 
     fn central_panel_add_contents(&mut self, ui: &mut Ui) {
+        // First, so the widgets added after it get their clicks.
+        self.central_panel_sky_view_controls(ui);
+
         let tai = {
             let world_state_guard = self.arcrwl_world_state.read().unwrap();
             world_state_guard.time.tai
@@ -284,10 +339,37 @@ impl SunangleApp {
         self.central_panel_set_up_paint_callback(ui);
     }
 
+    /// Dragging pans and tilts the sky view, scrolling zooms it.
+    fn central_panel_sky_view_controls(&mut self, ui: &mut Ui) {
+        let mut sky_view = self.arcrwl_sky_view_state.write().unwrap();
+        if !sky_view.enabled {
+            return;
+        }
+
+        let rect = ui.max_rect();
+        let response = ui.interact(rect, ui.id().with("sky_view_controls"), Sense::drag());
+
+        // The sky follows the pointer, so the view turns the other way.
+        let deg_per_point = sky_view.fov_y_deg / rect.height().max(1.0) as f64;
+        let drag = response.drag_delta();
+        sky_view.pan_tilt(
+            -drag.x as f64 * deg_per_point,
+            drag.y as f64 * deg_per_point,
+        );
+
+        if response.hovered() {
+            let scroll_y = ui.input(|input| input.scroll_delta.y);
+            if scroll_y != 0.0 {
+                sky_view.zoom((-scroll_y as f64 / 200.0).exp());
+            }
+        }
+    }
+
     // Request a callback to paint the central panel using `threedapp`.
     fn central_panel_set_up_paint_callback(&mut self, ui: &mut Ui) {
         let arcrwl_animation_state = self.arcrwl_animation_state.clone();
         let arcrwl_world_state = self.arcrwl_world_state.clone();
+        let arcrwl_sky_view_state = self.arcrwl_sky_view_state.clone();
 
         let egui_glow_callbackfn = egui_glow::CallbackFn::new(
             move |paint_callback_info: epaint::PaintCallbackInfo,
@@ -295,18 +377,21 @@ impl SunangleApp {
                 let glow_context = egui_glow_painter.gl();
                 let arcrwl_animation_state = arcrwl_animation_state.clone();
                 let arcrwl_world_state = arcrwl_world_state.clone();
+                let arcrwl_sky_view_state = arcrwl_sky_view_state.clone();
 
                 //self.draw_frame_info.start_paint();
 
                 crate::threed::threedapp::with_three_d_app(glow_context, move |threedapp| {
                     let arcrwl_animation_state = arcrwl_animation_state.clone();
                     let arcrwl_world_state = arcrwl_world_state.clone();
+                    let arcrwl_sky_view_state = arcrwl_sky_view_state.clone();
 
                     threedapp.paint_callback(
                         &paint_callback_info,
                         egui_glow_painter,
                         arcrwl_animation_state,
                         arcrwl_world_state,
+                        arcrwl_sky_view_state,
                     )
                 });
 
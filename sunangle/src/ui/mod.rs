@@ -34,3 +34,6 @@ pub mod ecs_explore_window;
 pub use ecs_explore_window::EcsExploreWindow;
 
 pub mod showable;
+
+pub mod sun_path_window;
+pub use sun_path_window::SunPathWindow;
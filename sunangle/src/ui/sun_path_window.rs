@@ -0,0 +1,403 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//? use std::any::Any;
+use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::{RangeBounds, RangeInclusive};
+use std::sync::{Arc, RwLock};
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+//? use derive_more::{Deref, DerefMut, Display, From, Into};
+use hecs::{Bundle, Entity, World};
+use log::{debug, error, info, trace, warn};
+//? use num_enum::{IntoPrimitive, TryFromPrimitive};
+//? use num_integer::Integer;
+//? use num_rational::Ratio;
+//? use num_traits::{NumCast, ToPrimitive, Zero};
+//? use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+//? use strum::{self, EnumCount, EnumDiscriminants, EnumProperty, EnumString, FromRepr};
+
+use astro::sun_path::{self, SunPathCurve, SunPathProjection, SunSample};
+use astro::Observer;
+use chrono::Datelike;
+use egui::{Align2, Color32, FontId, Pos2, Rect, Sense, Shape, Stroke, Vec2};
+
+use crate::ui::showable::ShowableEguiWindow;
+use crate::world_state::WorldState;
+
+/// Everything the curves depend on. When this changes, the curves are recomputed.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+struct SunPathInputs {
+    observer: Observer,
+    utc_offset_hours: f64,
+    year: i32,
+    dates: Vec<(u32, u32)>,
+    step_minutes: u32,
+    opt_analemma_clock_hours: Option<f64>,
+}
+
+impl SunPathInputs {
+    fn compute_curves(&self) -> Vec<SunPathCurve> {
+        let mut curves: Vec<SunPathCurve> = self
+            .dates
+            .iter()
+            .map(|&(month, day)| {
+                SunPathCurve::day_path(
+                    &self.observer,
+                    self.year,
+                    month,
+                    day,
+                    self.utc_offset_hours,
+                    self.step_minutes,
+                )
+            })
+            .collect();
+
+        if let Some(clock_hours) = self.opt_analemma_clock_hours {
+            curves.push(SunPathCurve::analemma(
+                &self.observer,
+                self.year,
+                clock_hours,
+                self.utc_offset_hours,
+            ));
+        }
+
+        curves
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SunPathWindow {
+    arcrwl_world_state: Arc<RwLock<WorldState>>,
+    projection: SunPathProjection,
+    year: i32,
+    dates: Vec<(u32, u32)>,
+    new_date: (u32, u32),
+    step_minutes: u32,
+    show_analemma: bool,
+    analemma_clock_hours: f64,
+
+    #[serde(skip)]
+    opt_cache: Option<(SunPathInputs, Vec<SunPathCurve>)>,
+
+    #[serde(skip)]
+    status: String,
+}
+
+impl SunPathWindow {
+    const NAME_STR: &'static str = "Sun path";
+
+    const PLOT_HEIGHT: f32 = 280.0;
+
+    const EXPORT_HEIGHT_PX: u32 = 480;
+
+    pub fn new(arcrwl_world_state: Arc<RwLock<WorldState>>) -> SunPathWindow {
+        let year = {
+            let world_state_guard = arcrwl_world_state.read().unwrap();
+            world_state_guard.time.tai.to_utc().year()
+        };
+
+        SunPathWindow {
+            arcrwl_world_state,
+            projection: SunPathProjection::default(),
+            year,
+            // Approximate equinoxes and solstices.
+            dates: vec![(3, 20), (6, 21), (9, 22), (12, 21)],
+            new_date: (1, 1),
+            step_minutes: 10,
+            show_analemma: true,
+            analemma_clock_hours: 12.0,
+            opt_cache: None,
+            status: String::new(),
+        }
+    }
+
+    fn inputs(&self) -> SunPathInputs {
+        let observer_position = self.arcrwl_world_state.read().unwrap().observer_position;
+        SunPathInputs {
+            observer: observer_position.observer,
+            utc_offset_hours: observer_position.utc_offset_hours,
+            year: self.year,
+            dates: self.dates.clone(),
+            step_minutes: self.step_minutes,
+            opt_analemma_clock_hours: self.show_analemma.then_some(self.analemma_clock_hours),
+        }
+    }
+
+    /// The curves for the current inputs, recomputed only when something changed.
+    fn curves(&mut self) -> &[SunPathCurve] {
+        let inputs = self.inputs();
+        let stale =
+            !matches!(&self.opt_cache, Some((cached_inputs, _)) if *cached_inputs == inputs);
+        if stale {
+            let curves = inputs.compute_curves();
+            self.opt_cache = Some((inputs, curves));
+        }
+        self.opt_cache
+            .as_ref()
+            .map(|(_, curves)| curves.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn add_observer_controls(&mut self, ui: &mut egui::Ui) {
+        let mut observer_position = self.arcrwl_world_state.read().unwrap().observer_position;
+        let prev_observer_position = observer_position;
+
+        ui.horizontal(|ui| {
+            let observer = &mut observer_position.observer;
+            ui.label("lat");
+            ui.add(
+                egui::DragValue::new(&mut observer.lat_deg)
+                    .speed(0.1)
+                    .clamp_range(-90.0..=90.0)
+                    .suffix("°"),
+            );
+            ui.label("lon");
+            ui.add(
+                egui::DragValue::new(&mut observer.lon_deg)
+                    .speed(0.1)
+                    .clamp_range(-180.0..=180.0)
+                    .suffix("°"),
+            );
+            ui.label("UTC offset");
+            ui.add(
+                egui::DragValue::new(&mut observer_position.utc_offset_hours)
+                    .speed(0.25)
+                    .clamp_range(-12.0..=14.0)
+                    .suffix(" h"),
+            );
+        });
+
+        let changed = observer_position.observer != prev_observer_position.observer
+            || observer_position.utc_offset_hours != prev_observer_position.utc_offset_hours;
+        if changed {
+            self.arcrwl_world_state.write().unwrap().observer_position = observer_position;
+        }
+    }
+
+    fn add_curve_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("year");
+            ui.add(egui::DragValue::new(&mut self.year).clamp_range(-4000..=8000));
+
+            egui::ComboBox::from_id_source("sun_path_projection")
+                .selected_text(self.projection.name())
+                .show_ui(ui, |ui| {
+                    for projection in SunPathProjection::ALL {
+                        ui.selectable_value(&mut self.projection, projection, projection.name());
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_analemma, "analemma at");
+            ui.add(
+                egui::DragValue::new(&mut self.analemma_clock_hours)
+                    .speed(0.05)
+                    .clamp_range(0.0..=24.0)
+                    .custom_formatter(|h, _| sun_path::format_clock_hours(h)),
+            );
+        });
+
+        let mut opt_remove_ix = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.label("dates");
+            for (ix, &(month, day)) in self.dates.iter().enumerate() {
+                if ui
+                    .button(format!("{month:02}-{day:02} ×"))
+                    .on_hover_text("Remove")
+                    .clicked()
+                {
+                    opt_remove_ix = Some(ix);
+                }
+            }
+        });
+        if let Some(ix) = opt_remove_ix {
+            self.dates.remove(ix);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.new_date.0).clamp_range(1..=12));
+            ui.label("-");
+            ui.add(egui::DragValue::new(&mut self.new_date.1).clamp_range(1..=31));
+            if ui.button("add date").clicked() && !self.dates.contains(&self.new_date) {
+                self.dates.push(self.new_date);
+                self.dates.sort();
+            }
+        });
+    }
+
+    fn add_plot(&mut self, ui: &mut egui::Ui) {
+        let projection = self.projection;
+        let current_sample = {
+            let world_state = self.arcrwl_world_state.read().unwrap();
+            SunSample::new(
+                &world_state.observer_position.observer,
+                world_state.time.tai.to_jd_utc(),
+            )
+        };
+
+        let curves = self.curves();
+
+        let plot_size = Vec2::new(
+            Self::PLOT_HEIGHT * projection.aspect_ratio() as f32,
+            Self::PLOT_HEIGHT,
+        );
+        let (response, painter) =
+            ui.allocate_painter(plot_size + Vec2::splat(32.0), Sense::hover());
+        let plot_rect = Rect::from_center_size(response.rect.center(), plot_size);
+
+        let to_screen = |[x, y]: [f64; 2]| -> Pos2 {
+            Pos2::new(
+                plot_rect.left() + (x as f32 + 1.0) * 0.5 * plot_rect.width(),
+                plot_rect.top() + (1.0 - y as f32) * 0.5 * plot_rect.height(),
+            )
+        };
+        let from_screen = |pos: Pos2| -> [f64; 2] {
+            [
+                ((pos.x - plot_rect.left()) / plot_rect.width() * 2.0 - 1.0) as f64,
+                (1.0 - (pos.y - plot_rect.top()) / plot_rect.height() * 2.0) as f64,
+            ]
+        };
+
+        let grid_stroke = Stroke::new(1.0_f32, Color32::DARK_GRAY);
+        for line in projection.grid_lines() {
+            painter.add(Shape::line(
+                line.into_iter().map(to_screen).collect(),
+                grid_stroke,
+            ));
+        }
+        for (xy, label) in projection.compass_labels() {
+            painter.text(
+                to_screen(xy),
+                Align2::CENTER_CENTER,
+                label,
+                FontId::proportional(12.0),
+                Color32::GRAY,
+            );
+        }
+
+        for (ix, curve) in curves.iter().enumerate() {
+            let [r, g, b] = sun_path::CURVE_COLORS[ix % sun_path::CURVE_COLORS.len()];
+            let stroke = Stroke::new(2.0_f32, Color32::from_rgb(r, g, b));
+            for segment in curve.projected_segments(projection) {
+                painter.add(Shape::line(
+                    segment.into_iter().map(to_screen).collect(),
+                    stroke,
+                ));
+            }
+        }
+
+        if let Some(xy) =
+            projection.project(current_sample.azimuth_deg, current_sample.elevation_deg)
+        {
+            painter.circle_filled(to_screen(xy), 5.0, Color32::YELLOW);
+        }
+
+        // Hover readout: the direction under the pointer and the nearest sample.
+        if let Some(pos) = response.hover_pos() {
+            if let Some((az, el)) = projection.unproject(from_screen(pos)) {
+                let mut text = format!("az {az:.1}°  el {el:.1}°");
+
+                let opt_nearest = curves
+                    .iter()
+                    .flat_map(|curve| curve.samples.iter().map(move |s| (curve, s)))
+                    .filter_map(|(curve, s)| {
+                        let xy = projection.project(s.azimuth_deg, s.elevation_deg)?;
+                        Some((to_screen(xy).distance(pos), curve, s))
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                if let Some((dist, curve, s)) = opt_nearest {
+                    if dist < 8.0 {
+                        text = format!(
+                            "{}\n{}\naz {:.2}°  el {:.2}°",
+                            curve.label,
+                            sun_path::format_jd_utc(s.jd_ut),
+                            s.azimuth_deg,
+                            s.elevation_deg
+                        );
+                    }
+                }
+
+                response.on_hover_text_at_pointer(text);
+            }
+        }
+    }
+
+    fn add_export_controls(&mut self, ui: &mut egui::Ui) {
+        let projection = self.projection;
+        let curves = self.curves().to_vec();
+
+        ui.horizontal(|ui| {
+            if ui.button("copy CSV").clicked() {
+                let csv = sun_path::to_csv(&curves);
+                ui.output_mut(|o| o.copied_text = csv);
+                self.status = "CSV copied to clipboard".to_string();
+            }
+            if ui.button("copy SVG").clicked() {
+                let svg = sun_path::to_svg(&curves, projection, Self::EXPORT_HEIGHT_PX);
+                ui.output_mut(|o| o.copied_text = svg);
+                self.status = "SVG copied to clipboard".to_string();
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                if ui.button("save CSV").clicked() {
+                    self.status = save_file("sun_path.csv", &sun_path::to_csv(&curves));
+                }
+                if ui.button("save SVG").clicked() {
+                    let svg = sun_path::to_svg(&curves, projection, Self::EXPORT_HEIGHT_PX);
+                    self.status = save_file("sun_path.svg", &svg);
+                }
+            }
+        });
+
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_file(path: &str, contents: &str) -> String {
+    match std::fs::write(path, contents) {
+        Ok(()) => {
+            info!("Wrote {path}");
+            format!("Wrote {path}")
+        }
+        Err(e) => {
+            error!("Writing {path}: {e}");
+            format!("Error writing {path}: {e}")
+        }
+    }
+}
+
+impl ShowableEguiWindow for SunPathWindow {
+    fn name(&self) -> Cow<'_, str> {
+        Self::NAME_STR.into()
+    }
+
+    fn add_contents(&mut self, ui: &mut egui::Ui, world: &mut World) {
+        self.add_observer_controls(ui);
+        self.add_curve_controls(ui);
+        self.add_plot(ui);
+        self.add_export_controls(ui);
+    }
+}
@@ -95,6 +95,55 @@ impl AnimationState {
     }
 }
 
+//======================================================================== topocentric sky view
+
+/// Where the observer is looking in the topocentric sky view. Angles are degrees, azimuth from
+/// north through east.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SkyViewState {
+    /// Show the sky as seen by the observer, rather than the celestial sphere from outside.
+    pub enabled: bool,
+
+    pub look_az_deg: f64,
+    pub look_el_deg: f64,
+
+    /// Vertical field of view.
+    pub fov_y_deg: f64,
+}
+
+impl Default for SkyViewState {
+    fn default() -> Self {
+        SkyViewState {
+            enabled: false,
+            look_az_deg: 180.0,
+            look_el_deg: 20.0,
+            fov_y_deg: 60.0,
+        }
+    }
+}
+
+impl SkyViewState {
+    /// Short of the zenith, where the camera's up vector would be undefined.
+    pub const MAX_LOOK_EL_DEG: f64 = 89.0;
+    pub const MIN_LOOK_EL_DEG: f64 = -30.0;
+
+    pub const MIN_FOV_Y_DEG: f64 = 5.0;
+    pub const MAX_FOV_Y_DEG: f64 = 120.0;
+
+    /// Turns the view right by `d_az_deg` and up by `d_el_deg`.
+    pub fn pan_tilt(&mut self, d_az_deg: f64, d_el_deg: f64) {
+        self.look_az_deg = (self.look_az_deg + d_az_deg).rem_euclid(360.0);
+        self.look_el_deg =
+            (self.look_el_deg + d_el_deg).clamp(Self::MIN_LOOK_EL_DEG, Self::MAX_LOOK_EL_DEG);
+    }
+
+    /// Multiplies the field of view by `factor`, so less than 1 zooms in.
+    pub fn zoom(&mut self, factor: f64) {
+        self.fov_y_deg = (self.fov_y_deg * factor).clamp(Self::MIN_FOV_Y_DEG, Self::MAX_FOV_Y_DEG);
+    }
+}
+
 //======================================================================== overall view state
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -56,8 +56,12 @@ impl TimeState {
 //======================================================================== observer position
 
 #[derive(Debug, Default, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
 pub struct ObserverPositionState {
-    //?
+    pub observer: astro::Observer,
+
+    /// Offset of the observer's local clock from UTC, hours, east positive.
+    pub utc_offset_hours: f64,
 }
 
 //======================================================================== overall world state
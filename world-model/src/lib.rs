@@ -22,7 +22,7 @@
 //? use std::sync::Arc;
 //? use std::time::Instant;
 
-//? use anyhow::{anyhow, bail, ensure, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 //? use derive_more::Display;
 //? use log::{debug, error, info, trace, warn};
 //? use num_integer::Integer;
@@ -31,6 +31,55 @@
 //? use once_cell::sync::Lazy;
 //? use serde::{Deserialize, Serialize};
 //? use strum::{self, EnumProperty, EnumString};
+use hecs::World;
+
+use coordinate_systems::gis::lookup_epsg;
+
+pub mod transform_graph;
+pub use crate::transform_graph::{
+    Epoch, Frame, FrameEdge, FrameKind, FrameOp, PathPreference, Transform, TransformGraph,
+};
+
+//=================================================================================================|
+
+/// The ECS world, and the frames of reference among its entities.
+pub struct WorldModel {
+    pub world: World,
+    pub frames: TransformGraph,
+}
+
+impl WorldModel {
+    pub fn new() -> Result<WorldModel> {
+        let mut world = World::default();
+        coordinate_systems::ecs_add_stuff(&mut world);
+
+        let mut frames = TransformGraph::default();
+        frames.add_celestial_frames()?;
+
+        // Geocentric and geographic 3D CRSs of ITRF2020, ITRF2014, IGS20 and WGS 84 (G2139).
+        for code in [9988, 9989, 7789, 7912, 10176, 10177, 9753, 9754] {
+            let e_crs = lookup_epsg(&mut world, code)?;
+            frames.add_crs(&world, e_crs)?;
+        }
+
+        // Polar motion, which the TIRS leaves out, is up to about 0.5 arc-seconds.
+        let earth_fixed = frames.find("earth-fixed")?;
+        let itrf2020 = frames.find("EPSG:9988")?;
+        let op = FrameOp::Matrix(cgmath::SquareMatrix::identity());
+        frames.add_edge(earth_fixed, itrf2020, "TIRS to ITRS", op, Some(15.0));
+
+        Ok(WorldModel { world, frames })
+    }
+
+    /// See [`TransformGraph::transform`].
+    pub fn transform(&self, from: &str, to: &str, jd_tai: f64) -> Result<Transform> {
+        let from = self.frames.find(from)?;
+        let to = self.frames.find(to)?;
+        self.frames.transform(from, to, jd_tai)
+    }
+}
+
+//=================================================================================================|
 
 #[cfg(test)]
 #[allow(non_snake_case)]
@@ -39,7 +88,12 @@ mod t {
     use insta::assert_ron_snapshot;
 
     #[test]
-    fn t() {
-        //assert_ron_snapshot!(, @"");
+    fn t() -> anyhow::Result<()> {
+        let wm = WorldModel::new()?;
+        let t = wm.transform("EPSG:10177", "galactic", 2_460_000.5)?;
+        assert_eq!(t.accuracy, None);
+        let t = wm.transform("EPSG:9989", "galactic", 2_460_000.5)?;
+        assert_eq!(t.accuracy, Some(435.0));
+        Ok(())
     }
 }
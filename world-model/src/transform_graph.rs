@@ -0,0 +1,712 @@
+// Copyright 2023 Marsh J. Ray
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#![allow(dead_code)] //? TODO for development
+#![allow(unused_mut)] //? TODO for development
+#![allow(unused_variables)] //? TODO for development
+#![allow(unused_imports)] //? TODO for development
+#![allow(non_snake_case)] //? TODO for development
+#![allow(clippy::new_without_default)] //? TODO for development
+#![allow(clippy::let_and_return)] //? TODO for development
+#![allow(clippy::redundant_closure)] //? TODO for development
+#![allow(clippy::too_many_arguments)]
+
+//! A graph whose nodes are coordinate reference systems and other frames, and whose edges are
+//! the transformations between them, which may depend on the time.
+//!
+//! The celestial frames all have their origin at the geocenter, so they only differ in their
+//! axes. Accuracies are in meters, and for rotations are taken at the Earth's surface.
+
+//? use std::any::Any;
+//? use std::borrow::Cow;
+//? use std::fmt::{Debug, Display};
+//? use std::ops::RangeInclusive;
+//? use std::sync::Arc;
+//? use std::time::Instant;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use cgmath::{SquareMatrix, Vector4};
+use derive_more::Display;
+use hecs::{Entity, World};
+//? use log::{debug, error, info, trace, warn};
+use petgraph::graph::{EdgeIndex, NodeIndex, UnGraph};
+use petgraph::visit::EdgeRef;
+//? use serde::{Deserialize, Serialize};
+
+use astro::julian::{
+    tai_minus_utc_seconds, DAYS_PER_JULIAN_YEAR, J2000, SECONDS_PER_DAY, TT_MINUS_TAI_SECONDS,
+};
+use coordinate_systems::gis::{
+    datum_spheroid, AuthorityId, DatumDef, DatumRef, DatumTransformDef, Geodetic, LocalEnuFrame,
+    OblateSpheroidParams,
+};
+use coordinate_systems::gl::{CoordinateSystemDef, CoordinateSystemRef, CrsKind, CsType};
+use coordinate_systems::*;
+use ecs_namespace::Name;
+
+pub type Matrix4 = cgmath::Matrix4<f64>;
+
+//=================================================================================================|
+
+/// An instant on the time scales the transformations need.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Epoch {
+    pub jd_tai: f64,
+    pub jd_tt: f64,
+    pub jd_ut1: f64,
+}
+
+impl Epoch {
+    /// UT1 is taken as UTC, which is within a second of it.
+    pub fn from_jd_tai(jd_tai: f64) -> Self {
+        Self {
+            jd_tai,
+            jd_tt: jd_tai + TT_MINUS_TAI_SECONDS / SECONDS_PER_DAY,
+            jd_ut1: jd_tai - tai_minus_utc_seconds(jd_tai) / SECONDS_PER_DAY,
+        }
+    }
+
+    /// TT as a decimal year, as datum transformations want it.
+    pub fn decimal_year(&self) -> f64 {
+        2000.0 + (self.jd_tt - J2000) / DAYS_PER_JULIAN_YEAR
+    }
+}
+
+//=================================================================================================|
+
+/// What a node of the [`TransformGraph`] stands for.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum FrameKind {
+    /// A geodetic or geographic CRS entity with three axes. Geographic coordinates are
+    /// latitude and longitude in degrees and height in meters.
+    #[display("Crs({kind})")]
+    Crs {
+        entity: Entity,
+        datum: Entity,
+        kind: CrsKind,
+    },
+
+    /// IAU 1958 galactic coordinates, as defined for the Hipparcos catalogue.
+    Galactic,
+
+    /// Mean ecliptic and equinox of date, IAU 2006.
+    Ecliptic,
+
+    /// The GCRS, whose axes are those of the ICRS.
+    Equatorial,
+
+    /// The TIRS, which is the ITRS without polar motion.
+    EarthFixed,
+
+    /// East, north and up at a point on an ellipsoid.
+    Topocentric,
+
+    Camera,
+
+    /// Normalized device coordinates of a camera's projection.
+    Ndc,
+}
+
+#[derive(Clone, Debug, Display)]
+#[display("Frame {{ {name:?}, {kind} }}")]
+pub struct Frame {
+    pub name: String,
+    pub kind: FrameKind,
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// How an edge of the [`TransformGraph`] maps coordinates from its source to its target.
+#[derive(Clone, Copy, Debug)]
+pub enum FrameOp {
+    /// `x' = m x`, in homogeneous coordinates with the division if `m` is projective.
+    Matrix(Matrix4),
+
+    /// A matrix that depends on the time.
+    Timed(fn(&Epoch) -> Matrix4),
+
+    /// Geographic latitude, longitude and height to geocentric Cartesian.
+    GeographicToGeocentric(OblateSpheroidParams),
+
+    /// Between the geocentric CRSs of two datums.
+    Datum(DatumTransformDef),
+}
+
+#[derive(Clone, Debug, Display)]
+#[display("FrameEdge {{ {name:?}, accuracy: {accuracy:?} }}")]
+pub struct FrameEdge {
+    pub name: String,
+    pub op: FrameOp,
+
+    /// Meters, if known.
+    pub accuracy: Option<f64>,
+}
+
+//-------------------------------------------------------------------------------------------------|
+
+/// Which path [`TransformGraph::transform_by`] looks for.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum PathPreference {
+    FewestSteps,
+    MostAccurate,
+}
+
+/// Cost in meters of an edge of unknown accuracy, when looking for the most accurate path.
+const UNKNOWN_ACCURACY_COST: f64 = 1000.0;
+
+/// Added for each step so that, of equally accurate paths, the shortest wins.
+const STEP_COST: f64 = 1e-6;
+
+//=================================================================================================|
+
+/// A transformation composed along a path through the [`TransformGraph`], for one [`Epoch`].
+#[derive(Clone, Debug)]
+pub struct Transform {
+    /// The frames passed through, from first to last.
+    pub frames: Vec<NodeIndex>,
+
+    /// Sum of the accuracies of the steps, if they are all known.
+    pub accuracy: Option<f64>,
+
+    pub epoch: Epoch,
+
+    steps: Vec<Step>,
+}
+
+/// Consecutive matrices are multiplied together.
+#[derive(Clone, Copy, Debug)]
+enum Step {
+    Matrix(Matrix4),
+    ToGeocentric(OblateSpheroidParams),
+    ToGeographic(OblateSpheroidParams),
+    Datum {
+        def: DatumTransformDef,
+        reverse: bool,
+    },
+}
+
+impl Transform {
+    fn push(&mut self, step: Step) {
+        match (self.steps.last_mut(), step) {
+            (Some(Step::Matrix(m_prev)), Step::Matrix(m)) => *m_prev = m * *m_prev,
+            _ => self.steps.push(step),
+        }
+    }
+
+    /// The number of steps after consecutive matrices are combined.
+    pub fn step_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// The world is needed for datum transformations that refer to ellipsoids.
+    pub fn apply(&self, world: &World, p: Vector3) -> Result<Vector3> {
+        let mut p = p;
+        for step in &self.steps {
+            p = match *step {
+                Step::Matrix(m) => {
+                    let v = m * Vector4::new(p.x, p.y, p.z, 1.0);
+                    ensure!(v.w != 0.0, "point maps to infinity");
+                    Vector3::new(v.x / v.w, v.y / v.w, v.z / v.w)
+                }
+                Step::ToGeocentric(ellipsoid) => {
+                    ellipsoid.geodetic_to_ecef(&Geodetic::from_degrees(p.x, p.y, p.z))
+                }
+                Step::ToGeographic(ellipsoid) => {
+                    let g = ellipsoid.ecef_to_geodetic(p);
+                    Vector3::new(g.lat.to_degrees(), g.lon.to_degrees(), g.h)
+                }
+                Step::Datum { def, reverse } => {
+                    def.apply(world, p, self.epoch.decimal_year(), reverse)?
+                }
+            };
+        }
+        Ok(p)
+    }
+}
+
+//=================================================================================================|
+
+/// Frames and the transformations between them. Each edge can be followed either way.
+#[derive(Clone, Debug, Default)]
+pub struct TransformGraph {
+    pub graph: UnGraph<Frame, FrameEdge>,
+}
+
+impl TransformGraph {
+    pub fn find(&self, name: &str) -> Result<NodeIndex> {
+        self.graph
+            .node_indices()
+            .find(|&ix| self.graph[ix].name == name)
+            .with_context(|| format!("No frame {name:?}"))
+    }
+
+    pub fn add_frame(&mut self, name: &str, kind: FrameKind) -> Result<NodeIndex> {
+        ensure!(self.find(name).is_err(), "Frame {name:?} already exists");
+        Ok(self.graph.add_node(Frame {
+            name: name.into(),
+            kind,
+        }))
+    }
+
+    pub fn add_edge(
+        &mut self,
+        source: NodeIndex,
+        target: NodeIndex,
+        name: &str,
+        op: FrameOp,
+        accuracy: Option<f64>,
+    ) -> EdgeIndex {
+        let edge = FrameEdge {
+            name: name.into(),
+            op,
+            accuracy,
+        };
+        self.graph.add_edge(source, target, edge)
+    }
+
+    //---------------------------------------------------------------------------------------------|
+
+    /// Adds a node for a geodetic CRS with Cartesian axes or a geographic CRS with three axes,
+    /// named by its [`AuthorityId`] if it has one. It is connected to the CRSs already in the
+    /// graph on the same datum, and to those on other datums by the
+    /// [`DatumTransformDef`]s in the world. Transformations added to the world later aren't
+    /// picked up, so add them before the CRSs they connect.
+    pub fn add_crs(&mut self, world: &World, e_crs: Entity) -> Result<NodeIndex> {
+        let name = match world.get::<&AuthorityId>(e_crs) {
+            Ok(id) => id.to_string(),
+            Err(_) => world.get::<&Name>(e_crs).context("CRS Name")?.to_string(),
+        };
+        let kind = *world.get::<&CrsKind>(e_crs).context("CrsKind")?;
+        let datum_ref = *world.get::<&DatumRef>(e_crs).context("DatumRef")?;
+        let datum = datum_ref.entity();
+        let e_cs = world
+            .get::<&CoordinateSystemRef>(e_crs)
+            .context("CoordinateSystemRef")?
+            .entity();
+        let cs_def = world
+            .get::<&CoordinateSystemDef>(e_cs)
+            .context("CoordinateSystemDef")?
+            .clone();
+        let expected_cs_type = match kind {
+            CrsKind::Geodetic => CsType::Cartesian,
+            CrsKind::Geographic => CsType::Ellipsoidal,
+        };
+        ensure!(
+            cs_def.cs_type == expected_cs_type && cs_def.axes.len() == 3,
+            "{name} has a {} CS with {} axes",
+            cs_def.cs_type,
+            cs_def.axes.len()
+        );
+        let ellipsoid = datum_spheroid(world, datum_ref)?;
+
+        let node = self.add_frame(
+            &name,
+            FrameKind::Crs {
+                entity: e_crs,
+                datum,
+                kind,
+            },
+        )?;
+
+        let crs_nodes: Vec<(NodeIndex, Entity, CrsKind)> = self
+            .graph
+            .node_indices()
+            .filter(|&ix| ix != node)
+            .filter_map(|ix| match self.graph[ix].kind {
+                FrameKind::Crs { datum, kind, .. } => Some((ix, datum, kind)),
+                _ => None,
+            })
+            .collect();
+        for (other, other_datum, other_kind) in crs_nodes {
+            if other_datum == datum {
+                let (geographic, geocentric) = match (kind, other_kind) {
+                    (CrsKind::Geographic, CrsKind::Geodetic) => (node, other),
+                    (CrsKind::Geodetic, CrsKind::Geographic) => (other, node),
+                    _ => continue,
+                };
+                let op = FrameOp::GeographicToGeocentric(ellipsoid);
+                self.add_edge(
+                    geographic,
+                    geocentric,
+                    "geographic to geocentric",
+                    op,
+                    Some(0.0),
+                );
+            } else if kind == CrsKind::Geodetic && other_kind == CrsKind::Geodetic {
+                for (_, (name, def)) in world.query::<(&Name, &DatumTransformDef)>().iter() {
+                    let (name, def) = (name.to_string(), *def);
+                    let (source, target) = (def.source.entity(), def.target.entity());
+                    if (source, target) == (datum, other_datum) {
+                        self.add_edge(node, other, &name, FrameOp::Datum(def), def.accuracy);
+                    } else if (source, target) == (other_datum, datum) {
+                        self.add_edge(other, node, &name, FrameOp::Datum(def), def.accuracy);
+                    }
+                }
+            }
+        }
+
+        Ok(node)
+    }
+
+    /// Adds the galactic, ecliptic, equatorial and earth-fixed frames.
+    pub fn add_celestial_frames(&mut self) -> Result<()> {
+        let galactic = self.add_frame("galactic", FrameKind::Galactic)?;
+        let ecliptic = self.add_frame("ecliptic", FrameKind::Ecliptic)?;
+        let equatorial = self.add_frame("equatorial", FrameKind::Equatorial)?;
+        let earth_fixed = self.add_frame("earth-fixed", FrameKind::EarthFixed)?;
+
+        let op = FrameOp::Matrix(rotation(&ICRS_TO_GALACTIC));
+        self.add_edge(equatorial, galactic, "ICRS to galactic", op, Some(0.0));
+
+        let op = FrameOp::Timed(gcrs_to_ecliptic);
+        self.add_edge(
+            equatorial,
+            ecliptic,
+            "GCRS to ecliptic of date",
+            op,
+            Some(0.0),
+        );
+
+        // Taking UT1 as UTC, it may be off by up to 0.9 s of rotation.
+        let op = FrameOp::Timed(gcrs_to_tirs);
+        self.add_edge(equatorial, earth_fixed, "GCRS to TIRS", op, Some(420.0));
+
+        Ok(())
+    }
+
+    /// Adds a frame of east, north and up at `origin` on `ellipsoid`, from a geocentric frame.
+    pub fn add_topocentric(
+        &mut self,
+        name: &str,
+        geocentric: NodeIndex,
+        ellipsoid: &OblateSpheroidParams,
+        origin: Geodetic,
+    ) -> Result<NodeIndex> {
+        let node = self.add_frame(name, FrameKind::Topocentric)?;
+        let enu = LocalEnuFrame::new(ellipsoid, origin);
+        let mut m = Matrix4::from(enu.ecef_to_enu);
+        m.w = (-(enu.ecef_to_enu * enu.origin_ecef)).extend(1.0);
+        self.add_edge(
+            geocentric,
+            node,
+            "geocentric to ENU",
+            FrameOp::Matrix(m),
+            Some(0.0),
+        );
+        Ok(node)
+    }
+
+    /// Adds a camera whose pose in `parent` is `camera_to_parent`.
+    pub fn add_camera(
+        &mut self,
+        name: &str,
+        parent: NodeIndex,
+        camera_to_parent: Matrix4,
+    ) -> Result<NodeIndex> {
+        let node = self.add_frame(name, FrameKind::Camera)?;
+        let op = FrameOp::Matrix(camera_to_parent);
+        self.add_edge(node, parent, "camera to parent", op, Some(0.0));
+        Ok(node)
+    }
+
+    /// Adds the normalized device coordinates of `camera`'s `projection`.
+    pub fn add_ndc(
+        &mut self,
+        name: &str,
+        camera: NodeIndex,
+        projection: Matrix4,
+    ) -> Result<NodeIndex> {
+        let node = self.add_frame(name, FrameKind::Ndc)?;
+        let op = FrameOp::Matrix(projection);
+        self.add_edge(camera, node, "projection", op, Some(0.0));
+        Ok(node)
+    }
+
+    //---------------------------------------------------------------------------------------------|
+
+    /// The most accurate transformation from one frame to another at an instant, given as a
+    /// Julian date in TAI.
+    pub fn transform(&self, from: NodeIndex, to: NodeIndex, jd_tai: f64) -> Result<Transform> {
+        self.transform_by(from, to, jd_tai, PathPreference::MostAccurate)
+    }
+
+    pub fn transform_by(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        jd_tai: f64,
+        preference: PathPreference,
+    ) -> Result<Transform> {
+        let cost = |edge: &FrameEdge| match preference {
+            PathPreference::FewestSteps => 1.0,
+            PathPreference::MostAccurate => {
+                edge.accuracy.unwrap_or(UNKNOWN_ACCURACY_COST) + STEP_COST
+            }
+        };
+        let (_, frames) = petgraph::algo::astar(
+            &self.graph,
+            from,
+            |ix| ix == to,
+            |e| cost(e.weight()),
+            |_| 0.0,
+        )
+        .ok_or_else(|| {
+            anyhow!(
+                "No path from {:?} to {:?}",
+                self.graph[from].name,
+                self.graph[to].name
+            )
+        })?;
+
+        let epoch = Epoch::from_jd_tai(jd_tai);
+        let mut transform = Transform {
+            frames: frames.clone(),
+            accuracy: Some(0.0),
+            epoch,
+            steps: vec![],
+        };
+        for pair in frames.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let e = self
+                .graph
+                .edges(a)
+                .filter(|e| e.source() == b || e.target() == b)
+                .min_by(|e1, e2| cost(e1.weight()).total_cmp(&cost(e2.weight())))
+                .context("edge")?
+                .id();
+            let (source, _) = self.graph.edge_endpoints(e).context("edge endpoints")?;
+            let edge = &self.graph[e];
+            let reverse = source != a;
+            transform.accuracy = transform.accuracy.zip(edge.accuracy).map(|(a, b)| a + b);
+            transform.push(edge_step(edge, &epoch, reverse)?);
+            if let (FrameOp::GeographicToGeocentric(ellipsoid), true) = (edge.op, reverse) {
+                transform.steps.pop();
+                transform.push(Step::ToGeographic(ellipsoid));
+            }
+        }
+        Ok(transform)
+    }
+}
+
+fn edge_step(edge: &FrameEdge, epoch: &Epoch, reverse: bool) -> Result<Step> {
+    let matrix = |m: Matrix4| -> Result<Step> {
+        if reverse {
+            let m = m
+                .invert()
+                .with_context(|| format!("{:?} is not invertible", edge.name))?;
+            Ok(Step::Matrix(m))
+        } else {
+            Ok(Step::Matrix(m))
+        }
+    };
+    match edge.op {
+        FrameOp::Matrix(m) => matrix(m),
+        FrameOp::Timed(f) => matrix(f(epoch)),
+        FrameOp::GeographicToGeocentric(ellipsoid) => Ok(Step::ToGeocentric(ellipsoid)),
+        FrameOp::Datum(def) => Ok(Step::Datum { def, reverse }),
+    }
+}
+
+//=================================================================================================|
+
+/// Rows are the galactic axes in the ICRS. From the Hipparcos and Tycho Catalogues, ESA SP-1200,
+/// vol. 1, section 1.5.3, as given to more digits by Astropy.
+#[allow(clippy::excessive_precision)]
+const ICRS_TO_GALACTIC: astro::rotation::Matrix3 = [
+    [
+        -0.054_875_560_416_215_4,
+        -0.873_437_090_234_885_0,
+        -0.483_835_015_548_713_2,
+    ],
+    [
+        0.494_109_427_875_583_7,
+        -0.444_829_629_960_011_2,
+        0.746_982_244_497_218_9,
+    ],
+    [
+        -0.867_666_149_019_004_7,
+        -0.198_076_373_431_201_5,
+        0.455_983_776_175_066_9,
+    ],
+];
+
+/// From the row-major matrices of the `astro` crate.
+fn rotation(m: &astro::rotation::Matrix3) -> Matrix4 {
+    Matrix4::from(Matrix3::new(
+        m[0][0], m[1][0], m[2][0], m[0][1], m[1][1], m[2][1], m[0][2], m[1][2], m[2][2],
+    ))
+}
+
+fn gcrs_to_ecliptic(epoch: &Epoch) -> Matrix4 {
+    use astro::rotation::{mul, rx};
+    let eps = astro::precession::mean_obliquity_2006(epoch.jd_tt);
+    rotation(&mul(&rx(eps), &astro::frames::gcrs_to_mod(epoch.jd_tt)))
+}
+
+fn gcrs_to_tirs(epoch: &Epoch) -> Matrix4 {
+    let nut = astro::nutation::iau2000b(epoch.jd_tt);
+    rotation(&astro::frames::gcrs_to_tirs(epoch.jd_tt, epoch.jd_ut1, nut))
+}
+
+//=================================================================================================|
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod t {
+    use super::*;
+    use cgmath::InnerSpace;
+    use coordinate_systems::gis::datum::ecs_add_datum;
+    use coordinate_systems::gis::{ecs_add_crs_from_wkt, lookup_epsg, transform_ecef};
+    use ecs_namespace::{ecs_ns_find_or_create, NamePathSpec};
+    use insta::assert_ron_snapshot;
+
+    const SITE_GEOGRAPHIC: &str = r#"GEOGCRS["Site 3D",DATUM["Site datum",ELLIPSOID["WGS 84",6378137,298.257223563,LENGTHUNIT["metre",1]]],PRIMEM["Greenwich",0,ANGLEUNIT["degree",0.0174532925199433]],CS[ellipsoidal,3],AXIS["latitude",north,ORDER[1],ANGLEUNIT["degree",0.0174532925199433]],AXIS["longitude",east,ORDER[2],ANGLEUNIT["degree",0.0174532925199433]],AXIS["ellipsoidal height (h)",up,ORDER[3],LENGTHUNIT["metre",1]]]"#;
+    const SITE_GEOCENTRIC: &str = r#"GEODCRS["Site ECEF",DATUM["Site datum",ELLIPSOID["WGS 84",6378137,298.257223563,LENGTHUNIT["metre",1]]],PRIMEM["Greenwich",0,ANGLEUNIT["degree",0.0174532925199433]],CS[Cartesian,3],AXIS["(X)",geocentricX,ORDER[1],LENGTHUNIT["metre",1]],AXIS["(Y)",geocentricY,ORDER[2],LENGTHUNIT["metre",1]],AXIS["(Z)",geocentricZ,ORDER[3],LENGTHUNIT["metre",1]]]"#;
+
+    #[test]
+    fn t() -> anyhow::Result<()> {
+        let mut world = World::default();
+        let world = &mut world;
+        coordinate_systems::ecs_add_stuff(world);
+        let mut tg = TransformGraph::default();
+        tg.add_celestial_frames()?;
+        for code in [9753, 9754, 7789, 9988, 9989, 10176] {
+            let e_crs = lookup_epsg(world, code)?;
+            tg.add_crs(world, e_crs)?;
+        }
+        let itrf2020 = tg.find("EPSG:9988")?;
+        let op = FrameOp::Matrix(Matrix4::identity());
+        tg.add_edge(
+            tg.find("earth-fixed")?,
+            itrf2020,
+            "TIRS to ITRS",
+            op,
+            Some(15.0),
+        );
+
+        let jd_tai = astro::jd_from_gregorian(2024, 6, 21.5);
+
+        // UT1 is taken as UTC, which trails TAI by the leap seconds of the date.
+        let epoch = Epoch::from_jd_tai(jd_tai);
+        assert!(((jd_tai - epoch.jd_ut1) * SECONDS_PER_DAY - 37.0).abs() < 1e-4);
+        let epoch = Epoch::from_jd_tai(J2000);
+        assert!(((J2000 - epoch.jd_ut1) * SECONDS_PER_DAY - 32.0).abs() < 1e-4);
+
+        // The north galactic pole.
+        let (ra, dec) = (192.859_48_f64.to_radians(), 27.128_25_f64.to_radians());
+        let ngp = Vector3::new(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin());
+        let t = tg.transform(tg.find("equatorial")?, tg.find("galactic")?, jd_tai)?;
+        assert!((t.apply(world, ngp)?.z - 1.0).abs() < 1e-9);
+
+        // The true pole of date is the true obliquity from the ecliptic pole.
+        let t = tg.transform(tg.find("earth-fixed")?, tg.find("ecliptic")?, jd_tai)?;
+        assert_eq!(t.step_count(), 1);
+        let z = t.apply(world, Vector3::unit_z())?;
+        assert_ron_snapshot!((z.z.acos().to_degrees() * 1e3).round() / 1e3, @"23.438");
+
+        // Geographic WGS 84 (G2139) to IGS20 takes the datum transformations, which don't
+        // know their accuracy.
+        let g2139 = tg.find("EPSG:9754")?;
+        let igs20 = tg.find("EPSG:10176")?;
+        let t = tg.transform(g2139, igs20, jd_tai)?;
+        let names: Vec<&str> = t
+            .frames
+            .iter()
+            .map(|&ix| tg.graph[ix].name.as_str())
+            .collect();
+        assert_ron_snapshot!(names, @r###"
+        [
+          "EPSG:9754",
+          "EPSG:9753",
+          "EPSG:7789",
+          "EPSG:9988",
+          "EPSG:10176",
+        ]
+        "###);
+        assert_eq!(t.accuracy, None);
+        let wgs84 = OblateSpheroidParams::WGS84;
+        let p = t.apply(world, Vector3::new(45.0, -120.0, 100.0))?;
+        let ecef = wgs84.geodetic_to_ecef(&Geodetic::from_degrees(45.0, -120.0, 100.0));
+        let g2139_datum = DatumRef::new(lookup_epsg(world, 1309)?, world);
+        let igs20_datum = DatumRef::new(lookup_epsg(world, 1333)?, world);
+        let year = t.epoch.decimal_year();
+        let expected = transform_ecef(world, g2139_datum, igs20_datum, ecef, year)?;
+        assert!((p - expected).magnitude() < 1e-6, "{p:?} {expected:?}");
+
+        // A point 100 m above a topocentric origin is 100 m higher in ITRF2020, which is on
+        // GRS 1980.
+        let origin = Geodetic::from_degrees(47.6, -122.3, 50.0);
+        let grs80 = OblateSpheroidParams::new(6_378_137.0, 1.0 / 298.257_222_101);
+        let enu = tg.add_topocentric("Seattle", itrf2020, &grs80, origin)?;
+        let t = tg.transform(enu, tg.find("EPSG:9989")?, jd_tai)?;
+        assert_eq!(t.accuracy, Some(0.0));
+        let p = t.apply(world, Vector3::new(0.0, 0.0, 100.0))?;
+        assert!(
+            (p - Vector3::new(47.6, -122.3, 150.0)).magnitude() < 1e-6,
+            "{p:?}"
+        );
+
+        // A camera 2 m up looking north, and a perspective projection.
+        let camera_to_enu = Matrix4::from_translation(Vector3::new(0.0, 0.0, 2.0))
+            * Matrix4::from_angle_x(cgmath::Deg(90.0));
+        let camera = tg.add_camera("camera", enu, camera_to_enu)?;
+        let projection = Matrix4::from(cgmath::perspective(cgmath::Deg(60.0), 1.5, 1.0, 1000.0));
+        let ndc = tg.add_ndc("ndc", camera, projection)?;
+        let t = tg.transform(enu, ndc, jd_tai)?;
+        assert_eq!(t.step_count(), 1);
+        let p = t.apply(world, Vector3::new(0.0, 10.0, 2.0))?;
+        assert!(
+            p.x.abs() < 1e-12 && p.y.abs() < 1e-12 && -1.0 < p.z && p.z < 1.0,
+            "{p:?}"
+        );
+        let back = tg.transform(ndc, enu, jd_tai)?.apply(world, p)?;
+        assert!(
+            (back - Vector3::new(0.0, 10.0, 2.0)).magnitude() < 1e-9,
+            "{back:?}"
+        );
+
+        // The zenith seen from the camera, in galactic coordinates. The accuracy comes from
+        // taking UT1 as UTC and leaving out polar motion.
+        let t = tg.transform(ndc, tg.find("galactic")?, jd_tai)?;
+        assert_eq!(t.accuracy, Some(435.0));
+        let t_by_steps = tg.transform_by(
+            ndc,
+            tg.find("galactic")?,
+            jd_tai,
+            PathPreference::FewestSteps,
+        )?;
+        assert_eq!(t_by_steps.frames, t.frames);
+
+        // A user's datum on the built-in WGS 84, whose semi-major axis is the entity of a
+        // defining constant rather than a number.
+        let ns_site = ecs_ns_find_or_create(world, NamePathSpec::absolute(["user", "site"]))?;
+        let datum_def = DatumDef {
+            ellipsoid: Some(OblateSpheroidRef::find(world, "WGS 84")?),
+            prime_meridian: None,
+            frame_epoch: None,
+        };
+        let site_datum = ecs_add_datum(world, ns_site, "site datum", datum_def)?;
+        let mut add_site_crs = |wkt: &str| -> Result<NodeIndex> {
+            let e_crs = ecs_add_crs_from_wkt(world, ns_site, wkt)?;
+            let datum_ref = DatumRef::new(site_datum, world);
+            world.insert_one(e_crs, datum_ref)?;
+            tg.add_crs(world, e_crs)
+        };
+        let site_geographic = add_site_crs(SITE_GEOGRAPHIC)?;
+        let site_geocentric = add_site_crs(SITE_GEOCENTRIC)?;
+        let t = tg.transform(site_geographic, site_geocentric, jd_tai)?;
+        assert_eq!(t.step_count(), 1);
+        let p = t.apply(world, Vector3::new(45.0, -120.0, 100.0))?;
+        assert!((p - ecef).magnitude() < 1e-6, "{p:?} {ecef:?}");
+
+        let lost = tg.add_frame("lost", FrameKind::Camera)?;
+        assert!(tg.transform(g2139, lost, jd_tai).is_err());
+        assert!(tg.find("nowhere").is_err());
+
+        Ok(())
+    }
+}